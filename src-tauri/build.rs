@@ -1,3 +1,39 @@
 fn main() {
+    // Build date for the About window (UTC, YYYY-MM-DD). SOURCE_DATE_EPOCH
+    // takes over for reproducible builds. Worked out again whenever the
+    // app's, the core's or the frontend's sources change, as those are what
+    // rebuild the exe (tauri_build's own triggers replace cargo's default of
+    // rerunning on any change).
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    for sources in ["src", "quickrun-core/src", "../src", "Cargo.toml", "tauri.conf.json"] {
+        println!("cargo:rerun-if-changed={}", sources);
+    }
+    println!("cargo:rustc-env=QUICKRUN_BUILD_DATE={}", build_date());
+
     tauri_build::build()
 }
+
+/// Today's date (or SOURCE_DATE_EPOCH's) as YYYY-MM-DD
+fn build_date() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default()
+        });
+
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,235 @@
+// main.rs - qr, QuickRun from the command line
+//
+// A console front end to quickrun-core for scripts and terminal users. It
+// resolves commands exactly like the launcher and shares its data files
+// (aliases.json, history.json, the index cache) in the config folder:
+//
+//   qr run <command>                    Run it as if typed into the launcher
+//   qr which <command>                  Show what <command> would run
+//   qr search <query>                   Print the launcher's suggestions
+//   qr alias add <name> <target> [args] Add or replace an alias
+//   qr history export <file>            Save the history as CSV or JSON
+//   qr history import <file>            Merge a saved history into this one
+//
+// The words after "run", "which" and "search" are joined back into one line,
+// so `qr run notes todo.txt` and `qr run "notes todo.txt"` are the same.
+// Errors go to stderr with exit code 1 (2 for usage errors).
+
+use quickrun_core::runner::{self, Resolution};
+use quickrun_core::{aliases, history, history_export, index, suggestions};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage:
+  qr run <command>                     Run a command as if typed into QuickRun
+  qr which <command>                   Show what a command would run
+  qr search <query>                    List QuickRun's suggestions for a query
+  qr alias add <name> <target> [args]  Add or replace an alias
+  qr history export <file>             Save the launch history (.csv or .json)
+  qr history import <file>             Merge a saved launch history into this one
+  qr --version";
+
+/// A parsed command line
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run(String),
+    Which(String),
+    Search(String),
+    AliasAdd { name: String, target: String, args: Vec<String> },
+    HistoryExport(PathBuf),
+    HistoryImport(PathBuf),
+    Help,
+    Version,
+}
+
+/// Parse the arguments after the program name
+fn parse(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Help);
+    };
+
+    // Everything after the subcommand, as one line of launcher input
+    let line = || {
+        let line = rest.join(" ");
+        if line.trim().is_empty() {
+            Err(format!("'qr {}' needs something to look for", command))
+        } else {
+            Ok(line)
+        }
+    };
+
+    match command.as_str() {
+        "run" => line().map(Command::Run),
+        "which" => line().map(Command::Which),
+        "search" => line().map(Command::Search),
+        "alias" => match rest {
+            [sub, name, target, args @ ..] if sub == "add" => Ok(Command::AliasAdd {
+                name: name.clone(),
+                target: target.clone(),
+                args: args.to_vec(),
+            }),
+            [sub, ..] if sub == "add" => Err("Usage: qr alias add <name> <target> [args]".to_string()),
+            _ => Err("Unknown alias command (try 'qr alias add')".to_string()),
+        },
+        "history" => match rest {
+            [sub, file] if sub == "export" => Ok(Command::HistoryExport(runner::expand_home(file))),
+            [sub, file] if sub == "import" => Ok(Command::HistoryImport(runner::expand_home(file))),
+            _ => Err("Usage: qr history export <file> | qr history import <file>".to_string()),
+        },
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        "-V" | "--version" => Ok(Command::Version),
+        other => Err(format!("Unknown command '{}'", other)),
+    }
+}
+
+/// Describe a resolution for `qr which`
+fn describe(resolution: &Resolution) -> String {
+    match resolution {
+        Resolution::Alias { alias, args } => {
+            let mut line = format!("{}: alias for {}", alias.name, alias.target);
+            for arg in alias.args.iter().chain(args) {
+                line.push(' ');
+                line.push_str(arg);
+            }
+            if let Some(env) = &alias.env {
+                line.push_str(&format!(" (in {})", env));
+            }
+            line
+        }
+        Resolution::Executable(path) | Resolution::Folder(path) => path.display().to_string(),
+        Resolution::Command { path, args } => {
+            let mut line = path.display().to_string();
+            for arg in args {
+                line.push(' ');
+                line.push_str(arg);
+            }
+            line
+        }
+        Resolution::App(app) => app.path.display().to_string(),
+    }
+}
+
+/// Carry out a parsed command
+fn execute(command: Command) -> Result<(), String> {
+    match command {
+        Command::Run(input) => {
+            index::load();
+            runner::run_command(&input)?;
+            // Same as the launcher: remember it for completion, but a failed
+            // write shouldn't fail the launch
+            if let Err(e) = history::record(&input) {
+                eprintln!("Warning: Could not save history: {}", e);
+            }
+        }
+        Command::Which(input) => {
+            index::load();
+            println!("{}", describe(&runner::resolve(&input)?));
+        }
+        Command::Search(query) => {
+            index::load();
+            let rows = suggestions::suggest(&query, &suggestions::begin_query()).unwrap_or_default();
+            for row in rows {
+                if row.subtitle.is_empty() {
+                    println!("{}", row.title);
+                } else {
+                    println!("{}\t{}", row.title, row.subtitle);
+                }
+            }
+        }
+        Command::AliasAdd { name, target, args } => {
+            aliases::save(aliases::Alias { name, target, args, compat: None, env: None })?;
+        }
+        Command::HistoryExport(file) => {
+            let count = history_export::export(&file)?;
+            println!("Saved {} commands to {}", count, file.display());
+        }
+        Command::HistoryImport(file) => {
+            let count = history_export::import(&file)?;
+            println!("Merged {} commands from {}", count, file.display());
+        }
+        Command::Help => println!("{}", USAGE),
+        Command::Version => println!("qr {}", env!("CARGO_PKG_VERSION")),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let command = match parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("qr: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match execute(command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("qr: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> Result<Command, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse(&args)
+    }
+
+    #[test]
+    fn words_after_the_subcommand_form_one_line() {
+        assert_eq!(parse_line("run notes todo.txt"), Ok(Command::Run("notes todo.txt".to_string())));
+        let quoted = ["which".to_string(), "notes todo.txt".to_string()];
+        assert_eq!(parse(&quoted), Ok(Command::Which("notes todo.txt".to_string())));
+        assert_eq!(parse_line("search visual studio"), Ok(Command::Search("visual studio".to_string())));
+    }
+
+    #[test]
+    fn alias_add_takes_name_target_and_args() {
+        assert_eq!(
+            parse_line("alias add np notepad++ -multiInst -nosession"),
+            Ok(Command::AliasAdd {
+                name: "np".to_string(),
+                target: "notepad++".to_string(),
+                args: vec!["-multiInst".to_string(), "-nosession".to_string()],
+            })
+        );
+        assert!(parse_line("alias add np").is_err());
+        assert!(parse_line("alias remove np").is_err());
+    }
+
+    #[test]
+    fn history_takes_one_file() {
+        assert_eq!(
+            parse_line("history export habits.csv"),
+            Ok(Command::HistoryExport(PathBuf::from("habits.csv")))
+        );
+        assert_eq!(
+            parse_line("history import habits.json"),
+            Ok(Command::HistoryImport(PathBuf::from("habits.json")))
+        );
+        assert!(parse_line("history export").is_err());
+        assert!(parse_line("history clear").is_err());
+    }
+
+    #[test]
+    fn missing_input_is_a_usage_error() {
+        assert!(parse_line("run").is_err());
+        assert!(parse(&["which".to_string(), "  ".to_string()]).is_err());
+        assert!(parse_line("launch notepad").is_err());
+    }
+
+    #[test]
+    fn no_arguments_shows_help() {
+        assert_eq!(parse_line(""), Ok(Command::Help));
+        assert_eq!(parse_line("--help"), Ok(Command::Help));
+        assert_eq!(parse_line("--version"), Ok(Command::Version));
+    }
+}
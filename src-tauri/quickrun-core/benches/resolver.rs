@@ -0,0 +1,75 @@
+// benches/resolver.rs - Resolver and suggestion engine benchmarks
+//
+// Run with `cargo bench`. Uses the same synthetic PATH/Start Menu fixture as
+// the integration tests, padded out with a few thousand extra files so the
+// numbers resemble a well-used machine rather than an empty one.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use quickrun_core::{index, ranking, runner, suggestions};
+use tokio_util::sync::CancellationToken;
+
+/// Extra executables on PATH and shortcuts in the Start Menu
+const PADDING: usize = 2000;
+
+/// Build the fixture plus padding, then re-index
+fn setup() {
+    let fixture = common::fixture();
+    let programs = fixture.root.join("AppData").join("Microsoft\\Windows\\Start Menu\\Programs");
+    for i in 0..PADDING {
+        let _ = std::fs::write(fixture.tools.join(format!("tool{:04}.exe", i)), b"");
+        let _ = std::fs::write(programs.join(format!("Application {:04}.lnk", i)), b"");
+    }
+    index::rebuild();
+}
+
+fn bench_resolve_on_path(c: &mut Criterion) {
+    setup();
+    // Found in the first folder, found in the last, and not found at all
+    c.bench_function("resolve_on_path/hit_first_dir", |b| {
+        b.iter(|| runner::resolve_on_path(black_box("node.cmd")))
+    });
+    c.bench_function("resolve_on_path/hit_last_dir", |b| {
+        b.iter(|| runner::resolve_on_path(black_box("7z")))
+    });
+    c.bench_function("resolve_on_path/miss", |b| {
+        b.iter(|| runner::resolve_on_path(black_box("does-not-exist")))
+    });
+}
+
+fn bench_list_path_commands(c: &mut Criterion) {
+    setup();
+    c.bench_function("list_path_commands", |b| b.iter(runner::list_path_commands));
+}
+
+fn bench_suggest(c: &mut Criterion) {
+    setup();
+    for query in ["n", "note", "application 19", "zzz"] {
+        c.bench_function(&format!("suggest/{}", query), |b| {
+            b.iter(|| suggestions::suggest(black_box(query), &CancellationToken::new()))
+        });
+    }
+}
+
+fn bench_match_score(c: &mut Criterion) {
+    let names: Vec<String> = (0..PADDING).map(|i| format!("Application {:04}", i)).collect();
+    c.bench_function("match_score/2000_names", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter_map(|name| ranking::match_score(name, black_box("app 1")))
+                .count()
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_resolve_on_path,
+    bench_list_path_commands,
+    bench_suggest,
+    bench_match_score
+);
+criterion_main!(benches);
@@ -0,0 +1,148 @@
+// aliases.rs - User-defined command aliases
+//
+// An alias is a short name for a program plus fixed arguments, e.g.
+// "notes" → "C:\Tools\Notepad++\notepad++.exe" with args ["-multiInst"].
+// Typing the alias name (optionally followed by more arguments) runs the
+// target with the alias's arguments, then the typed ones.
+//
+// Aliases are stored in aliases.json in the active profile's folder (the
+// config directory for the default profile):
+//
+//   [
+//     {
+//       "name": "oldgame",
+//       "target": "D:\\Games\\Old\\game.exe",
+//       "args": ["-windowed"],
+//       "compat": { "run_640x480": true, "windows8": true },
+//       "env": "JDK 17"
+//     }
+//   ]
+//
+// "env" names an environment preset from settings.json (see env_presets.rs)
+// to run the target in.
+
+use crate::compat::CompatSettings;
+use crate::shared_file;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A single alias
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alias {
+    /// What the user types (matched case-insensitively)
+    pub name: String,
+    /// Program name on PATH, full path, document, or URL
+    pub target: String,
+    /// Arguments always passed before any typed arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Compatibility-mode options applied before launching (executables only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compat: Option<CompatSettings>,
+    /// Environment preset to run the target in (see env_presets.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+}
+
+/// Get the path to the aliases file (each profile has its own, see profiles.rs)
+fn get_aliases_path() -> PathBuf {
+    crate::profiles::dir().join("aliases.json")
+}
+
+/// Load all aliases (empty if the file is missing or invalid)
+pub fn load() -> Vec<Alias> {
+    std::fs::read_to_string(get_aliases_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Write all aliases back to disk
+fn save_all(aliases: &[Alias]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(aliases)
+        .map_err(|e| format!("Failed to serialize aliases: {}", e))?;
+
+    shared_file::replace(&get_aliases_path(), json.as_bytes())
+        .map_err(|e| format!("Failed to save aliases: {}", e))
+}
+
+/// Find an alias by name (case-insensitive)
+pub fn find(name: &str) -> Option<Alias> {
+    load()
+        .into_iter()
+        .find(|a| a.name.eq_ignore_ascii_case(name))
+}
+
+/// Check that an alias can be saved (its name is one word, it has a target)
+pub fn validate(alias: &Alias) -> Result<(), String> {
+    let name = alias.name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err("Alias names must be a single word".to_string());
+    }
+    if alias.target.trim().is_empty() {
+        return Err("Alias target cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Add an alias, replacing any existing alias with the same name
+pub fn save(alias: Alias) -> Result<(), String> {
+    validate(&alias)?;
+    let name = alias.name.trim();
+
+    let _lock = shared_file::lock(&get_aliases_path())?;
+    let mut aliases = load();
+    // Options it no longer sets on the program it ran are removed
+    if let Some(old) = aliases.iter().find(|a| a.name.eq_ignore_ascii_case(name)) {
+        if alias.compat.is_none() || old.target.trim() != alias.target.trim() {
+            clear_compat(old);
+        }
+    }
+    aliases.retain(|a| !a.name.eq_ignore_ascii_case(name));
+    aliases.push(Alias {
+        name: name.to_string(),
+        ..alias
+    });
+    aliases.sort_by_key(|a| a.name.to_lowercase());
+
+    save_all(&aliases)
+}
+
+/// Replace all aliases (e.g., from an imported backup)
+///
+/// Nothing is written unless every alias is valid. Later duplicates of a
+/// name replace earlier ones, as if they had been saved one by one.
+pub fn replace_all(new_aliases: Vec<Alias>) -> Result<(), String> {
+    let mut aliases: Vec<Alias> = Vec::new();
+    for alias in new_aliases {
+        validate(&alias).map_err(|e| format!("Alias '{}': {}", alias.name, e))?;
+        let name = alias.name.trim().to_string();
+        aliases.retain(|a| !a.name.eq_ignore_ascii_case(&name));
+        aliases.push(Alias { name, ..alias });
+    }
+    aliases.sort_by_key(|a| a.name.to_lowercase());
+
+    let _lock = shared_file::lock(&get_aliases_path())?;
+    save_all(&aliases)
+}
+
+/// Remove an alias by name
+pub fn remove(name: &str) -> Result<(), String> {
+    let _lock = shared_file::lock(&get_aliases_path())?;
+    let mut aliases = load();
+    let Some(removed) = aliases.iter().find(|a| a.name.eq_ignore_ascii_case(name)) else {
+        return Err(format!("No alias named '{}'", name));
+    };
+    clear_compat(removed);
+    aliases.retain(|a| !a.name.eq_ignore_ascii_case(name));
+
+    save_all(&aliases)
+}
+
+/// Remove the compatibility options `alias` set (see runner::clear_compat);
+/// failing to is only logged, as the alias itself still changes
+fn clear_compat(alias: &Alias) {
+    if let Err(e) = crate::runner::clear_compat(alias) {
+        eprintln!("[Aliases] Failed to clear the compatibility options of '{}': {}", alias.name, e);
+    }
+}
@@ -0,0 +1,207 @@
+// backup.rs - Settings export and import
+//
+// Moving to a new machine means carrying over settings.json, aliases.json
+// and history.json. export() bundles all three into one file:
+//
+//   {
+//     "format": "quickrun-backup",
+//     "version": 1,
+//     "quickrun_version": "0.2.0",
+//     "settings": { "hotkey": "Alt+Space", ... },
+//     "aliases": [ { "name": "np", "target": "notepad++", "args": [] } ],
+//     "history": [ { "command": "code", "count": 12, "last_used": 1760000000 } ]
+//   }
+//
+// Importing checks the whole file before anything is written: the format
+// and version must be known, settings must have the right types (unknown
+// keys are kept, and older layouts are upgraded, as in settings.json), and
+// every alias must be valid. A section that is left out is left alone, so
+// a trimmed-down backup can restore just the aliases.
+
+use crate::aliases::{self, Alias};
+use crate::history::{self, HistoryEntry};
+use crate::settings::{self, Settings};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Marks a file as a QuickRun backup
+const FORMAT: &str = "quickrun-backup";
+
+/// Version of the backup layout (bump when it changes incompatibly)
+pub const VERSION: u32 = 1;
+
+/// Contents of a backup file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    /// The QuickRun that wrote the backup (informational)
+    #[serde(default)]
+    pub quickrun_version: String,
+    #[serde(default)]
+    pub settings: Option<Settings>,
+    #[serde(default)]
+    pub aliases: Option<Vec<Alias>>,
+    #[serde(default)]
+    pub history: Option<Vec<HistoryEntry>>,
+}
+
+/// What an import restored (None = the section wasn't in the backup)
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub settings: bool,
+    pub aliases: Option<usize>,
+    pub history: Option<usize>,
+}
+
+/// Bundle the current settings, aliases and history
+pub fn collect() -> Backup {
+    Backup {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        quickrun_version: env!("CARGO_PKG_VERSION").to_string(),
+        settings: Some(settings::load()),
+        aliases: Some(aliases::load()),
+        history: Some(history::load()),
+    }
+}
+
+/// Write a backup of the current settings, aliases and history to `path`
+pub fn export(path: &Path) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&collect())
+        .map_err(|e| format!("Failed to serialize backup: {}", e))?;
+
+    std::fs::write(path, json).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
+
+/// Read and check a backup without applying it
+pub fn read(path: &Path) -> Result<Backup, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    parse(&contents)
+}
+
+/// Parse a backup, rejecting anything that import would trip over
+fn parse(contents: &str) -> Result<Backup, String> {
+    let mut value: Value = serde_json::from_str(contents)
+        .map_err(|e| format!("Not a QuickRun backup (invalid JSON: {})", e))?;
+
+    // Check the header first, so an unrelated file gets a clear message
+    if value.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        return Err("Not a QuickRun backup".to_string());
+    }
+    match value.get("version").and_then(Value::as_u64) {
+        Some(version) if version > VERSION as u64 => {
+            return Err(format!(
+                "This backup is from a newer QuickRun (format version {}) - update QuickRun to import it",
+                version
+            ));
+        }
+        Some(version) if version >= 1 => {}
+        _ => return Err("Backup has no valid format version".to_string()),
+    }
+
+    // Settings from an older QuickRun are upgraded like settings.json
+    if let Some(Value::Object(keys)) = value.get_mut("settings") {
+        settings::migrate(keys);
+    }
+
+    let backup: Backup = serde_json::from_value(value).map_err(|e| format!("Invalid backup: {}", e))?;
+    for alias in backup.aliases.iter().flatten() {
+        aliases::validate(alias).map_err(|e| format!("Alias '{}': {}", alias.name, e))?;
+    }
+    Ok(backup)
+}
+
+/// Replace the current settings, aliases and history with those in `backup`
+pub fn apply(backup: Backup) -> Result<ImportSummary, String> {
+    let summary = ImportSummary {
+        settings: backup.settings.is_some(),
+        aliases: backup.aliases.as_ref().map(Vec::len),
+        history: backup.history.as_ref().map(Vec::len),
+    };
+
+    if let Some(settings) = backup.settings {
+        settings::save(&settings)?;
+    }
+    if let Some(aliases) = backup.aliases {
+        aliases::replace_all(aliases)?;
+    }
+    if let Some(history) = backup.history {
+        history::replace_all(history)?;
+    }
+
+    eprintln!(
+        "[Backup] Imported settings: {}, aliases: {:?}, history: {:?}",
+        summary.settings, summary.aliases, summary.history
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn backup_json(extra: Value) -> String {
+        let mut backup = json!({ "format": FORMAT, "version": VERSION });
+        if let (Value::Object(backup), Value::Object(extra)) = (&mut backup, extra) {
+            backup.extend(extra);
+        }
+        backup.to_string()
+    }
+
+    #[test]
+    fn an_exported_backup_parses_back() {
+        let original = Backup {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            quickrun_version: "0.2.0".to_string(),
+            settings: Some(Settings { watch_downloads: true, ..Settings::default() }),
+            aliases: Some(vec![Alias {
+                name: "np".to_string(),
+                target: "notepad++".to_string(),
+                args: vec!["-multiInst".to_string()],
+                compat: None,
+                env: None,
+            }]),
+            history: Some(Vec::new()),
+        };
+
+        let parsed = parse(&serde_json::to_string(&original).unwrap()).unwrap();
+        assert_eq!(parsed.settings, original.settings);
+        assert_eq!(parsed.aliases.unwrap()[0].args, ["-multiInst"]);
+        assert_eq!(parsed.history.unwrap().len(), 0);
+    }
+
+    #[test]
+    fn missing_sections_are_left_alone() {
+        let parsed = parse(&backup_json(json!({ "aliases": [] }))).unwrap();
+        assert!(parsed.settings.is_none());
+        assert!(parsed.history.is_none());
+        assert_eq!(parsed.aliases.unwrap().len(), 0);
+    }
+
+    #[test]
+    fn other_files_and_newer_versions_are_rejected() {
+        assert!(parse("not json").is_err());
+        assert!(parse(r#"{ "hotkey": "Alt+Space" }"#).is_err());
+        let newer = json!({ "format": FORMAT, "version": VERSION + 1 }).to_string();
+        assert!(parse(&newer).unwrap_err().contains("newer QuickRun"));
+        assert!(parse(&json!({ "format": FORMAT }).to_string()).is_err());
+    }
+
+    #[test]
+    fn bad_settings_and_aliases_are_rejected() {
+        assert!(parse(&backup_json(json!({ "settings": { "watch_downloads": "yes" } }))).is_err());
+        let alias = json!({ "aliases": [ { "name": "two words", "target": "notepad" } ] });
+        assert!(parse(&backup_json(alias)).unwrap_err().contains("two words"));
+    }
+
+    #[test]
+    fn unknown_settings_are_kept() {
+        let parsed = parse(&backup_json(json!({ "settings": { "future_key": 1 } }))).unwrap();
+        assert_eq!(parsed.settings.unwrap().other.get("future_key"), Some(&json!(1)));
+    }
+}
@@ -0,0 +1,78 @@
+// compat.rs - Windows compatibility-mode options for legacy programs
+//
+// The same settings as the Compatibility tab in a program's Properties
+// dialog. Windows reads them from a per-user registry value named after the
+// executable's full path:
+//
+//   HKCU\Software\Microsoft\Windows NT\CurrentVersion\AppCompatFlags\Layers
+//   "C:\Games\old.exe" = "~ 640X480 DISABLEDXMAXIMIZEDWINDOWEDMODE WIN8RTM"
+//
+// QuickRun writes the value just before spawning, so the alias definition
+// is the single source of truth.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Compatibility options for an alias (all off by default)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompatSettings {
+    /// Run in 640 x 480 screen resolution
+    pub run_640x480: bool,
+    /// Disable fullscreen optimizations
+    pub disable_fullscreen_optimizations: bool,
+    /// Run in compatibility mode for Windows 8
+    pub windows8: bool,
+}
+
+impl CompatSettings {
+    /// Build the layer string, e.g. "~ 640X480 WIN8RTM" (None if nothing is enabled)
+    fn layers(&self) -> Option<String> {
+        let mut layers = Vec::new();
+        if self.run_640x480 {
+            layers.push("640X480");
+        }
+        if self.disable_fullscreen_optimizations {
+            layers.push("DISABLEDXMAXIMIZEDWINDOWEDMODE");
+        }
+        if self.windows8 {
+            layers.push("WIN8RTM");
+        }
+
+        // "~" marks the layers as user-applied (no elevation needed)
+        (!layers.is_empty()).then(|| format!("~ {}", layers.join(" ")))
+    }
+}
+
+/// Write (or clear) the compatibility layers for `exe` before launching it
+#[cfg(windows)]
+pub fn apply(exe: &Path, settings: &CompatSettings) -> Result<(), String> {
+    use winreg::enums::{HKEY_CURRENT_USER, KEY_WRITE};
+    use winreg::RegKey;
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (layers_key, _) = hkcu
+        .create_subkey_with_flags(
+            "Software\\Microsoft\\Windows NT\\CurrentVersion\\AppCompatFlags\\Layers",
+            KEY_WRITE,
+        )
+        .map_err(|e| format!("Failed to open compatibility settings: {}", e))?;
+
+    let exe = exe.to_string_lossy().to_string();
+
+    match settings.layers() {
+        Some(layers) => layers_key
+            .set_value(&exe, &layers)
+            .map_err(|e| format!("Failed to set compatibility mode: {}", e)),
+        // Nothing enabled: remove our value (a missing value is fine)
+        None => {
+            let _ = layers_key.delete_value(&exe);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn apply(_exe: &Path, _settings: &CompatSettings) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// completion.rs - Inline (ghost-text) completion
+//
+// Given the partial input, find the single best completion so the frontend
+// can show the rest of the command greyed out after the cursor.
+//
+// Paths ("C:\Prog") complete against the filesystem instead (see
+// path_completion.rs). Otherwise sources, in priority order:
+// 1. History - commands the user has launched before (most recent first)
+// 2. App index - Start Menu application names
+// 3. PATH - executables reachable by name
+//
+// Matching is a case-insensitive prefix match. The returned string keeps the
+// user's typed characters as-is and appends the candidate's remaining
+// characters, so accepting a completion never changes what was already typed.
+
+use crate::{history, index, path_completion, runner};
+
+/// Return the rest of `candidate` after a case-insensitive `prefix`.
+///
+/// Returns None when `candidate` doesn't start with `prefix`, or when it is an
+/// exact match (nothing left to complete).
+fn completion_suffix<'a>(candidate: &'a str, prefix: &str) -> Option<&'a str> {
+    let mut rest = candidate.char_indices();
+
+    for p in prefix.chars() {
+        let (_, c) = rest.next()?;
+        if !c.to_lowercase().eq(p.to_lowercase()) {
+            return None;
+        }
+    }
+
+    let (idx, _) = rest.next()?;
+    Some(&candidate[idx..])
+}
+
+/// Find the first candidate that completes `input`
+fn first_completion<I, S>(input: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    candidates.into_iter().find_map(|candidate| {
+        completion_suffix(candidate.as_ref(), input).map(|suffix| format!("{}{}", input, suffix))
+    })
+}
+
+/// Get the best single completion for `input`
+///
+/// Returns the full completed text, or None if the input is empty or nothing
+/// matches. Sources are checked lazily so PATH is only enumerated when
+/// history and the app index have no match.
+pub fn complete(input: &str) -> Option<String> {
+    if input.trim().is_empty() {
+        return None;
+    }
+
+    if runner::is_explicit_path(input) {
+        return path_completion::complete_path(input).into_iter().next();
+    }
+
+    first_completion(input, history::load().iter().map(|e| &e.command))
+        .or_else(|| first_completion(input, index::entries().iter().map(|e| &e.name)))
+        .or_else(|| first_completion(input, index::path_commands()))
+}
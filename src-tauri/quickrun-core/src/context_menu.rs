@@ -0,0 +1,94 @@
+// context_menu.rs - "Run with QuickRun" in Explorer's context menu
+//
+// When switched on in Settings, right-clicking a file or folder in Explorer
+// offers "Run with QuickRun", which opens the launcher with its path typed
+// in (quoted if it has spaces) and the cursor after it, ready for arguments
+// or for running it as administrator.
+//
+// Explorer reads the entry from the classes under HKEY_CURRENT_USER, so no
+// admin rights are needed:
+//
+//   Software\Classes\*\shell\QuickRun            (Default) = label, Icon = exe,0
+//   Software\Classes\*\shell\QuickRun\command    (Default) = "exe" --input-path "%1"
+//
+// and the same under Directory\shell for folders. Switching it off deletes
+// both keys. The label is written in the language in use when it's switched
+// on. Like startup.rs, this goes through os::Registry so it's unit-tested
+// with an in-memory registry.
+
+use crate::launch_args::INPUT_PATH_FLAG;
+use crate::os::Registry;
+use std::path::Path;
+
+/// Entry keys for files and for folders (under HKEY_CURRENT_USER)
+const KEYS: [&str; 2] = [
+    "Software\\Classes\\*\\shell\\QuickRun",
+    "Software\\Classes\\Directory\\shell\\QuickRun",
+];
+
+/// Is the context menu entry registered?
+pub fn is_enabled(registry: &dyn Registry) -> Result<bool, String> {
+    Ok(registry.get_string(&command_key(KEYS[0]), "")?.is_some())
+}
+
+/// Register (or remove) the entry, running `exe_path` and labeled `label`
+///
+/// Disabling when it isn't registered is not an error.
+pub fn set_enabled(registry: &dyn Registry, enabled: bool, exe_path: &Path, label: &str) -> Result<(), String> {
+    let exe = exe_path.to_string_lossy();
+    for key in KEYS {
+        if enabled {
+            registry.set_string(key, "", label)?;
+            registry.set_string(key, "Icon", &format!("{},0", exe))?;
+            registry.set_string(&command_key(key), "", &format!("\"{}\" {} \"%1\"", exe, INPUT_PATH_FLAG))?;
+        } else {
+            registry.delete_key(key)?;
+        }
+    }
+    Ok(())
+}
+
+fn command_key(key: &str) -> String {
+    format!("{}\\command", key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::MemoryRegistry;
+
+    const EXE: &str = "C:\\Program Files\\QuickRun\\quickrun.exe";
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(!is_enabled(&MemoryRegistry::new()).unwrap());
+    }
+
+    #[test]
+    fn enabling_registers_files_and_folders() {
+        let registry = MemoryRegistry::new();
+        set_enabled(&registry, true, Path::new(EXE), "Run with QuickRun").unwrap();
+
+        assert!(is_enabled(&registry).unwrap());
+        for key in KEYS {
+            assert_eq!(registry.get_string(key, "").unwrap().as_deref(), Some("Run with QuickRun"));
+            assert_eq!(
+                registry.get_string(&command_key(key), "").unwrap().as_deref(),
+                Some("\"C:\\Program Files\\QuickRun\\quickrun.exe\" --input-path \"%1\"")
+            );
+        }
+    }
+
+    #[test]
+    fn disabling_removes_the_keys() {
+        let registry = MemoryRegistry::new();
+        set_enabled(&registry, true, Path::new(EXE), "Run with QuickRun").unwrap();
+        registry.set_string("Software\\Classes\\*\\shell\\Other", "", "Other").unwrap();
+        set_enabled(&registry, false, Path::new(EXE), "").unwrap();
+
+        assert!(!is_enabled(&registry).unwrap());
+        assert_eq!(registry.get_string(KEYS[1], "Icon").unwrap(), None);
+        assert!(registry.get_string("Software\\Classes\\*\\shell\\Other", "").unwrap().is_some());
+        assert!(set_enabled(&registry, false, Path::new(EXE), "").is_ok());
+    }
+}
@@ -0,0 +1,206 @@
+// env_presets.rs - Named environments to launch programs in
+//
+// A preset is a named set of environment variables plus folders to put in
+// front of PATH, so "run Maven with JDK 17" or "use this venv's Python"
+// doesn't need a wrapper .bat file. Presets live in settings.json
+// ("env_presets"):
+//
+//   "env_presets": [
+//     {
+//       "name": "JDK 17",
+//       "vars": { "JAVA_HOME": "C:\\Java\\jdk-17" },
+//       "path": ["%JAVA_HOME%\\bin"]
+//     }
+//   ]
+//
+// and are used by name:
+// - An alias's "env" (see aliases.rs)
+// - "terminal_env_preset", for the ">" prefix
+//
+// %NAME% in a value is replaced with the variable's value, looking at the
+// preset's own variables first and then QuickRun's environment; a leading ~
+// in a path entry is the home folder. Variables not mentioned are inherited
+// as usual.
+
+use crate::runner;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Separates the folders in PATH
+const PATH_SEPARATOR: &str = if cfg!(windows) { ";" } else { ":" };
+
+/// One preset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvPreset {
+    /// What aliases and settings refer to it by (matched case-insensitively)
+    pub name: String,
+    /// Variables to set
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+    /// Folders put in front of PATH, first one first
+    #[serde(default)]
+    pub path: Vec<String>,
+}
+
+/// Variables to set on a process, in order
+pub type Environment = Vec<(String, String)>;
+
+/// Check that `presets` can be saved: named, no duplicate names, and
+/// variable names Windows accepts
+pub fn validate(presets: &[EnvPreset]) -> Result<(), String> {
+    for (i, preset) in presets.iter().enumerate() {
+        let name = preset.name.trim();
+        if name.is_empty() {
+            return Err("Environment presets need a name".to_string());
+        }
+        if presets[..i].iter().any(|other| other.name.trim().eq_ignore_ascii_case(name)) {
+            return Err(format!("There are two environment presets named '{}'", name));
+        }
+        if let Some(var) = preset.vars.keys().find(|var| var.is_empty() || var.contains('=')) {
+            return Err(format!("'{}' in preset '{}' is not a valid variable name", var, name));
+        }
+    }
+    Ok(())
+}
+
+/// Find a preset by name (case-insensitive)
+pub fn find<'a>(presets: &'a [EnvPreset], name: &str) -> Option<&'a EnvPreset> {
+    presets.iter().find(|preset| preset.name.trim().eq_ignore_ascii_case(name.trim()))
+}
+
+/// The variables `preset` sets, with %NAME% references expanded
+///
+/// `inherited` looks up a variable in the environment the process would
+/// otherwise get.
+pub fn environment_in(preset: &EnvPreset, inherited: &dyn Fn(&str) -> Option<String>) -> Environment {
+    let lookup = |var: &str| {
+        preset
+            .vars
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(var))
+            .map(|(_, value)| expand_vars(value, inherited))
+            .or_else(|| inherited(var))
+    };
+
+    let mut environment: Environment = preset
+        .vars
+        .iter()
+        .filter(|(name, _)| !name.eq_ignore_ascii_case("PATH") || preset.path.is_empty())
+        .map(|(name, value)| (name.clone(), expand_vars(value, inherited)))
+        .collect();
+
+    if !preset.path.is_empty() {
+        let mut folders: Vec<String> = preset
+            .path
+            .iter()
+            .map(|dir| runner::expand_home(&expand_vars(dir.trim(), &lookup)).to_string_lossy().into_owned())
+            .collect();
+        folders.extend(lookup("PATH").filter(|path| !path.is_empty()));
+        environment.push(("PATH".to_string(), folders.join(PATH_SEPARATOR)));
+    }
+    environment
+}
+
+/// The variables the preset called `name` sets ("" = none)
+pub fn environment(name: &str) -> Result<Environment, String> {
+    if name.trim().is_empty() {
+        return Ok(Environment::new());
+    }
+    let presets = crate::settings::load().env_presets;
+    let preset =
+        find(&presets, name).ok_or_else(|| format!("There is no environment preset named '{}'", name.trim()))?;
+    Ok(environment_in(preset, &|var| std::env::var(var).ok()))
+}
+
+/// Replace %NAME% with the value `lookup` gives (unknown names and a lone
+/// % are left as they are, like cmd does)
+fn expand_vars(value: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(end) if end > 0 => {
+                let var = &after[..end];
+                match lookup(var) {
+                    Some(value) => expanded.push_str(&value),
+                    None => {
+                        expanded.push('%');
+                        expanded.push_str(var);
+                        expanded.push('%');
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                expanded.push('%');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(vars: &[(&str, &str)], path: &[&str]) -> EnvPreset {
+        EnvPreset {
+            name: "JDK 17".to_string(),
+            vars: vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            path: path.iter().map(|dir| dir.to_string()).collect(),
+        }
+    }
+
+    /// QuickRun's own environment in the tests
+    fn inherited(var: &str) -> Option<String> {
+        match var.to_ascii_uppercase().as_str() {
+            "PATH" => Some("C:\\Windows".to_string()),
+            "USERPROFILE" => Some("C:\\Users\\me".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn references_are_expanded() {
+        let lookup = |var: &str| (var == "A").then(|| "1".to_string());
+        assert_eq!(expand_vars("%A%\\bin", &lookup), "1\\bin");
+        assert_eq!(expand_vars("%B%;%A%", &lookup), "%B%;1");
+        assert_eq!(expand_vars("100% sure", &lookup), "100% sure");
+        assert_eq!(expand_vars("%%", &lookup), "%%");
+    }
+
+    #[test]
+    fn path_folders_go_first_and_may_use_the_presets_variables() {
+        let preset = preset(&[("JAVA_HOME", "C:\\Java\\jdk-17")], &["%JAVA_HOME%\\bin"]);
+        let environment = environment_in(&preset, &inherited);
+
+        assert_eq!(environment[0], ("JAVA_HOME".to_string(), "C:\\Java\\jdk-17".to_string()));
+        let path = format!("C:\\Java\\jdk-17\\bin{}C:\\Windows", PATH_SEPARATOR);
+        assert_eq!(environment[1], ("PATH".to_string(), path));
+    }
+
+    #[test]
+    fn variables_can_refer_to_the_inherited_environment() {
+        let environment = environment_in(&preset(&[("VENV", "%USERPROFILE%\\venv")], &[]), &inherited);
+        assert_eq!(environment, [("VENV".to_string(), "C:\\Users\\me\\venv".to_string())]);
+    }
+
+    #[test]
+    fn presets_are_found_by_name_ignoring_case() {
+        let presets = [preset(&[], &[])];
+        assert!(find(&presets, "jdk 17").is_some());
+        assert!(find(&presets, "JDK 21").is_none());
+    }
+
+    #[test]
+    fn names_must_be_present_and_unique() {
+        assert!(validate(&[preset(&[("JAVA_HOME", "x")], &[])]).is_ok());
+        assert!(validate(&[preset(&[], &[]), preset(&[], &[])]).is_err());
+        assert!(validate(&[EnvPreset { name: " ".to_string(), ..preset(&[], &[]) }]).is_err());
+        assert!(validate(&[preset(&[("A=B", "x")], &[])]).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+// file_actions.rs - Secondary actions for file results
+//
+// Rows that point at a file on disk get a menu of extra actions (Shift+Enter
+// or right-click in the launcher). Which actions are offered depends on the
+// file type - e.g., only executables can be run elevated or in the sandbox.
+
+use crate::results::{Action, RowAction};
+use std::path::Path;
+
+/// Extensions that can be run directly (elevated or in the sandbox)
+const EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "msi", "bat", "cmd", "ps1", "com"];
+
+/// Is `path` something we can execute?
+pub fn is_executable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| EXECUTABLE_EXTENSIONS.iter().any(|x| e.eq_ignore_ascii_case(x)))
+        .unwrap_or(false)
+}
+
+/// Is `path` a zip file (by extension)?
+pub fn is_zip(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.eq_ignore_ascii_case("zip"))
+        .unwrap_or(false)
+}
+
+/// Build the secondary actions for a file result
+pub fn actions_for(path: &Path) -> Vec<RowAction> {
+    let target = path.to_string_lossy().to_string();
+    let mut actions = Vec::new();
+
+    let is_shortcut = path
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("lnk"))
+        .unwrap_or(false);
+
+    // Shortcuts can be elevated (the shell resolves the target), but only a
+    // real executable can be copied into the sandbox
+    if is_executable(path) || is_shortcut {
+        actions.push(RowAction::new("Run as administrator", Action::RunAsAdmin(target.clone())));
+    }
+    if is_executable(path) {
+        actions.push(RowAction::new("Run in Windows Sandbox", Action::Sandbox(target.clone())));
+    }
+
+    actions.push(RowAction::new("Open file location", Action::Reveal(target.clone())));
+    actions.push(RowAction::new("Copy path", Action::Copy(target.clone())));
+    if is_zip(path) {
+        actions.push(RowAction::new("Extract here", Action::Extract(target.clone())));
+    } else {
+        actions.push(RowAction::new("Compress to zip here", Action::Compress(target.clone())));
+    }
+    actions.push(RowAction::new("Move to Recycle Bin", Action::Recycle(target)));
+
+    actions
+}
+
+/// Build the secondary actions for a folder result
+pub fn folder_actions_for(path: &Path) -> Vec<RowAction> {
+    let target = path.to_string_lossy().to_string();
+    vec![
+        RowAction::new("Open file location", Action::Reveal(target.clone())),
+        RowAction::new("Copy path", Action::Copy(target.clone())),
+        RowAction::new("Compress to zip here", Action::Compress(target.clone())),
+        RowAction::new("Move to Recycle Bin", Action::Recycle(target)),
+    ]
+}
+
+/// Show `path` in Explorer with the file selected
+pub fn reveal(path: &str) -> Result<(), String> {
+    std::process::Command::new("explorer")
+        .arg(format!("/select,{}", path))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open file location: {}", e))
+}
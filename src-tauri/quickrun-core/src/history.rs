@@ -0,0 +1,358 @@
+// history.rs - Launch history persistence
+//
+// Every command that launches successfully is recorded in history.json in the
+// config directory, together with how often and how recently it was used.
+// The history feeds inline completion: commands the user actually runs are
+// the best guess for what they're typing now.
+//
+// Completion and suggestions read the history on every keystroke, so it is
+// kept in memory after the first read. Launches are applied to the memory
+// copy right away and written to disk:
+// - Straight away by default (e.g., the qr CLI, which exits right after)
+// - In the background once start_writer() has been called (the app), a
+//   moment after the last launch; flush() writes anything still pending
+//   (the app calls it on exit)
+// Writes re-read the file and replay the launches since the last write,
+// holding the file's lock throughout (see shared_file.rs), so launches
+// recorded by another process (e.g., qr) in between aren't lost.
+//
+// With "remember_last_input" on, the launcher's last input (launched or
+// not) is also kept, in last_input.txt, so reopening the launcher can
+// offer it again - handy for fixing a typo.
+
+use crate::retention::{self, Limits};
+use crate::shared_file;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Maximum number of distinct commands kept in history
+const MAX_ENTRIES: usize = 500;
+
+/// A single remembered command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The command text exactly as the user typed it (trimmed)
+    pub command: String,
+    /// How many times the command has been launched
+    pub count: u32,
+    /// Unix timestamp (seconds) of the most recent launch
+    pub last_used: u64,
+}
+
+impl HistoryEntry {
+    /// How often and how recently the command was used, as one number
+    ///
+    /// Each launch counts for more the more recent the last one was: 100
+    /// within 4 days, 70 within 2 weeks, 50 within a month, 30 within 3
+    /// months, 10 after that.
+    pub fn frecency(&self, now: u64) -> u32 {
+        const DAY: u64 = 24 * 60 * 60;
+        let weight = match now.saturating_sub(self.last_used) / DAY {
+            0..=3 => 100,
+            4..=13 => 70,
+            14..=30 => 50,
+            31..=89 => 30,
+            _ => 10,
+        };
+        self.count.saturating_mul(weight)
+    }
+}
+
+/// Get the path to the history file (next to settings.json)
+fn get_history_path() -> PathBuf {
+    crate::get_config_dir().join("history.json")
+}
+
+/// Get the path to the last input file (next to settings.json)
+fn get_last_input_path() -> PathBuf {
+    crate::get_config_dir().join("last_input.txt")
+}
+
+/// Current time as Unix seconds
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The history as last read or written by this process (None = not read yet)
+static CACHE: RwLock<Option<Vec<HistoryEntry>>> = RwLock::new(None);
+
+/// Launches (command, time) recorded in memory but not yet written
+static PENDING: Mutex<Vec<(String, u64)>> = Mutex::new(Vec::new());
+
+/// Serializes writes to history.json within this process (shared_file::lock
+/// does the same across processes)
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Wakes the background writer (set by start_writer)
+static WRITER: Mutex<Option<mpsc::Sender<()>>> = Mutex::new(None);
+
+/// Whether start_writer() has been called
+static WRITE_BEHIND: AtomicBool = AtomicBool::new(false);
+
+/// The launcher's last input as last read or written (None = not read yet)
+static LAST_INPUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// The background writer waits this long after a launch, so a burst of
+/// launches is written once
+const WRITE_DELAY: Duration = Duration::from_secs(2);
+
+/// Read history.json, most recently used first (empty if missing or invalid)
+fn read_file() -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = std::fs::read_to_string(get_history_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+
+    entries.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    entries
+}
+
+/// Load the history, most recently used first
+///
+/// Returns an empty list if the file doesn't exist or can't be parsed
+pub fn load() -> Vec<HistoryEntry> {
+    if let Some(entries) = CACHE.read().ok().and_then(|cache| cache.clone()) {
+        return entries;
+    }
+    let entries = read_file();
+    if let Ok(mut cache) = CACHE.write() {
+        cache.get_or_insert_with(|| entries.clone());
+    }
+    entries
+}
+
+/// Write the history back to disk
+fn save(entries: &[HistoryEntry]) -> Result<(), String> {
+    // Nowhere to write: the memory copy is all there is (see storage.rs)
+    if crate::storage::is_in_memory() {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+
+    shared_file::replace(&get_history_path(), json.as_bytes())
+        .map_err(|e| format!("Failed to save history: {}", e))
+}
+
+/// Count a launch of `command` at `time` in `entries`
+///
+/// Commands are matched case-insensitively so "Notepad" and "notepad" share
+/// one entry; the most recent spelling wins.
+fn apply_launch(entries: &mut Vec<HistoryEntry>, command: &str, time: u64) {
+    if let Some(entry) = entries
+        .iter_mut()
+        .find(|e| e.command.eq_ignore_ascii_case(command))
+    {
+        entry.command = command.to_string();
+        entry.count += 1;
+        entry.last_used = entry.last_used.max(time);
+    } else {
+        entries.push(HistoryEntry {
+            command: command.to_string(),
+            count: 1,
+            last_used: time,
+        });
+    }
+
+    entries.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    entries.truncate(MAX_ENTRIES);
+}
+
+/// Replace the whole history (e.g., from an imported backup)
+///
+/// Blank commands are dropped, and only the MAX_ENTRIES most recent are kept.
+/// Launches not yet written are dropped too.
+pub fn replace_all(mut entries: Vec<HistoryEntry>) -> Result<(), String> {
+    entries.retain(|e| !e.command.trim().is_empty());
+    entries.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    entries.truncate(MAX_ENTRIES);
+
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let _lock = shared_file::lock(&get_history_path())?;
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    save(&entries)?;
+    if let Ok(mut cache) = CACHE.write() {
+        *cache = Some(entries);
+    }
+    Ok(())
+}
+
+/// Drop entries beyond `limits` (see retention.rs), returning how many
+pub fn prune(limits: &Limits) -> Result<usize, String> {
+    // Launches still waiting are written first, so they're pruned too
+    flush()?;
+
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let _lock = shared_file::lock(&get_history_path())?;
+    let mut entries = if crate::storage::is_in_memory() { load() } else { read_file() };
+    let pruned = retention::trim(&mut entries, limits, |e| e.last_used, now());
+    if pruned > 0 {
+        save(&entries)?;
+        if let Ok(mut cache) = CACHE.write() {
+            *cache = Some(entries);
+        }
+    }
+    Ok(pruned)
+}
+
+/// Record a successful launch of `command`
+///
+/// It counts towards completion immediately; see the top of this file for
+/// when it reaches the disk.
+pub fn record(command: &str) -> Result<(), String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Ok(());
+    }
+
+    let time = now();
+    let mut entries = load();
+    apply_launch(&mut entries, command, time);
+    if let Ok(mut cache) = CACHE.write() {
+        *cache = Some(entries);
+    }
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).push((command.to_string(), time));
+
+    if WRITE_BEHIND.load(Ordering::SeqCst) {
+        if let Some(writer) = WRITER.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            let _ = writer.send(());
+        }
+        Ok(())
+    } else {
+        flush()
+    }
+}
+
+/// Write any launches that haven't reached the disk yet
+pub fn flush() -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let pending = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+    // Without a writable folder the memory copy already has them
+    if pending.is_empty() || crate::storage::is_in_memory() {
+        return Ok(());
+    }
+
+    // Replay onto the file rather than overwrite it, so launches recorded
+    // by another process since our last read are kept; the lock keeps that
+    // process from writing in between
+    let _lock = match shared_file::lock(&get_history_path()) {
+        Ok(lock) => lock,
+        Err(e) => {
+            PENDING.lock().unwrap_or_else(|e| e.into_inner()).splice(0..0, pending);
+            return Err(e);
+        }
+    };
+    let mut entries = read_file();
+    for (command, time) in &pending {
+        apply_launch(&mut entries, command, *time);
+    }
+    if let Err(e) = save(&entries) {
+        // Keep them for the next attempt
+        PENDING.lock().unwrap_or_else(|e| e.into_inner()).splice(0..0, pending);
+        return Err(e);
+    }
+    if let Ok(mut cache) = CACHE.write() {
+        *cache = Some(entries);
+    }
+    Ok(())
+}
+
+/// Write launches in the background from now on (call once at startup)
+pub fn start_writer() {
+    let (sender, launches) = mpsc::channel::<()>();
+    *WRITER.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    WRITE_BEHIND.store(true, Ordering::SeqCst);
+
+    std::thread::spawn(move || {
+        while launches.recv().is_ok() {
+            // Let a burst of launches settle, then write them together
+            while launches.recv_timeout(WRITE_DELAY).is_ok() {}
+            if let Err(e) = flush() {
+                eprintln!("[History] {}", e);
+            }
+        }
+    });
+}
+
+/// The launcher's last input, if one is remembered
+pub fn last_input() -> Option<String> {
+    let mut last = LAST_INPUT.lock().unwrap_or_else(|e| e.into_inner());
+    let input = last.get_or_insert_with(|| {
+        std::fs::read_to_string(get_last_input_path())
+            .map(|contents| contents.trim().to_string())
+            .unwrap_or_default()
+    });
+    (!input.is_empty()).then(|| input.clone())
+}
+
+/// Remember `input` as the launcher's last input ("" forgets it)
+pub fn set_last_input(input: &str) -> Result<(), String> {
+    let input = input.trim();
+    let mut last = LAST_INPUT.lock().unwrap_or_else(|e| e.into_inner());
+    if last.as_deref() == Some(input) {
+        return Ok(());
+    }
+    *last = Some(input.to_string());
+    if crate::storage::is_in_memory() {
+        return Ok(());
+    }
+
+    let path = get_last_input_path();
+    if input.is_empty() {
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to forget the last input: {}", e))
+            }
+            _ => Ok(()),
+        }
+    } else {
+        shared_file::replace(&path, input.as_bytes()).map_err(|e| format!("Failed to save the last input: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launches_of_the_same_command_share_an_entry() {
+        let mut entries = Vec::new();
+        apply_launch(&mut entries, "notepad", 100);
+        apply_launch(&mut entries, "Notepad", 200);
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].command, "Notepad");
+        assert_eq!(entries[0].count, 2);
+        assert_eq!(entries[0].last_used, 200);
+    }
+
+    #[test]
+    fn replaying_an_older_launch_keeps_the_newer_time() {
+        let mut entries = Vec::new();
+        apply_launch(&mut entries, "code", 300);
+        apply_launch(&mut entries, "calc", 250);
+        apply_launch(&mut entries, "code", 200);
+
+        assert_eq!(entries[0].command, "code");
+        assert_eq!(entries[0].last_used, 300);
+        assert_eq!(entries[1].command, "calc");
+    }
+
+    #[test]
+    fn only_the_most_recent_entries_are_kept() {
+        let mut entries = Vec::new();
+        for i in 0..=MAX_ENTRIES as u64 {
+            apply_launch(&mut entries, &format!("cmd{}", i), i);
+        }
+
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert!(entries.iter().all(|e| e.command != "cmd0"));
+    }
+}
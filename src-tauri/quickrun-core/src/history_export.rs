@@ -0,0 +1,272 @@
+// history_export.rs - Launch history as a file of its own
+//
+// The backup (backup.rs) carries history along with everything else; this
+// exports just the history, with each command's frecency, for moving launch
+// habits to another PC or for looking at in a spreadsheet. The format
+// follows the file's extension:
+//
+//   .csv    command,count,last_used,frecency (one header row, then a row per
+//           command; last_used is Unix seconds)
+//   other   JSON, an array of {"command", "count", "last_used", "frecency"}
+//
+// Commands are listed by frecency, highest first. frecency is only for
+// reading: import ignores it and works it out again from count and
+// last_used.
+//
+// Import merges into the current history instead of replacing it. A command
+// in both (matched case-insensitively, like launches) keeps the higher count
+// and the later last_used, so importing the same file twice changes nothing.
+
+use crate::history::{self, HistoryEntry};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// CSV columns, in order
+const CSV_HEADER: [&str; 4] = ["command", "count", "last_used", "frecency"];
+
+/// A history entry as exported
+#[derive(Debug, Serialize, Deserialize)]
+struct Row {
+    command: String,
+    count: u32,
+    last_used: u64,
+    #[serde(default, skip_deserializing)]
+    frecency: u32,
+}
+
+/// Write the history to `path`, as CSV or JSON by its extension
+///
+/// Returns how many commands were written.
+pub fn export(path: &Path) -> Result<usize, String> {
+    // Launches not yet on disk count too
+    history::flush()?;
+    let mut rows: Vec<Row> = history::load()
+        .into_iter()
+        .map(|e| Row {
+            frecency: e.frecency(history::now()),
+            command: e.command,
+            count: e.count,
+            last_used: e.last_used,
+        })
+        .collect();
+    rows.sort_by(|a, b| b.frecency.cmp(&a.frecency).then(b.last_used.cmp(&a.last_used)));
+
+    let contents = if is_csv(path) {
+        to_csv(&rows)
+    } else {
+        serde_json::to_string_pretty(&rows).map_err(|e| format!("Failed to serialize history: {}", e))?
+    };
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+    Ok(rows.len())
+}
+
+/// Merge the history in `path` (CSV or JSON, by its extension) into the
+/// current one
+///
+/// The whole file is read before anything changes. Returns how many
+/// commands were in the file.
+pub fn import(path: &Path) -> Result<usize, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    let imported = if is_csv(path) { from_csv(&contents)? } else { from_json(&contents)? };
+    let count = imported.len();
+
+    history::flush()?;
+    let mut entries = history::load();
+    merge(&mut entries, imported);
+    history::replace_all(entries)?;
+
+    eprintln!("[History] Imported {} commands from {}", count, path.display());
+    Ok(count)
+}
+
+/// Whether `path` is a .csv file
+fn is_csv(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// Add `imported` to `entries` (see the top of this file)
+fn merge(entries: &mut Vec<HistoryEntry>, imported: Vec<HistoryEntry>) {
+    for entry in imported {
+        match entries.iter_mut().find(|e| e.command.eq_ignore_ascii_case(&entry.command)) {
+            Some(existing) => {
+                existing.count = existing.count.max(entry.count);
+                existing.last_used = existing.last_used.max(entry.last_used);
+            }
+            None => entries.push(entry),
+        }
+    }
+}
+
+/// Check an imported entry
+fn checked(command: String, count: u32, last_used: u64) -> Result<HistoryEntry, String> {
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return Err("Command is empty".to_string());
+    }
+    Ok(HistoryEntry { command, count: count.max(1), last_used })
+}
+
+fn from_json(contents: &str) -> Result<Vec<HistoryEntry>, String> {
+    let rows: Vec<Row> = serde_json::from_str(contents)
+        .map_err(|e| format!("Not a QuickRun history export (invalid JSON: {})", e))?;
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| checked(row.command, row.count, row.last_used).map_err(|e| format!("Entry {}: {}", i + 1, e)))
+        .collect()
+}
+
+fn to_csv(rows: &[Row]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+    for row in rows {
+        csv.push_str(&format!("{},{},{},{}\r\n", csv_field(&row.command), row.count, row.last_used, row.frecency));
+    }
+    csv
+}
+
+/// Quote `value` if it needs it (commas, quotes, line breaks)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Read entries from CSV with a header row
+///
+/// Columns are found by name, so they may come in any order and extra ones
+/// (e.g., added in a spreadsheet) are ignored.
+fn from_csv(contents: &str) -> Result<Vec<HistoryEntry>, String> {
+    let mut records = parse_csv(contents.trim_start_matches('\u{feff}'))?.into_iter();
+    let header = records.next().ok_or("The file is empty")?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Not a QuickRun history export (no '{}' column)", name))
+    };
+    let (command, count, last_used) = (column("command")?, column("count")?, column("last_used")?);
+
+    records
+        .enumerate()
+        // Spreadsheets like to leave blank rows at the end
+        .filter(|(_, record)| record.iter().any(|field| !field.trim().is_empty()))
+        .map(|(i, record)| {
+            let field = |column: usize| record.get(column).map(|field| field.trim()).unwrap_or_default();
+            let entry = match (field(count).parse(), field(last_used).parse()) {
+                (Ok(count), Ok(last_used)) => checked(field(command).to_string(), count, last_used),
+                _ => Err("count and last_used must be whole numbers".to_string()),
+            };
+            entry.map_err(|e| format!("Line {}: {}", i + 2, e))
+        })
+        .collect()
+}
+
+/// Split CSV into records of fields (RFC 4180: quoted fields may hold commas,
+/// line breaks and doubled quotes)
+fn parse_csv(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unfinished quoted field at the end of the file".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(command: &str, count: u32, last_used: u64) -> HistoryEntry {
+        HistoryEntry { command: command.to_string(), count, last_used }
+    }
+
+    fn row(command: &str, count: u32, last_used: u64) -> Row {
+        Row { command: command.to_string(), count, last_used, frecency: count * 10 }
+    }
+
+    #[test]
+    fn csv_round_trips_awkward_commands() {
+        let rows = [
+            row("notepad", 3, 1_700_000_000),
+            row("echo \"a, b\"", 1, 1_700_000_100),
+            row("cmd /c \"dir\r\ndir\"", 2, 1_700_000_200),
+        ];
+        let csv = to_csv(&rows);
+        assert!(csv.starts_with("command,count,last_used,frecency\r\nnotepad,3,1700000000,30\r\n"));
+        assert!(csv.contains("\"echo \"\"a, b\"\"\",1,"));
+
+        let entries = from_csv(&csv).unwrap();
+        let commands: Vec<&str> = entries.iter().map(|e| e.command.as_str()).collect();
+        assert_eq!(commands, ["notepad", "echo \"a, b\"", "cmd /c \"dir\r\ndir\""]);
+        assert_eq!((entries[2].count, entries[2].last_used), (2, 1_700_000_200));
+    }
+
+    #[test]
+    fn csv_columns_are_found_by_name() {
+        let csv = "\u{feff}Last_Used,notes,Command,Count\n1700000000,mine,code .,4\n,,,\n";
+        let entries = from_csv(csv).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].command.as_str(), entries[0].count, entries[0].last_used), ("code .", 4, 1_700_000_000));
+    }
+
+    #[test]
+    fn bad_files_are_rejected() {
+        assert!(from_csv("").is_err());
+        assert!(from_csv("name,target\nnp,notepad\n").unwrap_err().contains("'command'"));
+        assert!(from_csv("command,count,last_used\nnotepad,lots,0\n").unwrap_err().starts_with("Line 2"));
+        assert!(from_csv("command,count,last_used\n\"notepad,1,0\n").is_err());
+        assert!(from_json(r#"{ "hotkey": "Alt+Space" }"#).is_err());
+        assert!(from_json(r#"[{ "command": " ", "count": 1, "last_used": 0 }]"#).unwrap_err().starts_with("Entry 1"));
+    }
+
+    #[test]
+    fn json_ignores_frecency() {
+        let entries = from_json(r#"[{ "command": "calc", "count": 2, "last_used": 5, "frecency": 999 }]"#).unwrap();
+        assert_eq!((entries[0].count, entries[0].last_used), (2, 5));
+    }
+
+    #[test]
+    fn importing_keeps_the_higher_count_and_later_use() {
+        let mut entries = vec![entry("Notepad", 5, 100), entry("calc", 1, 300)];
+        let imported = vec![entry("notepad", 2, 200), entry("calc", 4, 50), entry("wt", 1, 10)];
+        merge(&mut entries, imported);
+
+        let summary: Vec<(&str, u32, u64)> =
+            entries.iter().map(|e| (e.command.as_str(), e.count, e.last_used)).collect();
+        assert_eq!(summary, [("Notepad", 5, 200), ("calc", 4, 300), ("wt", 1, 10)]);
+
+        // Again: nothing changes
+        merge(&mut entries, vec![entry("notepad", 2, 200), entry("wt", 1, 10)]);
+        assert_eq!(entries.len(), 3);
+        assert_eq!((entries[0].count, entries[2].count), (5, 1));
+    }
+}
@@ -0,0 +1,183 @@
+// i18n.rs - Translations
+//
+// Text shown to the user - the tray menu, the Settings window, and the
+// errors from running commands - is looked up by key ("tray.quit") in a
+// translation file built into the binary (quickrun-core/locales/<lang>.json).
+// en.json has every key; other languages may leave some out, and those
+// show in English.
+//
+// The language is "language" in settings.json, e.g. "de" ("" = Windows'
+// display language). Only the language part of a locale counts, so de-AT
+// and de-CH both get de.json, and a language without a file gets English.
+//
+// Placeholders are {0}, {1}, ... in the order of the arguments (see
+// format), so a translation can move them around.
+//
+// Windows get the whole table for their language with the get_strings
+// command and apply it themselves (settings.ts).
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Language of the complete translation, and the fallback for missing keys
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// The bundled translations, by language
+const TRANSLATIONS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("de", include_str!("../locales/de.json")),
+];
+
+/// A translation table, key → text
+pub type Strings = BTreeMap<String, String>;
+
+/// The table last used by text(), with the language it's for
+static CURRENT: Mutex<Option<(String, Strings)>> = Mutex::new(None);
+
+/// Languages with a bundled translation
+pub fn languages() -> Vec<&'static str> {
+    TRANSLATIONS.iter().map(|(language, _)| *language).collect()
+}
+
+/// The language part of a locale, lowercased ("de-AT" → "de", "pt_BR.UTF-8"
+/// → "pt")
+pub fn language_of(locale: &str) -> String {
+    locale
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// One bundled translation (empty if there isn't one for `language`)
+fn translation(language: &str) -> Strings {
+    TRANSLATIONS
+        .iter()
+        .find(|(name, _)| *name == language)
+        .and_then(|(name, json)| match serde_json::from_str(json) {
+            Ok(strings) => Some(strings),
+            Err(e) => {
+                eprintln!("[I18n] Ignoring broken {}.json: {}", name, e);
+                None
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Every string for `locale`: its language's translation, with English for
+/// anything missing
+pub fn strings(locale: &str) -> Strings {
+    let mut strings = translation(DEFAULT_LANGUAGE);
+    let language = language_of(locale);
+    if language != DEFAULT_LANGUAGE {
+        strings.extend(translation(&language));
+    }
+    strings
+}
+
+/// Windows' display language (e.g. "en-GB")
+#[cfg(windows)]
+pub fn system_locale() -> String {
+    use windows::Win32::Globalization::{GetUserDefaultUILanguage, LCIDToLocaleName};
+
+    let mut name = [0u16; 85];
+    let len = unsafe { LCIDToLocaleName(GetUserDefaultUILanguage() as u32, Some(&mut name), 0) };
+    if len <= 1 {
+        return DEFAULT_LANGUAGE.to_string();
+    }
+    String::from_utf16_lossy(&name[..len as usize - 1])
+}
+
+/// The locale from the environment (e.g. LANG=de_DE.UTF-8)
+#[cfg(not(windows))]
+pub fn system_locale() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+}
+
+/// The locale in use: "language" in settings.json, or Windows' if unset
+pub fn current_locale() -> String {
+    let language = crate::settings::load().language;
+    if language.trim().is_empty() {
+        system_locale()
+    } else {
+        language.trim().to_string()
+    }
+}
+
+/// The text for `key` in the current language (the key itself if no
+/// translation has it)
+pub fn text(key: &str) -> String {
+    let language = language_of(&current_locale());
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    if current.as_ref().is_none_or(|(cached, _)| *cached != language) {
+        *current = Some((language.clone(), strings(&language)));
+    }
+    current
+        .as_ref()
+        .and_then(|(_, strings)| strings.get(key).cloned())
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Put `args` into `template` in place of {0}, {1}, ...
+pub fn fill(template: &str, args: &[&str]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(template.to_string(), |text, (i, arg)| text.replace(&format!("{{{}}}", i), arg))
+}
+
+/// The text for `key` in the current language, with `args` filled in
+pub fn format(key: &str, args: &[&str]) -> String {
+    fill(&text(key), args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// The {placeholders} in `text`
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.match_indices('{')
+            .filter_map(|(start, _)| text[start..].find('}').map(|end| &text[start..=start + end]))
+            .collect()
+    }
+
+    #[test]
+    fn locales_come_down_to_their_language() {
+        assert_eq!(language_of("de-AT"), "de");
+        assert_eq!(language_of("pt_BR.UTF-8"), "pt");
+        assert_eq!(language_of("EN"), "en");
+    }
+
+    #[test]
+    fn missing_languages_and_keys_fall_back_to_english() {
+        assert_eq!(strings("xx-YY"), strings("en"));
+        assert_eq!(strings("de-CH")["tray.quit"], "Beenden");
+        assert_eq!(strings("en-GB")["tray.quit"], "Quit");
+    }
+
+    #[test]
+    fn translations_only_have_keys_english_has() {
+        let english = translation(DEFAULT_LANGUAGE);
+        assert!(!english.is_empty());
+        for language in languages() {
+            for (key, text) in translation(language) {
+                let original = english
+                    .get(&key)
+                    .unwrap_or_else(|| panic!("{}.json has unknown key {}", language, key));
+                assert_eq!(placeholders(&text), placeholders(original), "{} in {}.json", key, language);
+            }
+        }
+    }
+
+    #[test]
+    fn placeholders_are_filled_by_position() {
+        assert_eq!(fill("{1} before {0}", &["a", "b"]), "b before a");
+        assert_eq!(fill("File not found: {0}", &["x.txt"]), "File not found: x.txt");
+    }
+}
@@ -0,0 +1,192 @@
+// icons.rs - Application icon extraction for result rows
+//
+// Result rows show the real app icon next to each suggestion. Icons are
+// pulled from the shell (SHGetFileInfo), which handles every target type
+// QuickRun launches:
+// - Executables: the embedded icon resource
+// - Shortcuts (.lnk): the target's icon (or the shortcut's custom icon)
+// - UWP/Store apps: "shell:AppsFolder\<AUMID>" parsed to a shell item
+//
+// The HICON is converted to a 32x32 RGBA PNG and returned as a data URL the
+// frontend can put straight into <img src>. Results are cached in memory per
+// path, so each icon is only extracted once per session.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Extracted icons keyed by lowercase path (None = extraction failed, don't retry)
+static CACHE: Mutex<Option<HashMap<String, Option<String>>>> = Mutex::new(None);
+
+/// Get the icon for a file, shortcut, or UWP app as a PNG data URL
+///
+/// Returns None if the shell has no icon for the target.
+pub fn icon_for(path: &str) -> Option<String> {
+    let key = path.to_lowercase();
+
+    if let Ok(cache) = CACHE.lock() {
+        if let Some(cached) = cache.as_ref().and_then(|c| c.get(&key)) {
+            return cached.clone();
+        }
+    }
+
+    let icon = extract_icon_png(path).map(|png| {
+        use base64::Engine;
+        format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(png)
+        )
+    });
+
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.get_or_insert_with(HashMap::new).insert(key, icon.clone());
+    }
+
+    icon
+}
+
+/// Encode a top-down RGBA buffer as PNG
+#[cfg(windows)]
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().ok()?;
+        writer.write_image_data(rgba).ok()?;
+    }
+    Some(out)
+}
+
+#[cfg(windows)]
+fn extract_icon_png(path: &str) -> Option<Vec<u8>> {
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+    use windows::Win32::System::Com::{CoInitializeEx, CoTaskMemFree, COINIT_APARTMENTTHREADED};
+    use windows::Win32::UI::Shell::Common::ITEMIDLIST;
+    use windows::Win32::UI::Shell::{
+        SHGetFileInfoW, SHParseDisplayName, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_PIDL,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::DestroyIcon;
+
+    let mut info = SHFILEINFOW::default();
+    let size = std::mem::size_of::<SHFILEINFOW>() as u32;
+
+    unsafe {
+        // The shell needs COM on this thread; S_FALSE (already initialized) is fine
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let found = if path.to_lowercase().starts_with("shell:appsfolder\\") {
+            // UWP app: resolve the AUMID to a PIDL and ask for its icon
+            let mut pidl: *mut ITEMIDLIST = std::ptr::null_mut();
+            if SHParseDisplayName(&HSTRING::from(path), None, &mut pidl, 0, None).is_err() {
+                return None;
+            }
+            let result = SHGetFileInfoW(
+                windows::core::PCWSTR(pidl as *const u16),
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                Some(&mut info),
+                size,
+                SHGFI_PIDL | SHGFI_ICON | SHGFI_LARGEICON,
+            );
+            CoTaskMemFree(Some(pidl as *const _));
+            result
+        } else {
+            SHGetFileInfoW(
+                &HSTRING::from(path),
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                Some(&mut info),
+                size,
+                SHGFI_ICON | SHGFI_LARGEICON,
+            )
+        };
+
+        if found == 0 || info.hIcon.is_invalid() {
+            return None;
+        }
+
+        let png = hicon_to_png(info.hIcon);
+        let _ = DestroyIcon(info.hIcon);
+        png
+    }
+}
+
+/// Render an HICON's color bitmap into RGBA and encode it as PNG
+#[cfg(windows)]
+unsafe fn hicon_to_png(icon: windows::Win32::UI::WindowsAndMessaging::HICON) -> Option<Vec<u8>> {
+    use windows::Win32::Graphics::Gdi::{
+        DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO,
+        BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetIconInfo, ICONINFO};
+
+    let mut icon_info = ICONINFO::default();
+    GetIconInfo(icon, &mut icon_info).ok()?;
+
+    let mut bitmap = BITMAP::default();
+    let got = GetObjectW(
+        icon_info.hbmColor.into(),
+        std::mem::size_of::<BITMAP>() as i32,
+        Some(&mut bitmap as *mut _ as *mut _),
+    );
+
+    let width = bitmap.bmWidth;
+    let height = bitmap.bmHeight;
+
+    // Monochrome icons have no color bitmap - nothing we can render
+    if got == 0 || width <= 0 || height <= 0 {
+        let _ = DeleteObject(icon_info.hbmColor.into());
+        let _ = DeleteObject(icon_info.hbmMask.into());
+        return None;
+    }
+
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    let mut bmi = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height, // negative = top-down rows
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let dc = GetDC(None);
+    let lines = GetDIBits(
+        dc,
+        icon_info.hbmColor,
+        0,
+        height as u32,
+        Some(pixels.as_mut_ptr() as *mut _),
+        &mut bmi,
+        DIB_RGB_COLORS,
+    );
+    ReleaseDC(None, dc);
+
+    let _ = DeleteObject(icon_info.hbmColor.into());
+    let _ = DeleteObject(icon_info.hbmMask.into());
+
+    if lines == 0 {
+        return None;
+    }
+
+    // GDI gives BGRA; PNG wants RGBA. Icons without an alpha channel report
+    // all-zero alpha, so treat those as fully opaque.
+    let has_alpha = pixels.chunks_exact(4).any(|px| px[3] != 0);
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        if !has_alpha {
+            px[3] = 255;
+        }
+    }
+
+    encode_png(width as u32, height as u32, &pixels)
+}
+
+#[cfg(not(windows))]
+fn extract_icon_png(_path: &str) -> Option<Vec<u8>> {
+    None
+}
@@ -0,0 +1,327 @@
+// importer.rs - Bring web searches and shortcuts over from other launchers
+//
+// Reads the settings of other launchers installed for this user and turns
+// what has a QuickRun counterpart into bangs and aliases:
+//
+//   PowerToys Run   %LOCALAPPDATA%\Microsoft\PowerToys\PowerToys Run\settings.json
+//                   The Web Search plugin's keyword (e.g. "??") becomes a bang
+//                   using the web search template from settings.json
+//   Wox             %APPDATA%\Wox\Settings\Plugins\Wox.Plugin.WebSearch\Settings.json
+//   Flow Launcher   %APPDATA%\FlowLauncher\Settings\Plugins\Flow.Launcher.Plugin.WebSearch\Settings.json
+//                   Each enabled search source (keyword + URL with {q})
+//                   becomes a bang
+//                   %APPDATA%\FlowLauncher\Settings\Settings.json
+//                   Custom query shortcuts that expand to a URL or a full
+//                   path become aliases
+//
+// Keywords of other plugins (">", "=", ...) mean something different in
+// each launcher, so they aren't carried over. Nothing already in QuickRun
+// is replaced: a bang keyword or alias name that exists is skipped, as is
+// a bang named like an alias or a command on PATH (typing it should keep
+// running that), and every skip is listed in the summary with the reason.
+
+use crate::aliases::{self, Alias};
+use crate::runner;
+use crate::web_search::{self, Bang};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Name PowerToys Run gives its web search plugin
+const POWERTOYS_WEB_SEARCH: &str = "Web Search";
+
+/// What an import brought over
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSummary {
+    /// Launchers whose settings were found
+    pub sources: Vec<String>,
+    /// How many bangs were added
+    pub bangs: usize,
+    /// How many aliases were added
+    pub aliases: usize,
+    /// What wasn't brought over, and why
+    pub skipped: Vec<String>,
+}
+
+/// Bangs and aliases read from other launchers, before they're added
+#[derive(Debug, Default)]
+struct Found {
+    bangs: Vec<Bang>,
+    aliases: Vec<Alias>,
+    skipped: Vec<String>,
+}
+
+/// Import from every supported launcher installed for this user
+///
+/// Fails if none of them is found.
+pub fn import() -> Result<ImportSummary, String> {
+    let local = dirs::data_local_dir().unwrap_or_default();
+    let roaming = dirs::data_dir().unwrap_or_default();
+    let mut found = Found::default();
+    let mut sources = Vec::new();
+
+    if let Some(json) = read_json(&local.join(r"Microsoft\PowerToys\PowerToys Run\settings.json"), &mut found) {
+        sources.push("PowerToys Run".to_string());
+        read_powertoys(&json, &crate::settings::load().web_search_template, &mut found);
+    }
+    for (name, folder, plugin) in [
+        ("Wox", "Wox", "Wox.Plugin.WebSearch"),
+        ("Flow Launcher", "FlowLauncher", "Flow.Launcher.Plugin.WebSearch"),
+    ] {
+        let settings = roaming.join(folder).join("Settings");
+        let web_search = read_json(&settings.join("Plugins").join(plugin).join("Settings.json"), &mut found);
+        let shortcuts = read_json(&settings.join("Settings.json"), &mut found);
+        if web_search.is_none() && shortcuts.is_none() {
+            continue;
+        }
+        sources.push(name.to_string());
+        if let Some(json) = web_search {
+            read_search_sources(name, &json, &mut found);
+        }
+        if let Some(json) = shortcuts {
+            read_shortcuts(name, &json, &mut found);
+        }
+    }
+
+    if sources.is_empty() {
+        return Err("No PowerToys Run, Wox or Flow Launcher settings found".to_string());
+    }
+    let summary = add(found, sources)?;
+    eprintln!(
+        "[Import] From {}: {} bangs, {} aliases, {} skipped",
+        summary.sources.join(", "),
+        summary.bangs,
+        summary.aliases,
+        summary.skipped.len()
+    );
+    Ok(summary)
+}
+
+/// Read a settings file (None if it's missing; a broken one is noted as skipped)
+fn read_json(path: &Path, found: &mut Found) -> Option<Value> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(contents.trim_start_matches('\u{feff}')) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            found.skipped.push(format!("{}: not valid JSON ({})", path.display(), e));
+            None
+        }
+    }
+}
+
+/// PowerToys Run: the Web Search plugin's keyword, searching with `template`
+fn read_powertoys(json: &Value, template: &str, found: &mut Found) {
+    let plugins = json.get("plugins").and_then(Value::as_array).into_iter().flatten();
+    for plugin in plugins {
+        if plugin.get("Name").and_then(Value::as_str) != Some(POWERTOYS_WEB_SEARCH) {
+            continue;
+        }
+        let keyword = plugin.get("ActionKeyword").and_then(Value::as_str).unwrap_or_default().trim();
+        if plugin.get("Disabled").and_then(Value::as_bool).unwrap_or(false) || keyword.is_empty() {
+            continue;
+        }
+        found.bangs.push(Bang {
+            keyword: keyword.to_string(),
+            template: template.to_string(),
+            name: "PowerToys Run web search".to_string(),
+        });
+    }
+}
+
+/// Wox and Flow Launcher: the web search plugin's sources
+fn read_search_sources(launcher: &str, json: &Value, found: &mut Found) {
+    let sources = json.get("SearchSources").and_then(Value::as_array).into_iter().flatten();
+    for source in sources {
+        let text = |key: &str| source.get(key).and_then(Value::as_str).unwrap_or_default().trim();
+        let (keyword, title, url) = (text("ActionKeyword"), text("Title"), text("Url"));
+        if !source.get("Enabled").and_then(Value::as_bool).unwrap_or(true) {
+            continue;
+        }
+        if keyword.is_empty() || keyword == "*" {
+            found.skipped.push(format!("{} search '{}': it has no keyword of its own", launcher, title));
+        } else if !url.contains("{q}") {
+            found.skipped.push(format!("{} search '{}': its URL has no {{q}}", launcher, title));
+        } else {
+            found.bangs.push(Bang {
+                keyword: keyword.to_string(),
+                template: url.replace("{q}", "{query}"),
+                name: title.to_string(),
+            });
+        }
+    }
+}
+
+/// Flow Launcher: custom query shortcuts that stand for a URL or a file
+fn read_shortcuts(launcher: &str, json: &Value, found: &mut Found) {
+    let shortcuts = json.get("CustomShortcuts").and_then(Value::as_array).into_iter().flatten();
+    for shortcut in shortcuts {
+        let text = |key: &str| shortcut.get(key).and_then(Value::as_str).unwrap_or_default().trim();
+        let (key, value) = (text("Key"), text("Value"));
+        if key.is_empty() || value.is_empty() {
+            continue;
+        }
+        if key.contains(char::is_whitespace) {
+            found.skipped.push(format!("{} shortcut '{}': alias names are one word", launcher, key));
+        } else if is_target(value) {
+            found.aliases.push(Alias {
+                name: key.to_string(),
+                target: value.to_string(),
+                args: Vec::new(),
+                compat: None,
+                env: None,
+            });
+        } else {
+            found.skipped.push(format!("{} shortcut '{}': '{}' isn't a URL or a full path", launcher, key, value));
+        }
+    }
+}
+
+/// Is `value` something an alias can open by itself (a URL or a full path)?
+///
+/// Anything else is launcher input (e.g., "g rust", a search), which means
+/// nothing to QuickRun.
+fn is_target(value: &str) -> bool {
+    is_full_path(value) || (value.contains("://") && !value.contains(char::is_whitespace))
+}
+
+/// "C:\..." or "\\server\share\..." (checked by hand, as Path::is_absolute
+/// only knows them on Windows)
+fn is_full_path(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let drive = bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    (drive && matches!(bytes[2], b'\\' | b'/')) || value.starts_with(r"\\")
+}
+
+/// Add what was found to QuickRun, keeping what's already there
+fn add(found: Found, sources: Vec<String>) -> Result<ImportSummary, String> {
+    let mut summary = ImportSummary { sources, skipped: found.skipped, ..Default::default() };
+
+    let mut bangs = web_search::load_bangs();
+    let is_command = |name: &str| aliases::find(name).is_some() || runner::resolve_on_path(name).is_some();
+    for bang in found.bangs {
+        let keyword = bang.keyword.clone();
+        let Some(bang) = web_search::normalize_bang(bang) else {
+            summary.skipped.push(format!("Bang '{}': it has no keyword besides \"!\"", keyword));
+            continue;
+        };
+        match bang_conflict(&bang.keyword, &bangs, is_command) {
+            Some(reason) => summary.skipped.push(format!("Bang '{}': {}", bang.keyword, reason)),
+            None => {
+                bangs.push(bang);
+                summary.bangs += 1;
+            }
+        }
+    }
+    if summary.bangs > 0 {
+        web_search::save_bangs(bangs)?;
+    }
+
+    let existing = aliases::load();
+    for alias in found.aliases {
+        if existing.iter().any(|a| a.name.eq_ignore_ascii_case(&alias.name)) {
+            summary.skipped.push(format!("Alias '{}': QuickRun already has it", alias.name));
+        } else {
+            aliases::save(alias)?;
+            summary.aliases += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// Why a bang with `keyword` can't be added next to `bangs`, if it can't;
+/// `is_command` tells whether a name is an alias or a command on PATH
+fn bang_conflict(keyword: &str, bangs: &[Bang], is_command: impl Fn(&str) -> bool) -> Option<&'static str> {
+    if bangs.iter().any(|b| b.keyword.eq_ignore_ascii_case(keyword)) {
+        Some("QuickRun already has it")
+    } else if is_command(keyword) {
+        Some("an alias or a command on this PC has that name")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn powertoys_web_search_keyword_becomes_a_bang() {
+        let settings = json!({ "plugins": [
+            { "Name": "Shell", "ActionKeyword": ">", "Disabled": false },
+            { "Name": "Web Search", "ActionKeyword": "??", "Disabled": false },
+        ] });
+        let mut found = Found::default();
+        read_powertoys(&settings, "https://duckduckgo.com/?q={query}", &mut found);
+
+        assert_eq!(found.bangs.len(), 1);
+        assert_eq!(found.bangs[0].keyword, "??");
+        assert_eq!(found.bangs[0].template, "https://duckduckgo.com/?q={query}");
+
+        let disabled = json!({ "plugins": [ { "Name": "Web Search", "ActionKeyword": "??", "Disabled": true } ] });
+        let mut found = Found::default();
+        read_powertoys(&disabled, "https://duckduckgo.com/?q={query}", &mut found);
+        assert!(found.bangs.is_empty());
+    }
+
+    #[test]
+    fn search_sources_become_bangs() {
+        let settings = json!({ "SearchSources": [
+            { "Title": "Google", "ActionKeyword": "g", "Url": "https://www.google.com/search?q={q}", "Enabled": true },
+            { "Title": "Maps", "ActionKeyword": "map", "Url": "https://maps.example.com/{q}", "Enabled": false },
+            { "Title": "Everywhere", "ActionKeyword": "*", "Url": "https://example.com/?q={q}", "Enabled": true },
+            { "Title": "Home", "ActionKeyword": "home", "Url": "https://example.com/", "Enabled": true },
+        ] });
+        let mut found = Found::default();
+        read_search_sources("Flow Launcher", &settings, &mut found);
+
+        assert_eq!(
+            found.bangs,
+            [Bang {
+                keyword: "g".to_string(),
+                template: "https://www.google.com/search?q={query}".to_string(),
+                name: "Google".to_string(),
+            }]
+        );
+        assert_eq!(found.skipped.len(), 2);
+        assert!(found.skipped[0].contains("'Everywhere'"));
+        assert!(found.skipped[1].contains("{q}"));
+    }
+
+    #[test]
+    fn shortcuts_to_urls_and_paths_become_aliases() {
+        let settings = json!({ "CustomShortcuts": [
+            { "Key": "gh", "Value": "https://github.com" },
+            { "Key": "np", "Value": "C:\\Program Files\\Notepad++\\notepad++.exe" },
+            { "Key": "share", "Value": "\\\\nas\\media" },
+            { "Key": "docs", "Value": "%USERPROFILE%\\Documents" },
+            { "Key": "rs", "Value": "g rust" },
+        ] });
+        let mut found = Found::default();
+        read_shortcuts("Flow Launcher", &settings, &mut found);
+
+        let names: Vec<&str> = found.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["gh", "np", "share"]);
+        assert_eq!(found.aliases[1].target, "C:\\Program Files\\Notepad++\\notepad++.exe");
+        let skipped: Vec<bool> = found.skipped.iter().map(|s| s.contains("'docs'") || s.contains("'rs'")).collect();
+        assert_eq!(skipped, [true, true]);
+    }
+
+    #[test]
+    fn unrelated_files_find_nothing() {
+        let mut found = Found::default();
+        read_powertoys(&json!({ "hotkey": "Alt+Space" }), "", &mut found);
+        read_search_sources("Wox", &json!([]), &mut found);
+        read_shortcuts("Flow Launcher", &json!({ "CustomShortcuts": "none" }), &mut found);
+        assert!(found.bangs.is_empty() && found.aliases.is_empty() && found.skipped.is_empty());
+    }
+
+    #[test]
+    fn bangs_keep_out_of_the_way_of_existing_names() {
+        let bangs = vec![Bang { keyword: "g".to_string(), template: String::new(), name: String::new() }];
+        let git_on_path = |name: &str| name.eq_ignore_ascii_case("git");
+        assert_eq!(bang_conflict("G", &bangs, git_on_path), Some("QuickRun already has it"));
+        assert!(bang_conflict("git", &bangs, git_on_path).is_some());
+        assert_eq!(bang_conflict("so", &bangs, git_on_path), None);
+    }
+}
@@ -0,0 +1,477 @@
+// index.rs - Installed application index
+//
+// Most GUI apps aren't on PATH - they're only reachable through Start Menu
+// shortcuts. This module scans the Start Menu folders (per-user and
+// all-users) and remembers each shortcut by its display name, so typing
+// "Visual Studio Code" works just like typing "notepad". Apps registered
+// under "App Paths" in the registry are added too, and the commands on PATH
+// are indexed separately so suggestions don't rescan PATH on every keystroke.
+//
+// The index is kept fresh by a background scheduler (start_scheduler):
+// - A full rebuild at startup, then every "index_rebuild_minutes" (default 30),
+//   except on battery saver (see resource_policy.rs)
+// - File system watchers on the Start Menu folders and PATH directories
+//   trigger an early rebuild (debounced) when apps are installed or removed
+// - After each rebuild the caller is handed an IndexUpdated summary (the app
+//   forwards it to the launcher as an event, so it knows
+//   fresher results are available)
+//
+// Each rebuild is also written to a compact binary cache (index.bin in the
+// config folder). At startup the cache is loaded first, so the very first
+// query is answered instantly even when scanning a huge Start Menu on a slow
+// disk takes seconds; the real rebuild then follows in the background.
+// With a size limit (see retention.rs) only the most used entries are
+// cached, and the rest arrive with that rebuild.
+
+use crate::retention::{self, Limits};
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// An application found in the Start Menu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppEntry {
+    /// Display name (shortcut file name without extension)
+    pub name: String,
+    /// Full path to the shortcut or executable
+    pub path: PathBuf,
+}
+
+/// The in-memory index, replaced wholesale on every rebuild
+static INDEX: RwLock<Vec<AppEntry>> = RwLock::new(Vec::new());
+
+/// Command names found on PATH (see runner::list_path_commands)
+static PATH_COMMANDS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Wait this long after the last file system change before rebuilding
+/// (installers touch many files in a burst)
+const WATCH_DEBOUNCE: Duration = Duration::from_secs(3);
+
+/// With a cache to serve from, the startup rebuild waits this long so it
+/// doesn't compete with the launcher starting up
+const CACHED_STARTUP_DELAY: Duration = Duration::from_secs(10);
+
+/// Cache format version; bump when the cached layout changes so old caches
+/// are ignored instead of misread
+const CACHE_VERSION: u32 = 1;
+
+/// What index.bin holds
+#[derive(Serialize, Deserialize)]
+struct IndexCache {
+    version: u32,
+    apps: Vec<AppEntry>,
+    path_commands: Vec<String>,
+}
+
+/// Summary of a finished rebuild (passed to start_scheduler's callback)
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexUpdated {
+    pub apps: usize,
+    pub path_commands: usize,
+}
+
+/// File extensions that count as launchable Start Menu entries
+const APP_EXTENSIONS: &[&str] = &["lnk", "url", "exe", "appref-ms"];
+
+/// Get the Start Menu "Programs" folders to scan
+///
+/// - Per-user: %APPDATA%\Microsoft\Windows\Start Menu\Programs
+/// - All users: %ProgramData%\Microsoft\Windows\Start Menu\Programs
+fn start_menu_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Ok(appdata) = std::env::var("APPDATA") {
+        dirs.push(PathBuf::from(appdata).join("Microsoft\\Windows\\Start Menu\\Programs"));
+    }
+    if let Ok(program_data) = std::env::var("ProgramData") {
+        dirs.push(PathBuf::from(program_data).join("Microsoft\\Windows\\Start Menu\\Programs"));
+    }
+
+    dirs
+}
+
+/// Recursively collect launchable entries under `dir`
+fn scan_dir(dir: &Path, entries: &mut Vec<AppEntry>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            scan_dir(&path, entries);
+            continue;
+        }
+
+        let is_app = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| APP_EXTENSIONS.iter().any(|a| ext.eq_ignore_ascii_case(a)))
+            .unwrap_or(false);
+
+        if !is_app {
+            continue;
+        }
+
+        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+            // Skip the uninstaller shortcuts most installers drop next to the app
+            if name.to_lowercase().starts_with("uninstall") {
+                continue;
+            }
+            entries.push(AppEntry {
+                name: name.to_string(),
+                path: path.clone(),
+            });
+        }
+    }
+}
+
+/// Collect apps registered under App Paths (HKCU and HKLM)
+///
+/// Each subkey is an executable name ("chrome.exe") whose default value is
+/// the full path.
+#[cfg(windows)]
+fn scan_app_paths(entries: &mut Vec<AppEntry>) {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+    use winreg::RegKey;
+
+    const APP_PATHS: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\App Paths";
+
+    for hive in [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE] {
+        let Ok(app_paths) = RegKey::predef(hive).open_subkey(APP_PATHS) else {
+            continue;
+        };
+        for key_name in app_paths.enum_keys().flatten() {
+            let Ok(key) = app_paths.open_subkey(&key_name) else {
+                continue;
+            };
+            let Ok(target) = key.get_value::<String, _>("") else {
+                continue;
+            };
+            let path = PathBuf::from(target.trim_matches('"'));
+            if !path.is_file() {
+                continue;
+            }
+            let name = key_name
+                .strip_suffix(".exe")
+                .or_else(|| key_name.strip_suffix(".EXE"))
+                .unwrap_or(&key_name);
+            entries.push(AppEntry {
+                name: name.to_string(),
+                path,
+            });
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn scan_app_paths(_entries: &mut Vec<AppEntry>) {}
+
+/// Scan the Start Menu, App Paths, and PATH and replace the in-memory index
+///
+/// Duplicate names keep the first hit: Start Menu shortcuts (per-user, then
+/// all-users) win over App Paths.
+pub fn rebuild() {
+    let mut entries = Vec::new();
+    for dir in start_menu_dirs() {
+        scan_dir(&dir, &mut entries);
+    }
+    scan_app_paths(&mut entries);
+
+    entries.sort_by_key(|e| e.name.to_lowercase());
+    entries.dedup_by(|a, b| a.name.eq_ignore_ascii_case(&b.name));
+
+    let mut commands = crate::runner::list_path_commands();
+    commands.sort_by_key(|c| c.to_lowercase());
+    commands.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+    eprintln!(
+        "[Index] Indexed {} applications, {} PATH commands",
+        entries.len(),
+        commands.len()
+    );
+
+    if let Ok(mut index) = INDEX.write() {
+        *index = entries;
+    }
+    if let Ok(mut path_commands) = PATH_COMMANDS.write() {
+        *path_commands = commands;
+    }
+}
+
+/// Get the path to the index cache
+fn cache_path() -> PathBuf {
+    crate::get_config_dir().join("index.bin")
+}
+
+/// Fill the in-memory index from the cache, if there is a usable one
+///
+/// Returns true if the cache was loaded.
+fn load_cache() -> bool {
+    let Ok(data) = std::fs::read(cache_path()) else {
+        return false;
+    };
+    let cache: IndexCache = match bincode::deserialize(&data) {
+        Ok(cache) => cache,
+        Err(e) => {
+            eprintln!("[Index] Ignoring unreadable cache: {}", e);
+            return false;
+        }
+    };
+    if cache.version != CACHE_VERSION {
+        return false;
+    }
+
+    eprintln!(
+        "[Index] Loaded {} applications, {} PATH commands from cache",
+        cache.apps.len(),
+        cache.path_commands.len()
+    );
+    if let Ok(mut index) = INDEX.write() {
+        *index = cache.apps;
+    }
+    if let Ok(mut path_commands) = PATH_COMMANDS.write() {
+        *path_commands = cache.path_commands;
+    }
+    true
+}
+
+/// How much each launched command is used, by lowercase name (see
+/// HistoryEntry::frecency)
+fn usage() -> HashMap<String, u32> {
+    let now = crate::history::now();
+    let mut usage = HashMap::new();
+    for entry in crate::history::load() {
+        let score = usage.entry(entry.command.to_lowercase()).or_insert(0);
+        *score = entry.frecency(now).max(*score);
+    }
+    usage
+}
+
+/// Keep as many of `items` as `limits` allow, the most used first, then
+/// put them back in name order; returns how many were dropped
+fn keep_most_used<T: Serialize>(
+    items: &mut Vec<T>,
+    name: impl Fn(&T) -> &str,
+    usage: &HashMap<String, u32>,
+    limits: &Limits,
+) -> usize {
+    let score = |item: &T| usage.get(&name(item).to_lowercase()).copied().unwrap_or(0);
+    items.sort_by_key(|item| std::cmp::Reverse(score(item)));
+    let dropped = retention::trim(items, &Limits { max_age_days: None, ..*limits }, |_| 0, 0);
+    items.sort_by_key(|item| name(item).to_lowercase());
+    dropped
+}
+
+/// Write the current index to the cache, returning how many entries were
+/// left out of it
+///
+/// The cache is kept within the "retention" limits in settings.json (see
+/// retention.rs), apps before PATH commands and the most used of each
+/// first. Written to a temporary file and renamed into place, so a crash
+/// mid-write never leaves a truncated cache behind.
+fn save_cache() -> Result<usize, String> {
+    let limits = crate::settings::load().retention.index;
+    let usage = usage();
+
+    let mut apps = entries();
+    let mut dropped = keep_most_used(&mut apps, |app| &app.name, &usage, &limits);
+    // PATH commands get what the apps left
+    let rest = Limits {
+        max_entries: limits.max_entries.map(|max| max.saturating_sub(apps.len())),
+        max_age_days: None,
+        max_kb: limits.max_kb.map(|kb| kb.saturating_sub(apps.iter().map(retention::entry_size).sum::<u64>() / 1024)),
+    };
+    let mut commands = path_commands();
+    dropped += keep_most_used(&mut commands, |command| command, &usage, &rest);
+
+    let cache = IndexCache {
+        version: CACHE_VERSION,
+        apps,
+        path_commands: commands,
+    };
+    let data = bincode::serialize(&cache).map_err(|e| format!("Failed to serialize index: {}", e))?;
+
+    let path = cache_path();
+    let temp = path.with_extension("bin.tmp");
+    std::fs::write(&temp, data).map_err(|e| format!("Failed to write index cache: {}", e))?;
+    std::fs::rename(&temp, &path).map_err(|e| format!("Failed to replace index cache: {}", e))?;
+    Ok(dropped)
+}
+
+/// Rewrite the cache within the current limits (see retention.rs),
+/// returning how many entries were left out
+pub fn prune_cache() -> Result<usize, String> {
+    // Nothing indexed yet: leave the cache for the first scan to replace
+    if entries().is_empty() || !cache_path().exists() {
+        return Ok(0);
+    }
+    save_cache()
+}
+
+/// Directories on PATH (watched for added/removed commands)
+fn path_dirs() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).filter(|dir| dir.is_dir()).collect())
+        .unwrap_or_default()
+}
+
+/// Time between scheduled rebuilds
+fn rebuild_interval() -> Duration {
+    let minutes = crate::settings::load().index_rebuild_minutes.max(1);
+    Duration::from_secs(minutes * 60)
+}
+
+/// Rebuild now, update the cache, and tell the caller
+fn rebuild_and_notify(on_rebuilt: &impl Fn(IndexUpdated)) {
+    rebuild();
+    if let Err(e) = save_cache() {
+        eprintln!("[Index] {}", e);
+    }
+    on_rebuilt(IndexUpdated {
+        apps: INDEX.read().map(|i| i.len()).unwrap_or(0),
+        path_commands: PATH_COMMANDS.read().map(|p| p.len()).unwrap_or(0),
+    });
+}
+
+/// Fill the index once, without the scheduler (e.g., for the qr CLI)
+///
+/// Uses the cache the app keeps up to date, and only scans when there is no
+/// usable cache (then saves one).
+pub fn load() {
+    if !load_cache() {
+        rebuild();
+        if let Err(e) = save_cache() {
+            eprintln!("[Index] {}", e);
+        }
+    }
+}
+
+/// Start the background rebuild scheduler (call once at startup)
+///
+/// Serves the cached index right away (if there is one), builds the real
+/// index, then rebuilds on the interval or shortly after the watched folders
+/// change. `on_rebuilt` runs on the scheduler thread after every rebuild.
+pub fn start_scheduler(on_rebuilt: impl Fn(IndexUpdated) + Send + 'static) {
+    let cached = load_cache();
+
+    std::thread::spawn(move || {
+        if cached {
+            std::thread::sleep(CACHED_STARTUP_DELAY);
+        }
+        rebuild_and_notify(&on_rebuilt);
+
+        // File system changes arrive on this channel
+        let (sender, changes) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                if event.kind.is_create() || event.kind.is_remove() || event.kind.is_modify() {
+                    let _ = sender.send(());
+                }
+            }
+        });
+
+        // Keep the watcher alive for the life of the thread; without one we
+        // still rebuild on the interval
+        let _watcher = match watcher {
+            Ok(mut watcher) => {
+                for dir in start_menu_dirs() {
+                    if let Err(e) = watcher.watch(&dir, RecursiveMode::Recursive) {
+                        eprintln!("[Index] Can't watch {}: {}", dir.display(), e);
+                    }
+                }
+                for dir in path_dirs() {
+                    let _ = watcher.watch(&dir, RecursiveMode::NonRecursive);
+                }
+                Some(watcher)
+            }
+            Err(e) => {
+                eprintln!("[Index] File watching unavailable: {}", e);
+                None
+            }
+        };
+
+        let mut next_rebuild = Instant::now() + rebuild_interval();
+        let mut pending_change: Option<Instant> = None;
+
+        loop {
+            // Wake up for whichever comes first: the debounce or the schedule
+            let wake_at = pending_change
+                .map(|changed| changed + WATCH_DEBOUNCE)
+                .map_or(next_rebuild, |debounced| debounced.min(next_rebuild));
+            let timeout = wake_at.saturating_duration_since(Instant::now());
+
+            match changes.recv_timeout(timeout) {
+                Ok(()) => {
+                    pending_change = Some(Instant::now());
+                    continue;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    // No watcher: sleep until the next scheduled rebuild
+                    std::thread::sleep(timeout);
+                }
+            }
+
+            if pending_change.is_some() {
+                eprintln!("[Index] Change detected, rebuilding");
+            } else if !crate::resource_policy::current().scheduled_rebuilds {
+                // Battery saver: skip this one, changes still get picked up
+                next_rebuild = Instant::now() + rebuild_interval();
+                continue;
+            }
+            rebuild_and_notify(&on_rebuilt);
+            pending_change = None;
+            next_rebuild = Instant::now() + rebuild_interval();
+        }
+    });
+}
+
+/// Get a snapshot of all indexed applications
+pub fn entries() -> Vec<AppEntry> {
+    INDEX.read().map(|index| index.clone()).unwrap_or_default()
+}
+
+/// Get a snapshot of the command names on PATH
+pub fn path_commands() -> Vec<String> {
+    PATH_COMMANDS.read().map(|commands| commands.clone()).unwrap_or_default()
+}
+
+/// Find an indexed application by exact (case-insensitive) name
+pub fn find(name: &str) -> Option<AppEntry> {
+    INDEX
+        .read()
+        .ok()?
+        .iter()
+        .find(|e| e.name.eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_most_used_entries_are_cached() {
+        let mut commands: Vec<String> = ["git", "curl", "node", "python"].map(String::from).to_vec();
+        let usage = HashMap::from([("python".to_string(), 500), ("curl".to_string(), 100)]);
+        let limits = Limits { max_entries: Some(2), ..Limits::default() };
+
+        assert_eq!(keep_most_used(&mut commands, |c| c, &usage, &limits), 2);
+        assert_eq!(commands, vec!["curl", "python"]);
+    }
+
+    #[test]
+    fn without_limits_everything_is_cached_in_name_order() {
+        let mut commands: Vec<String> = ["node", "Curl", "git"].map(String::from).to_vec();
+        let usage = HashMap::from([("node".to_string(), 10)]);
+
+        assert_eq!(keep_most_used(&mut commands, |c| c, &usage, &Limits::default()), 0);
+        assert_eq!(commands, vec!["Curl", "git", "node"]);
+    }
+}
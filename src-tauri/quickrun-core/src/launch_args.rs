@@ -0,0 +1,213 @@
+// launch_args.rs - quickrun.exe's command line
+//
+// Scripts and other tools can drive the launcher through the exe itself:
+//
+//   quickrun.exe --run "notepad todo.txt"   Run a command as if typed into it
+//   quickrun.exe --show                     Open the launcher
+//   quickrun.exe --hide                     Close the launcher
+//   quickrun.exe --settings                 Open the Settings window
+//   quickrun.exe --input "calc "            Open the launcher with text typed in
+//   quickrun.exe --input-path "C:\a b.exe"  The same with a path, quoted if
+//                                           needed and followed by a space
+//                                           (Explorer's context menu, see
+//                                           context_menu.rs)
+//
+// If QuickRun is already running, the new process hands its arguments to
+// the running one (single-instance plugin) and exits; otherwise QuickRun
+// starts as usual and then does what was asked. Flags are matched without
+// regard to case and handled in order. --silent (see startup.rs) and
+// anything unknown are skipped, so a newer shortcut doesn't stop an older
+// QuickRun from starting.
+//
+// The same requests come as quickrun:// links (see parse_url), which Windows
+// passes as the only argument once the scheme is registered (deep_link.rs
+// in the app):
+//
+//   quickrun://run?cmd=notepad%20todo.txt
+//   quickrun://show   (and hide, settings)
+//
+// Windows puts the link into the registered command line without escaping
+// it, so a link with a quote in it can add arguments of its own
+// (quickrun://x" --run "calc). A command line with a link is therefore
+// taken for just the link (see find_url), and none of its flags are
+// handled.
+
+use crate::startup::SILENT_FLAG;
+use url::Url;
+
+/// Scheme of QuickRun's links
+pub const URL_SCHEME: &str = "quickrun";
+
+/// Flag that opens the launcher with a path typed in
+pub const INPUT_PATH_FLAG: &str = "--input-path";
+
+/// Something asked for on the command line or by a link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Run(String),
+    Show,
+    Hide,
+    Settings,
+    /// Open the launcher with this text typed in
+    Input(String),
+}
+
+/// What `args` (with the program name first) ask for - nothing if they
+/// carry a link (see find_url)
+///
+/// Fails only if --run, --input or --input-path has nothing after it.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Vec<Request>, String> {
+    let args: Vec<String> = args.into_iter().collect();
+    if find_url(&args).is_some() {
+        return Ok(Vec::new());
+    }
+    let mut requests = Vec::new();
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.to_ascii_lowercase().as_str() {
+            "--run" => match args.next().filter(|command| !command.trim().is_empty()) {
+                Some(command) => requests.push(Request::Run(command)),
+                None => return Err("--run needs a command, e.g. --run \"notepad\"".to_string()),
+            },
+            "--show" => requests.push(Request::Show),
+            "--hide" => requests.push(Request::Hide),
+            "--settings" => requests.push(Request::Settings),
+            "--input" => match args.next() {
+                Some(text) => requests.push(Request::Input(text)),
+                None => return Err("--input needs the text to type in".to_string()),
+            },
+            flag if flag == INPUT_PATH_FLAG => match args.next().filter(|path| !path.trim().is_empty()) {
+                Some(path) => requests.push(Request::Input(input_for_path(&path))),
+                None => return Err(format!("{} needs a path", INPUT_PATH_FLAG)),
+            },
+            flag if flag == SILENT_FLAG => {}
+            _ => eprintln!("[Args] Ignoring unknown argument '{}'", arg),
+        }
+    }
+    Ok(requests)
+}
+
+/// Launcher input for `path`, quoted if it has spaces, with a space after
+/// it for arguments
+fn input_for_path(path: &str) -> String {
+    if path.contains(char::is_whitespace) {
+        format!("\"{}\" ", path)
+    } else {
+        format!("{} ", path)
+    }
+}
+
+/// Is `arg` a quickrun:// link?
+pub fn is_url(arg: &str) -> bool {
+    arg.get(..URL_SCHEME.len() + 3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}://", URL_SCHEME)))
+}
+
+/// The quickrun:// link in `args` (with the program name first), if there
+/// is one: the only thing a command line with a link is taken for, as the
+/// rest of it may have come from the link
+pub fn find_url(args: &[String]) -> Option<&str> {
+    args.iter().skip(1).map(String::as_str).find(|arg| is_url(arg))
+}
+
+/// What a quickrun:// link asks for
+pub fn parse_url(link: &str) -> Result<Request, String> {
+    let url = Url::parse(link).map_err(|e| format!("Invalid link '{}': {}", link, e))?;
+    if url.scheme() != URL_SCHEME {
+        return Err(format!("Not a {}:// link: {}", URL_SCHEME, link));
+    }
+
+    match url.host_str().unwrap_or_default().to_ascii_lowercase().as_str() {
+        "run" => url
+            .query_pairs()
+            .find(|(key, _)| key == "cmd")
+            .map(|(_, command)| command.into_owned())
+            .filter(|command| !command.trim().is_empty())
+            .map(Request::Run)
+            .ok_or_else(|| format!("Link has no command to run (?cmd=...): {}", link)),
+        "show" => Ok(Request::Show),
+        "hide" => Ok(Request::Hide),
+        "settings" => Ok(Request::Settings),
+        action => Err(format!("Unknown link action '{}': {}", action, link)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("quickrun.exe").chain(list.iter().copied()).map(String::from).collect()
+    }
+
+    #[test]
+    fn flags_are_handled_in_order() {
+        assert_eq!(
+            parse(args(&["--settings", "--RUN", "notepad todo.txt", "--hide"])).unwrap(),
+            vec![Request::Settings, Request::Run("notepad todo.txt".to_string()), Request::Hide]
+        );
+    }
+
+    #[test]
+    fn paths_are_typed_in_ready_for_arguments() {
+        assert_eq!(
+            parse(args(&["--input-path", "C:\\Tools\\app.exe"])).unwrap(),
+            vec![Request::Input("C:\\Tools\\app.exe ".to_string())]
+        );
+        assert_eq!(
+            parse(args(&["--input-path", "C:\\My Tools\\app.exe"])).unwrap(),
+            vec![Request::Input("\"C:\\My Tools\\app.exe\" ".to_string())]
+        );
+        assert_eq!(parse(args(&["--input", "calc "])).unwrap(), vec![Request::Input("calc ".to_string())]);
+        assert!(parse(args(&["--input-path"])).is_err());
+        assert!(parse(args(&["--input"])).is_err());
+    }
+
+    #[test]
+    fn silent_and_unknown_arguments_are_skipped() {
+        assert_eq!(parse(args(&[])).unwrap(), vec![]);
+        assert_eq!(parse(args(&["--silent", "--future-flag", "--show"])).unwrap(), vec![Request::Show]);
+    }
+
+    #[test]
+    fn run_needs_a_command() {
+        assert!(parse(args(&["--run"])).is_err());
+        assert!(parse(args(&["--run", " "])).is_err());
+    }
+
+    #[test]
+    fn links_are_not_flags() {
+        assert!(is_url("QuickRun://show"));
+        assert!(!is_url("https://example.com"));
+        assert_eq!(parse(args(&["quickrun://show"])).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn arguments_next_to_a_link_are_ignored() {
+        // What Windows passes for quickrun://x" --run "calc
+        let injected = args(&["quickrun://x", "--run", "calc"]);
+        assert_eq!(parse(injected.clone()).unwrap(), vec![]);
+        assert_eq!(find_url(&injected), Some("quickrun://x"));
+        assert_eq!(find_url(&args(&["--run", "calc"])), None);
+        // The program name isn't an argument
+        assert_eq!(find_url(&["quickrun://show".to_string()]), None);
+    }
+
+    #[test]
+    fn links_ask_for_the_same_things() {
+        assert_eq!(
+            parse_url("quickrun://run?cmd=notepad%20todo.txt").unwrap(),
+            Request::Run("notepad todo.txt".to_string())
+        );
+        assert_eq!(parse_url("quickrun://run/?cmd=calc&from=web").unwrap(), Request::Run("calc".to_string()));
+        assert_eq!(parse_url("quickrun://show").unwrap(), Request::Show);
+        assert_eq!(parse_url("quickrun://settings/").unwrap(), Request::Settings);
+    }
+
+    #[test]
+    fn bad_links_are_refused() {
+        assert!(parse_url("quickrun://run").is_err());
+        assert!(parse_url("quickrun://format?drive=c").is_err());
+        assert!(parse_url("https://run?cmd=calc").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+// lib.rs - quickrun-core: the launcher without the window
+//
+// Everything that decides what a typed command means lives here, with no
+// dependency on Tauri:
+// - Resolution and execution (runner, aliases, compat, env_presets, pipe)
+// - The app index and PATH commands (index)
+// - Suggestions, completion and ranking (suggestions, completion, ranking)
+// - Suggestion sources (providers, plugins, history, web_search)
+// - Translations of what the user reads (i18n)
+// - quickrun.exe's own command line, and the Explorer context menu entry
+//   that uses it (launch_args, context_menu)
+// - Settings, the theme, profiles, backups of them (and of history alone),
+//   where files are kept, and writing them safely alongside other processes
+//   (settings, theme, profiles, backup, history_export, storage, shared_file)
+// - Bringing bangs and aliases over from other launchers (importer)
+// - Doing less when Windows asks for reduced effects or battery saver
+//   (resource_policy)
+// - Keeping history and caches within limits (retention)
+// - Seams over the OS for testing (os)
+//
+// The Tauri app (src-tauri/src) is a shell around this crate: it owns the
+// windows, tray, hotkeys and events, and calls in here for everything else.
+// Anything that needs to reach the UI (e.g., the index being rebuilt) is
+// reported through a callback instead of an event.
+
+pub mod aliases;
+pub mod backup;
+pub mod compat;
+pub mod completion;
+pub mod context_menu;
+pub mod env_presets;
+pub mod file_actions;
+pub mod history;
+pub mod history_export;
+pub mod i18n;
+pub mod icons;
+pub mod importer;
+pub mod index;
+pub mod launch_args;
+pub mod os;
+pub mod path_completion;
+pub mod pipe;
+pub mod plugins;
+pub mod profiles;
+pub mod providers;
+pub mod ranking;
+pub mod resource_policy;
+pub mod results;
+pub mod retention;
+pub mod runner;
+pub mod settings;
+pub mod shared_file;
+pub mod startup;
+pub mod storage;
+pub mod suggestions;
+pub mod theme;
+pub mod web_search;
+
+pub use settings::Settings;
+pub use storage::get_config_dir;
@@ -0,0 +1,324 @@
+// os/memory.rs - In-memory OS implementations for unit tests
+//
+// MemoryFs compares paths case-insensitively, like NTFS, so resolution tests
+// behave the same on every platform.
+
+use super::{FileSystem, HttpClient, HttpResponse, ProcessSpawner, Registry};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Case-insensitive lookup key for a path
+fn key(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// Files (with contents) and folders held in memory
+#[derive(Default)]
+pub struct MemoryFs {
+    files: Mutex<BTreeMap<String, (PathBuf, Vec<u8>)>>,
+    dirs: Mutex<BTreeMap<String, PathBuf>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an empty file (and its parent folders)
+    pub fn with_file(self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.write(&path, b"").expect("in-memory write");
+        self
+    }
+
+    /// Contents of a file, if it exists
+    pub fn contents(&self, path: &Path) -> Option<Vec<u8>> {
+        self.files.lock().unwrap().get(&key(path)).map(|(_, data)| data.clone())
+    }
+
+    fn add_dirs(&self, path: &Path) {
+        let mut dirs = self.dirs.lock().unwrap();
+        for ancestor in path.ancestors().skip(1).filter(|a| !a.as_os_str().is_empty()) {
+            dirs.insert(key(ancestor), ancestor.to_path_buf());
+        }
+    }
+}
+
+impl FileSystem for MemoryFs {
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.lock().unwrap().contains_key(&key(path))
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.dirs.lock().unwrap().contains_key(&key(path))
+    }
+
+    fn read_dir(&self, dir: &Path) -> Vec<PathBuf> {
+        let is_child = |path: &PathBuf| path.parent().is_some_and(|parent| key(parent) == key(dir));
+        let files = self.files.lock().unwrap();
+        let dirs = self.dirs.lock().unwrap();
+        files
+            .values()
+            .map(|(path, _)| path)
+            .chain(dirs.values())
+            .filter(|path| is_child(path))
+            .cloned()
+            .collect()
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>, String> {
+        self.contents(path)
+            .ok_or_else(|| format!("Failed to read {}: not found", path.display()))
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), String> {
+        self.add_dirs(path);
+        self.files
+            .lock()
+            .unwrap()
+            .insert(key(path), (path.to_path_buf(), data.to_vec()));
+        Ok(())
+    }
+
+    fn append(&self, path: &Path, data: &[u8]) -> Result<(), String> {
+        self.add_dirs(path);
+        self.files
+            .lock()
+            .unwrap()
+            .entry(key(path))
+            .or_insert_with(|| (path.to_path_buf(), Vec::new()))
+            .1
+            .extend_from_slice(data);
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<(), String> {
+        self.files.lock().unwrap().remove(&key(path));
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), String> {
+        let mut files = self.files.lock().unwrap();
+        let (_, data) = files
+            .remove(&key(from))
+            .ok_or_else(|| format!("Failed to rename {}: not found", from.display()))?;
+        files.insert(key(to), (to.to_path_buf(), data));
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> Result<(), String> {
+        let data = self
+            .contents(from)
+            .ok_or_else(|| format!("Failed to copy {}: not found", from.display()))?;
+        self.write(to, &data)
+    }
+}
+
+/// Registry values held in memory, keyed by (key, name)
+#[derive(Default)]
+pub struct MemoryRegistry {
+    values: Mutex<HashMap<(String, String), String>>,
+    dwords: Mutex<HashMap<(String, String), u32>>,
+}
+
+impl MemoryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a DWORD value (the app never writes one, so Registry has no setter)
+    pub fn with_dword(self, key: &str, name: &str, value: u32) -> Self {
+        self.dwords
+            .lock()
+            .unwrap()
+            .insert((key.to_lowercase(), name.to_lowercase()), value);
+        self
+    }
+}
+
+impl Registry for MemoryRegistry {
+    fn get_string(&self, key: &str, name: &str) -> Result<Option<String>, String> {
+        Ok(self
+            .values
+            .lock()
+            .unwrap()
+            .get(&(key.to_lowercase(), name.to_lowercase()))
+            .cloned())
+    }
+
+    fn get_dword(&self, key: &str, name: &str) -> Result<Option<u32>, String> {
+        Ok(self
+            .dwords
+            .lock()
+            .unwrap()
+            .get(&(key.to_lowercase(), name.to_lowercase()))
+            .copied())
+    }
+
+    fn set_string(&self, key: &str, name: &str, value: &str) -> Result<(), String> {
+        self.values
+            .lock()
+            .unwrap()
+            .insert((key.to_lowercase(), name.to_lowercase()), value.to_string());
+        Ok(())
+    }
+
+    fn delete_value(&self, key: &str, name: &str) -> Result<(), String> {
+        self.values
+            .lock()
+            .unwrap()
+            .remove(&(key.to_lowercase(), name.to_lowercase()))
+            .map(|_| ())
+            .ok_or_else(|| "Failed to delete registry value: not found".to_string())
+    }
+
+    fn delete_key(&self, key: &str) -> Result<(), String> {
+        let key = key.to_lowercase();
+        let subkeys = format!("{}\\", key);
+        let under = |(k, _): &(String, String)| *k == key || k.starts_with(&subkeys);
+        self.values.lock().unwrap().retain(|name, _| !under(name));
+        self.dwords.lock().unwrap().retain(|name, _| !under(name));
+        Ok(())
+    }
+
+    /// Names come back lowercase, like every key held here
+    fn subkeys(&self, key: &str) -> Result<Vec<String>, String> {
+        let prefix = format!("{}\\", key.to_lowercase());
+        let values = self.values.lock().unwrap();
+        let dwords = self.dwords.lock().unwrap();
+        let mut names: Vec<String> = values
+            .keys()
+            .chain(dwords.keys())
+            .filter_map(|(k, _)| k.strip_prefix(&prefix))
+            .map(|rest| rest.split('\\').next().unwrap_or(rest).to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+/// A process start that was requested
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spawned {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub dir: Option<PathBuf>,
+    pub creation_flags: u32,
+}
+
+/// Records process starts instead of performing them
+#[derive(Default)]
+pub struct RecordingSpawner {
+    pub spawned: Mutex<Vec<Spawned>>,
+}
+
+impl RecordingSpawner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawned(&self) -> Vec<Spawned> {
+        self.spawned.lock().unwrap().clone()
+    }
+}
+
+impl ProcessSpawner for RecordingSpawner {
+    fn spawn(
+        &self,
+        program: &Path,
+        args: &[String],
+        env: &[(String, String)],
+        dir: Option<&Path>,
+        creation_flags: u32,
+    ) -> Result<(), String> {
+        self.spawned.lock().unwrap().push(Spawned {
+            program: program.to_path_buf(),
+            args: args.to_vec(),
+            env: env.to_vec(),
+            dir: dir.map(Path::to_path_buf),
+            creation_flags,
+        });
+        Ok(())
+    }
+}
+
+/// Size of the pieces FakeHttp streams a body in, small so that tests see
+/// several
+const STREAM_CHUNK: usize = 4;
+
+/// Canned responses by URL; unknown URLs fail like a network error
+#[derive(Default)]
+pub struct FakeHttp {
+    responses: HashMap<String, HttpResponse>,
+    pub requested: Mutex<Vec<String>>,
+    /// Headers sent with each get(), in the order of `requested`
+    pub sent_headers: Mutex<Vec<Vec<(String, String)>>>,
+}
+
+impl FakeHttp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_response(mut self, url: &str, status: u16, body: impl Into<Vec<u8>>) -> Self {
+        self.responses
+            .insert(url.to_string(), HttpResponse { status, headers: Vec::new(), body: body.into() });
+        self
+    }
+
+    /// Add headers to the response for `url` (set with with_response first)
+    pub fn with_response_headers(mut self, url: &str, headers: &[(&str, &str)]) -> Self {
+        let response = self.responses.get_mut(url).expect("with_response before with_response_headers");
+        response
+            .headers
+            .extend(headers.iter().map(|(name, value)| (name.to_string(), value.to_string())));
+        self
+    }
+}
+
+impl HttpClient for FakeHttp {
+    fn get(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<HttpResponse, String>> + Send {
+        self.requested.lock().unwrap().push(url.to_string());
+        self.sent_headers
+            .lock()
+            .unwrap()
+            .push(headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect());
+        let response = self
+            .responses
+            .get(url)
+            .cloned()
+            .ok_or_else(|| format!("Request to {} failed: no route", url));
+        async move { response }
+    }
+
+    fn get_streaming(
+        &self,
+        url: &str,
+        on_chunk: &mut (dyn FnMut(&[u8], Option<u64>) -> Result<(), String> + Send),
+    ) -> impl Future<Output = Result<u16, String>> + Send {
+        self.requested.lock().unwrap().push(url.to_string());
+        let response = self
+            .responses
+            .get(url)
+            .cloned()
+            .ok_or_else(|| format!("Request to {} failed: no route", url));
+        async move {
+            let response = response?;
+            if response.is_success() {
+                let total = Some(response.body.len() as u64);
+                for chunk in response.body.chunks(STREAM_CHUNK) {
+                    on_chunk(chunk, total)?;
+                }
+            }
+            Ok(response.status)
+        }
+    }
+}
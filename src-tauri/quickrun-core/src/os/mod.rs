@@ -0,0 +1,405 @@
+// os/mod.rs - Seams over the operating system
+//
+// The parts of QuickRun that decide things (which file a command resolves
+// to, whether startup is enabled, whether an update is newer) are written
+// against these traits instead of calling the OS directly:
+// - FileSystem: existence checks, directory listings, writing, copying,
+//   renaming and deleting files
+// - Registry: string and DWORD values and keys under HKEY_CURRENT_USER
+// - ProcessSpawner: starting programs
+// - HttpClient: GET requests, whole or streamed
+//
+// The System* types are the real implementations used by the app. The
+// in-memory ones in memory.rs are for unit tests, so that logic can be
+// tested on any machine, not just a Windows box with the right setup.
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod memory;
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The file system operations the resolver and updater need
+pub trait FileSystem: Send + Sync {
+    fn is_file(&self, path: &Path) -> bool;
+    fn is_dir(&self, path: &Path) -> bool;
+    /// Full paths of the entries directly inside `dir` (empty if unreadable)
+    fn read_dir(&self, dir: &Path) -> Vec<PathBuf>;
+    fn read(&self, path: &Path) -> Result<Vec<u8>, String>;
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), String>;
+    /// Add `data` to the end of a file, creating it if needed
+    fn append(&self, path: &Path, data: &[u8]) -> Result<(), String>;
+    /// Delete a file (not an error if it's missing)
+    fn remove_file(&self, path: &Path) -> Result<(), String>;
+    /// Rename a file (on the same drive), replacing any file called `to`
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), String>;
+    /// Copy a file, replacing any file called `to` and creating its folder
+    /// if needed
+    fn copy(&self, from: &Path, to: &Path) -> Result<(), String>;
+}
+
+/// String and DWORD values and keys under HKEY_CURRENT_USER
+pub trait Registry: Send + Sync {
+    /// The value, or None if the key or value doesn't exist
+    fn get_string(&self, key: &str, name: &str) -> Result<Option<String>, String>;
+    /// The DWORD value, or None if the key or value doesn't exist
+    fn get_dword(&self, key: &str, name: &str) -> Result<Option<u32>, String>;
+    fn set_string(&self, key: &str, name: &str, value: &str) -> Result<(), String>;
+    fn delete_value(&self, key: &str, name: &str) -> Result<(), String>;
+    /// Delete `key` with its values and subkeys (not an error if it's missing)
+    fn delete_key(&self, key: &str) -> Result<(), String>;
+    /// Names of the keys directly under `key` (empty if it doesn't exist)
+    fn subkeys(&self, key: &str) -> Result<Vec<String>, String>;
+}
+
+/// Starting programs
+pub trait ProcessSpawner: Send + Sync {
+    /// Start `program` with `args` without waiting for it
+    ///
+    /// `env` is set on top of the inherited environment. `dir` is the
+    /// working directory (None = QuickRun's own). `creation_flags` are
+    /// Windows process creation flags (e.g., CREATE_NO_WINDOW); they are
+    /// ignored elsewhere.
+    fn spawn(
+        &self,
+        program: &Path,
+        args: &[String],
+        env: &[(String, String)],
+        dir: Option<&Path>,
+        creation_flags: u32,
+    ) -> Result<(), String>;
+}
+
+/// A completed HTTP response
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// (name, value) of each header, names as the server wrote them
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// The value of header `name` (any case), if the response has it
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+}
+
+/// HTTP GET requests
+pub trait HttpClient: Send + Sync {
+    fn get(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<HttpResponse, String>> + Send;
+
+    /// GET `url`, handing the body to `on_chunk` piece by piece as it
+    /// arrives, with the size the server announced (if it did), instead of
+    /// holding all of it. `on_chunk` returning an error stops the download
+    /// with that error. Returns the status; an unsuccessful response's body
+    /// isn't read.
+    fn get_streaming(
+        &self,
+        url: &str,
+        on_chunk: &mut (dyn FnMut(&[u8], Option<u64>) -> Result<(), String> + Send),
+    ) -> impl Future<Output = Result<u16, String>> + Send;
+}
+
+/// The real file system
+pub struct SystemFs;
+
+impl FileSystem for SystemFs {
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn read_dir(&self, dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default()
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>, String> {
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), String> {
+        std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    fn append(&self, path: &Path, data: &[u8]) -> Result<(), String> {
+        use std::io::Write;
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(data))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<(), String> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to delete {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), String> {
+        std::fs::rename(from, to)
+            .map_err(|e| format!("Failed to rename {} to {}: {}", from.display(), to.display(), e))
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> Result<(), String> {
+        if let Some(dir) = to.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {} to {}: {}", from.display(), to.display(), e))
+    }
+}
+
+/// The real registry (HKEY_CURRENT_USER)
+pub struct SystemRegistry;
+
+#[cfg(windows)]
+impl Registry for SystemRegistry {
+    fn get_string(&self, key: &str, name: &str) -> Result<Option<String>, String> {
+        use winreg::enums::HKEY_CURRENT_USER;
+        use winreg::RegKey;
+
+        let key = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(key) {
+            Ok(key) => key,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to open registry: {}", e)),
+        };
+        Ok(key.get_value::<String, _>(name).ok())
+    }
+
+    fn get_dword(&self, key: &str, name: &str) -> Result<Option<u32>, String> {
+        use winreg::enums::HKEY_CURRENT_USER;
+        use winreg::RegKey;
+
+        let key = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(key) {
+            Ok(key) => key,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to open registry: {}", e)),
+        };
+        Ok(key.get_value::<u32, _>(name).ok())
+    }
+
+    fn set_string(&self, key: &str, name: &str, value: &str) -> Result<(), String> {
+        use winreg::enums::HKEY_CURRENT_USER;
+        use winreg::RegKey;
+
+        let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+            .create_subkey(key)
+            .map_err(|e| format!("Failed to open registry: {}", e))?;
+        key.set_value(name, &value)
+            .map_err(|e| format!("Failed to set registry value: {}", e))
+    }
+
+    fn delete_value(&self, key: &str, name: &str) -> Result<(), String> {
+        use winreg::enums::{HKEY_CURRENT_USER, KEY_WRITE};
+        use winreg::RegKey;
+
+        let key = RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey_with_flags(key, KEY_WRITE)
+            .map_err(|e| format!("Failed to open registry: {}", e))?;
+        key.delete_value(name)
+            .map_err(|e| format!("Failed to delete registry value: {}", e))
+    }
+
+    fn delete_key(&self, key: &str) -> Result<(), String> {
+        use winreg::enums::HKEY_CURRENT_USER;
+        use winreg::RegKey;
+
+        match RegKey::predef(HKEY_CURRENT_USER).delete_subkey_all(key) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to delete registry key: {}", e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn subkeys(&self, key: &str) -> Result<Vec<String>, String> {
+        use winreg::enums::HKEY_CURRENT_USER;
+        use winreg::RegKey;
+
+        let key = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(key) {
+            Ok(key) => key,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to open registry: {}", e)),
+        };
+        key.enum_keys()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to list registry keys: {}", e))
+    }
+}
+
+#[cfg(not(windows))]
+impl Registry for SystemRegistry {
+    fn get_string(&self, _key: &str, _name: &str) -> Result<Option<String>, String> {
+        Ok(None)
+    }
+
+    fn get_dword(&self, _key: &str, _name: &str) -> Result<Option<u32>, String> {
+        Ok(None)
+    }
+
+    fn set_string(&self, _key: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Err("The registry is only available on Windows".to_string())
+    }
+
+    fn delete_value(&self, _key: &str, _name: &str) -> Result<(), String> {
+        Err("The registry is only available on Windows".to_string())
+    }
+
+    fn delete_key(&self, _key: &str) -> Result<(), String> {
+        Err("The registry is only available on Windows".to_string())
+    }
+
+    fn subkeys(&self, _key: &str) -> Result<Vec<String>, String> {
+        Ok(Vec::new())
+    }
+}
+
+/// Starts real processes
+pub struct SystemSpawner;
+
+impl ProcessSpawner for SystemSpawner {
+    fn spawn(
+        &self,
+        program: &Path,
+        args: &[String],
+        env: &[(String, String)],
+        dir: Option<&Path>,
+        creation_flags: u32,
+    ) -> Result<(), String> {
+        let mut command = std::process::Command::new(program);
+        command.args(args).envs(env.iter().cloned());
+        if let Some(dir) = dir {
+            command.current_dir(dir);
+        }
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(creation_flags);
+        }
+        #[cfg(not(windows))]
+        let _ = creation_flags;
+
+        command
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to spawn process: {}", e))
+    }
+}
+
+/// HTTP over reqwest
+pub struct SystemHttp {
+    user_agent: String,
+    timeout: Duration,
+}
+
+impl SystemHttp {
+    /// `user_agent` should name the app and its version (e.g., "QuickRun/0.2.0")
+    pub fn new(user_agent: impl Into<String>, timeout: Duration) -> Self {
+        Self { user_agent: user_agent.into(), timeout }
+    }
+
+    fn client(&self) -> Result<reqwest::Client, String> {
+        reqwest::Client::builder()
+            .user_agent(self.user_agent.as_str())
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))
+    }
+}
+
+impl HttpClient for SystemHttp {
+    fn get(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<HttpResponse, String>> + Send {
+        let client = self.client();
+        let url = url.to_string();
+        let headers: Vec<(String, String)> =
+            headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+
+        async move {
+            let client = client?;
+            let mut request = client.get(&url);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect();
+            let body = response
+                .bytes()
+                .await
+                .map_err(|e| format!("Failed to read response: {}", e))?;
+            Ok(HttpResponse { status, headers, body: body.to_vec() })
+        }
+    }
+
+    fn get_streaming(
+        &self,
+        url: &str,
+        on_chunk: &mut (dyn FnMut(&[u8], Option<u64>) -> Result<(), String> + Send),
+    ) -> impl Future<Output = Result<u16, String>> + Send {
+        let client = self.client();
+        let url = url.to_string();
+
+        async move {
+            let mut response = client?
+                .get(&url)
+                .send()
+                .await
+                .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+            let status = response.status().as_u16();
+            if !response.status().is_success() {
+                return Ok(status);
+            }
+            let total = response.content_length();
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| format!("Failed to read response: {}", e))?
+            {
+                on_chunk(&chunk, total)?;
+            }
+            Ok(status)
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// path_completion.rs - Filesystem path completion
+//
+// When the input looks like a partial path ("C:\Prog", "~\Dow", ".\scr"),
+// list the directory entries that could complete it so Tab can cycle
+// through them, like a shell does.
+//
+// Rules:
+// - The text after the last separator is matched as a case-insensitive prefix
+// - Directories come first and get a trailing "\" so the next Tab descends
+// - Hidden and system files are skipped unless the prefix starts with "."
+// - "~" at the start means the user's home folder (kept as "~" in the output)
+
+use std::path::{Path, PathBuf};
+
+/// Maximum number of completions returned (huge folders like System32)
+const MAX_COMPLETIONS: usize = 200;
+
+/// Split input into (directory part including trailing separator, name prefix)
+///
+/// "C:\Prog" → ("C:\", "Prog"), "~\Dow" → ("~\", "Dow"), "C:\" → ("C:\", "")
+fn split_at_last_separator(input: &str) -> Option<(&str, &str)> {
+    let idx = input.rfind(['\\', '/'])?;
+    Some((&input[..=idx], &input[idx + 1..]))
+}
+
+/// Is this entry hidden (or a system file) and should be skipped?
+#[cfg(windows)]
+fn is_hidden(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+
+    std::fs::symlink_metadata(path)
+        .map(|m| m.file_attributes() & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM) != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// List completions for a partial path
+///
+/// Returns full replacement inputs (the typed directory part + entry name),
+/// directories first, each group sorted alphabetically. Empty if the input
+/// doesn't look like a path or the directory can't be read.
+pub fn complete_path(input: &str) -> Vec<String> {
+    if !crate::runner::is_explicit_path(input) {
+        return Vec::new();
+    }
+
+    let Some((dir_part, prefix)) = split_at_last_separator(input) else {
+        return Vec::new();
+    };
+
+    let dir: PathBuf = crate::runner::expand_home(dir_part);
+    let Ok(read_dir) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let show_hidden = prefix.starts_with('.');
+    let prefix_lower = prefix.to_lowercase();
+
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+
+    for entry in read_dir.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.to_lowercase().starts_with(&prefix_lower) {
+            continue;
+        }
+
+        let path = entry.path();
+        if !show_hidden && is_hidden(&path) {
+            continue;
+        }
+
+        if path.is_dir() {
+            dirs.push(format!("{}{}\\", dir_part, name));
+        } else {
+            files.push(format!("{}{}", dir_part, name));
+        }
+    }
+
+    dirs.sort_by_key(|d| d.to_lowercase());
+    files.sort_by_key(|f| f.to_lowercase());
+
+    dirs.into_iter().chain(files).take(MAX_COMPLETIONS).collect()
+}
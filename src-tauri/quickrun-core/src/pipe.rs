@@ -0,0 +1,107 @@
+// pipe.rs - "file | program": open a result with a chosen program
+//
+// Like Explorer's "Open with", a file or folder can be handed to an alias
+// (or a program on PATH) by typing it after a |:
+//
+//   ~\Pictures\cat.png | gimp
+//   "C:\My Files\report.pdf" | sumatra
+//
+// The program gets the path as its last argument (see runner::open_with).
+// In the launcher, pressing | with a file result selected puts that file in
+// front, and the aliases are then suggested as "Open with ..." rows.
+//
+// The left side must be an existing file or folder (relative paths are in
+// the folder the command runs in); otherwise the | is left alone, so it can
+// still be part of a bang search or an argument. The ">" prefix is never
+// split: a | there is the shell's.
+
+use crate::runner;
+use std::path::{Path, PathBuf};
+
+/// Split `input` at its last | outside double quotes: the subject (without
+/// surrounding quotes) and the program (may be empty while it's typed)
+pub fn split(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim();
+    if input.starts_with('>') {
+        return None;
+    }
+
+    let mut in_quotes = false;
+    let mut bar = None;
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '|' if !in_quotes => bar = Some(i),
+            _ => {}
+        }
+    }
+
+    let bar = bar?;
+    let subject = input[..bar].trim();
+    let subject = subject
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(subject);
+    if subject.is_empty() {
+        return None;
+    }
+    Some((subject, input[bar + 1..].trim()))
+}
+
+/// The existing file or folder on the left of `input`'s |, and the program
+/// on the right (relative paths are looked up in `dir`)
+pub fn parse<'a>(input: &'a str, dir: Option<&Path>) -> Option<(PathBuf, &'a str)> {
+    let (subject, program) = split(input)?;
+    let target = runner::explicit_path(subject, dir);
+    target.exists().then_some((target, program))
+}
+
+/// The input that hands `target` to `program` (`"C:\cat.png" | gimp`)
+pub fn compose(target: &str, program: &str) -> String {
+    format!("\"{}\" | {}", target, program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_last_bar_splits_subject_and_program() {
+        assert_eq!(split("~\\cat.png | gimp"), Some(("~\\cat.png", "gimp")));
+        assert_eq!(split("a|b | gimp --new"), Some(("a|b", "gimp --new")));
+        assert_eq!(split("report.pdf |"), Some(("report.pdf", "")));
+    }
+
+    #[test]
+    fn quoted_subjects_may_contain_bars() {
+        assert_eq!(split("\"C:\\a | b.txt\" | notepad"), Some(("C:\\a | b.txt", "notepad")));
+        assert_eq!(split("\"C:\\a | b.txt\""), None);
+    }
+
+    #[test]
+    fn terminal_commands_and_empty_subjects_are_not_split() {
+        assert_eq!(split("> dir | findstr foo"), None);
+        assert_eq!(split("| gimp"), None);
+        assert_eq!(split("notepad"), None);
+    }
+
+    #[test]
+    fn composed_input_splits_back() {
+        let input = compose("C:\\My Files\\cat.png", "gimp");
+        assert_eq!(split(&input), Some(("C:\\My Files\\cat.png", "gimp")));
+    }
+
+    #[test]
+    fn only_existing_files_are_piped() {
+        let dir = std::env::temp_dir();
+        assert!(parse("quickrun-pipe-missing.png | gimp", Some(&dir)).is_none());
+
+        let name = format!("quickrun-pipe-{}.txt", std::process::id());
+        let file = dir.join(&name);
+        std::fs::write(&file, "").unwrap();
+        let input = format!("{} | notepad", name);
+        let parsed = parse(&input, Some(&dir));
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(parsed, Some((file, "notepad")));
+    }
+}
@@ -0,0 +1,231 @@
+// plugins/host.rs - Plugin process lifecycle and JSON-RPC transport
+//
+// Protocol: JSON-RPC 2.0, one JSON message per line, requests on the
+// plugin's stdin and responses on its stdout. Anything the plugin writes to
+// stderr is passed through to QuickRun's log.
+//
+//   → {"jsonrpc":"2.0","id":1,"method":"initialize","params":{"version":"1.2.0"}}
+//   ← {"jsonrpc":"2.0","id":1,"result":{}}
+//   → {"jsonrpc":"2.0","id":2,"method":"query","params":{"query":"quick"}}
+//   ← {"jsonrpc":"2.0","id":2,"result":{"results":[{"title":"...","subtitle":"...","data":{...}}]}}
+//   → {"jsonrpc":"2.0","id":3,"method":"execute","params":{"data":{...}}}
+//   ← {"jsonrpc":"2.0","id":3,"result":{}}                    (hide the launcher)
+//   ← {"jsonrpc":"2.0","id":3,"result":{"results":[...]}}     (show these rows)
+//   → {"jsonrpc":"2.0","method":"cancel","params":{"id":2}}   (notification)
+//
+// "cancel" is sent when the user typed on before a query was answered; a
+// plugin may stop working on that request, and any answer it still sends is
+// discarded. Plugins that don't know the method can ignore it.
+//
+// Lifecycle:
+// - The process is started on the first request and kept running
+// - A response that misses its deadline is dropped (and discarded if it
+//   arrives later - every request has its own id)
+// - If the process exits it is restarted on the next request, at most once
+//   per RESTART_BACKOFF so a crashing plugin doesn't spin
+
+use super::Manifest;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Minimum time between restarts of a plugin that exited
+const RESTART_BACKOFF: Duration = Duration::from_secs(10);
+
+/// How often a pending request checks whether it was cancelled
+const CANCEL_POLL: Duration = Duration::from_millis(20);
+
+/// A started plugin process
+struct Running {
+    child: Child,
+    stdin: ChildStdin,
+    /// Parsed lines from stdout (a reader thread feeds this)
+    responses: Receiver<Value>,
+    next_id: u64,
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+/// One plugin: its manifest and (maybe) its running process
+pub struct PluginHost {
+    pub manifest: Manifest,
+    dir: PathBuf,
+    process: Mutex<Option<Running>>,
+    last_start: Mutex<Option<Instant>>,
+}
+
+impl PluginHost {
+    pub fn new(manifest: Manifest, dir: PathBuf) -> Self {
+        Self {
+            manifest,
+            dir,
+            process: Mutex::new(None),
+            last_start: Mutex::new(None),
+        }
+    }
+
+    /// The plugin's executable, if it's a file in the plugin folder
+    pub fn executable_path(&self) -> Option<PathBuf> {
+        Some(self.dir.join(&self.manifest.executable)).filter(|p| p.is_file())
+    }
+
+    /// Start the process and send "initialize"
+    fn start(&self) -> Result<Running, String> {
+        {
+            let mut last_start = self.last_start.lock().unwrap_or_else(|e| e.into_inner());
+            if last_start.is_some_and(|t| t.elapsed() < RESTART_BACKOFF) {
+                return Err("restarting too often, waiting".to_string());
+            }
+            *last_start = Some(Instant::now());
+        }
+
+        let program = self
+            .executable_path()
+            .unwrap_or_else(|| PathBuf::from(&self.manifest.executable));
+
+        let mut command = Command::new(&program);
+        command
+            .args(&self.manifest.args)
+            .current_dir(&self.dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Failed to start '{}': {}", program.display(), e))?;
+
+        let stdin = child.stdin.take().ok_or("Plugin has no stdin")?;
+        let stdout = child.stdout.take().ok_or("Plugin has no stdout")?;
+        let stderr = child.stderr.take().ok_or("Plugin has no stderr")?;
+
+        let (sender, responses) = mpsc::channel();
+        let name = self.manifest.name.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                match serde_json::from_str::<Value>(&line) {
+                    Ok(message) => {
+                        if sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => eprintln!("[Plugins] {}: ignoring non-JSON output: {}", name, line),
+                }
+            }
+        });
+
+        let name = self.manifest.name.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                eprintln!("[Plugins] {}: {}", name, line);
+            }
+        });
+
+        let mut running = Running { child, stdin, responses, next_id: 1 };
+        let params = serde_json::json!({ "version": env!("CARGO_PKG_VERSION") });
+        send_request(&mut running, "initialize", params, Duration::from_secs(5))?;
+
+        eprintln!("[Plugins] Started '{}'", self.manifest.name);
+        Ok(running)
+    }
+
+    /// Send a request and wait up to `timeout_ms` for its result
+    ///
+    /// Requests to the same plugin are serialized; different plugins run in
+    /// parallel.
+    pub fn request(&self, method: &str, params: Value, timeout_ms: u64) -> Result<Value, String> {
+        self.request_cancellable(method, params, timeout_ms, &CancellationToken::new())
+    }
+
+    /// Like request, but gives up early (and tells the plugin) once `cancel`
+    /// is cancelled
+    pub fn request_cancellable(
+        &self,
+        method: &str,
+        params: Value,
+        timeout_ms: u64,
+        cancel: &CancellationToken,
+    ) -> Result<Value, String> {
+        let mut process = self.process.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Restart if it was never started or has exited
+        let alive = process
+            .as_mut()
+            .is_some_and(|running| matches!(running.child.try_wait(), Ok(None)));
+        if !alive {
+            *process = Some(self.start()?);
+        }
+
+        let running = process.as_mut().ok_or("Plugin is not running")?;
+        let result = send_request(running, method, params, Duration::from_millis(timeout_ms), cancel);
+
+        // A broken pipe means the process died mid-request; start fresh next time
+        if matches!(&result, Err(e) if e.starts_with("Failed to write")) {
+            *process = None;
+        }
+        result
+    }
+}
+
+/// Write one request and wait for the response with the same id
+fn send_request(
+    running: &mut Running,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+    cancel: &CancellationToken,
+) -> Result<Value, String> {
+    let id = running.next_id;
+    running.next_id += 1;
+
+    let request = serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+    writeln!(running.stdin, "{}", request)
+        .and_then(|_| running.stdin.flush())
+        .map_err(|e| format!("Failed to write to plugin: {}", e))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if cancel.is_cancelled() {
+            let notice = serde_json::json!({ "jsonrpc": "2.0", "method": "cancel", "params": { "id": id } });
+            let _ = writeln!(running.stdin, "{}", notice).and_then(|_| running.stdin.flush());
+            return Err(format!("'{}' cancelled", method));
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(format!("'{}' timed out after {} ms", method, timeout.as_millis()));
+        }
+        let message = match running.responses.recv_timeout(remaining.min(CANCEL_POLL)) {
+            Ok(message) => message,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return Err("Plugin exited".to_string()),
+        };
+
+        // Late answers to earlier (timed out) requests are skipped
+        if message.get("id").and_then(Value::as_u64) != Some(id) {
+            continue;
+        }
+        if let Some(error) = message.get("error") {
+            let text = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+            return Err(format!("'{}' failed: {}", method, text));
+        }
+        return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+    }
+}
@@ -0,0 +1,254 @@
+// plugins/mod.rs - External plugins
+//
+// A plugin is a program in any language that QuickRun starts and talks to
+// over stdin/stdout (see host.rs for the protocol). Plugins contribute
+// suggestion rows and handle what happens when one is selected.
+//
+// Each plugin lives in its own folder under %APPDATA%\QuickRun\plugins with
+// a plugin.json manifest:
+//
+//   {
+//     "name": "github",
+//     "description": "Search my GitHub repositories",
+//     "executable": "github-plugin.exe",
+//     "args": [],
+//     "prefix": "gh",
+//     "timeout_ms": 500
+//   }
+//
+// - executable: relative to the plugin folder (or a program on PATH, e.g.
+//   "python" with "args": ["plugin.py"])
+// - prefix (optional): the plugin is only asked when the input starts with
+//   this word ("gh quickrun"), and its rows come first. Without a prefix it
+//   is asked for every query and its rows are merged with the others.
+// - timeout_ms (optional, default 500): how long a query may take before
+//   its answer is dropped, so a slow plugin can't stall typing
+//
+// Plugins are discovered once, on first use; processes are started lazily
+// and restarted if they exit.
+
+mod host;
+
+use crate::results::{Action, ResultRow};
+use host::PluginHost;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use tokio_util::sync::CancellationToken;
+
+/// Default per-query time budget
+const DEFAULT_TIMEOUT_MS: u64 = 500;
+
+/// Score given to plugin rows that don't set one (between "word start" and
+/// "prefix" matches of the built-in sources)
+const DEFAULT_SCORE: u32 = 70;
+
+/// Rows from a plugin selected by its prefix rank above everything else
+const PREFIX_SCORE_BONUS: u32 = 200;
+
+/// plugin.json
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub executable: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// A row returned by a plugin's "query" method
+#[derive(Debug, Clone, Deserialize)]
+struct PluginResult {
+    title: String,
+    #[serde(default)]
+    subtitle: String,
+    /// 0-100, higher is better
+    #[serde(default)]
+    score: Option<u32>,
+    /// A built-in action (e.g., {"type": "open", "value": "https://..."});
+    /// when absent, selecting the row calls the plugin's "execute" method
+    #[serde(default)]
+    action: Option<Action>,
+    /// Opaque data handed back to "execute"
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+/// A plugin row, ready to be merged into suggestions
+pub struct Suggestion {
+    pub score: u32,
+    pub title: String,
+    pub subtitle: String,
+    /// File whose icon represents the row (the plugin's executable)
+    pub icon_path: Option<String>,
+    pub action: Action,
+}
+
+/// Folder containing plugin folders
+fn plugins_dir() -> PathBuf {
+    crate::get_config_dir().join("plugins")
+}
+
+/// Discover plugins (once)
+fn hosts() -> &'static [Arc<PluginHost>] {
+    static HOSTS: OnceLock<Vec<Arc<PluginHost>>> = OnceLock::new();
+    HOSTS.get_or_init(|| {
+        let Ok(entries) = std::fs::read_dir(plugins_dir()) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let dir = entry.path();
+                let json = std::fs::read_to_string(dir.join("plugin.json")).ok()?;
+                match serde_json::from_str::<Manifest>(&json) {
+                    Ok(manifest) => {
+                        eprintln!("[Plugins] Found plugin '{}' in {}", manifest.name, dir.display());
+                        Some(Arc::new(PluginHost::new(manifest, dir)))
+                    }
+                    Err(e) => {
+                        eprintln!("[Plugins] Invalid plugin.json in {}: {}", dir.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    })
+}
+
+/// Ask one plugin for rows
+fn query_host(host: &PluginHost, query: &str, bonus: u32, cancel: &CancellationToken) -> Vec<Suggestion> {
+    let timeout = host.manifest.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS);
+    let params = serde_json::json!({ "query": query });
+    let response = match host.request_cancellable("query", params, timeout, cancel) {
+        Ok(response) => response,
+        Err(_) if cancel.is_cancelled() => return Vec::new(),
+        Err(e) => {
+            eprintln!("[Plugins] {}: {}", host.manifest.name, e);
+            return Vec::new();
+        }
+    };
+
+    let results: Vec<PluginResult> = response
+        .get("results")
+        .cloned()
+        .and_then(|results| serde_json::from_value(results).ok())
+        .unwrap_or_default();
+
+    results
+        .into_iter()
+        .map(|result| Suggestion {
+            score: result.score.unwrap_or(DEFAULT_SCORE).min(100) + bonus,
+            title: result.title,
+            subtitle: result.subtitle,
+            icon_path: host.executable_path().map(|p| p.to_string_lossy().to_string()),
+            action: result.action.unwrap_or(Action::Plugin {
+                plugin: host.manifest.name.clone(),
+                data: result.data,
+            }),
+        })
+        .collect()
+}
+
+/// Query the plugins that apply to `query`, in parallel
+///
+/// If the first word is a plugin's prefix, only that plugin is asked (with
+/// the rest of the input); otherwise every prefix-less plugin is. Pending
+/// requests are abandoned once `cancel` is cancelled.
+pub fn query(query: &str, cancel: &CancellationToken) -> Vec<Suggestion> {
+    let hosts = hosts();
+    if hosts.is_empty() {
+        return Vec::new();
+    }
+
+    let (first, rest) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+    let prefixed: Vec<&Arc<PluginHost>> = hosts
+        .iter()
+        .filter(|h| h.manifest.prefix.as_deref().is_some_and(|p| p.eq_ignore_ascii_case(first)))
+        .collect();
+
+    let (targets, text, bonus) = if prefixed.is_empty() {
+        let global: Vec<&Arc<PluginHost>> = hosts.iter().filter(|h| h.manifest.prefix.is_none()).collect();
+        (global, query, 0)
+    } else {
+        (prefixed, rest.trim(), PREFIX_SCORE_BONUS)
+    };
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = targets
+            .into_iter()
+            .map(|host| scope.spawn(move || query_host(host, text, bonus, cancel)))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+/// Does the input start with a plugin's prefix? The plugin then answers it,
+/// before any bang with the same keyword.
+pub fn has_prefix(input: &str) -> bool {
+    let Some(first) = input.split_whitespace().next() else {
+        return false;
+    };
+    hosts()
+        .iter()
+        .any(|h| h.manifest.prefix.as_deref().is_some_and(|p| p.eq_ignore_ascii_case(first)))
+}
+
+/// If the input starts with a plugin prefix, the plugin's rows (for pressing
+/// Enter without choosing a suggestion)
+pub fn prefixed_rows(input: &str) -> Option<Vec<ResultRow>> {
+    has_prefix(input).then(|| {
+        query(input.trim(), &CancellationToken::new())
+            .into_iter()
+            .map(|s| ResultRow::new(s.title, s.subtitle).with_action(s.action))
+            .collect()
+    })
+}
+
+/// What a plugin's "execute" asked for
+pub enum Outcome {
+    /// Done; hide the launcher
+    Hide,
+    /// Show these rows
+    Rows(Vec<ResultRow>),
+}
+
+/// Ask `plugin` to perform the action for a row it returned
+pub fn execute(plugin: &str, data: serde_json::Value) -> Result<Outcome, String> {
+    let host = hosts()
+        .iter()
+        .find(|h| h.manifest.name == plugin)
+        .ok_or_else(|| format!("Plugin '{}' is not installed", plugin))?;
+
+    // Actions may do real work (open things, call APIs): allow more time
+    let timeout = host.manifest.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS).max(5000);
+    let response = host.request("execute", serde_json::json!({ "data": data }), timeout)?;
+
+    let rows: Option<Vec<PluginResult>> = response
+        .get("results")
+        .cloned()
+        .and_then(|results| serde_json::from_value(results).ok());
+
+    Ok(match rows {
+        Some(rows) => Outcome::Rows(
+            rows.into_iter()
+                .map(|r| {
+                    ResultRow::new(r.title, r.subtitle).with_action(r.action.unwrap_or(Action::Plugin {
+                        plugin: plugin.to_string(),
+                        data: r.data,
+                    }))
+                })
+                .collect(),
+        ),
+        None => Outcome::Hide,
+    })
+}
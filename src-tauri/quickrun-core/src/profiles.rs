@@ -0,0 +1,217 @@
+// profiles.rs - Named configuration profiles
+//
+// A profile is a set of settings and aliases, so "work" can have a different
+// hotkey, theme, aliases and suggestion sources than "home" or "demo":
+// - The "default" profile is settings.json and aliases.json in the config
+//   folder, exactly as before profiles existed
+// - Any other profile keeps its own pair in profiles\<name>\
+// - History, the index cache and everything else are shared
+//
+// The active profile is recorded in profile.json ({ "active": "work" }), so
+// it survives restarts and qr follows it too. Switching to a profile that
+// doesn't exist yet creates it as a copy of the current one.
+
+use crate::os::{FileSystem, SystemFs};
+use crate::{settings, storage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// The profile that lives directly in the config folder
+pub const DEFAULT: &str = "default";
+
+/// Files that belong to a profile (copied when a new one is created)
+const PROFILE_FILES: &[&str] = &[storage::SETTINGS_FILE, "aliases.json"];
+
+/// Longest profile name accepted
+const MAX_NAME_LEN: usize = 32;
+
+/// Contents of profile.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfileState {
+    #[serde(default)]
+    active: String,
+}
+
+/// The profiles and which one is active (for the Settings window and tray)
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+/// The active profile, once read from profile.json
+static ACTIVE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Path to the file recording the active profile
+fn state_path() -> PathBuf {
+    storage::get_config_dir().join("profile.json")
+}
+
+/// Folder holding the non-default profiles
+fn profiles_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join("profiles")
+}
+
+/// Folder holding the files of profile `name`
+fn dir_of(config_dir: &Path, name: &str) -> PathBuf {
+    if name.eq_ignore_ascii_case(DEFAULT) {
+        config_dir.to_path_buf()
+    } else {
+        profiles_dir(config_dir).join(name)
+    }
+}
+
+/// Check that `name` can be used as a profile (and folder) name
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if name.len() > MAX_NAME_LEN {
+        return Err(format!("Profile names can be at most {} characters", MAX_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Profile names can only use letters, digits, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+/// Profiles found in `config_dir`: "default" first, then the rest by name
+pub fn list_in(fs: &dyn FileSystem, config_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs
+        .read_dir(&profiles_dir(config_dir))
+        .into_iter()
+        .filter(|path| fs.is_dir(path))
+        .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .filter(|name| validate_name(name).is_ok() && !name.eq_ignore_ascii_case(DEFAULT))
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.insert(0, DEFAULT.to_string());
+    names
+}
+
+/// All profiles, "default" first
+pub fn list() -> Vec<String> {
+    list_in(&SystemFs, &storage::get_config_dir())
+}
+
+/// All profiles, and the active one
+pub fn overview() -> ProfileList {
+    ProfileList { active: active(), profiles: list() }
+}
+
+/// Name of the active profile
+///
+/// profile.json is read once; a profile that has since been deleted falls
+/// back to "default".
+pub fn active() -> String {
+    if let Some(name) = ACTIVE.read().ok().and_then(|active| active.clone()) {
+        return name;
+    }
+
+    let recorded = std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str::<ProfileState>(&contents).ok())
+        .map(|state| state.active)
+        .unwrap_or_default();
+    let name = list()
+        .into_iter()
+        .find(|name| name.eq_ignore_ascii_case(&recorded))
+        .unwrap_or_else(|| DEFAULT.to_string());
+
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = Some(name.clone());
+    }
+    name
+}
+
+/// Folder holding the active profile's settings.json and aliases.json
+pub fn dir() -> PathBuf {
+    let dir = dir_of(&storage::get_config_dir(), &active());
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Make `name` the active profile, creating it from the current one if
+/// it doesn't exist yet; returns the settings of the new profile
+pub fn switch(name: &str) -> Result<settings::Settings, String> {
+    let name = name.trim();
+    validate_name(name)?;
+    if storage::is_in_memory() {
+        return Err("Profiles can't be switched while settings can't be saved".to_string());
+    }
+
+    let config_dir = storage::get_config_dir();
+    // Use the existing spelling if the profile is already there
+    let name = list()
+        .into_iter()
+        .find(|existing| existing.eq_ignore_ascii_case(name))
+        .unwrap_or_else(|| name.to_string());
+
+    let target = dir_of(&config_dir, &name);
+    if !target.is_dir() {
+        std::fs::create_dir_all(&target)
+            .map_err(|e| format!("Failed to create profile '{}': {}", name, e))?;
+        let current = dir();
+        for file in PROFILE_FILES {
+            let source = current.join(file);
+            if source.is_file() {
+                std::fs::copy(&source, target.join(file))
+                    .map_err(|e| format!("Failed to create profile '{}': {}", name, e))?;
+            }
+        }
+        eprintln!("[Profiles] Created profile '{}'", name);
+    }
+
+    let json = serde_json::to_string_pretty(&ProfileState { active: name.clone() })
+        .map_err(|e| format!("Failed to serialize profile state: {}", e))?;
+    std::fs::write(state_path(), json).map_err(|e| format!("Failed to save active profile: {}", e))?;
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = Some(name.clone());
+    }
+
+    eprintln!("[Profiles] Switched to profile '{}'", name);
+    Ok(settings::reload())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::MemoryFs;
+
+    const CONFIG: &str = "C:\\Users\\me\\AppData\\Roaming\\QuickRun";
+
+    #[test]
+    fn the_default_profile_is_the_config_folder() {
+        let config = Path::new(CONFIG);
+        assert_eq!(dir_of(config, "default"), config);
+        assert_eq!(dir_of(config, "Default"), config);
+        assert_eq!(dir_of(config, "work"), config.join("profiles").join("work"));
+    }
+
+    #[test]
+    fn profiles_are_the_folders_under_profiles() {
+        let profiles = Path::new(CONFIG).join("profiles");
+        let fs = MemoryFs::new()
+            .with_file(profiles.join("work").join("settings.json"))
+            .with_file(profiles.join("Demo").join("aliases.json"))
+            .with_file(profiles.join("notes.txt"))
+            .with_file(profiles.join("bad name").join("settings.json"));
+        assert_eq!(list_in(&fs, Path::new(CONFIG)), ["default", "Demo", "work"]);
+    }
+
+    #[test]
+    fn with_no_profiles_folder_there_is_only_default() {
+        assert_eq!(list_in(&MemoryFs::new(), Path::new(CONFIG)), ["default"]);
+    }
+
+    #[test]
+    fn names_must_be_plain_words() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("home-pc_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("..").is_err());
+        assert!(validate_name("a\\b").is_err());
+        assert!(validate_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+}
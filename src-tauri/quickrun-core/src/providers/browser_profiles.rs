@@ -0,0 +1,209 @@
+// providers/browser_profiles.rs - Chrome, Edge and Brave profiles
+//
+// For people with several browser profiles (work, personal, a client's),
+// each one is suggested as "Chrome - Work", matching the profile's name
+// with or without the browser's ("work", "chrome work"). Choosing it starts
+// the browser with that profile (`chrome.exe --profile-directory="Profile 1"`),
+// or opens a new window in it if the browser is running.
+//
+// Profiles are read from the browser's Local State file (JSON, the
+// "profile" > "info_cache" object: profile folder → {"name": ...}):
+//   %LOCALAPPDATA%\Google\Chrome\User Data\Local State
+//   %LOCALAPPDATA%\Microsoft\Edge\User Data\Local State
+//   %LOCALAPPDATA%\BraveSoftware\Brave-Browser\User Data\Local State
+// A browser with only one profile is left out: the app itself does the
+// same. The browser's program is looked for where its installer puts it.
+
+use crate::ranking::match_score;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// A Chromium-based browser
+struct Browser {
+    name: &'static str,
+    /// Its User Data folder, under %LOCALAPPDATA%
+    user_data: &'static str,
+    /// Where its program may be, under %PROGRAMFILES%, %PROGRAMFILES(X86)%
+    /// or %LOCALAPPDATA% (per-user installs)
+    exe: &'static str,
+}
+
+const BROWSERS: [Browser; 3] = [
+    Browser {
+        name: "Chrome",
+        user_data: "Google\\Chrome\\User Data",
+        exe: "Google\\Chrome\\Application\\chrome.exe",
+    },
+    Browser {
+        name: "Edge",
+        user_data: "Microsoft\\Edge\\User Data",
+        exe: "Microsoft\\Edge\\Application\\msedge.exe",
+    },
+    Browser {
+        name: "Brave",
+        user_data: "BraveSoftware\\Brave-Browser\\User Data",
+        exe: "BraveSoftware\\Brave-Browser\\Application\\brave.exe",
+    },
+];
+
+/// Profiles found, reused until a Local State file's modified time changes
+static CACHE: Mutex<Option<(Vec<SystemTime>, Vec<Profile>)>> = Mutex::new(None);
+
+/// A browser profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// "Chrome", "Edge" or "Brave"
+    pub browser: &'static str,
+    /// The name shown in the browser ("Work")
+    pub name: String,
+    /// Its folder under User Data ("Default", "Profile 1")
+    pub directory: String,
+    /// The browser's program
+    pub exe: PathBuf,
+}
+
+impl Profile {
+    /// "Chrome - Work"
+    pub fn title(&self) -> String {
+        format!("{} - {}", self.browser, self.name)
+    }
+
+    /// The launcher input that opens the browser with this profile
+    pub fn command(&self) -> String {
+        format!("\"{}\" --profile-directory=\"{}\"", self.exe.display(), self.directory)
+    }
+
+    /// How well it matches `query`, by profile name or browser and name
+    pub fn score(&self, query: &str) -> Option<u32> {
+        match_score(&self.name, query).max(match_score(&format!("{} {}", self.browser, self.name), query))
+    }
+}
+
+/// Profiles of every installed browser that has more than one
+pub fn list() -> Vec<Profile> {
+    let Ok(local) = std::env::var("LOCALAPPDATA") else {
+        return Vec::new();
+    };
+    let local = PathBuf::from(local);
+    let files: Vec<(&Browser, PathBuf, SystemTime)> = BROWSERS
+        .iter()
+        .filter_map(|browser| {
+            let path = local.join(browser.user_data).join("Local State");
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((browser, path, modified))
+        })
+        .collect();
+    let modified: Vec<SystemTime> = files.iter().map(|(_, _, modified)| *modified).collect();
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_at, profiles)) = cache.as_ref() {
+        if *cached_at == modified {
+            return profiles.clone();
+        }
+    }
+
+    let mut profiles = Vec::new();
+    for (browser, path, _) in &files {
+        let Some(exe) = find_exe(browser, &local) else {
+            continue;
+        };
+        let local_state = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_str::<Value>(&contents).map_err(|e| e.to_string()));
+        match local_state {
+            Ok(local_state) => {
+                let found = parse(&local_state);
+                if found.len() > 1 {
+                    profiles.extend(found.into_iter().map(|(directory, name)| Profile {
+                        browser: browser.name,
+                        name,
+                        directory,
+                        exe: exe.clone(),
+                    }));
+                }
+            }
+            Err(e) => eprintln!("[BrowserProfiles] Failed to read '{}': {}", path.display(), e),
+        }
+    }
+    *cache = Some((modified, profiles.clone()));
+    profiles
+}
+
+/// The browser's program, if it's installed
+fn find_exe(browser: &Browser, local: &Path) -> Option<PathBuf> {
+    let program_files = ["ProgramFiles", "ProgramFiles(x86)"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok().map(PathBuf::from));
+    program_files
+        .chain(std::iter::once(local.to_path_buf()))
+        .map(|dir| dir.join(browser.exe))
+        .find(|exe| exe.is_file())
+}
+
+/// (profile folder, name) for each profile in a Local State file, in the
+/// order the browser lists them
+fn parse(local_state: &Value) -> Vec<(String, String)> {
+    let Some(info_cache) = local_state.pointer("/profile/info_cache").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let order: Vec<&str> = local_state
+        .pointer("/profile/profiles_order")
+        .and_then(Value::as_array)
+        .map(|order| order.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut profiles: Vec<(String, String)> = info_cache
+        .iter()
+        .filter_map(|(directory, info)| {
+            let name = info.get("name").and_then(Value::as_str)?.trim();
+            (!name.is_empty()).then(|| (directory.clone(), name.to_string()))
+        })
+        .collect();
+    // Profiles not in profiles_order go last, by folder
+    profiles.sort_by_key(|(directory, _)| {
+        (order.iter().position(|d| d == directory).unwrap_or(usize::MAX), directory.clone())
+    });
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn profiles_are_read_in_the_browsers_order() {
+        let local_state = json!({ "profile": {
+            "info_cache": {
+                "Default": { "name": "Personal", "is_using_default_name": false },
+                "Profile 1": { "name": "Work" },
+                "Profile 3": { "name": "Client X" },
+                "Profile 2": { "name": " " },
+            },
+            "profiles_order": ["Profile 1", "Default"],
+        } });
+        let profiles = parse(&local_state);
+        let names: Vec<&str> = profiles.iter().map(|(_, name)| name.as_str()).collect();
+        assert_eq!(names, ["Work", "Personal", "Client X"]);
+        assert_eq!(profiles[0].0, "Profile 1");
+        assert!(parse(&json!({ "browser": {} })).is_empty());
+    }
+
+    #[test]
+    fn profiles_open_with_their_folder() {
+        let profile = Profile {
+            browser: "Chrome",
+            name: "Work".to_string(),
+            directory: "Profile 1".to_string(),
+            exe: PathBuf::from("chrome.exe"),
+        };
+        assert_eq!(profile.title(), "Chrome - Work");
+        assert_eq!(profile.command(), "\"chrome.exe\" --profile-directory=\"Profile 1\"");
+        assert_eq!(profile.score("work"), Some(100));
+        assert_eq!(profile.score("chrome work"), Some(100));
+        assert_eq!(profile.score("chrome wo"), Some(80));
+        assert_eq!(profile.score("edge"), None);
+    }
+}
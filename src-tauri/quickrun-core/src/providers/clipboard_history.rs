@@ -0,0 +1,247 @@
+// providers/clipboard_history.rs - Opt-in clipboard history
+//
+// When enabled (setting "clipboard_history", off by default), a background
+// thread watches the clipboard and remembers the last N text entries
+// (setting "clipboard_history_size", default 25). The `clip` keyword searches
+// them; selecting one copies it back, and optionally pastes it into the
+// window that was active before the launcher opened
+// (setting "clipboard_paste_on_select").
+//
+// Privacy:
+// - Nothing is recorded while the setting is off (the thread just idles)
+// - Content marked by password managers as "exclude from clipboard history"
+//   (the same format Windows' own clipboard history honours) is skipped
+// - The history file is encrypted with DPAPI for the current user, so it
+//   is unreadable to other accounts and on other machines
+// - Entries older than 30 days are dropped (see retention.rs)
+// - `clip clear` forgets everything
+
+use crate::retention::{self, Limits};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Upper bound for the size setting
+const MAX_SIZE: usize = 500;
+/// Entries larger than this aren't recorded (huge pastes aren't worth keeping)
+const MAX_ENTRY_BYTES: usize = 100 * 1024;
+/// How often the clipboard is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// One remembered clipboard text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipEntry {
+    pub text: String,
+    /// Unix timestamp (seconds) of when it was copied
+    pub copied_at: u64,
+}
+
+/// In-memory history, most recent first (None until loaded from disk)
+static HISTORY: Mutex<Option<Vec<ClipEntry>>> = Mutex::new(None);
+
+/// Is clipboard history enabled in settings?
+pub fn is_enabled() -> bool {
+    crate::settings::load().clipboard_history
+}
+
+/// Should selecting an entry also paste it into the previous window?
+pub fn paste_on_select() -> bool {
+    crate::settings::load().clipboard_paste_on_select
+}
+
+fn max_entries() -> usize {
+    crate::settings::load().clipboard_history_size.clamp(1, MAX_SIZE)
+}
+
+fn history_path() -> PathBuf {
+    crate::get_config_dir().join("clipboard_history.dat")
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Encrypt/decrypt with DPAPI (current user scope)
+#[cfg(windows)]
+mod dpapi {
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::Security::Cryptography::{
+        CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    fn run(data: &[u8], protect: bool) -> Result<Vec<u8>, String> {
+        let input = CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        };
+        let mut output = CRYPT_INTEGER_BLOB::default();
+
+        unsafe {
+            let result = if protect {
+                CryptProtectData(&input, None, None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output)
+            } else {
+                CryptUnprotectData(&input, None, None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output)
+            };
+            result.map_err(|e| format!("DPAPI failed: {}", e))?;
+
+            let bytes = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+            let _ = LocalFree(Some(HLOCAL(output.pbData as *mut std::ffi::c_void)));
+            Ok(bytes)
+        }
+    }
+
+    pub fn protect(data: &[u8]) -> Result<Vec<u8>, String> {
+        run(data, true)
+    }
+
+    pub fn unprotect(data: &[u8]) -> Result<Vec<u8>, String> {
+        run(data, false)
+    }
+}
+
+/// Read the history file (empty if missing or unreadable)
+fn load_from_disk() -> Vec<ClipEntry> {
+    #[cfg(windows)]
+    {
+        std::fs::read(history_path())
+            .ok()
+            .and_then(|data| dpapi::unprotect(&data).ok())
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default()
+    }
+
+    // Without DPAPI the history is kept in memory only
+    #[cfg(not(windows))]
+    {
+        Vec::new()
+    }
+}
+
+/// Write the history file (encrypted)
+fn save_to_disk(entries: &[ClipEntry]) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        let json = serde_json::to_vec(entries)
+            .map_err(|e| format!("Failed to serialize clipboard history: {}", e))?;
+        let encrypted = dpapi::protect(&json)?;
+        std::fs::write(history_path(), encrypted)
+            .map_err(|e| format!("Failed to write clipboard history: {}", e))
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = entries;
+        Ok(())
+    }
+}
+
+/// Run `f` on the loaded history
+fn with_history<T>(f: impl FnOnce(&mut Vec<ClipEntry>) -> T) -> T {
+    let mut guard = HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+    let history = guard.get_or_insert_with(load_from_disk);
+    f(history)
+}
+
+/// All entries, most recent first
+pub fn entries() -> Vec<ClipEntry> {
+    with_history(|history| history.clone())
+}
+
+/// Forget everything (memory and disk)
+pub fn clear() -> Result<(), String> {
+    with_history(|history| history.clear());
+    match std::fs::remove_file(history_path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to delete clipboard history: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Drop entries beyond `limits` (see retention.rs), returning how many
+pub fn prune(limits: &Limits) -> Result<usize, String> {
+    with_history(|history| {
+        let pruned = retention::trim(history, limits, |entry| entry.copied_at, now());
+        if pruned > 0 {
+            save_to_disk(history)?;
+        }
+        Ok(pruned)
+    })
+}
+
+/// Add a newly copied text (moves it to the top if it's already known)
+fn record(text: String) {
+    let result = with_history(|history| {
+        history.retain(|entry| entry.text != text);
+        history.insert(0, ClipEntry { text, copied_at: now() });
+        history.truncate(max_entries());
+        save_to_disk(history)
+    });
+    if let Err(e) = result {
+        eprintln!("Warning: Could not save clipboard history: {}", e);
+    }
+}
+
+/// Clipboard change counter, and whether the current content asked to be
+/// left out of clipboard history
+#[cfg(windows)]
+fn clipboard_state() -> (u32, bool) {
+    use windows::core::w;
+    use windows::Win32::System::DataExchange::{
+        GetClipboardSequenceNumber, IsClipboardFormatAvailable, RegisterClipboardFormatW,
+    };
+
+    unsafe {
+        let sequence = GetClipboardSequenceNumber();
+        let exclude_format = RegisterClipboardFormatW(w!("ExcludeClipboardContentFromMonitorProcessing"));
+        let excluded = exclude_format != 0 && IsClipboardFormatAvailable(exclude_format).is_ok();
+        (sequence, excluded)
+    }
+}
+
+/// Without a change counter, every poll reads the text and compares
+#[cfg(not(windows))]
+fn clipboard_state() -> (u32, bool) {
+    (0, false)
+}
+
+/// Start the clipboard watcher thread (call once at startup)
+///
+/// The thread always runs but does nothing while the setting is off, so
+/// enabling it in Settings takes effect without a restart.
+pub fn start_watcher() {
+    std::thread::spawn(|| {
+        let mut last_sequence = 0u32;
+        let mut last_text = String::new();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            if !is_enabled() {
+                continue;
+            }
+
+            let (sequence, excluded) = clipboard_state();
+            if sequence != 0 && sequence == last_sequence {
+                continue;
+            }
+            last_sequence = sequence;
+            if excluded {
+                continue;
+            }
+
+            let Ok(text) = arboard::Clipboard::new().and_then(|mut c| c.get_text()) else {
+                continue;
+            };
+            if text.trim().is_empty() || text.len() > MAX_ENTRY_BYTES || text == last_text {
+                continue;
+            }
+
+            last_text = text.clone();
+            record(text);
+        }
+    });
+}
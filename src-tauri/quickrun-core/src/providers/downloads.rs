@@ -0,0 +1,115 @@
+// providers/downloads.rs - Offer a just-downloaded file when the launcher opens
+//
+// When enabled (setting "watch_downloads", off by default), a background
+// thread watches the Downloads folder. The newest file that finished
+// downloading in the last few minutes is offered as the top suggestion the
+// next time the launcher opens, with the usual open/run/reveal actions -
+// download an installer, press Alt+Space, press Enter.
+//
+// Browsers download to a temporary name (.crdownload, .part, ...) and rename
+// at the end, so temporary names are ignored and a file only counts once its
+// size has stopped changing.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the folder is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long a new download stays "new"
+const FRESH_FOR: Duration = Duration::from_secs(15 * 60);
+/// Extensions browsers use while a download is in progress
+const PARTIAL_EXTENSIONS: &[&str] = &["crdownload", "part", "partial", "tmp", "download", "opdownload"];
+
+/// The latest completed download and when it was noticed
+static LATEST: Mutex<Option<(PathBuf, SystemTime)>> = Mutex::new(None);
+
+/// Is the Downloads watcher enabled in settings?
+pub fn is_enabled() -> bool {
+    crate::settings::load().watch_downloads
+}
+
+fn is_partial(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| PARTIAL_EXTENSIONS.iter().any(|p| e.eq_ignore_ascii_case(p)))
+        .unwrap_or(false)
+}
+
+/// Current files in the Downloads folder with their sizes
+fn snapshot(dir: &std::path::Path) -> HashMap<PathBuf, u64> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| (entry.path(), metadata.len()))
+        })
+        .filter(|(path, _)| !is_partial(path))
+        .collect()
+}
+
+/// The new download to offer, if one arrived recently and still exists
+pub fn fresh_download() -> Option<(PathBuf, SystemTime)> {
+    if !is_enabled() {
+        return None;
+    }
+    let latest = LATEST.lock().ok()?.clone()?;
+    let age = latest.1.elapsed().unwrap_or_default();
+    (age < FRESH_FOR && latest.0.is_file()).then_some(latest)
+}
+
+/// Start the watcher thread (call once at startup)
+///
+/// Like the clipboard watcher, it idles while the setting is off.
+pub fn start_watcher() {
+    std::thread::spawn(|| {
+        let Some(dir) = dirs::download_dir() else {
+            eprintln!("[Downloads] No Downloads folder found - watcher not started");
+            return;
+        };
+
+        // Files present before watching started (or while disabled) aren't new
+        let mut known: HashMap<PathBuf, u64> = snapshot(&dir);
+        // New files whose size is still being checked: path → (size, first seen)
+        let mut pending: HashMap<PathBuf, (u64, Instant)> = HashMap::new();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = snapshot(&dir);
+
+            if !is_enabled() {
+                known = current;
+                pending.clear();
+                continue;
+            }
+
+            for (path, size) in &current {
+                if known.contains_key(path) {
+                    continue;
+                }
+                match pending.get(path) {
+                    // Same size as last poll: the download has finished
+                    Some((last_size, _)) if last_size == size => {
+                        eprintln!("[Downloads] New download: {}", path.display());
+                        if let Ok(mut latest) = LATEST.lock() {
+                            *latest = Some((path.clone(), SystemTime::now()));
+                        }
+                        pending.remove(path);
+                        known.insert(path.clone(), *size);
+                    }
+                    _ => {
+                        pending.insert(path.clone(), (*size, Instant::now()));
+                    }
+                }
+            }
+
+            // Forget deleted files so a re-download counts as new
+            known.retain(|path, _| current.contains_key(path));
+            pending.retain(|path, _| current.contains_key(path));
+        }
+    });
+}
@@ -0,0 +1,100 @@
+// providers/emoji.rs - `:` emoji and symbol picker
+//
+// Typing ":" followed by a name (":thumbs", ":arrow right", ":euro")
+// searches an index of emoji and symbols embedded in the binary
+// (data/emoji.tsv, Unicode character names). Choosing a result copies the
+// character to the clipboard - a picker that works in every app, unlike
+// Win+. which some programs don't support.
+
+use crate::results::{Action, ResultRow, RowAction};
+use std::sync::OnceLock;
+
+/// Most rows shown for a query
+const MAX_RESULTS: usize = 30;
+
+/// The embedded index: character, TAB, lowercase name, one per line
+const INDEX: &str = include_str!("../../data/emoji.tsv");
+
+/// (character, name) pairs parsed from INDEX
+fn entries() -> &'static [(&'static str, &'static str)] {
+    static ENTRIES: OnceLock<Vec<(&'static str, &'static str)>> = OnceLock::new();
+    ENTRIES.get_or_init(|| {
+        INDEX
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('\t'))
+            .collect()
+    })
+}
+
+/// Score a name against the query words (every word must match)
+///
+/// An exact name scores 100; otherwise each word adds 20 if it is a whole
+/// word of the name, 10 if it starts one, and 1 if it only appears inside
+/// one - so ":heart" prefers names with the word "heart" over "heartbeat".
+fn score(name: &str, words: &[&str]) -> Option<u32> {
+    if name == words.join(" ") {
+        return Some(100);
+    }
+
+    let mut total = 0;
+    for word in words {
+        if name.split(' ').any(|w| w == *word) {
+            total += 20;
+        } else if name.split(' ').any(|w| w.starts_with(word)) {
+            total += 10;
+        } else if name.contains(word) {
+            total += 1;
+        } else {
+            return None;
+        }
+    }
+    Some(total)
+}
+
+/// Matching (character, name) pairs for `query`, best first (ties keep
+/// index order: emoji before symbols)
+fn ranked(query: &str) -> Vec<(&'static str, &'static str)> {
+    let query = query.trim().to_lowercase();
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(u32, usize, &'static str, &'static str)> = entries()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (character, name))| score(name, &words).map(|s| (s, i, *character, *name)))
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    matches
+        .into_iter()
+        .map(|(_, _, character, name)| (character, name))
+        .collect()
+}
+
+/// Search the index; `query` is the text after ":"
+pub fn search(query: &str) -> Vec<ResultRow> {
+    if query.trim().is_empty() {
+        return vec![ResultRow::new("Type a name after \":\"", "e.g. :thumbs up, :arrow right, :euro")];
+    }
+
+    ranked(query)
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(character, name)| {
+            ResultRow::new(format!("{}  {}", character, name), "Select to copy")
+                .with_action(Action::Copy(character.to_string()))
+                .with_actions(vec![RowAction::new(
+                    "Paste into previous window",
+                    Action::Paste(character.to_string()),
+                )])
+        })
+        .collect()
+}
+
+/// The best match for `query`, for pressing Enter without choosing a row
+pub fn best_match(query: &str) -> Option<String> {
+    ranked(query).first().map(|(character, _)| character.to_string())
+}
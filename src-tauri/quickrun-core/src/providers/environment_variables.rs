@@ -0,0 +1,225 @@
+// providers/environment_variables.rs - `env` environment variable inspector
+//
+// Typing "env" and part of a name ("env java") lists the matching
+// environment variables with their values, in each scope they're set in:
+// - Process: what programs started from QuickRun get (user and machine
+//   merged, as of QuickRun's start)
+// - User: HKEY_CURRENT_USER\Environment
+// - Machine: HKEY_LOCAL_MACHINE\SYSTEM\CurrentControlSet\Control\Session
+//   Manager\Environment
+// User and machine values are shown as stored, %VARIABLES% unexpanded.
+// Choosing a variable (or Enter without choosing) copies its value. The
+// last row opens Windows' environment variable editor.
+//
+// "env" followed by anything but a single name ("env FOO=1 make", "env -i
+// sh"), or by a name nothing matches, is left for the env program that
+// Git for Windows and MSYS2 put on PATH.
+
+use crate::ranking;
+use crate::results::{Action, ResultRow, RowAction};
+
+/// The word that starts a variable search
+const KEYWORD: &str = "env";
+
+/// Most variable rows shown for a query
+const MAX_RESULTS: usize = 20;
+
+/// User variables (under HKEY_CURRENT_USER)
+#[cfg(windows)]
+const USER_KEY: &str = "Environment";
+/// Machine variables (under HKEY_LOCAL_MACHINE)
+#[cfg(windows)]
+const MACHINE_KEY: &str = "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment";
+
+/// What Windows runs for "Edit the system environment variables" (it asks
+/// for elevation itself)
+const EDIT_SYSTEM_COMMAND: &str = "SystemPropertiesAdvanced";
+/// What Windows runs for "Edit environment variables for your account"
+const EDIT_USER_COMMAND: &str = "rundll32 sysdm.cpl,EditEnvironmentVariables";
+
+/// Where a variable is set
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scope {
+    Process,
+    User,
+    Machine,
+}
+
+impl Scope {
+    fn label(self) -> &'static str {
+        match self {
+            Scope::Process => "Process",
+            Scope::User => "User",
+            Scope::Machine => "Machine",
+        }
+    }
+}
+
+/// A variable in one scope
+#[derive(Debug, Clone, PartialEq)]
+struct Variable {
+    name: String,
+    value: String,
+    scope: Scope,
+}
+
+/// The variable search in `input` ("env java" → "java"), if it is one
+pub fn keyword_query(input: &str) -> Option<&str> {
+    ranking::keyword_query(input, KEYWORD).filter(|query| is_name(query))
+}
+
+/// Could `query` be (part of) a variable name, rather than env's arguments?
+fn is_name(query: &str) -> bool {
+    !query.starts_with('-') && !query.contains(|c: char| c == '=' || c.is_whitespace())
+}
+
+/// Rows for the variables matching `query`, then the editor row
+pub fn search(query: &str) -> Vec<ResultRow> {
+    let mut rows: Vec<ResultRow> = ranked(load(), query)
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|variable| {
+            ResultRow::new(variable.name.clone(), format!("{} - {}", variable.scope.label(), variable.value))
+                .with_action(Action::Copy(variable.value))
+                .with_actions(vec![RowAction::new("Copy name", Action::Copy(variable.name))])
+        })
+        .collect();
+    rows.push(
+        ResultRow::new("Edit the system environment variables", "System Properties")
+            .with_action(Action::Run(EDIT_SYSTEM_COMMAND.to_string()))
+            .with_actions(vec![RowAction::new(
+                "Edit environment variables for your account",
+                Action::Run(EDIT_USER_COMMAND.to_string()),
+            )]),
+    );
+    rows
+}
+
+/// The value of the best match, for pressing Enter without choosing a row
+pub fn best_match(query: &str) -> Option<String> {
+    ranked(load(), query).into_iter().next().map(|variable| variable.value)
+}
+
+/// Variables matching `query` by name, best first (process, user, machine
+/// among equal matches)
+fn ranked(variables: Vec<Variable>, query: &str) -> Vec<Variable> {
+    ranking::ranked(variables, query, |variable| variable.name.clone())
+}
+
+/// Every variable in every scope, each scope by name
+fn load() -> Vec<Variable> {
+    let process: Vec<(String, String)> = std::env::vars_os()
+        .map(|(name, value)| (name.to_string_lossy().to_string(), value.to_string_lossy().to_string()))
+        .collect();
+    scoped(process, user_variables(), machine_variables())
+}
+
+/// The variables of each scope, process ones sorted by name (the
+/// registry's come sorted)
+fn scoped(
+    mut process: Vec<(String, String)>,
+    user: Vec<(String, String)>,
+    machine: Vec<(String, String)>,
+) -> Vec<Variable> {
+    process.sort_by_key(|(name, _)| name.to_lowercase());
+    let scopes = [(Scope::Process, process), (Scope::User, user), (Scope::Machine, machine)];
+    scopes
+        .into_iter()
+        .flat_map(|(scope, variables)| {
+            variables.into_iter().map(move |(name, value)| Variable { name, value, scope })
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn user_variables() -> Vec<(String, String)> {
+    registry_variables(&winreg::RegKey::predef(winreg::enums::HKEY_CURRENT_USER), USER_KEY)
+}
+
+#[cfg(windows)]
+fn machine_variables() -> Vec<(String, String)> {
+    registry_variables(&winreg::RegKey::predef(winreg::enums::HKEY_LOCAL_MACHINE), MACHINE_KEY)
+}
+
+/// The string values of `key` under `hive`, by name
+#[cfg(windows)]
+fn registry_variables(hive: &winreg::RegKey, key: &str) -> Vec<(String, String)> {
+    let key = match hive.open_subkey(key) {
+        Ok(key) => key,
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                eprintln!("[Env] Failed to open registry: {}", e);
+            }
+            return Vec::new();
+        }
+    };
+    let mut variables: Vec<(String, String)> = key
+        .enum_values()
+        .flatten()
+        .filter_map(|(name, _)| key.get_value::<String, _>(&name).ok().map(|value| (name, value)))
+        .collect();
+    variables.sort_by_key(|(name, _)| name.to_lowercase());
+    variables
+}
+
+#[cfg(not(windows))]
+fn user_variables() -> Vec<(String, String)> {
+    Vec::new()
+}
+
+#[cfg(not(windows))]
+fn machine_variables() -> Vec<(String, String)> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(name: &str, value: &str, scope: Scope) -> Variable {
+        Variable { name: name.to_string(), value: value.to_string(), scope }
+    }
+
+    #[test]
+    fn matches_keep_scope_order_among_equals() {
+        let variables = vec![
+            variable("JAVA_OPTS", "-Xmx2g", Scope::Process),
+            variable("JAVA_HOME", "C:\\jdk-21", Scope::Process),
+            variable("JAVA_HOME", "C:\\jdk-17", Scope::User),
+            variable("PATH", "C:\\Windows", Scope::Machine),
+        ];
+        assert_eq!(ranked(variables.clone(), "java_home"), [variables[1].clone(), variables[2].clone()]);
+        assert_eq!(ranked(variables.clone(), "java")[0], variables[0]);
+        assert_eq!(ranked(variables, "").len(), 4);
+    }
+
+    #[test]
+    fn env_keyword_needs_a_space() {
+        assert_eq!(keyword_query("ENV  java "), Some("java"));
+        assert_eq!(keyword_query("env "), None);
+        assert_eq!(keyword_query("envoy proxy"), None);
+    }
+
+    #[test]
+    fn env_arguments_are_left_for_the_env_program() {
+        assert_eq!(keyword_query("env FOO=1 make"), None);
+        assert_eq!(keyword_query("env -i sh"), None);
+        assert_eq!(keyword_query("env java home"), None);
+    }
+
+    #[test]
+    fn the_editor_row_comes_last() {
+        let rows = search("no variable is called this");
+        assert_eq!(rows.len(), 1);
+        assert!(matches!(&rows[0].action, Action::Run(command) if command == EDIT_SYSTEM_COMMAND));
+    }
+
+    #[test]
+    fn process_variables_are_listed_by_name_first() {
+        let process = vec![("windir".to_string(), "C:\\Windows".to_string()), ("TEMP".to_string(), "T".to_string())];
+        let user = vec![("TEMP".to_string(), "%USERPROFILE%\\Temp".to_string())];
+        let variables = scoped(process, user, Vec::new());
+        assert_eq!(variables[0], variable("TEMP", "T", Scope::Process));
+        assert_eq!(ranked(variables, "temp")[1], variable("TEMP", "%USERPROFILE%\\Temp", Scope::User));
+    }
+}
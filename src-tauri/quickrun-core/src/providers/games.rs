@@ -0,0 +1,365 @@
+// providers/games.rs - Installed Steam and Epic Games games
+//
+// Games are suggested by name like apps ("portal" → Portal 2) and start
+// through their launcher's protocol, so the launcher handles updates,
+// cloud saves and overlays as when started from its library:
+//
+//   Steam   steam://rungameid/<appid>
+//           Steam's folder is SteamPath under HKEY_CURRENT_USER\Software\Valve\Steam.
+//           Its steamapps\libraryfolders.vdf lists the library folders, and
+//           each library's steamapps\appmanifest_<appid>.acf describes one
+//           installed app. Both are Valve's KeyValues ("VDF") text format.
+//   Epic    com.epicgames.launcher://apps/<namespace>%3A<item>%3A<app>?action=launch&silent=true
+//           One JSON .item file per installed game in
+//           %PROGRAMDATA%\Epic\EpicGamesLauncher\Data\Manifests
+//
+// Only fully installed games are listed; Steam's redistributables and
+// runtimes, which are apps too, are left out. The libraries are scanned
+// again at most once a minute.
+
+use crate::os::{Registry, SystemRegistry};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Where Steam records its install folder (under HKEY_CURRENT_USER)
+const STEAM_KEY: &str = "Software\\Valve\\Steam";
+
+/// AppState StateFlags bit for a fully installed app
+const STEAM_FULLY_INSTALLED: u32 = 4;
+
+/// Steam apps that aren't games (Steamworks redistributables, runtimes)
+const STEAM_NOT_GAMES: [&str; 4] = ["228980", "1070560", "1391110", "1628350"];
+
+/// How long a scan of the libraries is reused
+const RESCAN_AFTER: Duration = Duration::from_secs(60);
+
+/// Games found by the last scan, and when it ran
+static CACHE: Mutex<Option<(Instant, Vec<Game>)>> = Mutex::new(None);
+
+/// An installed game
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    pub name: String,
+    /// "Steam" or "Epic Games"
+    pub store: &'static str,
+    /// Protocol URI that starts it
+    pub uri: String,
+    /// A file whose icon stands for it (the store's own program)
+    pub icon_path: Option<String>,
+}
+
+/// Every installed game (Steam first, each store's games by name)
+pub fn list() -> Vec<Game> {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((scanned_at, games)) = cache.as_ref() {
+        if scanned_at.elapsed() < RESCAN_AFTER {
+            return games.clone();
+        }
+    }
+
+    let mut games = steam_games(&SystemRegistry);
+    games.extend(epic_games());
+    *cache = Some((Instant::now(), games.clone()));
+    games
+}
+
+fn steam_games(registry: &dyn Registry) -> Vec<Game> {
+    let Some(steam) = registry.get_string(STEAM_KEY, "SteamPath").ok().flatten().map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let exe = steam.join("steam.exe");
+    let icon_path = exe.is_file().then(|| exe.to_string_lossy().to_string());
+
+    // Steam's own folder may also be listed (a game found twice is kept once)
+    let mut libraries = vec![steam.clone()];
+    if let Ok(contents) = std::fs::read_to_string(steam.join("steamapps").join("libraryfolders.vdf")) {
+        libraries.extend(library_folders(&parse_vdf(&contents)));
+    }
+
+    let mut games: Vec<Game> = Vec::new();
+    for library in libraries {
+        let Ok(entries) = std::fs::read_dir(library.join("steamapps")) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let is_manifest = path.file_name().and_then(|n| n.to_str()).is_some_and(|name| {
+                name.starts_with("appmanifest_") && name.ends_with(".acf")
+            });
+            if !is_manifest {
+                continue;
+            }
+            let app = std::fs::read_to_string(&path).ok().and_then(|contents| installed_app(&parse_vdf(&contents)));
+            let Some((appid, name)) = app else {
+                continue;
+            };
+            if !games.iter().any(|g| g.uri.ends_with(&format!("/{}", appid))) {
+                games.push(Game {
+                    name,
+                    store: "Steam",
+                    uri: format!("steam://rungameid/{}", appid),
+                    icon_path: icon_path.clone(),
+                });
+            }
+        }
+    }
+    games.sort_by_key(|game| game.name.to_lowercase());
+    games
+}
+
+/// Library folders in libraryfolders.vdf
+///
+/// Current files have {"0": {"path": "C:\\..."}, ...}; older ones
+/// {"1": "D:\\SteamLibrary", ...}, which leave out Steam's own folder.
+fn library_folders(vdf: &Vdf) -> Vec<PathBuf> {
+    let Some(Vdf::Map(folders)) = vdf.get("libraryfolders") else {
+        return Vec::new();
+    };
+    folders
+        .iter()
+        .filter(|(key, _)| key.chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|(_, folder)| match folder {
+            Vdf::Text(path) => Some(path.as_str()),
+            Vdf::Map(_) => folder.text("path"),
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// (appid, name) of an appmanifest, if it's a fully installed game
+fn installed_app(vdf: &Vdf) -> Option<(String, String)> {
+    let app = vdf.get("AppState")?;
+    let appid = app.text("appid")?;
+    let name = app.text("name")?.trim();
+    let flags: u32 = app.text("StateFlags")?.parse().ok()?;
+    let is_game = !STEAM_NOT_GAMES.contains(&appid) && !name.starts_with("Proton") && !name.is_empty();
+    (is_game && flags & STEAM_FULLY_INSTALLED != 0).then(|| (appid.to_string(), name.to_string()))
+}
+
+fn epic_games() -> Vec<Game> {
+    let Ok(program_data) = std::env::var("PROGRAMDATA") else {
+        return Vec::new();
+    };
+    let manifests = Path::new(&program_data).join("Epic\\EpicGamesLauncher\\Data\\Manifests");
+    let Ok(entries) = std::fs::read_dir(manifests) else {
+        return Vec::new();
+    };
+
+    let mut games: Vec<Game> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("item")))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|contents| serde_json::from_str::<Value>(&contents).ok())
+        .filter_map(|manifest| epic_game(&manifest))
+        .collect();
+    games.sort_by_key(|game| game.name.to_lowercase());
+    games
+}
+
+/// The game an Epic Games .item manifest describes, if it's installed
+fn epic_game(manifest: &Value) -> Option<Game> {
+    let text = |key: &str| manifest.get(key).and_then(Value::as_str).filter(|value| !value.is_empty());
+    if manifest.get("bIsIncompleteInstall").and_then(Value::as_bool).unwrap_or(false) {
+        return None;
+    }
+    let uri = format!(
+        "com.epicgames.launcher://apps/{}%3A{}%3A{}?action=launch&silent=true",
+        text("CatalogNamespace")?,
+        text("CatalogItemId")?,
+        text("AppName")?
+    );
+    Some(Game {
+        name: text("DisplayName")?.to_string(),
+        store: "Epic Games",
+        uri,
+        icon_path: text("InstallLocation")
+            .zip(text("LaunchExecutable"))
+            .map(|(folder, exe)| Path::new(folder).join(exe).to_string_lossy().to_string()),
+    })
+}
+
+/// A KeyValues value: text, or keys with values of their own
+#[derive(Debug, PartialEq)]
+enum Vdf {
+    Text(String),
+    Map(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    /// The value of `key` (keys are case-insensitive)
+    fn get(&self, key: &str) -> Option<&Vdf> {
+        match self {
+            Vdf::Map(entries) => entries.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v),
+            Vdf::Text(_) => None,
+        }
+    }
+
+    /// The text value of `key`
+    fn text(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            Vdf::Text(text) => Some(text),
+            Vdf::Map(_) => None,
+        }
+    }
+}
+
+/// Parse KeyValues text: "key" "value" pairs and "key" { ... } blocks
+///
+/// Quotes are optional around single words, "//" starts a comment and
+/// backslashes escape. Whatever can't be read (an unclosed block) ends the
+/// parse with what was read so far.
+fn parse_vdf(contents: &str) -> Vdf {
+    let tokens = vdf_tokens(contents);
+    parse_vdf_map(&mut tokens.iter().map(String::as_str))
+}
+
+fn parse_vdf_map<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Vdf {
+    let mut entries = Vec::new();
+    while let Some(key) = tokens.next() {
+        match key {
+            "}" => break,
+            "{" => continue,
+            _ => {}
+        }
+        let value = match tokens.next() {
+            Some("{") => parse_vdf_map(tokens),
+            Some(text) => Vdf::Text(text.to_string()),
+            None => break,
+        };
+        entries.push((key.to_string(), value));
+    }
+    Vdf::Map(entries)
+}
+
+/// Split KeyValues text into strings, "{" and "}"
+///
+/// A quoted "{" is returned as "{" too; keys and values never are one.
+fn vdf_tokens(contents: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '{' | '}' => tokens.push(c.to_string()),
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '"' => {
+                let mut token = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => token.push('\n'),
+                            Some('t') => token.push('\t'),
+                            Some(c) => token.push(c),
+                            None => {}
+                        },
+                        c => token.push(c),
+                    }
+                }
+                tokens.push(token);
+            }
+            c => {
+                let mut token = c.to_string();
+                while let Some(c) = chars.next_if(|&c| !c.is_whitespace() && !matches!(c, '{' | '}' | '"')) {
+                    token.push(c);
+                }
+                tokens.push(token);
+            }
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LIBRARY_FOLDERS: &str = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"apps"
+		{
+			"228980"		"29212173"
+			"620"		"12798591438"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary" // games drive
+	}
+}
+"#;
+
+    const APP_MANIFEST: &str = r#"
+"AppState"
+{
+	"appid"		"620"
+	"name"		"Portal 2"
+	"StateFlags"		"4"
+	"installdir"		"Portal 2"
+	"UserConfig" { "language" "english" }
+}
+"#;
+
+    #[test]
+    fn library_folders_are_read_in_both_layouts() {
+        let folders = library_folders(&parse_vdf(LIBRARY_FOLDERS));
+        assert_eq!(folders, [PathBuf::from("C:\\Program Files (x86)\\Steam"), PathBuf::from("D:\\SteamLibrary")]);
+
+        let old = "\"LibraryFolders\" { \"TimeNextStatsReport\" \"1\" \"1\" \"E:\\\\Games\" }";
+        assert_eq!(library_folders(&parse_vdf(old)), [PathBuf::from("E:\\Games")]);
+        assert!(library_folders(&parse_vdf("")).is_empty());
+    }
+
+    #[test]
+    fn installed_games_are_read_from_app_manifests() {
+        assert_eq!(installed_app(&parse_vdf(APP_MANIFEST)), Some(("620".to_string(), "Portal 2".to_string())));
+
+        let updating = APP_MANIFEST.replace("\"StateFlags\"\t\t\"4\"", "\"StateFlags\"\t\t\"1026\"");
+        assert_eq!(installed_app(&parse_vdf(&updating)), None);
+        let redistributables = APP_MANIFEST.replace("\"620\"", "\"228980\"");
+        assert_eq!(installed_app(&parse_vdf(&redistributables)), None);
+    }
+
+    #[test]
+    fn vdf_handles_escapes_comments_and_bare_words() {
+        let vdf = parse_vdf("// header\nroot { name \"say \\\"hi\\\"\" nested { } }");
+        let root = vdf.get("ROOT").unwrap();
+        assert_eq!(root.text("name"), Some("say \"hi\""));
+        assert_eq!(root.get("nested"), Some(&Vdf::Map(Vec::new())));
+        // Unclosed: what was read is kept
+        assert_eq!(parse_vdf("\"a\" { \"b\" \"c\"").get("a").and_then(|a| a.text("b")), Some("c"));
+    }
+
+    #[test]
+    fn epic_manifests_become_launcher_uris() {
+        let manifest = json!({
+            "DisplayName": "Hades",
+            "AppName": "Min",
+            "CatalogNamespace": "min",
+            "CatalogItemId": "fb39bac8",
+            "InstallLocation": "C:\\Games\\Hades",
+            "LaunchExecutable": "x64\\Hades.exe",
+            "bIsIncompleteInstall": false,
+        });
+        let game = epic_game(&manifest).unwrap();
+        assert_eq!(game.name, "Hades");
+        assert_eq!(game.uri, "com.epicgames.launcher://apps/min%3Afb39bac8%3AMin?action=launch&silent=true");
+
+        let mut incomplete = manifest.clone();
+        incomplete["bIsIncompleteInstall"] = json!(true);
+        assert_eq!(epic_game(&incomplete), None);
+    }
+
+    #[test]
+    fn steam_needs_its_install_folder() {
+        assert!(steam_games(&crate::os::memory::MemoryRegistry::new()).is_empty());
+    }
+}
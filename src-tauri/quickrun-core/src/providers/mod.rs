@@ -0,0 +1,20 @@
+// providers/mod.rs - Suggestion providers
+//
+// A provider contributes extra suggestion rows from a specific source
+// (shell history, open windows, SSH hosts, ...). suggestions.rs decides
+// which providers to consult for a given query and merges their rows.
+
+pub mod browser_profiles;
+pub mod clipboard_history;
+pub mod downloads;
+pub mod emoji;
+pub mod environment_variables;
+pub mod games;
+pub mod open_windows;
+pub mod rdp_connections;
+pub mod registry_jump;
+pub mod settings_pages;
+pub mod shell_history;
+pub mod ssh_hosts;
+pub mod terminal_profiles;
+pub mod vscode_recent;
@@ -0,0 +1,181 @@
+// providers/open_windows.rs - Switch to an open window by typing its title
+//
+// Enumerates the top-level windows that Alt+Tab would show (visible, titled,
+// not tool windows, not owned popups, not cloaked on another virtual desktop
+// or suspended UWP frames) so they can be offered as suggestions. Selecting
+// one restores it if minimized and brings it to the front - a searchable
+// Alt+Tab.
+//
+// Window handles are passed to the frontend as decimal strings in
+// Action::Focus and validated again (IsWindow) before use, since the window
+// may have closed in the meantime.
+
+/// A top-level window the user could switch to
+#[derive(Debug, Clone)]
+pub struct OpenWindow {
+    /// Window handle as an integer (HWND value)
+    pub handle: isize,
+    /// Title bar text
+    pub title: String,
+    /// Executable of the owning process, if it could be queried
+    pub exe_path: Option<String>,
+}
+
+impl OpenWindow {
+    /// Executable file name ("chrome.exe"), or "" if unknown
+    pub fn exe_name(&self) -> String {
+        self.exe_path
+            .as_deref()
+            .and_then(|p| std::path::Path::new(p).file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(windows)]
+mod win32 {
+    use super::OpenWindow;
+    use std::ffi::c_void;
+    use windows::core::{BOOL, PWSTR};
+    use windows::Win32::Foundation::{CloseHandle, HWND, LPARAM};
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindow, GetWindowLongW, GetWindowTextLengthW, GetWindowTextW,
+        GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, SetForegroundWindow,
+        ShowWindow, SwitchToThisWindow, GWL_EXSTYLE, GW_OWNER, SW_RESTORE, WS_EX_TOOLWINDOW,
+    };
+
+    /// Would Alt+Tab show this window?
+    fn is_switchable(hwnd: HWND) -> bool {
+        unsafe {
+            if !IsWindowVisible(hwnd).as_bool() {
+                return false;
+            }
+            // Owned windows (dialogs, popups) belong to their owner's entry
+            if GetWindow(hwnd, GW_OWNER).map(|owner| !owner.is_invalid()).unwrap_or(false) {
+                return false;
+            }
+            if GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0 {
+                return false;
+            }
+            // Cloaked: on another virtual desktop, or a hidden UWP frame
+            let mut cloaked: u32 = 0;
+            let cloaked_ok = DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_CLOAKED,
+                &mut cloaked as *mut u32 as *mut c_void,
+                std::mem::size_of::<u32>() as u32,
+            );
+            !(cloaked_ok.is_ok() && cloaked != 0)
+        }
+    }
+
+    fn window_title(hwnd: HWND) -> String {
+        unsafe {
+            let len = GetWindowTextLengthW(hwnd);
+            if len <= 0 {
+                return String::new();
+            }
+            let mut buffer = vec![0u16; len as usize + 1];
+            let copied = GetWindowTextW(hwnd, &mut buffer);
+            String::from_utf16_lossy(&buffer[..copied.max(0) as usize])
+        }
+    }
+
+    fn process_path(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buffer = [0u16; 1024];
+            let mut size = buffer.len() as u32;
+            let result = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buffer.as_mut_ptr()),
+                &mut size,
+            );
+            let _ = CloseHandle(process);
+            result.ok()?;
+            Some(String::from_utf16_lossy(&buffer[..size as usize]))
+        }
+    }
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam.0 as *mut Vec<OpenWindow>);
+
+        if is_switchable(hwnd) {
+            let title = window_title(hwnd);
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+
+            // Skip untitled windows, the desktop, and our own windows
+            if !title.trim().is_empty() && title != "Program Manager" && pid != std::process::id() {
+                windows.push(OpenWindow {
+                    handle: hwnd.0 as isize,
+                    title,
+                    exe_path: process_path(pid),
+                });
+            }
+        }
+
+        BOOL(1) // keep enumerating
+    }
+
+    pub fn list() -> Vec<OpenWindow> {
+        let mut windows: Vec<OpenWindow> = Vec::new();
+        unsafe {
+            let _ = EnumWindows(Some(collect), LPARAM(&mut windows as *mut Vec<OpenWindow> as isize));
+        }
+        windows
+    }
+
+    pub fn focus(handle: isize) -> Result<(), String> {
+        let hwnd = HWND(handle as *mut c_void);
+        unsafe {
+            if !IsWindow(Some(hwnd)).as_bool() {
+                return Err("That window has been closed".to_string());
+            }
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            // SetForegroundWindow is allowed because the launcher currently
+            // has focus; SwitchToThisWindow is the fallback when it isn't
+            if !SetForegroundWindow(hwnd).as_bool() {
+                SwitchToThisWindow(hwnd, true);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// All windows the user could switch to, in Z-order (most recent first)
+#[cfg(windows)]
+pub fn list() -> Vec<OpenWindow> {
+    win32::list()
+}
+
+#[cfg(not(windows))]
+pub fn list() -> Vec<OpenWindow> {
+    Vec::new()
+}
+
+/// Bring the window with the given handle (decimal string) to the front
+pub fn focus(handle: &str) -> Result<(), String> {
+    let handle: isize = handle
+        .parse()
+        .map_err(|_| format!("Invalid window handle: {}", handle))?;
+
+    #[cfg(windows)]
+    {
+        win32::focus(handle)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = handle;
+        Err("Switching windows is only supported on Windows".to_string())
+    }
+}
@@ -0,0 +1,305 @@
+// providers/rdp_connections.rs - Remote Desktop servers and .rdp files
+//
+// Typing "rdp" and a server name ("rdp prod-db01") lists:
+// - Servers Remote Desktop Connection has connected to: its recent list
+//   (MRU0-MRU9 under HKEY_CURRENT_USER\Software\Microsoft\Terminal Server
+//   Client\Default) and the servers it keeps a user name for (the keys
+//   under ...\Terminal Server Client\Servers). They open with
+//   `mstsc /v:<server>`.
+// - Saved .rdp files in Documents and on the Desktop (two folder levels
+//   down), listed by file name with the server they connect to. They open
+//   with `mstsc "<file>"`, so their display and device settings apply.
+//
+// A name that matches nothing is still a server: "rdp newbox" offers (and
+// Enter runs) `mstsc /v:newbox`. Enter without choosing a row opens a known
+// connection only if it has exactly the name typed, so "rdp prod" connects
+// to prod, not prod-db01. Documents\Default.rdp is mstsc's record of the
+// last session, not a saved file, so it's left out.
+//
+// The folders are scanned on a thread of their own (at most once a
+// minute) rather than while the user types, so files found since the last
+// scan show up a moment later.
+
+use crate::os::{Registry, SystemRegistry};
+use crate::ranking;
+use crate::results::{Action, ResultRow};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The word that starts a connection search
+const KEYWORD: &str = "rdp";
+
+/// Most rows shown for a query
+const MAX_RESULTS: usize = 8;
+
+/// Remote Desktop Connection's recent servers (MRU0, MRU1, ...)
+const MRU_KEY: &str = "Software\\Microsoft\\Terminal Server Client\\Default";
+const MRU_ENTRIES: usize = 10;
+
+/// One key per server Remote Desktop Connection remembers a user name for
+const SERVERS_KEY: &str = "Software\\Microsoft\\Terminal Server Client\\Servers";
+
+/// How many folder levels below Documents and the Desktop are searched
+const MAX_DEPTH: usize = 2;
+/// Stop scanning after this many entries (keeps huge folders responsive)
+const MAX_SCANNED: usize = 5_000;
+/// How long a scan of the folders is reused
+const RESCAN_AFTER: Duration = Duration::from_secs(60);
+
+/// .rdp files found by the last scan, and when it ran
+static FILES: Mutex<Option<(Instant, Vec<Connection>)>> = Mutex::new(None);
+/// Is a scan running?
+static SCANNING: AtomicBool = AtomicBool::new(false);
+
+/// Something to connect to
+#[derive(Debug, Clone, PartialEq)]
+enum Connection {
+    /// A server name (maybe with ":port")
+    Server(String),
+    /// A saved .rdp file, with the server it connects to
+    File { path: PathBuf, address: Option<String> },
+}
+
+impl Connection {
+    fn name(&self) -> String {
+        match self {
+            Connection::Server(server) => server.clone(),
+            Connection::File { path, .. } => {
+                path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+            }
+        }
+    }
+
+    /// The launcher input that connects
+    fn command(&self) -> String {
+        match self {
+            Connection::Server(server) => format!("mstsc /v:{}", server),
+            Connection::File { path, .. } => format!("mstsc \"{}\"", path.display()),
+        }
+    }
+
+    fn subtitle(&self) -> String {
+        match self {
+            Connection::Server(_) => "Remote Desktop".to_string(),
+            Connection::File { address: Some(address), .. } => format!("RDP file - {}", address),
+            Connection::File { path, .. } => format!("RDP file - {}", path.display()),
+        }
+    }
+}
+
+/// The connection search in `input` ("rdp prod-db01" → "prod-db01"), if it
+/// is one
+pub fn keyword_query(input: &str) -> Option<&str> {
+    ranking::keyword_query(input, KEYWORD)
+}
+
+/// Rows for the connections matching `query`, plus one for `query` itself
+/// as a server unless it's already listed
+pub fn search(query: &str) -> Vec<ResultRow> {
+    let mut connections: Vec<Connection> = ranked(load(), query).into_iter().take(MAX_RESULTS).collect();
+    if let Some(server) = typed_server(query) {
+        if !connections.contains(&server) {
+            connections.push(server);
+        }
+    }
+    connections
+        .into_iter()
+        .map(|connection| {
+            ResultRow::new(connection.name(), connection.subtitle()).with_action(Action::Run(connection.command()))
+        })
+        .collect()
+}
+
+/// The input for the connection called `name` (ignoring case), or for
+/// `name` as a server, for pressing Enter without choosing a row
+pub fn exact_match(name: &str) -> Option<String> {
+    named(load(), name).or_else(|| typed_server(name)).map(|connection| connection.command())
+}
+
+/// The connection called `name`, ignoring case
+fn named(connections: Vec<Connection>, name: &str) -> Option<Connection> {
+    connections.into_iter().find(|connection| connection.name().eq_ignore_ascii_case(name))
+}
+
+/// `query` as a server name, if it can be one (one word, no quotes)
+fn typed_server(query: &str) -> Option<Connection> {
+    let valid = !query.is_empty() && !query.contains(|c: char| c.is_whitespace() || c == '"');
+    valid.then(|| Connection::Server(query.to_string()))
+}
+
+/// Connections matching `query`, best first (servers before files, in the
+/// order they were found, among equal matches)
+fn ranked(connections: Vec<Connection>, query: &str) -> Vec<Connection> {
+    ranking::ranked(connections, query, Connection::name)
+}
+
+/// Every known server, then the saved files
+fn load() -> Vec<Connection> {
+    let mut connections: Vec<Connection> = servers(&SystemRegistry).into_iter().map(Connection::Server).collect();
+    connections.extend(files());
+    connections
+}
+
+/// Servers from Remote Desktop Connection's recent list, then its saved
+/// user names (each once)
+fn servers(registry: &dyn Registry) -> Vec<String> {
+    let recent = (0..MRU_ENTRIES).filter_map(|i| registry.get_string(MRU_KEY, &format!("MRU{}", i)).ok().flatten());
+    let saved = registry.subkeys(SERVERS_KEY).unwrap_or_else(|e| {
+        eprintln!("[Rdp] {}", e);
+        Vec::new()
+    });
+
+    let mut servers: Vec<String> = Vec::new();
+    for server in recent.chain(saved) {
+        let server = server.trim();
+        if !server.is_empty() && !servers.iter().any(|s| s.eq_ignore_ascii_case(server)) {
+            servers.push(server.to_string());
+        }
+    }
+    servers
+}
+
+/// .rdp files in Documents and on the Desktop, from the last scan (none
+/// before the first is done)
+///
+/// Starts a scan in the background if there's none yet or it's too old.
+fn files() -> Vec<Connection> {
+    let cached = FILES.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let is_fresh = cached.as_ref().is_some_and(|(scanned_at, _)| scanned_at.elapsed() < RESCAN_AFTER);
+    if !is_fresh && !SCANNING.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(|| {
+            let files = scan_files();
+            *FILES.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), files));
+            SCANNING.store(false, Ordering::SeqCst);
+        });
+    }
+    cached.map(|(_, files)| files).unwrap_or_default()
+}
+
+/// Scan Documents and the Desktop for .rdp files
+fn scan_files() -> Vec<Connection> {
+    let mut paths = Vec::new();
+    let mut scanned = 0;
+    for dir in [dirs::document_dir(), dirs::desktop_dir()].into_iter().flatten() {
+        scan(&dir, 0, &mut scanned, &mut paths);
+    }
+    paths
+        .into_iter()
+        .filter(|path| !is_last_session(path))
+        .map(|path| {
+            let address = std::fs::read(&path).ok().and_then(|bytes| full_address(&decode(&bytes)));
+            Connection::File { path, address }
+        })
+        .collect()
+}
+
+/// Collect the .rdp files under `dir`
+fn scan(dir: &Path, depth: usize, scanned: &mut usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        *scanned += 1;
+        if *scanned > MAX_SCANNED {
+            return;
+        }
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() && depth < MAX_DEPTH {
+            scan(&path, depth + 1, scanned, files);
+        } else if file_type.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("rdp")) {
+            files.push(path);
+        }
+    }
+}
+
+/// Is `path` Documents\Default.rdp?
+fn is_last_session(path: &Path) -> bool {
+    let is_default = path.file_name().is_some_and(|name| name.eq_ignore_ascii_case("Default.rdp"));
+    is_default && path.parent() == dirs::document_dir().as_deref()
+}
+
+/// The text of an .rdp file (mstsc saves them as UTF-16 with a BOM)
+fn decode(bytes: &[u8]) -> String {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<u16> = rest.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(bytes).trim_start_matches('\u{feff}').to_string(),
+    }
+}
+
+/// The server an .rdp file connects to ("full address:s:prod-db01")
+fn full_address(contents: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let (name, value) = line.trim().split_once(":s:")?;
+        let value = value.trim();
+        (name.eq_ignore_ascii_case("full address") && !value.is_empty()).then(|| value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::MemoryRegistry;
+
+    #[test]
+    fn servers_come_from_the_recent_list_then_saved_user_names() {
+        let registry = MemoryRegistry::new();
+        registry.set_string(MRU_KEY, "MRU0", "prod-db01").unwrap();
+        registry.set_string(MRU_KEY, "MRU1", "jump.example.com:3390").unwrap();
+        registry.set_string(&format!("{}\\prod-db01", SERVERS_KEY), "UsernameHint", "CORP\\me").unwrap();
+        registry.set_string(&format!("{}\\build-01", SERVERS_KEY), "UsernameHint", "CORP\\me").unwrap();
+
+        assert_eq!(servers(&registry), ["prod-db01", "jump.example.com:3390", "build-01"]);
+        assert!(servers(&MemoryRegistry::new()).is_empty());
+    }
+
+    #[test]
+    fn rdp_files_are_read_as_utf16() {
+        let text = "screen mode id:i:2\r\nfull address:s:prod-db01.corp.local\r\nusername:s:CORP\\me\r\n";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+
+        assert_eq!(full_address(&decode(&bytes)).as_deref(), Some("prod-db01.corp.local"));
+        assert_eq!(full_address(&decode(text.as_bytes())).as_deref(), Some("prod-db01.corp.local"));
+        assert_eq!(full_address("full address:s:\r\n"), None);
+    }
+
+    #[test]
+    fn connections_open_with_mstsc() {
+        let server = Connection::Server("prod-db01".to_string());
+        let path = Path::new("Documents").join("Prod DB.rdp");
+        let file = Connection::File { path: path.clone(), address: None };
+        assert_eq!(server.command(), "mstsc /v:prod-db01");
+        assert_eq!(file.command(), format!("mstsc \"{}\"", path.display()));
+        assert_eq!(file.name(), "Prod DB");
+
+        let ranked = ranked(vec![server.clone(), file], "db");
+        assert_eq!(ranked[0], server);
+    }
+
+    #[test]
+    fn enter_opens_only_the_connection_named() {
+        let server = Connection::Server("prod-db01".to_string());
+        let file = Connection::File { path: Path::new("Documents").join("Prod.rdp"), address: None };
+        let connections = vec![server.clone(), file.clone()];
+        assert_eq!(named(connections.clone(), "PROD-DB01"), Some(server));
+        assert_eq!(named(connections.clone(), "prod"), Some(file));
+        assert_eq!(named(connections, "db01"), None);
+    }
+
+    #[test]
+    fn any_single_word_is_a_server() {
+        assert_eq!(keyword_query("RDP  newbox "), Some("newbox"));
+        assert_eq!(keyword_query("rdp"), None);
+        assert_eq!(typed_server("newbox:3390"), Some(Connection::Server("newbox:3390".to_string())));
+        assert_eq!(typed_server("two words"), None);
+        assert_eq!(typed_server(""), None);
+    }
+}
@@ -0,0 +1,129 @@
+// providers/registry_jump.rs - `reg` jumps to a key in Registry Editor
+//
+// Typing "reg" and a key path ("reg HKLM\SOFTWARE\Microsoft\Windows")
+// opens Registry Editor at that key. Paths can be written the ways they're
+// usually copied around: abbreviated (HKLM, HKCU, HKCR, HKU, HKCC) or in
+// full, from Registry Editor's address bar ("Computer\HKEY_..."), or in
+// PowerShell's form ("HKLM:\SOFTWARE"), with / or \.
+//
+// Registry Editor has no option to open at a key; it opens wherever it was
+// last closed, which it records in LastKey under
+// HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Applets\Regedit.
+// So the jump sets LastKey and starts a new Registry Editor window
+// (`regedit -m`: a running one would be brought up at its own key instead).
+//
+// "reg" followed by anything else ("reg query ...") is left for reg.exe.
+
+use crate::os::{ProcessSpawner, Registry, SystemRegistry, SystemSpawner};
+use crate::results::{Action, ResultRow, RowAction};
+use std::path::Path;
+
+/// The word that starts a jump
+const KEYWORD: &str = "reg";
+
+/// Where Registry Editor records the key it was closed at
+const REGEDIT_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Applets\\Regedit";
+const LAST_KEY: &str = "LastKey";
+
+/// (abbreviation, name) of each root key
+const ROOTS: [(&str, &str); 5] = [
+    ("HKLM", "HKEY_LOCAL_MACHINE"),
+    ("HKCU", "HKEY_CURRENT_USER"),
+    ("HKCR", "HKEY_CLASSES_ROOT"),
+    ("HKU", "HKEY_USERS"),
+    ("HKCC", "HKEY_CURRENT_CONFIG"),
+];
+
+/// Keeps cmd's own console hidden
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// The key path in `input` ("reg HKLM\SOFTWARE" → "HKEY_LOCAL_MACHINE\SOFTWARE"),
+/// if it is a jump
+pub fn keyword_query(input: &str) -> Option<String> {
+    let (keyword, path) = input.trim().split_once(char::is_whitespace)?;
+    if !keyword.eq_ignore_ascii_case(KEYWORD) {
+        return None;
+    }
+    normalize(path)
+}
+
+/// The row for jumping to `key`
+pub fn search(key: &str) -> Vec<ResultRow> {
+    vec![ResultRow::new(key, "Open in Registry Editor")
+        .with_action(Action::Run(format!("{} {}", KEYWORD, key)))
+        .with_actions(vec![RowAction::new("Copy key path", Action::Copy(key.to_string()))])]
+}
+
+/// Open Registry Editor at `key` (a full path, as from keyword_query)
+pub fn open(key: &str) -> Result<(), String> {
+    open_in(&SystemRegistry, &SystemSpawner, key)
+}
+
+/// Open Registry Editor at `key` through `registry` and `spawner` (see open)
+fn open_in(registry: &dyn Registry, spawner: &dyn ProcessSpawner, key: &str) -> Result<(), String> {
+    registry.set_string(REGEDIT_KEY, LAST_KEY, &format!("Computer\\{}", key))?;
+    // Through the shell: Registry Editor asks to run elevated for
+    // administrators, which starting it directly can't do
+    let args = ["/C", "start", "", "regedit", "-m"].map(String::from);
+    spawner
+        .spawn(Path::new("cmd"), &args, &[], None, CREATE_NO_WINDOW)
+        .map_err(|e| format!("Failed to open Registry Editor: {}", e))
+}
+
+/// `path` written out in full ("HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft"), or
+/// None if it doesn't start with a root key
+fn normalize(path: &str) -> Option<String> {
+    let path = path.trim().trim_matches('"').replace('/', "\\");
+    let path = path.trim_end_matches('\\');
+    let path = match path.get(..9) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("Computer\\") => &path[9..],
+        _ => path,
+    };
+    let (root, rest) = path.split_once('\\').unwrap_or((path, ""));
+    let root = root.strip_suffix(':').unwrap_or(root);
+    let (_, name) = ROOTS
+        .iter()
+        .find(|(abbreviation, name)| root.eq_ignore_ascii_case(abbreviation) || root.eq_ignore_ascii_case(name))?;
+
+    let rest = rest.trim_start_matches('\\');
+    Some(if rest.is_empty() { name.to_string() } else { format!("{}\\{}", name, rest) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::{MemoryRegistry, RecordingSpawner};
+
+    #[test]
+    fn key_paths_are_written_out_in_full() {
+        let full = Some("HKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Windows".to_string());
+        assert_eq!(normalize("HKLM\\SOFTWARE\\Microsoft\\Windows"), full);
+        assert_eq!(normalize("hklm:\\SOFTWARE\\Microsoft\\Windows\\"), full);
+        assert_eq!(normalize("Computer\\HKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Windows"), full);
+        assert_eq!(normalize("\"HKLM/SOFTWARE/Microsoft/Windows\""), full);
+        assert_eq!(normalize("HKCU").as_deref(), Some("HKEY_CURRENT_USER"));
+        assert_eq!(normalize("HKU\\.DEFAULT\\Control Panel").as_deref(), Some("HKEY_USERS\\.DEFAULT\\Control Panel"));
+        assert_eq!(normalize("HKLMX\\SOFTWARE"), None);
+    }
+
+    #[test]
+    fn other_reg_commands_are_left_for_reg_exe() {
+        assert_eq!(keyword_query("REG  hkcr\\.txt").as_deref(), Some("HKEY_CLASSES_ROOT\\.txt"));
+        assert_eq!(keyword_query("reg query HKLM\\SOFTWARE"), None);
+        assert_eq!(keyword_query("reg"), None);
+        assert_eq!(keyword_query("regedit HKLM"), None);
+    }
+
+    #[test]
+    fn regedit_opens_at_the_last_key() {
+        let registry = MemoryRegistry::new();
+        let spawner = RecordingSpawner::new();
+        open_in(&registry, &spawner, "HKEY_CURRENT_USER\\Software").unwrap();
+
+        let last_key = registry.get_string(REGEDIT_KEY, LAST_KEY).unwrap();
+        assert_eq!(last_key.as_deref(), Some("Computer\\HKEY_CURRENT_USER\\Software"));
+        let spawned = spawner.spawned();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].args, ["/C", "start", "", "regedit", "-m"]);
+    }
+}
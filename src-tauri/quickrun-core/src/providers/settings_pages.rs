@@ -0,0 +1,132 @@
+// providers/settings_pages.rs - Windows Settings pages and Control Panel items
+//
+// Settings pages are suggested by the task they're for, so "sound",
+// "uninstall" or "proxy" opens the right page without knowing its URI:
+// - Settings app pages open by URI (ms-settings:sound)
+// - Classic Control Panel items open with control.exe (control appwiz.cpl,
+//   control /name Microsoft.CredentialManager), the few without a
+//   Control Panel name with their own command
+// - "All Tasks" (God mode) opens the shell folder listing every Control
+//   Panel task
+//
+// The pages are listed in data/settings_pages.tsv, embedded in the binary:
+// each has a name, as Windows titles it, and the other words people search
+// for it by ("add or remove programs" for Installed apps).
+
+use crate::ranking::match_score;
+use crate::results::Action;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The embedded list: name, TAB, ms-settings: URI or command, TAB, other
+/// names (comma-separated), one page per line
+const INDEX: &str = include_str!("../../data/settings_pages.tsv");
+
+/// A Settings page or Control Panel item
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Page {
+    pub name: &'static str,
+    /// ms-settings: URI, or the command that opens it
+    pub target: &'static str,
+    /// Other names it's searched by, comma-separated
+    aliases: &'static str,
+}
+
+/// Pages parsed from INDEX
+fn pages() -> &'static [Page] {
+    static PAGES: OnceLock<Vec<Page>> = OnceLock::new();
+    PAGES.get_or_init(|| {
+        INDEX
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let (name, target) = (fields.next()?, fields.next()?);
+                Some(Page { name, target, aliases: fields.next().unwrap_or_default() })
+            })
+            .collect()
+    })
+}
+
+impl Page {
+    /// Is it a Settings app page (rather than a Control Panel item)?
+    pub fn is_settings_app(&self) -> bool {
+        self.target.starts_with("ms-settings:")
+    }
+
+    /// "Settings - ms-settings:sound" or "Control Panel - control mmsys.cpl"
+    pub fn subtitle(&self) -> String {
+        let source = if self.is_settings_app() { "Settings" } else { "Control Panel" };
+        format!("{} - {}", source, self.target)
+    }
+
+    /// What choosing it does
+    pub fn action(&self) -> Action {
+        if self.is_settings_app() {
+            Action::Open(self.target.to_string())
+        } else {
+            Action::Run(self.target.to_string())
+        }
+    }
+
+    /// The program whose icon stands for it
+    pub fn icon_path(&self) -> Option<String> {
+        let windows = PathBuf::from(std::env::var("WINDIR").ok()?);
+        let program = if self.is_settings_app() {
+            windows.join("ImmersiveControlPanel").join("SystemSettings.exe")
+        } else {
+            windows.join("System32").join("control.exe")
+        };
+        program.is_file().then(|| program.to_string_lossy().to_string())
+    }
+}
+
+/// Pages matching `query` by name or alias, with their score, in list order
+pub fn scored(query: &str) -> Vec<(u32, Page)> {
+    pages()
+        .iter()
+        .filter_map(|page| {
+            let by_alias = page.aliases.split(',').filter_map(|alias| match_score(alias, query)).max();
+            match_score(page.name, query).max(by_alias).map(|score| (score, *page))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(query: &str) -> Vec<&'static str> {
+        scored(query).into_iter().map(|(_, page)| page.name).collect()
+    }
+
+    #[test]
+    fn pages_are_found_by_name_or_alias() {
+        assert_eq!(names("proxy"), ["Proxy"]);
+        assert_eq!(names("add or remove programs"), ["Installed apps", "Programs and Features"]);
+        assert_eq!(names("god mode"), ["All Tasks"]);
+        assert!(names("wallpaper").contains(&"Background"));
+        assert!(names("zzz").is_empty());
+    }
+
+    #[test]
+    fn settings_open_by_uri_and_control_panel_items_run() {
+        let sound = scored("sound settings")[0].1;
+        assert!(matches!(sound.action(), Action::Open(uri) if uri == "ms-settings:sound"));
+        assert_eq!(sound.subtitle(), "Settings - ms-settings:sound");
+
+        let programs = scored("programs and features")[0].1;
+        assert!(matches!(programs.action(), Action::Run(command) if command == "control appwiz.cpl"));
+        assert_eq!(programs.subtitle(), "Control Panel - control appwiz.cpl");
+    }
+
+    #[test]
+    fn every_page_is_listed_once() {
+        let pages = pages();
+        assert!(pages.len() > 50);
+        for (i, page) in pages.iter().enumerate() {
+            assert!(!pages[..i].iter().any(|p| p.name.eq_ignore_ascii_case(page.name)), "{}", page.name);
+            assert!(!pages[..i].iter().any(|p| p.target == page.target), "{}", page.target);
+        }
+    }
+}
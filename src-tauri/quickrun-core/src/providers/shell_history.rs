@@ -0,0 +1,99 @@
+// providers/shell_history.rs - PowerShell (PSReadLine) history import
+//
+// Commands typed in a PowerShell console are saved by PSReadLine to:
+//   %APPDATA%\Microsoft\Windows\PowerShell\PSReadLine\ConsoleHost_history.txt
+//
+// When enabled (setting "import_shell_history", off by default), those
+// commands are offered as suggestions after the ">" terminal prefix, so the
+// launcher knows commands that were only ever run in a console.
+//
+// Ranking: more uses first, then most recent. Duplicates are merged.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Parsed history, reused until the file's modified time changes
+static CACHE: Mutex<Option<(SystemTime, Vec<String>)>> = Mutex::new(None);
+
+/// Is shell history import enabled in settings?
+pub fn is_enabled() -> bool {
+    crate::settings::load().import_shell_history
+}
+
+/// Path to the PSReadLine history file for the console host
+fn history_path() -> Option<PathBuf> {
+    let appdata = std::env::var("APPDATA").ok()?;
+    Some(
+        PathBuf::from(appdata)
+            .join("Microsoft\\Windows\\PowerShell\\PSReadLine\\ConsoleHost_history.txt"),
+    )
+}
+
+/// Parse the history file into commands, best first
+///
+/// PSReadLine writes multi-line commands with a trailing backtick on each
+/// continued line; those are joined back into one command.
+fn parse(contents: &str) -> Vec<String> {
+    let mut commands: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for line in contents.lines() {
+        if let Some(continued) = line.strip_suffix('`') {
+            current.push_str(continued);
+            current.push('\n');
+            continue;
+        }
+        current.push_str(line);
+        let command = current.trim().to_string();
+        current.clear();
+        if !command.is_empty() {
+            commands.push(command);
+        }
+    }
+
+    // (use count, position of last use) per distinct command
+    let mut stats: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (position, command) in commands.iter().enumerate() {
+        let entry = stats.entry(command.as_str()).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 = position;
+    }
+
+    let mut ranked: Vec<(&str, (usize, usize))> = stats.into_iter().collect();
+    ranked.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(b.1 .1.cmp(&a.1 .1)));
+    ranked.into_iter().map(|(command, _)| command.to_string()).collect()
+}
+
+/// Load the ranked, deduplicated shell history (empty if disabled or missing)
+pub fn load() -> Vec<String> {
+    if !is_enabled() {
+        return Vec::new();
+    }
+
+    let Some(path) = history_path() else {
+        return Vec::new();
+    };
+    let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+        return Vec::new();
+    };
+
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((cached_at, commands)) = cache.as_ref() {
+            if *cached_at == modified {
+                return commands.clone();
+            }
+        }
+    }
+
+    let commands = std::fs::read_to_string(&path)
+        .map(|contents| parse(&contents))
+        .unwrap_or_default();
+
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = Some((modified, commands.clone()));
+    }
+
+    commands
+}
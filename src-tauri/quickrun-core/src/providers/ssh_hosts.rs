@@ -0,0 +1,311 @@
+// providers/ssh_hosts.rs - SSH hosts and PuTTY sessions
+//
+// Typing "ssh" and part of a host name ("ssh web01") lists the hosts the
+// user already connects to:
+// - OpenSSH: the Host entries in ~/.ssh/config. Choosing one opens the
+//   preferred terminal running `ssh <host>` (the ">" prefix), so the
+//   config's user, port and keys apply.
+// - PuTTY: the saved sessions under
+//   HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions, opened with
+//   `putty -load "<session>"`.
+// Pressing Enter without choosing a row connects to the host with exactly
+// the name typed (ignoring case); anything else ("ssh web", "ssh
+// admin@10.0.0.5") is run as typed, as a partial name may be a real host.
+//
+// Host patterns (*, ?, !) aren't hosts, so they're left out, as are files
+// pulled in with Include. PuTTY's "Default Settings" isn't a session.
+
+use crate::os::{Registry, SystemRegistry};
+use crate::ranking;
+use crate::results::{Action, ResultRow};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// The word that starts a host search
+const KEYWORD: &str = "ssh";
+
+/// Most rows shown for a query
+const MAX_RESULTS: usize = 8;
+
+/// Where PuTTY keeps its saved sessions (under HKEY_CURRENT_USER)
+const PUTTY_SESSIONS: &str = "Software\\SimonTatham\\PuTTY\\Sessions";
+
+/// The session PuTTY's settings for new sessions are saved as
+const PUTTY_DEFAULTS: &str = "Default Settings";
+
+/// Parsed ~/.ssh/config hosts, reused until its modified time changes
+static CACHE: Mutex<Option<(SystemTime, Vec<Host>)>> = Mutex::new(None);
+
+/// Where a host comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    OpenSsh,
+    Putty,
+}
+
+/// A host the user can connect to
+#[derive(Debug, Clone, PartialEq)]
+struct Host {
+    /// The Host alias or session name
+    name: String,
+    /// The address it connects to, if set
+    host_name: Option<String>,
+    source: Source,
+}
+
+impl Host {
+    /// The launcher input that connects to it
+    fn command(&self) -> String {
+        match self.source {
+            Source::OpenSsh => format!("> ssh {}", self.name),
+            Source::Putty => format!("putty -load \"{}\"", self.name),
+        }
+    }
+
+    fn subtitle(&self) -> String {
+        let kind = match self.source {
+            Source::OpenSsh => "SSH host",
+            Source::Putty => "PuTTY session",
+        };
+        match &self.host_name {
+            Some(host_name) => format!("{} - {}", kind, host_name),
+            None => kind.to_string(),
+        }
+    }
+}
+
+/// The host search in `input` ("ssh web01" → "web01"), if it is one
+pub fn keyword_query(input: &str) -> Option<&str> {
+    ranking::keyword_query(input, KEYWORD)
+}
+
+/// Rows for the hosts matching `query` (empty if there are none)
+pub fn search(query: &str) -> Vec<ResultRow> {
+    ranked(load(), query)
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|host| ResultRow::new(host.name.clone(), host.subtitle()).with_action(Action::Run(host.command())))
+        .collect()
+}
+
+/// The input that connects to the host called `name` (ignoring case), for
+/// pressing Enter without choosing a row
+pub fn exact_match(name: &str) -> Option<String> {
+    named(load(), name).as_ref().map(Host::command)
+}
+
+/// The host called `name`, ignoring case
+fn named(hosts: Vec<Host>, name: &str) -> Option<Host> {
+    hosts.into_iter().find(|host| host.name.eq_ignore_ascii_case(name))
+}
+
+/// Hosts matching `query`, best first (config order, then PuTTY, among
+/// equal matches)
+fn ranked(hosts: Vec<Host>, query: &str) -> Vec<Host> {
+    ranking::ranked(hosts, query, |host| host.name.clone())
+}
+
+/// Every known host: ~/.ssh/config first, then PuTTY sessions
+fn load() -> Vec<Host> {
+    let mut hosts = ssh_config_hosts();
+    hosts.extend(putty_sessions(&SystemRegistry));
+    hosts
+}
+
+fn ssh_config_hosts() -> Vec<Host> {
+    let Some(path) = dirs::home_dir().map(|home| home.join(".ssh").join("config")) else {
+        return Vec::new();
+    };
+    let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+        return Vec::new();
+    };
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_at, hosts)) = cache.as_ref() {
+        if *cached_at == modified {
+            return hosts.clone();
+        }
+    }
+    let hosts = read_config(&path);
+    *cache = Some((modified, hosts.clone()));
+    hosts
+}
+
+fn read_config(path: &Path) -> Vec<Host> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => parse_config(&contents),
+        Err(e) => {
+            eprintln!("[SshHosts] Failed to read '{}': {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Host entries in an OpenSSH config, with the HostName each one sets
+///
+/// Keywords are case-insensitive and may be followed by "=" instead of
+/// spaces. A HostName applies to every name on the Host line above it.
+fn parse_config(contents: &str) -> Vec<Host> {
+    let mut hosts: Vec<Host> = Vec::new();
+    // Hosts from the current Host line (a Match block has none)
+    let mut block = 0..0;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, value) = line
+            .split_once(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or((line, ""));
+        let value = value.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim();
+
+        if keyword.eq_ignore_ascii_case("Host") {
+            let start = hosts.len();
+            for name in value.split_whitespace().map(|name| name.trim_matches('"')) {
+                let is_pattern = name.contains(['*', '?', '!']);
+                if !is_pattern && !name.is_empty() && !hosts.iter().any(|h| h.name.eq_ignore_ascii_case(name)) {
+                    hosts.push(Host { name: name.to_string(), host_name: None, source: Source::OpenSsh });
+                }
+            }
+            block = start..hosts.len();
+        } else if keyword.eq_ignore_ascii_case("Match") {
+            block = 0..0;
+        } else if keyword.eq_ignore_ascii_case("HostName") && !value.is_empty() {
+            // The first value given wins, as in ssh itself
+            for host in &mut hosts[block.clone()] {
+                host.host_name.get_or_insert_with(|| value.to_string());
+            }
+        }
+    }
+    hosts
+}
+
+/// PuTTY's saved sessions, with the host each one connects to
+fn putty_sessions(registry: &dyn Registry) -> Vec<Host> {
+    let keys = match registry.subkeys(PUTTY_SESSIONS) {
+        Ok(keys) => keys,
+        Err(e) => {
+            eprintln!("[SshHosts] {}", e);
+            return Vec::new();
+        }
+    };
+    keys.into_iter()
+        .filter_map(|key| {
+            let name = unescape_session(&key);
+            if name.eq_ignore_ascii_case(PUTTY_DEFAULTS) {
+                return None;
+            }
+            let host_name = registry
+                .get_string(&format!("{}\\{}", PUTTY_SESSIONS, key), "HostName")
+                .ok()
+                .flatten()
+                .filter(|host| !host.is_empty());
+            Some(Host { name, host_name, source: Source::Putty })
+        })
+        .collect()
+}
+
+/// A session name from its registry key (PuTTY writes spaces, "%" and
+/// other awkward characters as %XX)
+fn unescape_session(key: &str) -> String {
+    let bytes = key.as_bytes();
+    let mut name = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match hex {
+            Some(byte) => {
+                name.push(byte);
+                i += 3;
+            }
+            None => {
+                name.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&name).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::MemoryRegistry;
+
+    const CONFIG: &str = "
+# Work
+Host web01 web02
+    HostName 10.0.0.5
+    User deploy
+Host=bastion
+  hostname = jump.example.com
+  HostName ignored.example.com
+Host *.internal !secret
+    User admin
+Match host db*
+    HostName db.example.com
+Host  db01  \"web01\"
+";
+
+    fn names(hosts: &[Host]) -> Vec<&str> {
+        hosts.iter().map(|h| h.name.as_str()).collect()
+    }
+
+    #[test]
+    fn config_hosts_are_listed_without_patterns() {
+        let hosts = parse_config(CONFIG);
+        assert_eq!(names(&hosts), ["web01", "web02", "bastion", "db01"]);
+        assert_eq!(hosts[1].host_name.as_deref(), Some("10.0.0.5"));
+        assert_eq!(hosts[2].host_name.as_deref(), Some("jump.example.com"));
+        // The Match block's HostName isn't db01's
+        assert_eq!(hosts[3].host_name, None);
+        assert_eq!(hosts[0].subtitle(), "SSH host - 10.0.0.5");
+    }
+
+    #[test]
+    fn putty_sessions_are_read_from_the_registry() {
+        let registry = MemoryRegistry::new();
+        registry.set_string(&format!("{}\\Default%20Settings", PUTTY_SESSIONS), "HostName", "").unwrap();
+        registry.set_string(&format!("{}\\lab%20router", PUTTY_SESSIONS), "HostName", "192.168.1.1").unwrap();
+        registry.set_string(&format!("{}\\nas", PUTTY_SESSIONS), "Protocol", "ssh").unwrap();
+
+        let sessions = putty_sessions(&registry);
+        assert_eq!(names(&sessions), ["lab router", "nas"]);
+        assert_eq!(sessions[0].host_name.as_deref(), Some("192.168.1.1"));
+        assert_eq!(sessions[1].host_name, None);
+        assert!(putty_sessions(&MemoryRegistry::new()).is_empty());
+    }
+
+    #[test]
+    fn session_names_are_unescaped() {
+        assert_eq!(unescape_session("web%20server%25"), "web server%");
+        assert_eq!(unescape_session("50%"), "50%");
+        assert_eq!(unescape_session("a%zzb%+1"), "a%zzb%+1");
+    }
+
+    #[test]
+    fn hosts_open_in_the_right_program() {
+        let openssh = Host { name: "web01".to_string(), host_name: None, source: Source::OpenSsh };
+        let putty = Host { name: "lab router".to_string(), host_name: None, source: Source::Putty };
+        assert_eq!(openssh.command(), "> ssh web01");
+        assert_eq!(putty.command(), "putty -load \"lab router\"");
+
+        assert_eq!(keyword_query("ssh web"), Some("web"));
+        assert_eq!(keyword_query("ssh"), None);
+        let ranked = ranked(vec![putty, openssh], "web");
+        assert_eq!(names(&ranked), ["web01"]);
+    }
+
+    #[test]
+    fn enter_connects_only_to_the_host_named() {
+        let hosts = parse_config(CONFIG);
+        assert_eq!(named(hosts.clone(), "WEB01").map(|host| host.command()).as_deref(), Some("> ssh web01"));
+        assert!(named(hosts.clone(), "web").is_none());
+        assert!(named(hosts, "deploy@web01").is_none());
+    }
+}
@@ -0,0 +1,264 @@
+// providers/terminal_profiles.rs - Windows Terminal profiles
+//
+// Typing "term" and part of a profile name ("term ubuntu") lists the
+// profiles set up in Windows Terminal; choosing one, or pressing Enter for
+// the best match, opens a new Terminal window with it (`wt -p "Ubuntu"`).
+//
+// Profiles are read from Terminal's settings.json, wherever the installed
+// versions keep it:
+//   %LOCALAPPDATA%\Packages\Microsoft.WindowsTerminal_8wekyb3d8bbwe\LocalState\settings.json
+//   %LOCALAPPDATA%\Packages\Microsoft.WindowsTerminalPreview_8wekyb3d8bbwe\LocalState\settings.json
+//   %LOCALAPPDATA%\Microsoft\Windows Terminal\settings.json   (unpackaged)
+//
+// The file allows comments and trailing commas, which are removed before
+// it's parsed. Hidden profiles are left out, and a name in more than one
+// file is listed once. Rows keep the order of the file, which is the order
+// of Terminal's own menu, among equally good matches.
+
+use crate::ranking;
+use crate::results::{Action, ResultRow};
+use crate::{icons, runner};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// The word that starts a profile search
+const KEYWORD: &str = "term";
+
+/// Most rows shown for a query
+const MAX_RESULTS: usize = 8;
+
+/// Settings files under %LOCALAPPDATA%, stable Terminal first
+const SETTINGS_FILES: [&str; 3] = [
+    "Packages\\Microsoft.WindowsTerminal_8wekyb3d8bbwe\\LocalState\\settings.json",
+    "Packages\\Microsoft.WindowsTerminalPreview_8wekyb3d8bbwe\\LocalState\\settings.json",
+    "Microsoft\\Windows Terminal\\settings.json",
+];
+
+/// Parsed profile names, reused until a settings file's modified time changes
+static CACHE: Mutex<Option<(Vec<SystemTime>, Vec<String>)>> = Mutex::new(None);
+
+/// The profile search in `input` ("term ubuntu" → "ubuntu"), if it is one
+///
+/// "term" alone isn't, so typing "terminal" still finds the app.
+pub fn keyword_query(input: &str) -> Option<&str> {
+    ranking::keyword_query(input, KEYWORD)
+}
+
+/// The command that opens Windows Terminal with the profile `name`
+///
+/// wt reads ";" as the start of another command, even in quotes.
+pub fn command_for(name: &str) -> String {
+    format!("wt -p \"{}\"", name.replace(';', "\\;"))
+}
+
+/// Rows for the profiles matching `query` (empty if there are none, or no
+/// Windows Terminal)
+pub fn search(query: &str) -> Vec<ResultRow> {
+    let icon = runner::resolve_on_path("wt").and_then(|path| icons::icon_for(&path.to_string_lossy()));
+    ranked(load(), query)
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|name| {
+            ResultRow::new(name.clone(), "Windows Terminal profile")
+                .with_action(Action::Run(command_for(&name)))
+                .with_icon(icon.clone())
+        })
+        .collect()
+}
+
+/// The command for the best match, for pressing Enter without choosing a row
+pub fn best_match(query: &str) -> Option<String> {
+    ranked(load(), query).first().map(|name| command_for(name))
+}
+
+/// Profile names matching `query`, best first (in the file's order among
+/// equal matches)
+fn ranked(names: Vec<String>, query: &str) -> Vec<String> {
+    ranking::ranked(names, query, String::clone)
+}
+
+/// Profile names from every settings file found
+fn load() -> Vec<String> {
+    let Ok(local) = std::env::var("LOCALAPPDATA") else {
+        return Vec::new();
+    };
+    let files: Vec<(PathBuf, SystemTime)> = SETTINGS_FILES
+        .iter()
+        .map(|file| PathBuf::from(&local).join(file))
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    let modified: Vec<SystemTime> = files.iter().map(|(_, modified)| *modified).collect();
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_at, names)) = cache.as_ref() {
+        if *cached_at == modified {
+            return names.clone();
+        }
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for (path, _) in &files {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        match parse(&contents) {
+            Ok(found) => {
+                for name in found {
+                    if !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                        names.push(name);
+                    }
+                }
+            }
+            Err(e) => eprintln!("[TerminalProfiles] Failed to read '{}': {}", path.display(), e),
+        }
+    }
+
+    *cache = Some((modified, names.clone()));
+    names
+}
+
+/// Names of the visible profiles in a settings.json
+///
+/// Profiles are either {"profiles": {"list": [...]}} or, in older files,
+/// {"profiles": [...]}.
+fn parse(contents: &str) -> Result<Vec<String>, String> {
+    let json: Value = serde_json::from_str(&strip_jsonc(contents.trim_start_matches('\u{feff}')))
+        .map_err(|e| format!("Not valid JSON: {}", e))?;
+    let profiles = json.get("profiles").map(|p| p.get("list").unwrap_or(p));
+
+    Ok(profiles
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|profile| !profile.get("hidden").and_then(Value::as_bool).unwrap_or(false))
+        .filter_map(|profile| profile.get("name")?.as_str())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Remove // and /* */ comments and trailing commas (outside strings), so
+/// serde_json accepts the file
+fn strip_jsonc(contents: &str) -> String {
+    let mut json = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (']' | '}', _) => {
+                // A comma before the closing bracket (maybe with whitespace
+                // or a removed comment between) goes
+                let end = json.trim_end().len();
+                if json[..end].ends_with(',') {
+                    json.truncate(end - 1);
+                }
+                json.push(c);
+            }
+            _ => json.push(c),
+        }
+    }
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = r#"
+    // This file was initially generated by Windows Terminal
+    {
+        "$schema": "https://aka.ms/terminal-profiles-schema",
+        "defaultProfile": "{61c54bbd-c2c6-5271-96e7-009a87ff44bf}",
+        "profiles":
+        {
+            "defaults": {},
+            "list":
+            [
+                {
+                    "guid": "{61c54bbd-c2c6-5271-96e7-009a87ff44bf}",
+                    "name": "Windows PowerShell", // the default
+                    "commandline": "%SystemRoot%\\System32\\WindowsPowerShell\\v1.0\\powershell.exe",
+                },
+                { "name": "Command Prompt", "hidden": false },
+                /* generated by WSL */
+                { "name": "Ubuntu", "source": "Windows.Terminal.Wsl" },
+                { "name": "Azure Cloud Shell", "hidden": true },
+                { "name": "Dev // not a comment" },
+            ]
+        },
+    }"#;
+
+    #[test]
+    fn visible_profiles_are_listed_in_order() {
+        assert_eq!(
+            parse(SETTINGS).unwrap(),
+            ["Windows PowerShell", "Command Prompt", "Ubuntu", "Dev // not a comment"]
+        );
+    }
+
+    #[test]
+    fn older_files_list_profiles_directly() {
+        let settings = r#"{ "profiles": [ { "name": "cmd" }, { "name": "PowerShell" } ] }"#;
+        assert_eq!(parse(settings).unwrap(), ["cmd", "PowerShell"]);
+        assert!(parse(r#"{ "theme": "dark" }"#).unwrap().is_empty());
+        assert!(parse("{ \"profiles\": ").is_err());
+    }
+
+    #[test]
+    fn comments_and_trailing_commas_are_removed() {
+        assert_eq!(strip_jsonc("[1, 2, /* three */ ]"), "[1, 2]");
+        assert_eq!(strip_jsonc("{\"a\": \"x\\\"//y\", // note\n}"), "{\"a\": \"x\\\"//y\"}");
+    }
+
+    #[test]
+    fn term_keyword_finds_the_best_profile() {
+        assert_eq!(keyword_query("term ubuntu"), Some("ubuntu"));
+        assert_eq!(keyword_query("Term  power shell "), Some("power shell"));
+        assert_eq!(keyword_query("term"), None);
+        assert_eq!(keyword_query("terminal here"), None);
+
+        let names = parse(SETTINGS).unwrap();
+        assert_eq!(ranked(names.clone(), "ubuntu"), ["Ubuntu"]);
+        assert_eq!(ranked(names.clone(), "p"), ["Windows PowerShell", "Command Prompt"]);
+        assert!(ranked(names, "fish").is_empty());
+    }
+
+    #[test]
+    fn profiles_are_opened_by_name() {
+        assert_eq!(command_for("Ubuntu"), "wt -p \"Ubuntu\"");
+        assert_eq!(command_for("Dev; Build"), "wt -p \"Dev\\; Build\"");
+    }
+}
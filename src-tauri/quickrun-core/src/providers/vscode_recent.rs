@@ -0,0 +1,285 @@
+// providers/vscode_recent.rs - Folders and workspaces recently opened in VS Code
+//
+// VS Code's File > Open Recent list is offered in two ways:
+// - Typing a project's folder name ("quickrun") suggests it among apps and
+//   windows, so a project is one word away
+// - "code" and part of a name ("code quick") lists only recent folders and
+//   workspaces; Enter opens the one with exactly the name typed (ignoring
+//   case). Anything else ("code quick", "code .", "code notes.md") runs VS
+//   Code as typed, as it may be a file or folder to open.
+// Choosing one runs `code "<folder>"` (or the .code-workspace file); remote
+// folders (WSL, SSH, containers) open with `code --folder-uri <uri>`.
+//
+// The list is stored in VS Code's global state database:
+//   %APPDATA%\Code\User\globalStorage\state.vscdb
+//   (SQLite, key "history.recentlyOpenedPathsList")
+// or, before VS Code 1.64, in storage.json next to it ("openedPathsList").
+// VS Code Insiders (%APPDATA%\Code - Insiders, `code-insiders`) is read
+// too. Recent files (as opposed to folders) are left out.
+
+use crate::ranking::{self, match_score};
+use crate::results::{Action, ResultRow};
+use crate::runner;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// The word that starts a project search
+const KEYWORD: &str = "code";
+
+/// Most rows shown for a "code" query
+const MAX_RESULTS: usize = 8;
+
+/// Where the recent list is kept in state.vscdb
+const RECENT_KEY: &str = "history.recentlyOpenedPathsList";
+
+/// (folder under %APPDATA%, command) for each edition
+const EDITIONS: [(&str, &str); 2] = [("Code", "code"), ("Code - Insiders", "code-insiders")];
+
+/// Parsed recent lists, reused until a state file's modified time changes
+static CACHE: Mutex<Option<(Vec<SystemTime>, Vec<Recent>)>> = Mutex::new(None);
+
+/// A recently opened folder or workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recent {
+    /// Folder name, or workspace file name without ".code-workspace"
+    pub name: String,
+    /// Where it is: a path, or a URI for remote folders
+    pub location: String,
+    /// The launcher input that opens it
+    pub command: String,
+}
+
+/// The project search in `input` ("code quick" → "quick"), if it is one
+///
+/// Paths and options ("code .", "code C:\src", "code --new-window") are
+/// left for VS Code.
+pub fn keyword_query(input: &str) -> Option<&str> {
+    let query = ranking::keyword_query(input, KEYWORD)?;
+    let is_for_vscode = query.starts_with(['.', '-', '"']) || runner::is_explicit_path(query);
+    (!is_for_vscode).then_some(query)
+}
+
+/// Rows for the recent folders and workspaces matching `query`
+pub fn search(query: &str) -> Vec<ResultRow> {
+    matching(query)
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|recent| {
+            ResultRow::new(recent.name, format!("VS Code - {}", recent.location))
+                .with_action(Action::Run(recent.command))
+        })
+        .collect()
+}
+
+/// The command for the project called `name` (ignoring case), for pressing
+/// Enter without choosing a row
+pub fn exact_match(name: &str) -> Option<String> {
+    named(load(), name).map(|recent| recent.command)
+}
+
+/// The most recent project called `name`, ignoring case
+fn named(recent: Vec<Recent>, name: &str) -> Option<Recent> {
+    recent.into_iter().find(|recent| recent.name.eq_ignore_ascii_case(name))
+}
+
+/// Recent folders and workspaces with their match score for `query`, most
+/// recently opened first
+pub fn scored(query: &str) -> Vec<(u32, Recent)> {
+    load()
+        .into_iter()
+        .filter_map(|recent| match_score(&recent.name, query).map(|score| (score, recent)))
+        .collect()
+}
+
+/// Matches for `query`, best first (most recent first among equal matches)
+fn matching(query: &str) -> Vec<Recent> {
+    ranking::ranked(load(), query, |recent| recent.name.clone())
+}
+
+/// The recent lists of every edition installed
+fn load() -> Vec<Recent> {
+    let Some(appdata) = dirs::data_dir() else {
+        return Vec::new();
+    };
+    // (state file, command), and its modified time
+    let files: Vec<((PathBuf, &str), SystemTime)> = EDITIONS
+        .iter()
+        .filter_map(|(folder, command)| {
+            let storage = appdata.join(folder).join("User").join("globalStorage");
+            [storage.join("state.vscdb"), storage.join("storage.json")]
+                .into_iter()
+                .find_map(|path| {
+                    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                    Some(((path, *command), modified))
+                })
+        })
+        .collect();
+    let modified: Vec<SystemTime> = files.iter().map(|(_, modified)| *modified).collect();
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_at, recent)) = cache.as_ref() {
+        if *cached_at == modified {
+            return recent.clone();
+        }
+    }
+
+    let mut recent = Vec::new();
+    for ((path, command), _) in &files {
+        match read_list(path) {
+            Ok(list) => recent.extend(parse(&list, command)),
+            Err(e) => eprintln!("[VsCode] Failed to read '{}': {}", path.display(), e),
+        }
+    }
+    *cache = Some((modified, recent.clone()));
+    recent
+}
+
+/// The recent list (JSON with an "entries" array) from a state file
+fn read_list(path: &Path) -> Result<Value, String> {
+    use rusqlite::{Connection, OpenFlags, OptionalExtension};
+
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let storage: Value = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+        return Ok(storage.get("openedPathsList").cloned().unwrap_or_default());
+    }
+
+    // Read-only, so a running VS Code is never disturbed
+    let db = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| e.to_string())?;
+    let list: Option<String> = db
+        .query_row("SELECT value FROM ItemTable WHERE key = ?1", [RECENT_KEY], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match list {
+        Some(list) => serde_json::from_str(&list).map_err(|e| e.to_string()),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Folders and workspaces in a recent list, opened with `command`
+fn parse(list: &Value, command: &str) -> Vec<Recent> {
+    let entries = list.get("entries").and_then(Value::as_array).into_iter().flatten();
+    entries
+        .filter_map(|entry| {
+            let (uri, option, is_workspace) = if let Some(uri) = entry.get("folderUri") {
+                (uri, "--folder-uri", false)
+            } else {
+                (entry.get("workspace")?.get("configPath")?, "--file-uri", true)
+            };
+            let uri = uri.as_str()?;
+            let (location, command, name) = match local_path(uri) {
+                Some(path) => (path.clone(), format!("{} \"{}\"", command, path), last_part(&path).to_string()),
+                None => {
+                    let name = percent_decode(last_part(uri));
+                    (uri.to_string(), format!("{} {} \"{}\"", command, option, uri), name)
+                }
+            };
+            let name = if is_workspace { name.trim_end_matches(".code-workspace").to_string() } else { name };
+            (!name.is_empty()).then_some(Recent { name, location, command })
+        })
+        .collect()
+}
+
+/// The last folder or file name in a path or URI
+fn last_part(location: &str) -> &str {
+    location.trim_end_matches(['\\', '/']).rsplit(['\\', '/']).next().unwrap_or_default()
+}
+
+/// The Windows path of a file:// URI ("file:///c%3A/src/app" → "C:\src\app",
+/// "file://server/share" → "\\server\share"); None for other schemes
+fn local_path(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = match rest.strip_prefix('/') {
+        Some(path) => percent_decode(path),
+        None => format!("//{}", percent_decode(rest)),
+    };
+    let mut path = path.replace('/', "\\");
+    if path.as_bytes().get(1) == Some(&b':') {
+        path[..1].make_ascii_uppercase();
+    }
+    Some(path)
+}
+
+/// Decode %XX escapes (as in URIs)
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match byte {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn recent_list() -> Value {
+        json!({ "entries": [
+            { "folderUri": "file:///c%3A/Users/me/src/quick%20run" },
+            { "fileUri": "file:///c%3A/Users/me/notes.md" },
+            { "workspace": { "id": "4f1c", "configPath": "file:///d%3A/work/platform.code-workspace" } },
+            { "folderUri": "vscode-remote://wsl%2Bubuntu/home/me/api", "remoteAuthority": "wsl+ubuntu" },
+            { "folderUri": "file://nas/projects/site/" },
+        ] })
+    }
+
+    #[test]
+    fn folders_and_workspaces_are_listed_without_files() {
+        let recent = parse(&recent_list(), "code");
+        let names: Vec<&str> = recent.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["quick run", "platform", "api", "site"]);
+
+        assert_eq!(recent[0].location, "C:\\Users\\me\\src\\quick run");
+        assert_eq!(recent[0].command, "code \"C:\\Users\\me\\src\\quick run\"");
+        assert_eq!(recent[1].command, "code \"D:\\work\\platform.code-workspace\"");
+        assert_eq!(recent[2].command, "code --folder-uri \"vscode-remote://wsl%2Bubuntu/home/me/api\"");
+        assert_eq!(recent[3].location, "\\\\nas\\projects\\site\\");
+        assert!(parse(&Value::Null, "code").is_empty());
+    }
+
+    #[test]
+    fn file_uris_become_windows_paths() {
+        assert_eq!(local_path("file:///c%3A/src/app").as_deref(), Some("C:\\src\\app"));
+        assert_eq!(local_path("file://server/share/a%20b").as_deref(), Some("\\\\server\\share\\a b"));
+        assert_eq!(local_path("vscode-remote://ssh-remote%2Bbox/srv"), None);
+        assert_eq!(percent_decode("100%25%zz%"), "100%%zz%");
+    }
+
+    #[test]
+    fn paths_and_options_are_left_for_vscode() {
+        assert_eq!(keyword_query("code quick"), Some("quick"));
+        assert_eq!(keyword_query("Code  my project "), Some("my project"));
+        assert_eq!(keyword_query("code ."), None);
+        assert_eq!(keyword_query("code C:\\src\\app"), None);
+        assert_eq!(keyword_query("code --new-window"), None);
+        assert_eq!(keyword_query("code \"my file.txt\""), None);
+        assert_eq!(keyword_query("code"), None);
+    }
+
+    #[test]
+    fn enter_opens_only_the_project_named() {
+        let recent = parse(&recent_list(), "code");
+        assert_eq!(named(recent.clone(), "Quick Run").map(|r| r.name).as_deref(), Some("quick run"));
+        assert!(named(recent.clone(), "quick").is_none());
+        assert!(named(recent, "notes.md").is_none());
+    }
+}
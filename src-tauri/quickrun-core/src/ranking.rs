@@ -0,0 +1,304 @@
+// ranking.rs - Suggestion ranking pipeline
+//
+// Every suggestion candidate is ordered by these keys, in turn:
+// 1. Match score: how well the text matches the query (match_score), plus
+//    any bonus the source adds (history, plugin prefixes)
+// 2. Frecency: how often and how recently it was launched (0 if never)
+// 3. Title, alphabetically (case-insensitive, then case-sensitive)
+// 4. Provider priority: history, apps, PATH, windows, Settings pages,
+//    VS Code projects, games, browser profiles, plugins
+// 5. Detail (path or subtitle), so even identical titles have a fixed order
+//
+// The order is total: the same candidates always come out in the same order
+// no matter how the sources enumerated them, so the list doesn't jitter as
+// the user types one more character.
+//
+// Keyword providers ("ssh web", "env java", ...) list only their own items,
+// which are ordered by match score alone (see ranked).
+
+use std::cmp::Ordering;
+
+/// Where a candidate came from, highest priority first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Provider {
+    History,
+    App,
+    PathCommand,
+    Window,
+    Setting,
+    Project,
+    Game,
+    BrowserProfile,
+    Plugin,
+}
+
+/// What the pipeline needs to know about a candidate
+pub trait Rankable {
+    fn score(&self) -> u32;
+    fn frecency(&self) -> u32;
+    fn title(&self) -> &str;
+    fn provider(&self) -> Provider;
+    fn detail(&self) -> &str;
+}
+
+/// Score how well `candidate` matches `query` (case-insensitive)
+///
+/// Exact (100) > prefix (80) > word start (60) > substring (40). Returns None
+/// if it doesn't match at all.
+pub fn match_score(candidate: &str, query: &str) -> Option<u32> {
+    let candidate = candidate.to_lowercase();
+    let query = query.to_lowercase();
+
+    if candidate == query {
+        Some(100)
+    } else if candidate.starts_with(&query) {
+        Some(80)
+    } else if candidate
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(&query))
+    {
+        Some(60)
+    } else if candidate.contains(&query) {
+        Some(40)
+    } else {
+        None
+    }
+}
+
+/// Alphabetical order that ignores case first, then uses it to break ties
+fn compare_text(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b))
+}
+
+/// Compare two candidates: Less means `a` is shown first
+pub fn compare<T: Rankable>(a: &T, b: &T) -> Ordering {
+    b.score()
+        .cmp(&a.score())
+        .then_with(|| b.frecency().cmp(&a.frecency()))
+        .then_with(|| compare_text(a.title(), b.title()))
+        .then_with(|| a.provider().cmp(&b.provider()))
+        .then_with(|| compare_text(a.detail(), b.detail()))
+}
+
+/// Sort candidates best first
+pub fn rank<T: Rankable>(candidates: &mut [T]) {
+    candidates.sort_by(compare);
+}
+
+/// The query after `keyword` in `input` ("ssh  web " → "web"), if `input`
+/// starts with the keyword (case-insensitive) and a space
+pub fn keyword_query<'a>(input: &'a str, keyword: &str) -> Option<&'a str> {
+    let (first, query) = input.trim().split_once(char::is_whitespace)?;
+    first.eq_ignore_ascii_case(keyword).then_some(query.trim())
+}
+
+/// The `items` whose name matches `query`, best first (in their own order
+/// among equal matches, and all of them for an empty query)
+pub fn ranked<T>(items: Vec<T>, query: &str, name: impl Fn(&T) -> String) -> Vec<T> {
+    let mut matches: Vec<(u32, T)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let score = if query.is_empty() { Some(0) } else { match_score(&name(&item), query) };
+            score.map(|score| (score, item))
+        })
+        .collect();
+    // Stable sort: equal scores keep the given order
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+    matches.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A candidate as the suggestion engine would build it
+    #[derive(Debug, Clone, PartialEq)]
+    struct Fixture {
+        score: u32,
+        frecency: u32,
+        title: String,
+        provider: Provider,
+        detail: String,
+    }
+
+    impl Rankable for Fixture {
+        fn score(&self) -> u32 {
+            self.score
+        }
+        fn frecency(&self) -> u32 {
+            self.frecency
+        }
+        fn title(&self) -> &str {
+            &self.title
+        }
+        fn provider(&self) -> Provider {
+            self.provider
+        }
+        fn detail(&self) -> &str {
+            &self.detail
+        }
+    }
+
+    /// Apps in the fixture index: (name, path)
+    const APPS: &[(&str, &str)] = &[
+        ("Notepad++", "C:\\Program Files\\Notepad++\\notepad++.exe"),
+        ("Notepad", "C:\\Windows\\notepad.exe"),
+        ("Microsoft Teams", "C:\\Teams\\ms-teams.exe"),
+        ("Microsoft Edge", "C:\\Edge\\msedge.exe"),
+        ("Paint", "C:\\Windows\\mspaint.exe"),
+        ("paint.net", "C:\\Program Files\\paint.net\\paintdotnet.exe"),
+        ("Visual Studio Code", "C:\\VSCode\\Code.exe"),
+    ];
+
+    /// Commands in the fixture PATH
+    const PATH_COMMANDS: &[&str] = &["notepad", "node", "npm", "code", "mspaint"];
+
+    /// History in the fixture: (command, frecency)
+    const HISTORY: &[(&str, u32)] = &[("code", 400), ("npm", 100)];
+
+    /// Build every matching candidate for `query` from the fixtures
+    fn candidates(query: &str) -> Vec<Fixture> {
+        let history = HISTORY.iter().filter_map(|&(command, frecency)| {
+            Some(Fixture {
+                score: match_score(command, query)? + 10,
+                frecency,
+                title: command.to_string(),
+                provider: Provider::History,
+                detail: String::new(),
+            })
+        });
+        let apps = APPS.iter().filter_map(|&(name, path)| {
+            Some(Fixture {
+                score: match_score(name, query)?,
+                frecency: 0,
+                title: name.to_string(),
+                provider: Provider::App,
+                detail: path.to_string(),
+            })
+        });
+        let commands = PATH_COMMANDS.iter().filter_map(|&command| {
+            Some(Fixture {
+                score: match_score(command, query)?,
+                frecency: 0,
+                title: command.to_string(),
+                provider: Provider::PathCommand,
+                detail: format!("C:\\bin\\{}.exe", command),
+            })
+        });
+        history.chain(apps).chain(commands).collect()
+    }
+
+    fn ranked_titles(query: &str) -> Vec<String> {
+        let mut list = candidates(query);
+        rank(&mut list);
+        list.into_iter().map(|c| c.title).collect()
+    }
+
+    #[test]
+    fn match_score_prefers_exact_then_prefix_then_word_then_substring() {
+        assert_eq!(match_score("Notepad", "notepad"), Some(100));
+        assert_eq!(match_score("Notepad++", "note"), Some(80));
+        assert_eq!(match_score("Microsoft Edge", "edge"), Some(60));
+        assert_eq!(match_score("mspaint", "paint"), Some(40));
+        assert_eq!(match_score("Paint", "xyz"), None);
+    }
+
+    #[test]
+    fn higher_score_wins() {
+        // Exact "Notepad" and "notepad" outrank the "Notepad++" prefix match
+        let titles = ranked_titles("notepad");
+        assert_eq!(titles, ["Notepad", "notepad", "Notepad++"]);
+    }
+
+    #[test]
+    fn frecency_breaks_score_ties() {
+        let mut list = vec![
+            Fixture {
+                score: 80,
+                frecency: 5,
+                title: "alpha".to_string(),
+                provider: Provider::History,
+                detail: String::new(),
+            },
+            Fixture {
+                score: 80,
+                frecency: 50,
+                title: "beta".to_string(),
+                provider: Provider::History,
+                detail: String::new(),
+            },
+        ];
+        rank(&mut list);
+        assert_eq!(list[0].title, "beta");
+    }
+
+    #[test]
+    fn equal_scores_sort_alphabetically_ignoring_case() {
+        // Both are prefix matches (80)
+        assert_eq!(ranked_titles("micro"), ["Microsoft Edge", "Microsoft Teams"]);
+        // "node" < "Notepad" < "notepad" < "Notepad++", all prefix matches
+        assert_eq!(ranked_titles("no"), ["node", "Notepad", "notepad", "Notepad++"]);
+    }
+
+    #[test]
+    fn provider_priority_breaks_title_ties() {
+        // The same title from two sources: the app index comes first
+        let mut list = vec![
+            Fixture {
+                score: 100,
+                frecency: 0,
+                title: "code".to_string(),
+                provider: Provider::PathCommand,
+                detail: String::new(),
+            },
+            Fixture {
+                score: 100,
+                frecency: 0,
+                title: "code".to_string(),
+                provider: Provider::App,
+                detail: String::new(),
+            },
+        ];
+        rank(&mut list);
+        assert_eq!(list[0].provider, Provider::App);
+    }
+
+    #[test]
+    fn order_does_not_depend_on_input_order() {
+        for query in ["n", "no", "note", "paint", "code", "m", "s"] {
+            let mut forward = candidates(query);
+            let mut backward: Vec<Fixture> = forward.iter().rev().cloned().collect();
+            rank(&mut forward);
+            rank(&mut backward);
+            assert_eq!(forward, backward, "query {:?}", query);
+        }
+    }
+
+    #[test]
+    fn keywords_need_a_space_and_a_query() {
+        assert_eq!(keyword_query("SSH  web01 ", "ssh"), Some("web01"));
+        assert_eq!(keyword_query("ssh", "ssh"), None);
+        assert_eq!(keyword_query("sshd start", "ssh"), None);
+    }
+
+    #[test]
+    fn ranked_keeps_the_given_order_among_equal_matches() {
+        let names = vec!["Windows PowerShell", "Command Prompt", "Ubuntu", "PowerShell"];
+        let rank = |query| ranked(names.clone(), query, |name| name.to_string());
+        assert_eq!(rank("powershell"), ["PowerShell", "Windows PowerShell"]);
+        assert_eq!(rank("p"), ["PowerShell", "Windows PowerShell", "Command Prompt"]);
+        assert_eq!(rank(""), names);
+        assert!(rank("fish").is_empty());
+    }
+
+    #[test]
+    fn one_more_character_keeps_relative_order() {
+        // Candidates matching both "no" and "not" at the same strength keep
+        // their relative order, so rows don't swap places while typing
+        let before = ranked_titles("no");
+        let after = ranked_titles("not");
+        let common: Vec<&String> = before.iter().filter(|t| after.contains(t)).collect();
+        let after_common: Vec<&String> = after.iter().filter(|t| before.contains(t)).collect();
+        assert_eq!(common, after_common);
+    }
+}
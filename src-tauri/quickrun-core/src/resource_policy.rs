@@ -0,0 +1,185 @@
+// resource_policy.rs - Doing less when Windows asks for it
+//
+// Windows has two switches that mean "go easy":
+// - Settings > Accessibility > Visual effects: "Animation effects" (off =
+//   reduce animations) and "Transparency effects" (off = no see-through
+//   windows)
+// - Battery saver, on by hand or when the battery runs low
+//
+// This module turns them into one ResourcePolicy that the rest of the app
+// follows, so no feature queries the OS itself:
+// - animations: CSS transitions and spinners (off when animations are
+//   reduced or on battery saver)
+// - blur: see-through window backgrounds (off when transparency is off or on
+//   battery saver)
+// - scheduled_rebuilds: the index's timed rebuilds (off on battery saver;
+//   rebuilds after a watched folder changes still happen)
+//
+// Windows sends no event for these that a background thread can wait on
+// without a window, so watch() polls.
+
+use serde::Serialize;
+use std::time::Duration;
+
+/// How often watch() checks the OS settings
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What Windows currently asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemEffects {
+    /// "Animation effects" is on
+    pub animations: bool,
+    /// "Transparency effects" is on
+    pub transparency: bool,
+    /// Battery saver is on
+    pub battery_saver: bool,
+}
+
+impl Default for SystemEffects {
+    fn default() -> Self {
+        Self { animations: true, transparency: true, battery_saver: false }
+    }
+}
+
+/// What the app should do, given the OS settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ResourcePolicy {
+    /// Animate transitions and spinners
+    pub animations: bool,
+    /// Use see-through (blurred) window backgrounds
+    pub blur: bool,
+    /// Rebuild the index on its timer
+    pub scheduled_rebuilds: bool,
+    /// Battery saver is on (for display)
+    pub battery_saver: bool,
+}
+
+impl Default for ResourcePolicy {
+    fn default() -> Self {
+        Self::from_effects(SystemEffects::default())
+    }
+}
+
+impl ResourcePolicy {
+    /// The policy for the given OS settings
+    pub fn from_effects(effects: SystemEffects) -> Self {
+        let saving = effects.battery_saver;
+        Self {
+            animations: effects.animations && !saving,
+            blur: effects.transparency && !saving,
+            scheduled_rebuilds: !saving,
+            battery_saver: saving,
+        }
+    }
+}
+
+/// The policy for the current OS settings
+pub fn current() -> ResourcePolicy {
+    ResourcePolicy::from_effects(system_effects())
+}
+
+/// Check the OS settings every so often and call `on_change` with the new
+/// policy whenever it changes (call once at startup)
+pub fn watch(on_change: impl Fn(ResourcePolicy) + Send + 'static) {
+    std::thread::spawn(move || {
+        let mut last = current();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let policy = current();
+            if policy != last {
+                eprintln!(
+                    "[ResourcePolicy] animations: {}, blur: {}, scheduled rebuilds: {}",
+                    policy.animations, policy.blur, policy.scheduled_rebuilds
+                );
+                on_change(policy);
+                last = policy;
+            }
+        }
+    });
+}
+
+#[cfg(windows)]
+fn system_effects() -> SystemEffects {
+    use std::ffi::c_void;
+    use windows::core::BOOL;
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    let defaults = SystemEffects::default();
+
+    let mut enabled = BOOL(1);
+    let animations = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut enabled as *mut BOOL as *mut c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .map(|()| enabled.as_bool())
+    .unwrap_or(defaults.animations);
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    let battery_saver = unsafe { GetSystemPowerStatus(&mut status) }
+        .map(|()| status.SystemStatusFlag == 1)
+        .unwrap_or(defaults.battery_saver);
+
+    let transparency = transparency_enabled().unwrap_or(defaults.transparency);
+    SystemEffects { animations, transparency, battery_saver }
+}
+
+/// "Transparency effects", which Windows keeps only in the registry
+#[cfg(windows)]
+fn transparency_enabled() -> Option<bool> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let key = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize")
+        .ok()?;
+    key.get_value::<u32, _>("EnableTransparency").ok().map(|value| value != 0)
+}
+
+#[cfg(not(windows))]
+fn system_effects() -> SystemEffects {
+    SystemEffects::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The policy when the default OS settings are changed by `change`
+    fn policy_with(change: fn(&mut SystemEffects)) -> ResourcePolicy {
+        let mut effects = SystemEffects::default();
+        change(&mut effects);
+        ResourcePolicy::from_effects(effects)
+    }
+
+    #[test]
+    fn by_default_everything_is_on() {
+        let policy = ResourcePolicy::default();
+        assert!(policy.animations && policy.blur && policy.scheduled_rebuilds);
+        assert!(!policy.battery_saver);
+    }
+
+    #[test]
+    fn reduced_effects_only_turn_off_their_own_feature() {
+        let policy = policy_with(|e| e.animations = false);
+        assert!(!policy.animations);
+        assert!(policy.blur && policy.scheduled_rebuilds);
+
+        let policy = policy_with(|e| e.transparency = false);
+        assert!(!policy.blur);
+        assert!(policy.animations && policy.scheduled_rebuilds);
+    }
+
+    #[test]
+    fn battery_saver_turns_everything_off() {
+        let policy = policy_with(|e| e.battery_saver = true);
+        assert!(!policy.animations && !policy.blur && !policy.scheduled_rebuilds);
+        assert!(policy.battery_saver);
+    }
+}
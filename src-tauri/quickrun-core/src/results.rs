@@ -0,0 +1,132 @@
+// results.rs - Result rows shown below the launcher input
+//
+// Suggestions (while typing) and keywords (like `http <url>`) produce rows
+// for the launcher to display. Each row can carry an action
+// that runs when the user selects it (copy a value, open a URL, run a command).
+//
+// These types are serialized to the frontend as-is, so field names are part
+// of the frontend contract (see src/main.ts).
+
+use serde::{Deserialize, Serialize};
+
+/// What happens when a result row is selected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Action {
+    /// Informational row, selecting it does nothing
+    None,
+    /// Copy the text to the clipboard
+    Copy(String),
+    /// Run the text as if the user had typed it and pressed Enter
+    Run(String),
+    /// Open a file, folder, or URL with its default handler
+    Open(String),
+    /// Run an executable elevated (UAC prompt)
+    RunAsAdmin(String),
+    /// Run an executable inside Windows Sandbox
+    Sandbox(String),
+    /// Show a file in Explorer with it selected
+    Reveal(String),
+    /// Move a file or folder to the Recycle Bin
+    Recycle(String),
+    /// Restore a recycled file or folder to its original path (undo)
+    Restore(String),
+    /// Compress a file or folder into a zip next to it
+    Compress(String),
+    /// Extract a zip into a folder next to it
+    Extract(String),
+    /// Cancel the running compress/extract
+    CancelArchive,
+    /// Apply a batch rename (the text is the `rename` keyword's arguments)
+    ApplyRename(String),
+    /// Undo a batch rename (the text is the path of its undo record)
+    UndoRename(String),
+    /// Bring an open window to the front (the text is its handle)
+    Focus(String),
+    /// Copy the text, then paste it into the previously active window
+    Paste(String),
+    /// Let an external plugin handle its own row (see plugins/)
+    Plugin {
+        plugin: String,
+        data: serde_json::Value,
+    },
+}
+
+/// A secondary action offered for a row (Shift+Enter or right-click)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowAction {
+    /// Menu text (e.g., "Run as administrator")
+    pub label: String,
+    /// What it does
+    pub action: Action,
+}
+
+impl RowAction {
+    pub fn new(label: impl Into<String>, action: Action) -> Self {
+        Self {
+            label: label.into(),
+            action,
+        }
+    }
+}
+
+/// A single row in the results list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultRow {
+    /// Main text (e.g., "200 OK")
+    pub title: String,
+    /// Secondary, dimmer text (e.g., "Status")
+    pub subtitle: String,
+    /// Action performed when the row is selected
+    pub action: Action,
+    /// App icon as a PNG data URL (see icons.rs), if one was found
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Secondary actions (the main `action` runs on Enter)
+    #[serde(default)]
+    pub actions: Vec<RowAction>,
+}
+
+impl ResultRow {
+    /// Create an informational row with no action
+    pub fn new(title: impl Into<String>, subtitle: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            subtitle: subtitle.into(),
+            action: Action::None,
+            icon: None,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Attach an action to the row
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Attach secondary actions to the row
+    pub fn with_actions(mut self, actions: Vec<RowAction>) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    /// Attach an icon (PNG data URL) to the row
+    pub fn with_icon(mut self, icon: Option<String>) -> Self {
+        self.icon = icon;
+        self
+    }
+}
+
+/// Outcome of running the user's input
+///
+/// - Launched: a process was started, the launcher hides itself
+/// - Results: a keyword produced rows to show, the launcher stays open
+/// - Done: an action completed (e.g., copy), the current view is left as-is
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RunOutcome {
+    Launched,
+    Results { rows: Vec<ResultRow> },
+    Done,
+}
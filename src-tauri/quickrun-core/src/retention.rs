@@ -0,0 +1,280 @@
+// retention.rs - Keeping the data folder from growing without end
+//
+// Launch history, clipboard history and the app index cache are kept within
+// limits set under "retention" in settings.json, each with any of:
+// - "max_entries": the most recent (or most used) entries kept
+// - "max_age_days": entries not used for longer are dropped
+// - "max_kb": entries are kept, newest first, only while they fit
+// null means no limit. The defaults keep a year of launch history (and at
+// most 512 KB of it), 30 days of clipboard history, and an 8 MB index cache.
+//
+// The limits are applied by a maintenance pass, once shortly after startup
+// and then daily (start_maintenance), or on demand from Settings. The
+// history is also capped at 500 commands and the clipboard history at
+// "clipboard_history_size" as entries are added; these limits only go lower.
+//
+// The index cache is pruned by usage rather than age: apps and PATH
+// commands the user launches are kept first (see index::save_cache). It is
+// only a head start for the next scan, so a pruned cache just means the
+// rest of the index arrives a few seconds after startup.
+//
+// usage() reports what each file and folder in the data folder takes up,
+// for the Storage section of Settings.
+
+use crate::history;
+use crate::index;
+use crate::providers::clipboard_history;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// The first maintenance pass waits this long, to stay out of startup's way
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// Time between maintenance passes
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(DAY);
+
+/// Limits on one kind of data (None = no limit)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    pub max_entries: Option<usize>,
+    pub max_age_days: Option<u64>,
+    pub max_kb: Option<u64>,
+}
+
+/// The limits for each kind of data ("retention" in settings.json)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Retention {
+    /// Launch history (history.json)
+    pub history: Limits,
+    /// Clipboard history (clipboard_history.dat)
+    pub clipboard: Limits,
+    /// App index cache (index.bin); max_age_days doesn't apply
+    pub index: Limits,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            history: Limits { max_entries: None, max_age_days: Some(365), max_kb: Some(512) },
+            clipboard: Limits { max_entries: None, max_age_days: Some(30), max_kb: Some(1024) },
+            index: Limits { max_entries: None, max_age_days: None, max_kb: Some(8 * 1024) },
+        }
+    }
+}
+
+/// How many entries a maintenance pass dropped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Pruned {
+    pub history: usize,
+    pub clipboard: usize,
+    pub index: usize,
+}
+
+/// One file or folder in the data folder
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageItem {
+    /// File or folder name ("history.json", "plugins")
+    pub name: String,
+    /// Size on disk, folders included with everything in them
+    pub bytes: u64,
+    /// Entries held, for the data with limits
+    pub entries: Option<usize>,
+    /// The limits that apply, for the data with limits
+    pub limits: Option<Limits>,
+}
+
+/// What the data folder takes up
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageUsage {
+    pub dir: PathBuf,
+    pub total_bytes: u64,
+    /// Largest first
+    pub items: Vec<StorageItem>,
+}
+
+/// Size of `entry` once written, near enough (as compact JSON)
+pub fn entry_size<T: Serialize>(entry: &T) -> u64 {
+    serde_json::to_vec(entry).map_or(0, |json| json.len() as u64 + 1)
+}
+
+/// Trim `entries` (the ones to keep first) to `limits`: none last used
+/// longer than max_age_days before `now` (by `time`), then the first
+/// max_entries, then as many as fit in max_kb
+///
+/// Returns how many were dropped.
+pub fn trim<T: Serialize>(entries: &mut Vec<T>, limits: &Limits, time: impl Fn(&T) -> u64, now: u64) -> usize {
+    let before = entries.len();
+
+    if let Some(days) = limits.max_age_days {
+        let cutoff = now.saturating_sub(days.saturating_mul(DAY));
+        entries.retain(|entry| time(entry) >= cutoff);
+    }
+    if let Some(max_entries) = limits.max_entries {
+        entries.truncate(max_entries);
+    }
+    if let Some(max_kb) = limits.max_kb {
+        let budget = max_kb.saturating_mul(1024);
+        let mut used = 0;
+        let fits = entries
+            .iter()
+            .take_while(|entry| {
+                used += entry_size(entry);
+                used <= budget
+            })
+            .count();
+        entries.truncate(fits);
+    }
+
+    before - entries.len()
+}
+
+/// Apply the retention limits in settings.json to everything they cover
+pub fn run_maintenance() -> Result<Pruned, String> {
+    let retention = crate::settings::load().retention;
+    let pruned = Pruned {
+        history: history::prune(&retention.history)?,
+        clipboard: clipboard_history::prune(&retention.clipboard)?,
+        index: index::prune_cache()?,
+    };
+
+    if pruned != Pruned::default() {
+        eprintln!(
+            "[Retention] Dropped {} history, {} clipboard and {} index entries",
+            pruned.history, pruned.clipboard, pruned.index
+        );
+    }
+    Ok(pruned)
+}
+
+/// Run maintenance shortly after startup and then daily (call once)
+pub fn start_maintenance() {
+    std::thread::spawn(|| {
+        std::thread::sleep(STARTUP_DELAY);
+        loop {
+            if let Err(e) = run_maintenance() {
+                eprintln!("[Retention] {}", e);
+            }
+            std::thread::sleep(MAINTENANCE_INTERVAL);
+        }
+    });
+}
+
+/// Size of a file, or of a folder and everything in it
+fn disk_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|children| children.flatten().map(|child| disk_size(&child.path())).sum())
+        .unwrap_or(0)
+}
+
+/// The items in `dir`, largest first, with sizes
+fn list_items(dir: &Path) -> Vec<StorageItem> {
+    let mut items: Vec<StorageItem> = std::fs::read_dir(dir)
+        .map(|children| {
+            children
+                .flatten()
+                .map(|child| StorageItem {
+                    name: child.file_name().to_string_lossy().into_owned(),
+                    bytes: disk_size(&child.path()),
+                    entries: None,
+                    limits: None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    items.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    items
+}
+
+/// What each file and folder in the data folder takes up
+pub fn usage() -> StorageUsage {
+    let dir = crate::get_config_dir();
+    let retention = crate::settings::load().retention;
+    let mut items = list_items(&dir);
+
+    let limited = [
+        ("history.json", history::load().len(), retention.history),
+        ("clipboard_history.dat", clipboard_history::entries().len(), retention.clipboard),
+        ("index.bin", index::entries().len() + index::path_commands().len(), retention.index),
+    ];
+    for (name, entries, limits) in limited {
+        if let Some(item) = items.iter_mut().find(|item| item.name.eq_ignore_ascii_case(name)) {
+            item.entries = Some(entries);
+            item.limits = Some(limits);
+        }
+    }
+
+    StorageUsage { total_bytes: items.iter().map(|item| item.bytes).sum(), dir, items }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Entries (name, last used), newest first
+    fn entries() -> Vec<(String, u64)> {
+        (0..10).map(|i| (format!("cmd{}", i), 100 * DAY - i * DAY)).collect()
+    }
+
+    #[test]
+    fn no_limits_keep_everything() {
+        let mut kept = entries();
+        assert_eq!(trim(&mut kept, &Limits::default(), |e| e.1, 100 * DAY), 0);
+        assert_eq!(kept.len(), 10);
+    }
+
+    #[test]
+    fn old_entries_are_dropped() {
+        let mut kept = entries();
+        let limits = Limits { max_age_days: Some(3), ..Limits::default() };
+
+        assert_eq!(trim(&mut kept, &limits, |e| e.1, 100 * DAY), 6);
+        assert_eq!(kept.last().unwrap().0, "cmd3");
+    }
+
+    #[test]
+    fn only_the_first_entries_are_kept() {
+        let mut kept = entries();
+        let limits = Limits { max_entries: Some(4), ..Limits::default() };
+
+        assert_eq!(trim(&mut kept, &limits, |e| e.1, 100 * DAY), 6);
+        assert_eq!(kept.last().unwrap().0, "cmd3");
+    }
+
+    #[test]
+    fn entries_are_kept_while_they_fit() {
+        let limits = Limits { max_kb: Some(1), ..Limits::default() };
+
+        let mut small = entries();
+        assert_eq!(trim(&mut small, &limits, |e| e.1, 100 * DAY), 0);
+
+        // 303 bytes each, so three fit in 1 KB
+        let mut large: Vec<String> = (0..10).map(|_| "x".repeat(300)).collect();
+        assert_eq!(trim(&mut large, &limits, |_| 0, 0), 7);
+    }
+
+    #[test]
+    fn folders_count_everything_in_them() {
+        let dir = std::env::temp_dir().join(format!("quickrun-retention-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("plugins")).unwrap();
+        std::fs::write(dir.join("history.json"), [0u8; 10]).unwrap();
+        std::fs::write(dir.join("plugins").join("a.json"), [0u8; 30]).unwrap();
+
+        let items = list_items(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let sizes: Vec<(&str, u64)> = items.iter().map(|item| (item.name.as_str(), item.bytes)).collect();
+        assert_eq!(sizes, vec![("plugins", 30), ("history.json", 10)]);
+    }
+}
@@ -0,0 +1,711 @@
+// runner.rs - Windows PATH resolution and process spawning
+//
+// This module implements Windows-style command resolution:
+// 0. Check if the first word is a user alias (see aliases.rs)
+// 1. Check if input is an explicit path (absolute or relative with path separators)
+// 2. If explicit, verify existence and spawn directly ("path args" and
+//    "\"path with spaces\" args" run the path with the arguments)
+// 3. Otherwise, search the PATH environment variable
+// 4. Respect PATHEXT (.EXE, .CMD, .BAT, etc.) for extensionless commands
+// 5. Fall back to the Start Menu app index (shortcuts are opened via the shell)
+// 6. Otherwise, a PATH command followed by arguments ("code .")
+// 7. Spawn the process detached (no shell wrapper, direct execution)
+//
+// Commands can be run in a folder (run_command_in): relative paths are
+// looked up there and the process starts there. The launcher uses the folder
+// open in Explorer, so "code ." opens what the user is looking at.
+//
+// An alias can name an environment preset (see env_presets.rs); its
+// variables are set on the process, and its PATH is also where the alias's
+// target is looked up.
+//
+// File system and process calls go through the traits in os/, so the
+// resolution rules are unit-tested below without touching the real machine.
+
+use crate::aliases::Alias;
+use crate::env_presets;
+use crate::i18n;
+use crate::index::AppEntry;
+use crate::os::{FileSystem, ProcessSpawner, SystemFs, SystemSpawner};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Windows process creation flag: no console window for the new process
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Extensions of programs CreateProcess can start itself; anything else an
+/// alias points to (installers, scripts, documents) is opened by the shell
+const PROGRAM_EXTENSIONS: &[&str] = &["exe", "com"];
+
+/// Check if the input looks like an explicit file path (contains \ or / or :, or starts with ~)
+/// Examples: "C:\Windows\notepad.exe", ".\script.bat", "folder\app.exe", "~\tools\app.exe"
+pub fn is_explicit_path(input: &str) -> bool {
+    input.contains('\\') || input.contains('/') || input.contains(':') || input.starts_with('~')
+}
+
+/// Expand a leading "~" to the user's home folder.
+/// Examples: "~\Downloads" → "C:\Users\me\Downloads", "notepad" → "notepad"
+pub fn expand_home(input: &str) -> PathBuf {
+    if let Some(rest) = input.strip_prefix('~') {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest.trim_start_matches(['\\', '/']));
+        }
+    }
+    PathBuf::from(input)
+}
+
+/// An explicit path typed in `dir`: "~" expanded, relative paths joined to
+/// `dir` (if there is one)
+pub fn explicit_path(input: &str, dir: Option<&Path>) -> PathBuf {
+    let path = expand_home(input);
+    match dir {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    }
+}
+
+/// An explicit path (quoted if it has spaces) and the arguments after it
+fn split_explicit(input: &str, dir: Option<&Path>) -> Option<(PathBuf, Vec<String>)> {
+    let mut args = split_args(input);
+    if args.is_empty() {
+        return None;
+    }
+    let program = args.remove(0);
+    Some((explicit_path(&program, dir), args))
+}
+
+/// Extensions Windows tries for extensionless commands (PATHEXT)
+fn pathext() -> String {
+    env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+}
+
+/// The directories on PATH, in order
+fn path_dirs() -> Vec<PathBuf> {
+    env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect())
+        .unwrap_or_default()
+}
+
+/// The directories on PATH for a process started with `environment`
+fn path_dirs_with(environment: &[(String, String)]) -> Vec<PathBuf> {
+    match environment.iter().find(|(name, _)| name.eq_ignore_ascii_case("PATH")) {
+        Some((_, path)) => env::split_paths(path).collect(),
+        None => path_dirs(),
+    }
+}
+
+/// Resolve a command name by searching the PATH environment variable.
+/// Respects PATHEXT for extensionless commands (e.g., "notepad" → "notepad.exe").
+pub fn resolve_on_path(command: &str) -> Option<PathBuf> {
+    resolve_on_path_in(&SystemFs, &path_dirs(), &pathext(), command)
+}
+
+/// Resolve `command` against `dirs` (see resolve_on_path)
+///
+/// Algorithm:
+/// - If input already has an extension, try exact match in each directory
+/// - If no extension, append each PATHEXT extension and test
+/// - Return the first existing file
+pub fn resolve_on_path_in(fs: &dyn FileSystem, dirs: &[PathBuf], pathext: &str, command: &str) -> Option<PathBuf> {
+    let extensions: Vec<&str> = pathext.split(';').filter(|e| !e.is_empty()).collect();
+    
+    // Determine if the command already has an extension
+    let has_extension = command.contains('.');
+    
+    for dir in dirs {
+        if has_extension {
+            // Try exact match first
+            let candidate = dir.join(command);
+            if fs.is_file(&candidate) {
+                return Some(candidate);
+            }
+        } else {
+            // Try each PATHEXT extension
+            for ext in &extensions {
+                let candidate = dir.join(format!("{}{}", command, ext));
+                if fs.is_file(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+    
+    None
+}
+
+/// List every command name reachable via PATH (file stems of PATHEXT matches).
+///
+/// Used by inline completion. Names are returned without extension, e.g.
+/// "notepad" for C:\Windows\System32\notepad.exe, deduplicated case-insensitively.
+pub fn list_path_commands() -> Vec<String> {
+    list_path_commands_in(&SystemFs, &path_dirs(), &pathext())
+}
+
+/// List the commands in `dirs` (see list_path_commands)
+pub fn list_path_commands_in(fs: &dyn FileSystem, dirs: &[PathBuf], pathext: &str) -> Vec<String> {
+    let extensions: Vec<String> = pathext
+        .split(';')
+        .filter(|e| !e.is_empty())
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .collect();
+    
+    let mut commands = Vec::new();
+    for dir in dirs {
+        for path in fs.read_dir(dir) {
+            let matches_ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| extensions.contains(&e.to_lowercase()))
+                .unwrap_or(false);
+            
+            if matches_ext {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    commands.push(stem.to_string());
+                }
+            }
+        }
+    }
+    
+    commands.sort_by_key(|c| c.to_lowercase());
+    commands.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    commands
+}
+
+/// Open a file, shortcut, or URL with its registered handler.
+/// Uses `cmd /C start` so .lnk/.url files and URLs are resolved by the shell,
+/// which plain process spawning can't do.
+pub fn open_with_shell(target: &str) -> Result<(), String> {
+    open_with_shell_in(&SystemSpawner, target)
+}
+
+/// Open `target` through `spawner` (see open_with_shell)
+pub fn open_with_shell_in(spawner: &dyn ProcessSpawner, target: &str) -> Result<(), String> {
+    open_with_shell_env(spawner, target, &[], None)
+}
+
+/// Open `target` through the shell, with `environment` set on the shell
+/// (and so on whatever it starts), from `dir`
+fn open_with_shell_env(
+    spawner: &dyn ProcessSpawner,
+    target: &str,
+    environment: &[(String, String)],
+    dir: Option<&Path>,
+) -> Result<(), String> {
+    let (program, args) = if cfg!(windows) {
+        ("cmd", vec!["/C".to_string(), "start".to_string(), String::new(), target.to_string()])
+    } else {
+        ("xdg-open", vec![target.to_string()])
+    };
+    
+    spawner
+        .spawn(Path::new(program), &args, environment, dir, CREATE_NO_WINDOW)
+        .map_err(|e| format!("Failed to open '{}': {}", target, e))
+}
+
+/// Run an executable elevated ("Run as administrator").
+/// Uses ShellExecute with the "runas" verb, which shows the UAC prompt.
+/// Returns an error if the user declines the prompt.
+pub fn spawn_elevated(path: &str) -> Result<(), String> {
+    spawn_elevated_with_args(path, "")
+}
+
+/// Run an executable elevated (see spawn_elevated) with `parameters` as its
+/// command line
+///
+/// Windows starts elevated programs in System32 whatever folder is asked
+/// for, so a program that should start elsewhere must be told through its
+/// parameters.
+pub fn spawn_elevated_with_args(path: &str, parameters: &str) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        use windows::core::{w, HSTRING, PCWSTR};
+        use windows::Win32::UI::Shell::ShellExecuteW;
+        use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+        
+        // ShellExecute returns a value > 32 on success
+        let result = unsafe {
+            ShellExecuteW(
+                None,
+                w!("runas"),
+                &HSTRING::from(path),
+                &HSTRING::from(parameters),
+                PCWSTR::null(),
+                SW_SHOWNORMAL,
+            )
+        };
+        
+        if result.0 as usize <= 32 {
+            return Err(i18n::format("runner.elevation_failed", &[path]));
+        }
+    }
+    
+    #[cfg(not(windows))]
+    {
+        let _ = parameters;
+        return Err(i18n::format("runner.elevation_unsupported", &[path]));
+    }
+    
+    Ok(())
+}
+
+/// Run a program elevated (see spawn_elevated) without a window, wait for it
+/// to finish, and return its exit code
+///
+/// Blocks until the user answers the UAC prompt and the program exits.
+pub fn run_elevated_and_wait(path: &str, parameters: &str) -> Result<u32, String> {
+    #[cfg(windows)]
+    {
+        use windows::core::{w, HSTRING, PCWSTR};
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+        use windows::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW};
+        use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+        let file = HSTRING::from(path);
+        let parameters = HSTRING::from(parameters);
+        let mut info = SHELLEXECUTEINFOW {
+            cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+            fMask: SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC,
+            lpVerb: w!("runas"),
+            lpFile: PCWSTR(file.as_ptr()),
+            lpParameters: PCWSTR(parameters.as_ptr()),
+            nShow: SW_HIDE.0,
+            ..Default::default()
+        };
+        unsafe { ShellExecuteExW(&mut info) }.map_err(|_| i18n::format("runner.elevation_failed", &[path]))?;
+        if info.hProcess.is_invalid() {
+            return Err(i18n::format("runner.elevation_failed", &[path]));
+        }
+
+        let mut code = 0;
+        let finished = unsafe {
+            WaitForSingleObject(info.hProcess, INFINITE);
+            let finished = GetExitCodeProcess(info.hProcess, &mut code);
+            let _ = CloseHandle(info.hProcess);
+            finished
+        };
+        finished.map_err(|e| format!("Failed to get the exit code of '{}': {}", path, e))?;
+        Ok(code)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = parameters;
+        Err(i18n::format("runner.elevation_unsupported", &[path]))
+    }
+}
+
+/// Split an argument string into arguments, honoring double quotes.
+/// Example: `-a "two words" b` → ["-a", "two words", "b"]
+pub fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    
+    if has_arg {
+        args.push(current);
+    }
+    
+    args
+}
+
+/// Open `target` with `program` (an alias or a command, maybe with
+/// arguments of its own), in `dir`: the path goes after the other arguments
+/// (see pipe.rs)
+pub fn open_with(program: &str, target: &Path, dir: Option<&Path>) -> Result<(), String> {
+    if program.trim().is_empty() {
+        return Err(i18n::text("runner.pipe_needs_program"));
+    }
+    let target = target.to_string_lossy().into_owned();
+    match resolve_in(program, dir)? {
+        Resolution::Alias { alias, mut args } => {
+            args.push(target);
+            run_alias(&alias, &args, dir)
+        }
+        Resolution::Executable(path) => spawn_process_in(&SystemSpawner, &path, &[target], &[], dir),
+        Resolution::Command { path, mut args } => {
+            args.push(target);
+            spawn_process_in(&SystemSpawner, &path, &args, &[], dir)
+        }
+        Resolution::Folder(_) | Resolution::App(_) => {
+            Err(i18n::format("runner.cannot_open_files", &[program.trim()]))
+        }
+    }
+}
+
+/// Run a user alias: resolve its target, apply compatibility settings, and
+/// spawn it in `dir` with the alias's arguments followed by `extra_args`, in
+/// its environment preset (if any).
+/// Targets that aren't executables (documents, URLs, shortcuts) are opened
+/// via the shell instead.
+fn run_alias(alias: &Alias, extra_args: &[String], dir: Option<&Path>) -> Result<(), String> {
+    let environment = env_presets::environment(alias.env.as_deref().unwrap_or_default())?;
+    let Some(executable) = alias_program(alias, &environment, dir) else {
+        // Not something we can spawn directly - let the shell open it
+        return open_with_shell_env(&SystemSpawner, alias.target.trim(), &environment, dir);
+    };
+    
+    if let Some(compat) = &alias.compat {
+        crate::compat::apply(&executable, compat)?;
+    }
+    
+    let args: Vec<String> = alias.args.iter().cloned().chain(extra_args.iter().cloned()).collect();
+    spawn_process_in(&SystemSpawner, &executable, &args, &environment, dir)
+}
+
+/// The program `alias` runs, looked up with `environment`'s PATH and from
+/// `dir`, if it's one that can be spawned directly (see is_program)
+fn alias_program(alias: &Alias, environment: &[(String, String)], dir: Option<&Path>) -> Option<PathBuf> {
+    let target = alias.target.trim();
+    let executable = if is_explicit_path(target) {
+        Some(explicit_path(target, dir)).filter(|p| p.is_file())
+    } else {
+        resolve_on_path_in(&SystemFs, &path_dirs_with(environment), &pathext(), target)
+    };
+    executable.filter(|p| is_program(p))
+}
+
+/// Is `path` a program CreateProcess can start (see PROGRAM_EXTENSIONS)?
+fn is_program(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| PROGRAM_EXTENSIONS.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+/// Remove the compatibility layers `alias` set on its program (see
+/// compat.rs), for when its options are cleared or it's deleted
+pub fn clear_compat(alias: &Alias) -> Result<(), String> {
+    if alias.compat.is_none() {
+        return Ok(());
+    }
+    let environment = env_presets::environment(alias.env.as_deref().unwrap_or_default()).unwrap_or_default();
+    match alias_program(alias, &environment, None) {
+        Some(executable) => crate::compat::apply(&executable, &Default::default()),
+        None => Ok(()),
+    }
+}
+
+/// Spawn a process from the given executable path.
+/// Uses std::process::Command to spawn without blocking.
+/// Does NOT use cmd.exe or shell interpretation (direct execution for security).
+///
+/// On Windows, this will:
+/// - Spawn the process detached (no console window for GUI apps)
+/// - Return immediately (non-blocking)
+pub fn spawn_process(path: &Path) -> Result<(), String> {
+    spawn_process_with_args(path, &[])
+}
+
+/// Spawn a process with arguments (see spawn_process)
+pub fn spawn_process_with_args(path: &Path, args: &[String]) -> Result<(), String> {
+    spawn_process_with_env(path, args, &[])
+}
+
+/// Spawn a process with arguments and extra environment variables, e.g.
+/// from an environment preset (see spawn_process)
+pub fn spawn_process_with_env(path: &Path, args: &[String], environment: &[(String, String)]) -> Result<(), String> {
+    spawn_process_in(&SystemSpawner, path, args, environment, None)
+}
+
+/// Spawn through `spawner`, in `dir` if given (see spawn_process)
+pub fn spawn_process_in(
+    spawner: &dyn ProcessSpawner,
+    path: &Path,
+    args: &[String],
+    environment: &[(String, String)],
+    dir: Option<&Path>,
+) -> Result<(), String> {
+    // CREATE_NO_WINDOW prevents a console window for GUI apps
+    spawner.spawn(path, args, environment, dir, CREATE_NO_WINDOW)
+}
+
+/// What a command resolves to (see resolve)
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    /// A user alias, with the rest of the input as extra arguments
+    Alias { alias: Alias, args: Vec<String> },
+    /// An executable file (explicit path or found on PATH)
+    Executable(PathBuf),
+    /// A command on PATH followed by arguments ("code .")
+    Command { path: PathBuf, args: Vec<String> },
+    /// A folder (opened in Explorer)
+    Folder(PathBuf),
+    /// An indexed app (opened via the shell)
+    App(AppEntry),
+}
+
+/// Work out what `input` would run, without running it
+///
+/// Steps 1-5 of run_command; the app index must have been filled (see
+/// index::load) for Start Menu apps to be found.
+pub fn resolve(input: &str) -> Result<Resolution, String> {
+    resolve_in(input, None)
+}
+
+/// Work out what `input` would run in `dir` (see resolve); relative paths
+/// are looked up there
+pub fn resolve_in(input: &str, dir: Option<&Path>) -> Result<Resolution, String> {
+    let input = input.trim();
+    
+    if input.is_empty() {
+        return Err(i18n::text("runner.empty"));
+    }
+    
+    // Aliases take priority over everything else
+    let (first_word, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    if let Some(alias) = crate::aliases::find(first_word) {
+        return Ok(Resolution::Alias { alias, args: split_args(rest) });
+    }
+    
+    if is_explicit_path(input) {
+        // Explicit path: verify it exists (folders open in Explorer, like Win+R)
+        let path = explicit_path(input, dir);
+        if path.is_file() {
+            Ok(Resolution::Executable(path))
+        } else if path.is_dir() {
+            Ok(Resolution::Folder(path))
+        } else {
+            match split_explicit(input, dir) {
+                Some((path, args)) if path.is_file() => Ok(Resolution::Command { path, args }),
+                // A quoted folder, as the Explorer context menu passes it
+                Some((path, args)) if args.is_empty() && path.is_dir() => Ok(Resolution::Folder(path)),
+                _ => Err(i18n::format("runner.file_not_found", &[input])),
+            }
+        }
+    } else if let Some(path) = resolve_on_path(input) {
+        Ok(Resolution::Executable(path))
+    } else if let Some(app) = crate::index::find(input) {
+        Ok(Resolution::App(app))
+    } else if let Some(path) = resolve_on_path(first_word).filter(|_| !rest.is_empty()) {
+        // After the app index, so "Git Bash" still opens the app
+        Ok(Resolution::Command { path, args: split_args(rest) })
+    } else {
+        Err(i18n::format("runner.not_found", &[input]))
+    }
+}
+
+/// Main entry point: resolve and run a command from user input
+///
+/// This mimics the Windows Run dialog (Win+R) behavior:
+/// - Recognizes explicit paths: "C:\\Windows\\notepad.exe", ".\\script.bat"
+/// - Searches PATH for commands: "notepad", "calc", "code"
+/// - Handles extensionless commands via PATHEXT: "notepad" → "notepad.exe"
+///
+/// Flow:
+/// 1. Trim whitespace and check for empty input
+///    a. If the first word is an alias → run the alias with the remaining words as arguments
+/// 2. If input contains path separators (\\ / :) → treat as explicit path
+///    a. Verify the file exists
+///    b. If not found → return error
+/// 3. Otherwise → search PATH environment variable
+///    a. Try each directory in PATH
+///    b. Try each extension in PATHEXT if command has no extension
+///    c. Return first match found
+/// 4. If not on PATH → look up the Start Menu app index and open the shortcut
+/// 5. Otherwise, if the first word is on PATH → run it with the rest as arguments
+/// 6. Spawn the process detached (CREATE_NO_WINDOW on Windows)
+/// 7. Return Ok(()) on success, Err(message) on failure
+///
+/// Examples:
+/// - "notepad" → finds "C:\\Windows\\System32\\notepad.exe"
+/// - "calc" → finds "C:\\Windows\\System32\\calc.exe"
+/// - "code" → finds VS Code if installed in PATH
+/// - "Visual Studio Code" → opens the Start Menu shortcut
+/// - "code ." → runs VS Code with the argument "."
+/// - "C:\\test.exe" → runs C:\\test.exe directly
+/// - ".\\script.bat" → runs script.bat in current directory
+pub fn run_command(input: &str) -> Result<(), String> {
+    run_command_in(input, None)
+}
+
+/// Run a command (see run_command) in `dir`: relative paths are looked up
+/// there and programs start there (None = QuickRun's own directory)
+pub fn run_command_in(input: &str, dir: Option<&Path>) -> Result<(), String> {
+    match resolve_in(input, dir)? {
+        Resolution::Alias { alias, args } => run_alias(&alias, &args, dir),
+        Resolution::Executable(path) => spawn_process_in(&SystemSpawner, &path, &[], &[], dir),
+        Resolution::Command { path, args } => spawn_process_in(&SystemSpawner, &path, &args, &[], dir),
+        Resolution::Folder(path) => open_with_shell(&path.to_string_lossy()),
+        // Start Menu shortcut: let the shell resolve the .lnk target
+        Resolution::App(app) => open_with_shell(&app.path.to_string_lossy()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env_presets::Environment;
+    use crate::os::memory::{MemoryFs, RecordingSpawner};
+
+    const PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+    fn dirs() -> Vec<PathBuf> {
+        vec![PathBuf::from("C:\\bin"), PathBuf::from("C:\\tools")]
+    }
+
+    /// Two PATH folders; notepad.exe is in both
+    fn fixture() -> MemoryFs {
+        let dirs = dirs();
+        let (bin, tools) = (&dirs[0], &dirs[1]);
+        MemoryFs::new()
+            .with_file(bin.join("notepad.exe"))
+            .with_file(bin.join("build.bat"))
+            .with_file(bin.join("readme.txt"))
+            .with_file(tools.join("notepad.exe"))
+            .with_file(tools.join("Code.CMD"))
+            .with_file(tools.join("7z.exe"))
+    }
+
+    #[test]
+    fn first_path_folder_wins() {
+        let resolved = resolve_on_path_in(&fixture(), &dirs(), PATHEXT, "notepad");
+        assert_eq!(resolved, Some(dirs()[0].join("notepad.EXE")));
+    }
+
+    #[test]
+    fn pathext_is_tried_in_order() {
+        let fs = fixture().with_file(dirs()[0].join("build.com"));
+        let resolved = resolve_on_path_in(&fs, &dirs(), PATHEXT, "build");
+        assert_eq!(resolved, Some(dirs()[0].join("build.COM")));
+    }
+
+    #[test]
+    fn names_with_an_extension_are_matched_exactly() {
+        let fs = fixture();
+        assert_eq!(resolve_on_path_in(&fs, &dirs(), PATHEXT, "7z.exe"), Some(dirs()[1].join("7z.exe")));
+        assert_eq!(resolve_on_path_in(&fs, &dirs(), PATHEXT, "readme.txt"), Some(dirs()[0].join("readme.txt")));
+        assert_eq!(resolve_on_path_in(&fs, &dirs(), PATHEXT, "readme"), None);
+    }
+
+    #[test]
+    fn resolution_ignores_case() {
+        let resolved = resolve_on_path_in(&fixture(), &dirs(), PATHEXT, "CODE");
+        assert_eq!(resolved, Some(dirs()[1].join("CODE.CMD")));
+    }
+
+    #[test]
+    fn unknown_commands_do_not_resolve() {
+        assert_eq!(resolve_on_path_in(&fixture(), &dirs(), PATHEXT, "missing"), None);
+        assert_eq!(resolve_on_path_in(&fixture(), &[], PATHEXT, "notepad"), None);
+    }
+
+    #[test]
+    fn path_commands_are_deduplicated_stems() {
+        let commands = list_path_commands_in(&fixture(), &dirs(), PATHEXT);
+        assert_eq!(commands, ["7z", "build", "Code", "notepad"]);
+    }
+
+    #[test]
+    fn processes_start_without_a_console_window() {
+        let spawner = RecordingSpawner::new();
+        let args = vec!["--new-window".to_string()];
+        spawn_process_in(&spawner, Path::new("C:\\tools\\code.exe"), &args, &[], None).unwrap();
+
+        let spawned = spawner.spawned();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].program, Path::new("C:\\tools\\code.exe"));
+        assert_eq!(spawned[0].args, args);
+        assert_eq!(spawned[0].creation_flags, CREATE_NO_WINDOW);
+        assert_eq!(spawned[0].dir, None);
+    }
+
+    #[test]
+    fn processes_can_start_in_a_folder() {
+        let spawner = RecordingSpawner::new();
+        let dir = Path::new("C:\\src\\quickrun");
+        spawn_process_in(&spawner, Path::new("C:\\tools\\code.exe"), &[".".to_string()], &[], Some(dir)).unwrap();
+        assert_eq!(spawner.spawned()[0].dir.as_deref(), Some(dir));
+    }
+
+    #[test]
+    fn relative_paths_are_in_the_folder_given() {
+        let dir = PathBuf::from("projects").join("quickrun");
+        let script = PathBuf::from("scripts").join("build.bat");
+        assert_eq!(explicit_path(&script.to_string_lossy(), Some(&dir)), dir.join(&script));
+        assert_eq!(explicit_path(&script.to_string_lossy(), None), script);
+    }
+
+    #[test]
+    fn preset_variables_are_set_on_the_process() {
+        let spawner = RecordingSpawner::new();
+        let environment: Environment = vec![("JAVA_HOME".to_string(), "C:\\Java\\jdk-17".to_string())];
+        spawn_process_in(&spawner, Path::new("C:\\tools\\mvn.cmd"), &[], &environment, None).unwrap();
+        assert_eq!(spawner.spawned()[0].env, environment);
+    }
+
+    #[test]
+    fn a_preset_path_replaces_the_inherited_one_for_lookups() {
+        let environment: Environment = vec![("PATH".to_string(), "\\\\build\\tools".to_string())];
+        assert_eq!(path_dirs_with(&environment), [PathBuf::from("\\\\build\\tools")]);
+        assert_eq!(path_dirs_with(&[]), path_dirs());
+    }
+
+    #[test]
+    fn shell_open_passes_the_target_as_one_argument() {
+        let spawner = RecordingSpawner::new();
+        open_with_shell_in(&spawner, "C:\\My Files\\report.pdf").unwrap();
+
+        let spawned = spawner.spawned();
+        assert_eq!(spawned[0].args.last().map(String::as_str), Some("C:\\My Files\\report.pdf"));
+    }
+
+    #[test]
+    fn quoted_arguments_stay_together() {
+        assert_eq!(split_args("-a \"two words\" b"), ["-a", "two words", "b"]);
+        assert_eq!(split_args("\"\""), [""]);
+        assert!(split_args("   ").is_empty());
+    }
+
+    #[test]
+    fn only_programs_are_spawned_directly() {
+        assert!(is_program(Path::new("C:\\Games\\old.EXE")));
+        assert!(is_program(Path::new("C:\\Tools\\edit.com")));
+        for target in ["setup.msi", "build.ps1", "build.bat", "notes.txt", "app"] {
+            assert!(!is_program(Path::new(target)), "{}", target);
+        }
+    }
+
+    #[test]
+    fn explicit_paths_can_take_arguments() {
+        let (path, args) = split_explicit("\"C:\\My Tools\\app.exe\" -x \"a b\"", None).unwrap();
+        assert_eq!(path, PathBuf::from("C:\\My Tools\\app.exe"));
+        assert_eq!(args, ["-x", "a b"]);
+
+        let (path, args) = split_explicit("tools\\app.exe -x", Some(Path::new("C:\\work"))).unwrap();
+        assert_eq!(path, Path::new("C:\\work").join("tools\\app.exe"));
+        assert_eq!(args, ["-x"]);
+    }
+
+    #[test]
+    fn quoted_folders_open() {
+        let dir = std::env::temp_dir().join(format!("quickrun runner {}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let resolved = resolve_in(&format!("\"{}\"", dir.display()), None);
+        let with_args = resolve_in(&format!("\"{}\" -x", dir.display()), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(resolved, Ok(Resolution::Folder(path)) if path == dir));
+        assert!(with_args.is_err());
+    }
+}
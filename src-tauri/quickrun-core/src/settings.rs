@@ -0,0 +1,869 @@
+// settings.rs - The settings file
+//
+// Everything persisted (settings, history, aliases, caches) lives in the
+// config folder (see storage.rs). Settings are one JSON object in settings.json
+// (one per profile, see profiles.rs), described by the Settings struct below:
+// - Every field has a default, so a missing key (or a missing file) is fine
+// - A key with a value of the wrong type falls back to its default on its
+//   own, instead of resetting every other setting with it
+// - Keys this build doesn't know are kept and written back untouched, so a
+//   newer and an older QuickRun can share one file
+// - Writes go to a temporary file that then replaces settings.json, so a
+//   crash mid-write can't leave a truncated file behind, and changes hold a
+//   lock on the file so another process (qr) can't save in between (see
+//   shared_file.rs)
+// - watch() reports edits made outside the app (by hand, or by a dotfile
+//   sync tool), so they can be applied without a restart
+// - The file is read once and kept in memory, since settings are consulted
+//   on every query; saves update the memory copy, and watch()/reload()
+//   pick up changes from outside. Unlike history, saves are written right
+//   away: other processes (qr, a second install) read the same file
+// - "schema_version" records the layout of the file. When a setting is
+//   renamed or changes type, a step is added to MIGRATIONS and older files
+//   are upgraded as they're read (keeping a copy of the original), instead
+//   of the old value being dropped for the default
+// - When nothing can be written (see storage.rs), saves only update the
+//   memory copy, which stays in charge until QuickRun exits
+
+use crate::env_presets::EnvPreset;
+use crate::retention::Retention;
+use crate::theme::Theme;
+use crate::{profiles, shared_file, storage};
+use crate::web_search::Bang;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Which monitor the launcher opens on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LauncherMonitor {
+    /// The one the mouse pointer is on
+    #[default]
+    Cursor,
+    /// The one showing the window that was active
+    FocusedWindow,
+    /// The primary monitor
+    Primary,
+    /// The one the launcher was on last time
+    Remembered,
+}
+
+/// Where the launcher was dragged to on a monitor, in pixels from the
+/// monitor's top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowOffset {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Sound cues (see sounds.rs in the app); each file is a .wav, None = the
+/// Windows sound for a similar event
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sounds {
+    /// Play them at all
+    pub enabled: bool,
+    /// When the launcher opens
+    pub show: Option<String>,
+    /// When a command launches
+    pub launch: Option<String>,
+    /// When a command fails
+    pub error: Option<String>,
+}
+
+impl Sounds {
+    /// Check that the custom sounds changed from `before` are .wav files
+    /// that exist (used before saving a change). Nothing is checked while
+    /// sounds are off, so turning them off works even if a file is gone.
+    pub fn validate(&self, before: &Sounds) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let changed = [(&self.show, &before.show), (&self.launch, &before.launch), (&self.error, &before.error)]
+            .into_iter()
+            .filter(|(path, before)| path != before)
+            .filter_map(|(path, _)| path.as_ref());
+        for path in changed {
+            let path = path.trim();
+            if path.is_empty() {
+                continue;
+            }
+            let is_wav = Path::new(path)
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"));
+            if !is_wav {
+                return Err(format!("'{}' is not a .wav file", path));
+            }
+            if !crate::runner::expand_home(path).is_file() {
+                return Err(format!("Sound file not found: {}", path));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An extra tray menu entry that runs a command as if typed into the
+/// launcher (aliases, "> ..." and the rest work too)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrayAction {
+    /// Menu text, e.g. "VPN connect"
+    pub label: String,
+    /// What runs, e.g. "rasdial Work"
+    pub command: String,
+}
+
+/// The local HTTP API for Stream Deck actions and scripts (see http_api.rs
+/// in the app, which keeps its token in a file of its own so exports,
+/// backups and profiles don't carry it)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpApi {
+    /// Answer requests at all (only ever on 127.0.0.1)
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for HttpApi {
+    fn default() -> Self {
+        Self { enabled: false, port: 17345 }
+    }
+}
+
+impl HttpApi {
+    /// Check the port (used before saving a change); ports below 1024 are
+    /// for system services
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err(format!("Port must be between 1024 and 65535, not {}", self.port));
+        }
+        Ok(())
+    }
+}
+
+/// Smallest and largest "ui_scale" accepted
+pub const MIN_UI_SCALE: f64 = 0.75;
+pub const MAX_UI_SCALE: f64 = 2.0;
+
+/// Longest "startup_delay_secs" honored
+pub const MAX_STARTUP_DELAY_SECS: u64 = 600;
+
+/// Every persisted setting
+///
+/// Field names are the keys in settings.json and in the get_settings /
+/// update_settings commands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Layout version of the file (see MIGRATIONS); not a preference
+    pub schema_version: u32,
+
+    /// Dark/light mode, accent color and the rest of the look (see theme.rs)
+    pub theme: Theme,
+    /// Language of the tray menu, Settings and errors, e.g. "de" ("" =
+    /// Windows' language; see i18n.rs)
+    pub language: String,
+
+    /// Chord that toggles the launcher (e.g., "Alt+Space")
+    pub hotkey: String,
+    /// Modifier that opens the launcher when tapped twice ("ctrl", "shift",
+    /// "alt", or "" for off)
+    pub double_tap: String,
+    /// Longest gap between the two taps
+    pub double_tap_interval_ms: u32,
+    /// Monitor the launcher is centered on when it opens
+    pub launcher_monitor: LauncherMonitor,
+    /// Where the user dragged the launcher to, by monitor resolution
+    /// (e.g., "2560x1440"); monitors without one center it
+    pub launcher_positions: BTreeMap<String, WindowOffset>,
+    /// Hide the launcher when another window is clicked
+    pub hide_on_blur: bool,
+    /// Keep the launcher above other windows, fullscreen ones included
+    pub always_on_top: bool,
+    /// Open the launcher on the virtual desktop the user is on
+    pub all_desktops: bool,
+    /// Sounds when the launcher opens, a command launches or fails
+    pub sounds: Sounds,
+    /// Reopen the launcher with the last input (selected) instead of empty
+    pub remember_last_input: bool,
+    /// Size of the launcher and its text, on top of Windows' scaling
+    /// (MIN_UI_SCALE to MAX_UI_SCALE)
+    pub ui_scale: f64,
+
+    /// Seconds to wait after a start at login before registering the hotkey
+    /// and scanning for apps (see startup.rs)
+    pub startup_delay_secs: u64,
+    /// Commands at the top of the tray menu
+    pub tray_actions: Vec<TrayAction>,
+    /// Register quickrun:// links (see launch_args.rs)
+    pub deep_links: bool,
+    /// Local HTTP API (off by default)
+    pub http_api: HttpApi,
+
+    /// Hold suggestion queries until an IME composition commits
+    pub ime_wait_for_commit: bool,
+    /// Pause after a commit before querying
+    pub ime_commit_delay_ms: u64,
+
+    /// Suggest PowerShell history after the ">" prefix
+    pub import_shell_history: bool,
+    /// Record clipboard history
+    pub clipboard_history: bool,
+    /// Paste a chosen clipboard entry into the previous window
+    pub clipboard_paste_on_select: bool,
+    /// Clipboard entries kept
+    pub clipboard_history_size: usize,
+    /// Offer new downloads when the launcher opens
+    pub watch_downloads: bool,
+    /// Minutes between scheduled app index rebuilds
+    pub index_rebuild_minutes: u64,
+    /// Limits on history, clipboard history and the index cache (see
+    /// retention.rs)
+    pub retention: Retention,
+    /// Hours between background checks for a new release (0 = only when
+    /// asked, from the tray menu or About)
+    pub update_check_hours: u64,
+    /// Install updates without the installer's wizard, restarting QuickRun
+    /// afterwards
+    pub silent_updates: bool,
+    /// Release the user chose to skip; it (and anything older) isn't offered
+    /// as an update
+    pub skip_version: Option<String>,
+
+    /// Preferred terminal, editor and browser ("" = the system default)
+    pub preferred_terminal: String,
+    pub preferred_editor: String,
+    pub preferred_browser: String,
+
+    /// Named environments for aliases and the ">" prefix (see env_presets.rs)
+    pub env_presets: Vec<EnvPreset>,
+    /// Preset the ">" prefix runs commands in ("" = none)
+    pub terminal_env_preset: String,
+
+    /// URL for the "Search the web" fallback ("{query}" is replaced)
+    pub web_search_template: String,
+    /// The user's bang table (None = the built-in one)
+    pub search_bangs: Option<Vec<Bang>>,
+
+    /// Where `new project` creates projects (None = ~/Projects)
+    pub projects_dir: Option<String>,
+    /// Folders for `recent`, name → path (None = Downloads, Screenshots, ...)
+    pub recent_folders: Option<BTreeMap<String, String>>,
+    /// Endpoint overrides for `speedtest` and `myip` (None = built-in)
+    pub speedtest_ping_url: Option<String>,
+    pub speedtest_download_url: Option<String>,
+    pub speedtest_upload_url: Option<String>,
+    pub myip_v4_url: Option<String>,
+    pub myip_v6_url: Option<String>,
+    pub myip_geo_url: Option<String>,
+
+    /// Keys this build doesn't know, preserved as-is
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            theme: Theme::default(),
+            language: String::new(),
+            hotkey: "Alt+Space".to_string(),
+            double_tap: String::new(),
+            double_tap_interval_ms: 400,
+            launcher_monitor: LauncherMonitor::Cursor,
+            launcher_positions: BTreeMap::new(),
+            hide_on_blur: false,
+            always_on_top: false,
+            all_desktops: false,
+            sounds: Sounds::default(),
+            remember_last_input: false,
+            ui_scale: 1.0,
+            startup_delay_secs: 0,
+            tray_actions: Vec::new(),
+            deep_links: false,
+            http_api: HttpApi::default(),
+            ime_wait_for_commit: true,
+            ime_commit_delay_ms: 150,
+            import_shell_history: false,
+            clipboard_history: false,
+            clipboard_paste_on_select: false,
+            clipboard_history_size: 25,
+            watch_downloads: false,
+            index_rebuild_minutes: 30,
+            retention: Retention::default(),
+            update_check_hours: 24,
+            silent_updates: false,
+            skip_version: None,
+            preferred_terminal: String::new(),
+            preferred_editor: String::new(),
+            preferred_browser: String::new(),
+            env_presets: Vec::new(),
+            terminal_env_preset: String::new(),
+            web_search_template: "https://www.google.com/search?q={query}".to_string(),
+            search_bangs: None,
+            projects_dir: None,
+            recent_folders: None,
+            speedtest_ping_url: None,
+            speedtest_download_url: None,
+            speedtest_upload_url: None,
+            myip_v4_url: None,
+            myip_v6_url: None,
+            myip_geo_url: None,
+            other: Map::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Build settings from the JSON in settings.json
+    ///
+    /// Keys are applied one at a time on top of the defaults; a key whose
+    /// value doesn't fit is skipped (with a warning) rather than failing
+    /// the whole file.
+    pub fn from_json(json: &Value) -> Self {
+        let Value::Object(keys) = json else {
+            eprintln!("[Settings] settings.json is not an object - using defaults");
+            return Self::default();
+        };
+
+        let mut merged = Self::default().to_json();
+        for (key, value) in keys {
+            let previous = merged.insert(key.clone(), value.clone());
+            if serde_json::from_value::<Self>(Value::Object(merged.clone())).is_err() {
+                eprintln!("[Settings] Ignoring invalid value for '{}'", key);
+                match previous {
+                    Some(previous) => merged.insert(key.clone(), previous),
+                    None => merged.remove(key),
+                };
+            }
+        }
+        serde_json::from_value(Value::Object(merged)).unwrap_or_default()
+    }
+
+    /// The settings as a JSON object
+    pub fn to_json(&self) -> Map<String, Value> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        }
+    }
+
+    /// "ui_scale" kept within MIN_UI_SCALE to MAX_UI_SCALE (a hand-edited
+    /// value may be outside)
+    pub fn ui_scale(&self) -> f64 {
+        if self.ui_scale.is_finite() {
+            self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+        } else {
+            1.0
+        }
+    }
+
+    /// "startup_delay_secs", at most MAX_STARTUP_DELAY_SECS
+    pub fn startup_delay(&self) -> Duration {
+        Duration::from_secs(self.startup_delay_secs.min(MAX_STARTUP_DELAY_SECS))
+    }
+
+    /// The "tray_actions" with both a label and a command (a hand-edited
+    /// file may have blanks)
+    pub fn tray_actions(&self) -> Vec<&TrayAction> {
+        self.tray_actions
+            .iter()
+            .filter(|action| !action.label.trim().is_empty() && !action.command.trim().is_empty())
+            .collect()
+    }
+
+    /// Check "ui_scale" (used before saving a change)
+    pub fn validate_ui_scale(&self) -> Result<(), String> {
+        if !(MIN_UI_SCALE..=MAX_UI_SCALE).contains(&self.ui_scale) {
+            return Err(format!(
+                "Launcher size must be between {}% and {}%",
+                MIN_UI_SCALE * 100.0,
+                MAX_UI_SCALE * 100.0
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply a partial update (e.g., `{"watch_downloads": true}`)
+    ///
+    /// Unlike from_json, this is strict: unknown keys and values of the
+    /// wrong type are errors, so a typo in the frontend isn't silently
+    /// dropped.
+    pub fn with_patch(&self, patch: &Value) -> Result<Self, String> {
+        let Value::Object(patch) = patch else {
+            return Err("Settings update must be a JSON object".to_string());
+        };
+
+        let known = Self::default().to_json();
+        let mut merged = self.to_json();
+        for (key, value) in patch {
+            if !known.contains_key(key) || key == "schema_version" {
+                return Err(format!("Unknown setting '{}'", key));
+            }
+            merged.insert(key.clone(), value.clone());
+        }
+        serde_json::from_value(Value::Object(merged)).map_err(|e| format!("Invalid settings: {}", e))
+    }
+}
+
+/// A step that upgrades settings.json by one layout version, e.g. moving a
+/// renamed key or converting a value to its new type
+type Migration = fn(&mut Map<String, Value>);
+
+/// Upgrades between layout versions: MIGRATIONS[0] turns version 1 into 2,
+/// and so on. A file without "schema_version" is version 1.
+const MIGRATIONS: &[Migration] = &[light_mode_to_theme];
+
+/// Version 2: the "light_mode" bool became the "theme" object
+fn light_mode_to_theme(keys: &mut Map<String, Value>) {
+    let Some(light_mode) = keys.remove("light_mode") else {
+        return;
+    };
+    let mode = if light_mode == Value::Bool(true) { "light" } else { "dark" };
+    if let Value::Object(theme) = keys.entry("theme").or_insert_with(|| Value::Object(Map::new())) {
+        theme.entry("mode").or_insert_with(|| Value::from(mode));
+    }
+}
+
+/// Layout version this build reads and writes
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Upgrade the contents of settings.json to SCHEMA_VERSION; returns the
+/// version they were at
+///
+/// A file from a newer QuickRun is left alone: its keys are kept and its
+/// version isn't lowered.
+pub fn migrate(keys: &mut Map<String, Value>) -> u32 {
+    migrate_with(keys, MIGRATIONS)
+}
+
+fn migrate_with(keys: &mut Map<String, Value>, migrations: &[Migration]) -> u32 {
+    let current = migrations.len() as u32 + 1;
+    let from = keys
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .map_or(1, |version| version.clamp(1, u32::MAX as u64) as u32);
+    if from > current {
+        return from;
+    }
+
+    for migration in &migrations[from as usize - 1..] {
+        migration(keys);
+    }
+    keys.insert("schema_version".to_string(), Value::from(current));
+    from
+}
+
+/// Get the path to the settings file
+/// 
+/// Settings are stored as JSON in the active profile's folder (see
+/// profiles.rs), which for the default profile is the config directory
+fn get_settings_path() -> PathBuf {
+    profiles::dir().join(storage::SETTINGS_FILE)
+}
+
+/// Serializes read-modify-write cycles on settings.json within this process
+/// (shared_file::lock does the same across processes)
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// The settings as last read or written by this process (None = not read yet)
+static CACHE: RwLock<Option<Settings>> = RwLock::new(None);
+
+/// Load the settings (defaults if the file is missing or unreadable)
+///
+/// Only the first call reads the file; see reload().
+pub fn load() -> Settings {
+    if let Some(settings) = CACHE.read().ok().and_then(|cache| cache.clone()) {
+        return settings;
+    }
+    reload()
+}
+
+/// Re-read settings.json (e.g., after another process changed it)
+///
+/// Without a writable folder the memory copy is kept, since it holds
+/// changes the file never got.
+pub fn reload() -> Settings {
+    if storage::is_in_memory() {
+        if let Some(settings) = CACHE.read().ok().and_then(|cache| cache.clone()) {
+            return settings;
+        }
+    }
+    let settings = load_from(&get_settings_path());
+    remember(&settings);
+    settings
+}
+
+/// Make `settings` what load() returns
+fn remember(settings: &Settings) {
+    if let Ok(mut cache) = CACHE.write() {
+        *cache = Some(settings.clone());
+    }
+}
+
+/// Replace the settings file with `settings`
+pub fn save(settings: &Settings) -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = get_settings_path();
+    let _lock = shared_file::lock(&path)?;
+    persist(&path, settings)?;
+    remember(settings);
+    Ok(())
+}
+
+/// Change some settings and save them; returns the saved settings
+///
+/// The file is re-read first, so changes made elsewhere since it was last
+/// loaded aren't lost.
+pub fn update(change: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+    try_update(|settings| {
+        change(settings);
+        Ok(())
+    })
+}
+
+/// update() for changes that can be rejected; nothing is saved on error
+pub fn try_update(change: impl FnOnce(&mut Settings) -> Result<(), String>) -> Result<Settings, String> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = get_settings_path();
+    let _lock = shared_file::lock(&path)?;
+    let mut settings = if storage::is_in_memory() { load() } else { load_from(&path) };
+    change(&mut settings)?;
+    persist(&path, &settings)?;
+    remember(&settings);
+    Ok(settings)
+}
+
+/// Wait for the file to settle before reloading (editors and sync tools
+/// often write in several steps)
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watch settings.json and call `on_change` with the new settings after
+/// every change (call once at startup)
+///
+/// The config folder is watched rather than the file, because saving
+/// replaces the file, and recursively, so the settings of whichever profile
+/// is active are covered. Our own saves (and edits to other profiles) are
+/// reported too; callers compare with what they already have.
+pub fn watch(on_change: impl Fn(Settings) + Send + 'static) {
+    use notify::{RecursiveMode, Watcher};
+
+    let dir = storage::get_config_dir();
+
+    std::thread::spawn(move || {
+        let (sender, changes) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                let file_name = Some(std::ffi::OsStr::new(storage::SETTINGS_FILE));
+                if event.paths.iter().any(|p| p.file_name() == file_name) {
+                    let _ = sender.send(());
+                }
+            }
+        });
+
+        // Keep the watcher alive for the life of the thread
+        let _watcher = match watcher {
+            Ok(mut watcher) => match watcher.watch(&dir, RecursiveMode::Recursive) {
+                Ok(()) => watcher,
+                Err(e) => {
+                    eprintln!("[Settings] Can't watch {}: {}", dir.display(), e);
+                    return;
+                }
+            },
+            Err(e) => {
+                eprintln!("[Settings] File watching unavailable: {}", e);
+                return;
+            }
+        };
+
+        while changes.recv().is_ok() {
+            // Swallow the rest of the burst
+            while changes.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+            on_change(reload());
+        }
+    });
+}
+
+fn load_from(path: &Path) -> Settings {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Settings::default();
+    };
+    match serde_json::from_str::<Value>(&contents) {
+        Ok(Value::Object(mut keys)) => {
+            let from = migrate(&mut keys);
+            let settings = Settings::from_json(&Value::Object(keys));
+            if from < SCHEMA_VERSION {
+                upgrade_file(path, from, &settings);
+            }
+            settings
+        }
+        Ok(json) => Settings::from_json(&json),
+        Err(e) => {
+            eprintln!("[Settings] Can't parse {}: {} - using defaults", path.display(), e);
+            Settings::default()
+        }
+    }
+}
+
+/// Write migrated settings back, keeping the original as
+/// settings.v<version>.json so it can be restored by hand
+fn upgrade_file(path: &Path, from: u32, settings: &Settings) {
+    let original = path.with_file_name(format!("settings.v{}.json", from));
+    if let Err(e) = std::fs::copy(path, &original) {
+        eprintln!("[Settings] Can't keep a copy of the old settings: {}", e);
+        return;
+    }
+    match persist(path, settings) {
+        Ok(()) => eprintln!(
+            "[Settings] Upgraded settings.json from version {} to {} (original kept as {})",
+            from,
+            SCHEMA_VERSION,
+            original.display()
+        ),
+        Err(e) => eprintln!("[Settings] Can't save upgraded settings: {}", e),
+    }
+}
+
+/// save_to(), unless there's nowhere to write (the memory copy is all there is)
+fn persist(path: &Path, settings: &Settings) -> Result<(), String> {
+    if storage::is_in_memory() {
+        return Ok(());
+    }
+    save_to(path, settings)
+}
+
+/// Write to a temporary file beside `path`, then move it into place
+fn save_to(path: &Path, settings: &Settings) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    shared_file::replace(path, json.as_bytes()).map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::ThemeMode;
+    use serde_json::json;
+
+    /// A settings.json path in a fresh temp folder
+    fn temp_settings_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quickrun-settings-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("settings.json")
+    }
+
+    #[test]
+    fn custom_sounds_must_be_existing_wav_files() {
+        let sounds = |error: &str| Sounds { enabled: true, error: Some(error.to_string()), ..Sounds::default() };
+        let before = Sounds::default();
+        assert!(Sounds::default().validate(&before).is_ok());
+        assert!(sounds("").validate(&before).is_ok());
+        assert!(sounds("~\\chime.mp3").validate(&before).is_err());
+        assert!(sounds("Z:\\no\\such\\chime.wav").validate(&before).is_err());
+
+        let file = std::env::temp_dir().join(format!("quickrun-sound-{}.WAV", std::process::id()));
+        std::fs::write(&file, "").unwrap();
+        let valid = sounds(&file.to_string_lossy()).validate(&before);
+        std::fs::remove_file(&file).unwrap();
+        assert!(valid.is_ok());
+    }
+
+    #[test]
+    fn only_changed_sounds_are_checked() {
+        let missing = Some("Z:\\no\\such\\chime.wav".to_string());
+        let before = Sounds { enabled: true, error: missing.clone(), ..Sounds::default() };
+
+        let off = Sounds { enabled: false, ..before.clone() };
+        assert!(off.validate(&before).is_ok());
+        assert!(before.validate(&off).is_ok());
+        let launch_changed = Sounds { launch: Some(String::new()), ..before.clone() };
+        assert!(launch_changed.validate(&before).is_ok());
+        let moved = Sounds { launch: missing, ..before.clone() };
+        assert!(moved.validate(&before).is_err());
+    }
+
+    #[test]
+    fn startup_delay_is_capped() {
+        let delayed = |startup_delay_secs: u64| Settings { startup_delay_secs, ..Settings::default() };
+        assert_eq!(delayed(30).startup_delay(), Duration::from_secs(30));
+        assert_eq!(delayed(u64::MAX).startup_delay(), Duration::from_secs(MAX_STARTUP_DELAY_SECS));
+    }
+
+    #[test]
+    fn tray_actions_need_a_label_and_a_command() {
+        let action = |label: &str, command: &str| TrayAction { label: label.into(), command: command.into() };
+        let settings = Settings {
+            tray_actions: vec![action("Terminal", "wt"), action(" ", "calc"), action("Nothing", "")],
+            ..Settings::default()
+        };
+        assert_eq!(settings.tray_actions(), vec![&action("Terminal", "wt")]);
+    }
+
+    #[test]
+    fn http_api_stays_off_system_ports() {
+        assert!(HttpApi::default().validate().is_ok());
+        assert!(HttpApi { port: 80, ..HttpApi::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn ui_scale_is_kept_in_range() {
+        let scaled = |ui_scale: f64| Settings { ui_scale, ..Settings::default() };
+        assert_eq!(scaled(1.5).ui_scale(), 1.5);
+        assert_eq!(scaled(10.0).ui_scale(), MAX_UI_SCALE);
+        assert_eq!(scaled(f64::NAN).ui_scale(), 1.0);
+        assert!(scaled(1.25).validate_ui_scale().is_ok());
+        assert!(scaled(0.5).validate_ui_scale().is_err());
+    }
+
+    #[test]
+    fn missing_keys_use_defaults() {
+        let settings = Settings::from_json(&json!({ "watch_downloads": true }));
+        assert!(settings.watch_downloads);
+        assert_eq!(settings.hotkey, "Alt+Space");
+        assert!(settings.ime_wait_for_commit);
+    }
+
+    #[test]
+    fn a_bad_value_only_resets_its_own_key() {
+        let settings = Settings::from_json(&json!({ "watch_downloads": "yes", "hotkey": "Ctrl+Space" }));
+        assert!(!settings.watch_downloads);
+        assert_eq!(settings.hotkey, "Ctrl+Space");
+    }
+
+    #[test]
+    fn unknown_keys_survive_a_round_trip() {
+        let path = temp_settings_path("unknown");
+        std::fs::write(&path, r#"{ "future_setting": [1, 2], "watch_downloads": true }"#).unwrap();
+
+        let settings = load_from(&path);
+        save_to(&path, &settings).unwrap();
+
+        let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["future_setting"], json!([1, 2]));
+        assert_eq!(saved["watch_downloads"], json!(true));
+    }
+
+    #[test]
+    fn unreadable_file_loads_defaults() {
+        let path = temp_settings_path("corrupt");
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        assert_eq!(load_from(&path.with_file_name("missing.json")), Settings::default());
+    }
+
+    #[test]
+    fn save_replaces_the_file_without_leaving_a_temp_file() {
+        let path = temp_settings_path("atomic");
+        let settings = Settings { hotkey: "Win+R".to_string(), ..Settings::default() };
+        save_to(&path, &settings).unwrap();
+
+        assert_eq!(load_from(&path), settings);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn patches_change_only_the_given_keys() {
+        let settings = Settings::default().with_patch(&json!({ "watch_downloads": true, "double_tap": "ctrl" })).unwrap();
+        assert!(settings.watch_downloads);
+        assert_eq!(settings.double_tap, "ctrl");
+        assert_eq!(settings.hotkey, "Alt+Space");
+    }
+
+    #[test]
+    fn patches_reject_unknown_keys_and_bad_values() {
+        let settings = Settings::default();
+        assert!(settings.with_patch(&json!({ "watch_download": true })).unwrap_err().contains("watch_download"));
+        assert!(settings.with_patch(&json!({ "watch_downloads": "on" })).is_err());
+        assert!(settings.with_patch(&json!(["watch_downloads"])).is_err());
+        assert!(settings.with_patch(&json!({ "schema_version": 7 })).is_err());
+    }
+
+    #[test]
+    fn launcher_monitor_modes_use_their_snake_case_names() {
+        let settings = Settings::from_json(&json!({ "launcher_monitor": "focused_window" }));
+        assert_eq!(settings.launcher_monitor, LauncherMonitor::FocusedWindow);
+        assert!(Settings::default().with_patch(&json!({ "launcher_monitor": "leftmost" })).is_err());
+        assert_eq!(Settings::default().launcher_monitor, LauncherMonitor::Cursor);
+    }
+
+    /// Sample history: version 2 renamed "downloads" to "watch_downloads",
+    /// version 3 made the interval a number instead of a string
+    const SAMPLE_MIGRATIONS: &[Migration] = &[
+        |keys| {
+            if let Some(value) = keys.remove("downloads") {
+                keys.insert("watch_downloads".to_string(), value);
+            }
+        },
+        |keys| {
+            if let Some(Value::String(ms)) = keys.get("double_tap_interval_ms") {
+                let ms = ms.parse::<u32>().map_or(Value::Null, Value::from);
+                keys.insert("double_tap_interval_ms".to_string(), ms);
+            }
+        },
+    ];
+
+    fn object(json: Value) -> Map<String, Value> {
+        match json {
+            Value::Object(keys) => keys,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn files_without_a_version_run_every_step() {
+        let mut keys = object(json!({ "downloads": true, "double_tap_interval_ms": "300" }));
+        assert_eq!(migrate_with(&mut keys, SAMPLE_MIGRATIONS), 1);
+
+        let settings = Settings::from_json(&Value::Object(keys));
+        assert!(settings.watch_downloads);
+        assert_eq!(settings.double_tap_interval_ms, 300);
+        assert_eq!(settings.schema_version, 3);
+        assert!(settings.other.is_empty());
+    }
+
+    #[test]
+    fn only_the_steps_after_the_file_version_run() {
+        // "downloads" means nothing at version 2, so it's kept as an unknown key
+        let mut keys = object(json!({ "schema_version": 2, "downloads": true, "double_tap_interval_ms": "250" }));
+        assert_eq!(migrate_with(&mut keys, SAMPLE_MIGRATIONS), 2);
+        assert_eq!(keys["downloads"], json!(true));
+        assert_eq!(keys["double_tap_interval_ms"], json!(250));
+        assert_eq!(keys["schema_version"], json!(3));
+    }
+
+    #[test]
+    fn files_from_a_newer_quickrun_are_left_alone() {
+        let mut keys = object(json!({ "schema_version": 9, "downloads": true }));
+        assert_eq!(migrate_with(&mut keys, SAMPLE_MIGRATIONS), 9);
+        assert_eq!(keys, object(json!({ "schema_version": 9, "downloads": true })));
+        assert_eq!(Settings::from_json(&Value::Object(keys)).schema_version, 9);
+    }
+
+    #[test]
+    fn current_files_are_not_rewritten() {
+        let path = temp_settings_path("current");
+        save_to(&path, &Settings::default()).unwrap();
+        assert_eq!(load_from(&path).schema_version, SCHEMA_VERSION);
+        assert!(!path.with_file_name("settings.v1.json").exists());
+    }
+
+    #[test]
+    fn light_mode_becomes_the_theme_mode() {
+        let mut keys = object(json!({ "light_mode": true, "hotkey": "Ctrl+Space" }));
+        assert_eq!(migrate(&mut keys), 1);
+        let settings = Settings::from_json(&Value::Object(keys.clone()));
+        assert_eq!(settings.theme.mode, ThemeMode::Light);
+        assert_eq!(settings.hotkey, "Ctrl+Space");
+        assert!(!keys.contains_key("light_mode"));
+
+        let mut keys = object(json!({ "light_mode": false }));
+        migrate(&mut keys);
+        assert_eq!(keys["theme"], json!({ "mode": "dark" }));
+    }
+}
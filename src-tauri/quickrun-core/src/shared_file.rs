@@ -0,0 +1,133 @@
+// shared_file.rs - Writing files that several processes share
+//
+// The app and qr (and anything else built on this crate) read and write the
+// same settings.json, history.json and aliases.json. Two things keep them
+// from corrupting each other's work:
+// - replace() writes a temporary file and moves it into place, so a reader
+//   never sees a half-written file
+// - lock() holds an exclusive lock on "<file>.lock" beside the file for a
+//   whole read-modify-write cycle, so two processes can't both read the old
+//   contents and have one's change overwrite the other's
+//
+// The lock is taken through the OS, which releases it if the process dies,
+// so a crash can't leave a stale lock behind. Within one process the
+// modules' own mutexes still order the writes; this only guards against
+// other processes.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Give up waiting for another process after this long
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often to retry while another process holds the lock
+const LOCK_RETRY: Duration = Duration::from_millis(20);
+
+/// An exclusive lock on a shared file, released when dropped
+pub struct FileLock {
+    _file: Option<File>,
+}
+
+/// Path of the lock file for `path` (e.g., settings.json.lock)
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Path of the temporary file replace() writes first (e.g., settings.json.tmp)
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Take the lock on `path`, waiting for other processes to finish with it
+///
+/// Without a writable folder nothing is written, so there's nothing to
+/// guard and no lock is taken (see storage.rs).
+pub fn lock(path: &Path) -> Result<FileLock, String> {
+    if crate::storage::is_in_memory() {
+        return Ok(FileLock { _file: None });
+    }
+    lock_with_timeout(path, LOCK_TIMEOUT)
+}
+
+fn lock_with_timeout(path: &Path, timeout: Duration) -> Result<FileLock, String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(path))
+        .map_err(|e| format!("Failed to lock '{}': {}", path.display(), e))?;
+
+    let started = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(FileLock { _file: Some(file) }),
+            Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                std::thread::sleep(LOCK_RETRY);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(format!(
+                    "'{}' is being written by another QuickRun process - try again",
+                    path.display()
+                ));
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(format!("Failed to lock '{}': {}", path.display(), e));
+            }
+        }
+    }
+}
+
+/// Replace the contents of `path` in one step
+pub fn replace(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let temp = temp_path(path);
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file path in a fresh temp folder
+    fn temp_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quickrun-shared-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("history.json")
+    }
+
+    #[test]
+    fn a_held_lock_makes_others_wait_and_then_give_up() {
+        let path = temp_file("held");
+        let held = lock_with_timeout(&path, LOCK_TIMEOUT).unwrap();
+        let error = lock_with_timeout(&path, Duration::from_millis(50)).err().unwrap();
+        assert!(error.contains("another QuickRun process"));
+
+        drop(held);
+        assert!(lock_with_timeout(&path, Duration::from_millis(50)).is_ok());
+    }
+
+    #[test]
+    fn replace_leaves_only_the_new_contents() {
+        let path = temp_file("replace");
+        std::fs::write(&path, "old").unwrap();
+        replace(&path, b"new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert!(!temp_path(&path).exists());
+    }
+
+    #[test]
+    fn lock_and_temp_files_sit_beside_the_file() {
+        let path = Path::new("config").join("settings.json");
+        assert_eq!(lock_path(&path), Path::new("config").join("settings.json.lock"));
+        assert_eq!(temp_path(&path), Path::new("config").join("settings.json.tmp"));
+    }
+}
@@ -0,0 +1,352 @@
+// startup.rs - "Start with Windows"
+//
+// Windows launches every program listed under
+// HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Run at login.
+// Startup is enabled by adding a "QuickRun" value there holding the path of
+// this exe, and disabled by removing it.
+//
+// Both ways of starting with Windows pass SILENT_FLAG, so QuickRun knows it
+// was started at login: it then waits "startup_delay_secs" before
+// registering its hotkey and scanning for apps, to keep out of the way of
+// everything else starting then. Entries made by versions from before the
+// flag are rewritten with it at startup (see refresh and refresh_task).
+//
+// The registry is reached through the os::Registry trait, so the rules are
+// unit-tested with an in-memory registry.
+//
+// Programs started from the Run key never get administrator rights, even
+// for an admin, so a QuickRun started that way can't send keys to or raise
+// elevated windows. "Start with Windows as administrator" instead registers
+// a scheduled task (task_name) that starts this exe at logon with the
+// highest privileges, which Windows does without a UAC prompt. Creating
+// and deleting the task needs admin rights, so schtasks runs elevated: one
+// UAC prompt each time the option is switched. Tasks are machine-wide, so
+// each user's is named after them and users don't replace each other's.
+
+use crate::os::Registry;
+use std::path::Path;
+
+/// Registry key Windows reads at login (under HKEY_CURRENT_USER)
+const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+/// Name of our value under RUN_KEY
+const VALUE_NAME: &str = "QuickRun";
+
+/// Command-line flag for a start at login
+pub const SILENT_FLAG: &str = "--silent";
+
+/// Was QuickRun started at login (with SILENT_FLAG)?
+pub fn is_silent_launch(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter().skip(1).any(|arg| arg.eq_ignore_ascii_case(SILENT_FLAG))
+}
+
+/// Is QuickRun registered to start at login?
+pub fn is_enabled(registry: &dyn Registry) -> Result<bool, String> {
+    Ok(registry.get_string(RUN_KEY, VALUE_NAME)?.is_some())
+}
+
+/// Register (or unregister) `exe_path` to start at login (with SILENT_FLAG)
+///
+/// Disabling when it isn't registered is not an error.
+pub fn set_enabled(registry: &dyn Registry, enabled: bool, exe_path: &Path) -> Result<(), String> {
+    if enabled {
+        let command_line = format!("\"{}\" {}", exe_path.to_string_lossy(), SILENT_FLAG);
+        registry.set_string(RUN_KEY, VALUE_NAME, &command_line)
+    } else if is_enabled(registry)? {
+        registry.delete_value(RUN_KEY, VALUE_NAME)
+    } else {
+        Ok(())
+    }
+}
+
+/// Does `command_line` pass SILENT_FLAG?
+fn has_silent_flag(command_line: &str) -> bool {
+    command_line.split_whitespace().any(|arg| arg.eq_ignore_ascii_case(SILENT_FLAG))
+}
+
+/// Point a Run value made without SILENT_FLAG (by an older version) at
+/// `exe_path` with it; one that has it, or none at all, is left alone
+pub fn refresh(registry: &dyn Registry, exe_path: &Path) -> Result<(), String> {
+    match registry.get_string(RUN_KEY, VALUE_NAME)? {
+        Some(command_line) if !has_silent_flag(&command_line) => {
+            eprintln!("[Startup] Adding {} to the Run value", SILENT_FLAG);
+            set_enabled(registry, true, exe_path)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Name of the scheduled task that starts QuickRun elevated for `user`
+/// ("DOMAIN\name"), in the root folder of the Task Scheduler library
+pub fn task_name(user: &str) -> String {
+    // \ separates folders, and the rest aren't allowed in task names
+    let user: String = user
+        .chars()
+        .map(|c| if matches!(c, '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '-' } else { c })
+        .collect();
+    format!("QuickRun ({})", user)
+}
+
+/// Escape `text` for use in XML
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The scheduled task that starts `exe_path` elevated when `user`
+/// ("DOMAIN\name") logs on
+///
+/// Unlike schtasks' own defaults, it also starts on battery power and runs
+/// for as long as QuickRun does, at normal priority.
+pub fn task_xml(exe_path: &Path, user: &str) -> String {
+    let user = xml_escape(user);
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo>
+    <Description>Starts QuickRun as administrator when you sign in</Description>
+  </RegistrationInfo>
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+      <UserId>{user}</UserId>
+    </LogonTrigger>
+  </Triggers>
+  <Principals>
+    <Principal id="Author">
+      <UserId>{user}</UserId>
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>HighestAvailable</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <Priority>5</Priority>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>{command}</Command>
+      <Arguments>{flag}</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        user = user,
+        command = xml_escape(&exe_path.to_string_lossy()),
+        flag = SILENT_FLAG,
+    )
+}
+
+/// The signed-in user as "DOMAIN\name"
+fn current_user() -> String {
+    let name = std::env::var("USERNAME").unwrap_or_default();
+    match std::env::var("USERDOMAIN") {
+        Ok(domain) if !domain.is_empty() => format!("{}\\{}", domain, name),
+        _ => name,
+    }
+}
+
+/// Is the elevated startup task registered?
+#[cfg(windows)]
+pub fn is_task_enabled() -> bool {
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Stdio};
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    Command::new("schtasks")
+        .args(["/Query", "/TN", &task_name(&current_user())])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(windows))]
+pub fn is_task_enabled() -> bool {
+    false
+}
+
+/// Register (or remove) the task that starts `exe_path` elevated at logon
+///
+/// Shows a UAC prompt, unless there's nothing to remove. Declining it is
+/// an error.
+pub fn set_task_enabled(enabled: bool, exe_path: &Path) -> Result<(), String> {
+    if !enabled {
+        if !is_task_enabled() {
+            return Ok(());
+        }
+        return run_schtasks(&format!("/Delete /F /TN \"{}\"", task_name(&current_user())));
+    }
+
+    // schtasks reads the definition from a file, which must be UTF-16
+    let xml_path = std::env::temp_dir().join(format!("quickrun-startup-{}.xml", std::process::id()));
+    let mut contents = vec![0xFF, 0xFE];
+    let user = current_user();
+    contents.extend(task_xml(exe_path, &user).encode_utf16().flat_map(u16::to_le_bytes));
+    std::fs::write(&xml_path, contents).map_err(|e| format!("Failed to write the startup task: {}", e))?;
+
+    let result = run_schtasks(&format!("/Create /F /TN \"{}\" /XML \"{}\"", task_name(&user), xml_path.display()));
+    let _ = std::fs::remove_file(&xml_path);
+    result
+}
+
+/// Recreate the elevated startup task for `exe_path` if an older version
+/// registered it without SILENT_FLAG
+///
+/// Only done while QuickRun runs elevated (as that task starts it), where
+/// schtasks needs no UAC prompt; otherwise it waits for the next login.
+#[cfg(windows)]
+pub fn refresh_task(exe_path: &Path) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+    use windows::Win32::UI::Shell::IsUserAnAdmin;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    if !unsafe { IsUserAnAdmin() }.as_bool() {
+        return Ok(());
+    }
+    let output = Command::new("schtasks")
+        .args(["/Query", "/TN", &task_name(&current_user()), "/XML"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to read the startup task: {}", e))?;
+    // Not registered
+    if !output.status.success() {
+        return Ok(());
+    }
+    if String::from_utf8_lossy(&output.stdout).contains(SILENT_FLAG) {
+        return Ok(());
+    }
+    eprintln!("[Startup] Adding {} to the startup task", SILENT_FLAG);
+    set_task_enabled(true, exe_path)
+}
+
+#[cfg(not(windows))]
+pub fn refresh_task(_exe_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// Run schtasks elevated with `parameters`
+fn run_schtasks(parameters: &str) -> Result<(), String> {
+    match crate::runner::run_elevated_and_wait("schtasks.exe", parameters)? {
+        0 => Ok(()),
+        code => Err(format!("Failed to update the startup task: schtasks exited with code {}", code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::MemoryRegistry;
+
+    const EXE: &str = "C:\\Program Files\\QuickRun\\quickrun.exe";
+    const RUN_VALUE: &str = "\"C:\\Program Files\\QuickRun\\quickrun.exe\" --silent";
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(!is_enabled(&MemoryRegistry::new()).unwrap());
+    }
+
+    #[test]
+    fn enabling_registers_the_exe_path() {
+        let registry = MemoryRegistry::new();
+        set_enabled(&registry, true, Path::new(EXE)).unwrap();
+
+        assert!(is_enabled(&registry).unwrap());
+        assert_eq!(registry.get_string(RUN_KEY, VALUE_NAME).unwrap().as_deref(), Some(RUN_VALUE));
+    }
+
+    #[test]
+    fn enabling_again_points_at_the_current_exe() {
+        let registry = MemoryRegistry::new();
+        set_enabled(&registry, true, Path::new("D:\\old\\quickrun.exe")).unwrap();
+        set_enabled(&registry, true, Path::new(EXE)).unwrap();
+
+        assert_eq!(registry.get_string(RUN_KEY, VALUE_NAME).unwrap().as_deref(), Some(RUN_VALUE));
+    }
+
+    #[test]
+    fn disabling_removes_the_value() {
+        let registry = MemoryRegistry::new();
+        set_enabled(&registry, true, Path::new(EXE)).unwrap();
+        set_enabled(&registry, false, Path::new(EXE)).unwrap();
+
+        assert!(!is_enabled(&registry).unwrap());
+    }
+
+    #[test]
+    fn disabling_when_not_registered_succeeds() {
+        let registry = MemoryRegistry::new();
+        assert!(set_enabled(&registry, false, Path::new(EXE)).is_ok());
+    }
+
+    #[test]
+    fn run_values_without_the_silent_flag_are_rewritten() {
+        let registry = MemoryRegistry::new();
+        registry.set_string(RUN_KEY, VALUE_NAME, "\"D:\\old\\quickrun.exe\"").unwrap();
+        refresh(&registry, Path::new(EXE)).unwrap();
+        assert_eq!(registry.get_string(RUN_KEY, VALUE_NAME).unwrap().as_deref(), Some(RUN_VALUE));
+
+        // Ones with it, or none at all, are left alone
+        let current = "\"D:\\QuickRun\\quickrun.exe\" --SILENT";
+        registry.set_string(RUN_KEY, VALUE_NAME, current).unwrap();
+        refresh(&registry, Path::new(EXE)).unwrap();
+        assert_eq!(registry.get_string(RUN_KEY, VALUE_NAME).unwrap().as_deref(), Some(current));
+
+        let registry = MemoryRegistry::new();
+        refresh(&registry, Path::new(EXE)).unwrap();
+        assert!(!is_enabled(&registry).unwrap());
+    }
+
+    #[test]
+    fn other_run_entries_are_left_alone() {
+        let registry = MemoryRegistry::new();
+        registry.set_string(RUN_KEY, "OneDrive", "C:\\OneDrive.exe").unwrap();
+        set_enabled(&registry, true, Path::new(EXE)).unwrap();
+        set_enabled(&registry, false, Path::new(EXE)).unwrap();
+
+        assert_eq!(registry.get_string(RUN_KEY, "OneDrive").unwrap().as_deref(), Some("C:\\OneDrive.exe"));
+    }
+
+    #[test]
+    fn the_task_starts_the_exe_elevated_at_logon() {
+        let xml = task_xml(Path::new(EXE), "PC\\Ann");
+
+        assert!(xml.contains("<Command>C:\\Program Files\\QuickRun\\quickrun.exe</Command>"));
+        assert!(xml.contains("<Arguments>--silent</Arguments>"));
+        assert!(xml.contains("<RunLevel>HighestAvailable</RunLevel>"));
+        assert_eq!(xml.matches("<UserId>PC\\Ann</UserId>").count(), 2);
+        assert!(xml.contains("<ExecutionTimeLimit>PT0S</ExecutionTimeLimit>"));
+    }
+
+    #[test]
+    fn the_task_escapes_paths_and_names() {
+        let xml = task_xml(Path::new("C:\\Tools & Co\\<quickrun>.exe"), "PC\\O'Brien");
+
+        assert!(xml.contains("<Command>C:\\Tools &amp; Co\\&lt;quickrun&gt;.exe</Command>"));
+        assert!(xml.contains("<UserId>PC\\O&apos;Brien</UserId>"));
+    }
+
+    #[test]
+    fn each_user_has_a_task_of_their_own() {
+        assert_eq!(task_name("PC\\Ann"), "QuickRun (PC-Ann)");
+        assert_ne!(task_name("PC\\Ann"), task_name("PC\\Bob"));
+        assert_eq!(task_name("a/b:c*?\"<>|"), "QuickRun (a-b-c------)");
+    }
+
+    #[test]
+    fn only_the_silent_flag_marks_a_start_at_login() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert!(is_silent_launch(args(&[EXE, "--silent"])));
+        assert!(!is_silent_launch(args(&[EXE])));
+        assert!(!is_silent_launch(args(&["--silent"])));
+    }
+}
@@ -0,0 +1,250 @@
+// storage.rs - Where QuickRun keeps its files
+//
+// Settings, aliases, history, the index cache and everything else QuickRun
+// writes live in one folder:
+// - Normally %APPDATA%\QuickRun (per user, roams with the profile)
+// - In portable mode, the folder the exe is in. Portable mode is on when a
+//   "portable.flag" file (contents ignored) or a settings.json sits next to
+//   the exe, so QuickRun can run from a USB stick without leaving files
+//   behind. Start with Windows is unavailable there, since it's a registry
+//   write.
+//
+// Some machines won't let QuickRun write there (mandatory profiles, locked
+// down AppData, a portable copy on read-only media). Rather than every save
+// failing, QuickRun then moves to the first writable fallback
+// (%LOCALAPPDATA%\QuickRun, then the temp folder), carrying the existing
+// files over; if nothing is writable, changes are kept in memory until it
+// exits. Either way `warning` says what happened, for the Settings window.
+//
+// The folder is worked out once per process (current). The rules are in
+// locate() and choose_writable(), which are unit-tested without touching
+// the disk. QUICKRUN_CONFIG_DIR, if set, names the folder outright: the
+// integration tests and benchmarks use it so they never read or write the
+// user's own settings, history and index.
+
+use crate::os::{FileSystem, SystemFs};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Marker file that turns on portable mode
+pub const PORTABLE_FLAG: &str = "portable.flag";
+
+/// Name of the settings file in the storage folder
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Environment variable that overrides the storage folder
+pub const CONFIG_DIR_VAR: &str = "QUICKRUN_CONFIG_DIR";
+
+/// Where QuickRun's files are kept
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Storage {
+    /// Folder holding settings.json and everything else
+    pub dir: PathBuf,
+    /// The folder is next to the exe rather than in the user profile
+    pub portable: bool,
+    /// Nothing could be written: `dir` is only read, and changes last until
+    /// QuickRun exits
+    pub in_memory: bool,
+    /// Why the usual folder isn't being used, for the user
+    pub warning: Option<String>,
+}
+
+/// The storage location of this process, once worked out
+static STORAGE: OnceLock<Storage> = OnceLock::new();
+
+/// Pick the storage folder: `exe_dir` if it is marked portable, otherwise
+/// `standard_dir`
+pub fn locate(fs: &dyn FileSystem, exe_dir: Option<&Path>, standard_dir: PathBuf) -> Storage {
+    if let Some(exe_dir) = exe_dir {
+        if fs.is_file(&exe_dir.join(PORTABLE_FLAG)) || fs.is_file(&exe_dir.join(SETTINGS_FILE)) {
+            return Storage { dir: exe_dir.to_path_buf(), portable: true, ..Storage::default() };
+        }
+    }
+    Storage { dir: standard_dir, ..Storage::default() }
+}
+
+/// Keep `preferred` if `can_write` it, otherwise move to the first writable
+/// folder in `fallbacks`, otherwise stay in `preferred` in memory only
+pub fn choose_writable(preferred: Storage, fallbacks: &[PathBuf], can_write: impl Fn(&Path) -> bool) -> Storage {
+    if can_write(&preferred.dir) {
+        return preferred;
+    }
+
+    match fallbacks.iter().find(|dir| **dir != preferred.dir && can_write(dir)) {
+        Some(fallback) => Storage {
+            warning: Some(format!(
+                "Can't save to {}, so settings are being saved to {} instead",
+                preferred.dir.display(),
+                fallback.display()
+            )),
+            dir: fallback.clone(),
+            ..preferred
+        },
+        None => Storage {
+            in_memory: true,
+            warning: Some(format!(
+                "Can't save to {} or any other folder, so changes will be lost when QuickRun exits",
+                preferred.dir.display()
+            )),
+            ..preferred
+        },
+    }
+}
+
+/// Whether QuickRun can create and replace files in `dir`
+fn can_write(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    // A read-only settings.json can't be replaced even in a writable folder
+    if let Ok(metadata) = std::fs::metadata(dir.join(SETTINGS_FILE)) {
+        if metadata.permissions().readonly() {
+            return false;
+        }
+    }
+    let probe = dir.join(".write-test");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+/// Copy the files in `from` that `to` doesn't have yet, so moving to a
+/// fallback folder keeps the existing settings, aliases and history
+fn carry_over(from: &Path, to: &Path) {
+    let Ok(entries) = std::fs::read_dir(from) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let target = to.join(entry.file_name());
+        if entry.path().is_file() && !target.exists() {
+            if let Err(e) = std::fs::copy(entry.path(), &target) {
+                eprintln!("[Storage] Can't copy {} to {}: {}", entry.path().display(), to.display(), e);
+            }
+        }
+    }
+}
+
+/// The storage location of this process
+pub fn current() -> &'static Storage {
+    STORAGE.get_or_init(|| {
+        let located = match std::env::var_os(CONFIG_DIR_VAR) {
+            Some(dir) => Storage { dir: PathBuf::from(dir), ..Storage::default() },
+            None => {
+                let exe = std::env::current_exe().ok();
+                let standard_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join("QuickRun");
+                locate(&SystemFs, exe.as_deref().and_then(Path::parent), standard_dir)
+            }
+        };
+        if located.portable {
+            eprintln!("[Storage] Portable mode: files are kept in {}", located.dir.display());
+        }
+
+        let fallbacks: Vec<PathBuf> = dirs::data_local_dir()
+            .into_iter()
+            .chain([std::env::temp_dir()])
+            .map(|dir| dir.join("QuickRun"))
+            .collect();
+        let storage = choose_writable(located.clone(), &fallbacks, can_write);
+        if let Some(warning) = &storage.warning {
+            eprintln!("[Storage] {}", warning);
+        }
+        if storage.dir != located.dir {
+            carry_over(&located.dir, &storage.dir);
+        }
+        storage
+    })
+}
+
+/// Whether QuickRun is running in portable mode
+pub fn is_portable() -> bool {
+    current().portable
+}
+
+/// Whether changes can only be kept in memory (see choose_writable)
+pub fn is_in_memory() -> bool {
+    current().in_memory
+}
+
+/// Get the QuickRun config directory
+///
+/// All persisted data (settings, history, aliases, caches) lives here:
+/// - Windows: C:\Users\<username>\AppData\Roaming\QuickRun
+/// - Portable mode: the folder containing the exe
+/// - Creates the directory if it doesn't exist
+///
+/// This approach is platform-agnostic (uses dirs crate to find the right location)
+pub fn get_config_dir() -> PathBuf {
+    let dir = current().dir.clone();
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::MemoryFs;
+
+    const EXE_DIR: &str = "E:\\Tools\\QuickRun";
+    const APPDATA: &str = "C:\\Users\\me\\AppData\\Roaming\\QuickRun";
+    const LOCAL: &str = "C:\\Users\\me\\AppData\\Local\\QuickRun";
+
+    fn locate_with(fs: &MemoryFs) -> Storage {
+        locate(fs, Some(Path::new(EXE_DIR)), PathBuf::from(APPDATA))
+    }
+
+    #[test]
+    fn installed_copies_use_the_profile() {
+        let fs = MemoryFs::new().with_file(Path::new(EXE_DIR).join("quickrun.exe"));
+        assert_eq!(locate_with(&fs), Storage { dir: PathBuf::from(APPDATA), ..Storage::default() });
+    }
+
+    #[test]
+    fn a_flag_file_makes_it_portable() {
+        let fs = MemoryFs::new().with_file(Path::new(EXE_DIR).join(PORTABLE_FLAG));
+        assert_eq!(
+            locate_with(&fs),
+            Storage { dir: PathBuf::from(EXE_DIR), portable: true, ..Storage::default() }
+        );
+    }
+
+    #[test]
+    fn settings_next_to_the_exe_make_it_portable() {
+        let fs = MemoryFs::new().with_file(Path::new(EXE_DIR).join(SETTINGS_FILE));
+        assert!(locate_with(&fs).portable);
+    }
+
+    #[test]
+    fn an_unknown_exe_folder_uses_the_profile() {
+        let storage = locate(&MemoryFs::new(), None, PathBuf::from(APPDATA));
+        assert!(!storage.portable);
+    }
+
+    fn choose(preferred: Storage, writable: &[&str]) -> Storage {
+        let fallbacks = [PathBuf::from(LOCAL), PathBuf::from("C:\\Temp\\QuickRun")];
+        choose_writable(preferred, &fallbacks, |dir| writable.iter().any(|w| dir == Path::new(w)))
+    }
+
+    #[test]
+    fn a_writable_folder_is_kept() {
+        let preferred = Storage { dir: PathBuf::from(APPDATA), ..Storage::default() };
+        assert_eq!(choose(preferred.clone(), &[APPDATA, LOCAL]), preferred);
+    }
+
+    #[test]
+    fn a_read_only_folder_moves_to_a_fallback_with_a_warning() {
+        let preferred = Storage { dir: PathBuf::from(EXE_DIR), portable: true, ..Storage::default() };
+        let storage = choose(preferred, &[LOCAL]);
+        assert_eq!(storage.dir, PathBuf::from(LOCAL));
+        assert!(storage.portable && !storage.in_memory);
+        assert!(storage.warning.unwrap().contains(EXE_DIR));
+    }
+
+    #[test]
+    fn with_nothing_writable_changes_stay_in_memory() {
+        let storage = choose(Storage { dir: PathBuf::from(APPDATA), ..Storage::default() }, &[]);
+        assert_eq!(storage.dir, PathBuf::from(APPDATA));
+        assert!(storage.in_memory);
+        assert!(storage.warning.is_some());
+    }
+}
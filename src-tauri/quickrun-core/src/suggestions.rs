@@ -0,0 +1,515 @@
+// suggestions.rs - As-you-type suggestion list
+//
+// While the user types, the launcher shows a short list of matching things
+// they could launch, each with its app icon:
+// - History: commands launched before
+// - App index: Start Menu applications
+// - PATH: executables reachable by name
+// - Open windows: switch to a running window by title (providers/open_windows.rs)
+// - Settings pages: Settings app pages and Control Panel items by task
+//   (providers/settings_pages.rs)
+// - VS Code projects: folders and workspaces opened recently
+//   (providers/vscode_recent.rs)
+// - Games: installed Steam and Epic Games games (providers/games.rs)
+// - Browser profiles: Chrome, Edge and Brave profiles
+//   (providers/browser_profiles.rs)
+// - Plugins: external plugin processes (plugins/)
+// - New downloads: a just-finished download is offered first, even before
+//   anything is typed (providers/downloads.rs)
+//
+// Matches are scored (exact > prefix > word start > substring) and history
+// gets a small bonus, so familiar commands float to the top. The full
+// ordering, with its tie-breakers, is in ranking.rs.
+//
+// Input starting with ">" (terminal prefix) suggests shell commands instead:
+// ">" commands from history, plus PowerShell history when imported
+// (see providers/shell_history.rs). Input starting with ":" searches emoji
+// and symbols (providers/emoji.rs). "term" followed by a name lists
+// Windows Terminal profiles (providers/terminal_profiles.rs), "ssh"
+// followed by one lists SSH hosts and PuTTY sessions (providers/ssh_hosts.rs)
+// and "rdp" Remote Desktop servers and .rdp files (providers/rdp_connections.rs).
+// "code" followed by a project name lists only VS Code projects, "env"
+// followed by a name environment variables (providers/environment_variables.rs),
+// and "reg" followed by a registry key offers to open it (providers/registry_jump.rs).
+// A file followed by | lists the aliases it can be opened with (pipe.rs).
+//
+// Every query gets a cancellation token (begin_query). When the user types
+// on, the previous query's token is cancelled: the engine stops between
+// sources, plugins abandon their pending requests, and the stale query
+// returns None instead of rows that could land after fresher ones.
+
+use crate::providers::{
+    browser_profiles, downloads, emoji, environment_variables, games, open_windows,
+    rdp_connections, registry_jump, settings_pages, shell_history, ssh_hosts, terminal_profiles,
+    vscode_recent,
+};
+use crate::ranking::{self, match_score, Provider, Rankable};
+use crate::results::{Action, ResultRow};
+use crate::{aliases, file_actions, history, icons, index, pipe, plugins, runner, web_search};
+use std::path::Path;
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Maximum number of suggestions shown
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Bonus for commands the user has launched before
+const HISTORY_BONUS: u32 = 10;
+
+/// Token of the query currently being answered
+static CURRENT_QUERY: Mutex<Option<CancellationToken>> = Mutex::new(None);
+
+/// Start a new query, cancelling the one before it
+pub fn begin_query() -> CancellationToken {
+    let token = CancellationToken::new();
+    let mut current = CURRENT_QUERY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(previous) = current.replace(token.clone()) {
+        previous.cancel();
+    }
+    token
+}
+
+/// Find the file a command would launch, for icon lookup
+fn target_path(command: &str) -> Option<String> {
+    let path = if runner::is_explicit_path(command) {
+        Some(runner::expand_home(command))
+    } else {
+        runner::resolve_on_path(command).or_else(|| index::find(command).map(|app| app.path))
+    };
+    path.map(|p| p.to_string_lossy().to_string())
+}
+
+/// A scored candidate before it becomes a row
+struct Candidate {
+    score: u32,
+    /// History frecency (0 for things never launched)
+    frecency: u32,
+    provider: Provider,
+    title: String,
+    subtitle: String,
+    target: Option<String>,
+    /// What selecting the row does, when it isn't "run the title" (open
+    /// windows, plugin results); such rows get no file actions
+    action: Option<Action>,
+}
+
+impl Rankable for Candidate {
+    fn score(&self) -> u32 {
+        self.score
+    }
+    fn frecency(&self) -> u32 {
+        self.frecency
+    }
+    fn title(&self) -> &str {
+        &self.title
+    }
+    fn provider(&self) -> Provider {
+        self.provider
+    }
+    fn detail(&self) -> &str {
+        &self.subtitle
+    }
+}
+
+/// Suggestions for the ">" terminal prefix
+///
+/// `query` is the text after ">". With an empty query the most-used
+/// commands are listed.
+fn suggest_shell_commands(query: &str) -> Vec<ResultRow> {
+    let launched = history::load()
+        .into_iter()
+        .filter_map(|e| e.command.strip_prefix('>').map(|c| c.trim().to_string()))
+        .map(|command| (command, "History"));
+    let imported = shell_history::load()
+        .into_iter()
+        .map(|command| (command, "PowerShell history"));
+
+    let mut seen: Vec<String> = Vec::new();
+    let mut candidates: Vec<(u32, String, &str)> = Vec::new();
+
+    for (command, source) in launched.chain(imported) {
+        let key = command.to_lowercase();
+        if command.is_empty() || seen.contains(&key) {
+            continue;
+        }
+        let score = if query.is_empty() { Some(0) } else { match_score(&command, query) };
+        if let Some(score) = score {
+            seen.push(key);
+            candidates.push((score, command, source));
+        }
+    }
+
+    // Stable sort: equal scores keep history-then-PowerShell, best-ranked order
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
+
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, command, source)| {
+            let input = format!("> {}", command);
+            ResultRow::new(input.clone(), source).with_action(Action::Run(input))
+        })
+        .collect()
+}
+
+/// A single row for an existing file or folder typed as a path
+fn suggest_path(query: &str) -> Option<ResultRow> {
+    let path = runner::expand_home(query);
+    let target = path.to_string_lossy().to_string();
+
+    let (subtitle, actions) = if path.is_dir() {
+        ("Folder", file_actions::folder_actions_for(&path))
+    } else if path.is_file() {
+        ("File", file_actions::actions_for(&path))
+    } else {
+        return None;
+    };
+
+    Some(
+        ResultRow::new(query, subtitle)
+            .with_action(Action::Run(query.to_string()))
+            .with_icon(icons::icon_for(&target))
+            .with_actions(actions),
+    )
+}
+
+/// "Open with" rows for `target`: the aliases matching `program` (all of
+/// them until something is typed), or `program` itself if none does
+fn suggest_pipe(target: &Path, program: &str) -> Vec<ResultRow> {
+    let path = target.to_string_lossy();
+    let name = target.file_name().map(|name| name.to_string_lossy()).unwrap_or_else(|| path.clone());
+
+    let mut matches: Vec<(u32, aliases::Alias)> = aliases::load()
+        .into_iter()
+        .filter_map(|alias| {
+            let score = if program.is_empty() { Some(0) } else { match_score(&alias.name, program) };
+            score.map(|score| (score, alias))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut rows: Vec<ResultRow> = matches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, alias)| {
+            let icon = target_path(&alias.target).and_then(|target| icons::icon_for(&target));
+            ResultRow::new(format!("Open {} with {}", name, alias.name), alias.target)
+                .with_action(Action::Run(pipe::compose(&path, &alias.name)))
+                .with_icon(icon)
+        })
+        .collect();
+
+    if rows.is_empty() && !program.is_empty() {
+        rows.push(
+            ResultRow::new(format!("Open {} with {}", name, program), path.to_string())
+                .with_action(Action::Run(pipe::compose(&path, program))),
+        );
+    }
+    rows
+}
+
+/// Row for a just-finished download, if there is one and it matches `query`
+fn suggest_download(query: &str) -> Option<ResultRow> {
+    let (path, noticed) = downloads::fresh_download()?;
+    let name = path.file_name()?.to_string_lossy().to_string();
+    if !query.is_empty() && match_score(&name, query).is_none() {
+        return None;
+    }
+
+    let minutes = noticed.elapsed().unwrap_or_default().as_secs() / 60;
+    let subtitle = match minutes {
+        0 => "New download - just now".to_string(),
+        m => format!("New download - {} min ago", m),
+    };
+    let target = path.to_string_lossy().to_string();
+
+    Some(
+        ResultRow::new(name, subtitle)
+            .with_action(Action::Open(target.clone()))
+            .with_icon(icons::icon_for(&target))
+            .with_actions(file_actions::actions_for(&path)),
+    )
+}
+
+/// Build the suggestion rows for `query`
+///
+/// Returns None if `cancel` was cancelled (a newer query superseded this one).
+pub fn suggest(query: &str, cancel: &CancellationToken) -> Option<Vec<ResultRow>> {
+    suggest_in(query, None, cancel)
+}
+
+/// Like suggest, for a command that would run in `dir` (relative paths on
+/// the left of a | are looked up there, as Enter does)
+pub fn suggest_in(
+    query: &str,
+    dir: Option<&Path>,
+    cancel: &CancellationToken,
+) -> Option<Vec<ResultRow>> {
+    let query = query.trim();
+    if query.is_empty() {
+        // The launcher just opened: only a new download is worth showing
+        return Some(suggest_download(query).into_iter().collect());
+    }
+
+    if let Some(shell_query) = query.strip_prefix('>') {
+        return Some(suggest_shell_commands(shell_query.trim()));
+    }
+
+    if let Some(emoji_query) = query.strip_prefix(':') {
+        return Some(emoji::search(emoji_query));
+    }
+
+    // "term ubuntu": Windows Terminal profiles (other input if there are none)
+    if let Some(profile_query) = terminal_profiles::keyword_query(query) {
+        let rows = terminal_profiles::search(profile_query);
+        if !rows.is_empty() {
+            return Some(rows);
+        }
+    }
+
+    // "ssh web01": known hosts (other input, like "ssh admin@10.0.0.5", if none match)
+    if let Some(host_query) = ssh_hosts::keyword_query(query) {
+        let rows = ssh_hosts::search(host_query);
+        if !rows.is_empty() {
+            return Some(rows);
+        }
+    }
+
+    // "rdp prod-db01": known servers and .rdp files, and the name as a server
+    if let Some(server_query) = rdp_connections::keyword_query(query).filter(|q| !q.is_empty()) {
+        return Some(rdp_connections::search(server_query));
+    }
+
+    // "code quick": VS Code projects ("code ." and other input if none match)
+    if let Some(project_query) = vscode_recent::keyword_query(query) {
+        let rows = vscode_recent::search(project_query);
+        if !rows.is_empty() {
+            return Some(rows);
+        }
+    }
+
+    // "env java": environment variables in every scope, and the editor
+    if let Some(variable_query) = environment_variables::keyword_query(query) {
+        return Some(environment_variables::search(variable_query));
+    }
+
+    // "reg HKLM\SOFTWARE": open the key in Registry Editor
+    if let Some(key) = registry_jump::keyword_query(query) {
+        return Some(registry_jump::search(&key));
+    }
+
+    // A bang ("yt lofi") gets a single row describing the search, unless a
+    // plugin has the same prefix (its rows are among the candidates below)
+    if let Some((site, url)) = web_search::bang_search(query).filter(|_| !plugins::has_prefix(query)) {
+        let (_, search) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+        return Some(vec![ResultRow::new(format!("Search {} for \"{}\"", site, search.trim()), url.clone())
+            .with_action(Action::Open(url))]);
+    }
+
+    // "file | program": offer the aliases to open it with
+    if let Some((target, program)) = pipe::parse(query, dir) {
+        return Some(suggest_pipe(&target, program));
+    }
+
+    // An existing file or folder typed as a path gets a row of its own, so
+    // its actions (reveal, recycle, ...) are reachable
+    if runner::is_explicit_path(query) {
+        return Some(suggest_path(query).into_iter().collect());
+    }
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    let now = history::now();
+    for entry in history::load() {
+        if let Some(score) = match_score(&entry.command, query) {
+            candidates.push(Candidate {
+                score: score + HISTORY_BONUS,
+                frecency: entry.frecency(now),
+                provider: Provider::History,
+                target: target_path(&entry.command),
+                subtitle: "History".to_string(),
+                title: entry.command,
+                action: None,
+            });
+        }
+    }
+
+    for app in index::entries() {
+        if let Some(score) = match_score(&app.name, query) {
+            candidates.push(Candidate {
+                score,
+                frecency: 0,
+                provider: Provider::App,
+                subtitle: app.path.to_string_lossy().to_string(),
+                target: Some(app.path.to_string_lossy().to_string()),
+                title: app.name,
+                action: None,
+            });
+        }
+    }
+
+    for command in index::path_commands() {
+        if let Some(score) = match_score(&command, query) {
+            let path = runner::resolve_on_path(&command).map(|p| p.to_string_lossy().to_string());
+            candidates.push(Candidate {
+                score,
+                frecency: 0,
+                provider: Provider::PathCommand,
+                subtitle: path.clone().unwrap_or_default(),
+                target: path,
+                title: command,
+                action: None,
+            });
+        }
+    }
+
+    if cancel.is_cancelled() {
+        return None;
+    }
+
+    // Open windows match on their title or their program's name ("chrome")
+    for window in open_windows::list() {
+        let exe_name = window.exe_name();
+        let exe_stem = exe_name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(&exe_name);
+        if let Some(score) = match_score(&window.title, query).max(match_score(exe_stem, query)) {
+            candidates.push(Candidate {
+                score,
+                frecency: 0,
+                provider: Provider::Window,
+                subtitle: format!("Switch to window - {}", exe_name),
+                target: window.exe_path,
+                title: window.title,
+                action: Some(Action::Focus(window.handle.to_string())),
+            });
+        }
+    }
+
+    for (score, page) in settings_pages::scored(query) {
+        candidates.push(Candidate {
+            score,
+            frecency: 0,
+            provider: Provider::Setting,
+            subtitle: page.subtitle(),
+            target: page.icon_path(),
+            title: page.name.to_string(),
+            action: Some(page.action()),
+        });
+    }
+
+    let projects = vscode_recent::scored(query);
+    // VS Code's own icon for every project
+    let code = projects.first().and_then(|_| target_path("code"));
+    for (score, project) in projects {
+        candidates.push(Candidate {
+            score,
+            frecency: 0,
+            provider: Provider::Project,
+            subtitle: format!("VS Code - {}", project.location),
+            target: code.clone(),
+            title: project.name,
+            action: Some(Action::Run(project.command)),
+        });
+    }
+
+    for game in games::list() {
+        if let Some(score) = match_score(&game.name, query) {
+            candidates.push(Candidate {
+                score,
+                frecency: 0,
+                provider: Provider::Game,
+                subtitle: format!("{} game", game.store),
+                target: game.icon_path,
+                title: game.name,
+                action: Some(Action::Open(game.uri)),
+            });
+        }
+    }
+
+    for profile in browser_profiles::list() {
+        if let Some(score) = profile.score(query) {
+            candidates.push(Candidate {
+                score,
+                frecency: 0,
+                provider: Provider::BrowserProfile,
+                subtitle: "Browser profile".to_string(),
+                target: Some(profile.exe.to_string_lossy().to_string()),
+                title: profile.title(),
+                action: Some(Action::Run(profile.command())),
+            });
+        }
+    }
+
+    candidates.extend(plugins::query(query, cancel).into_iter().map(|result| Candidate {
+        score: result.score,
+        frecency: 0,
+        provider: Provider::Plugin,
+        title: result.title,
+        subtitle: result.subtitle,
+        target: result.icon_path,
+        action: Some(result.action),
+    }));
+
+    if cancel.is_cancelled() {
+        return None;
+    }
+
+    // Best first, in a fixed order (see ranking.rs); the first of several
+    // equal titles is the one kept below
+    ranking::rank(&mut candidates);
+
+    let mut seen: Vec<String> = Vec::new();
+    let mut rows: Vec<ResultRow> = suggest_download(query).into_iter().collect();
+
+    for candidate in candidates {
+        // Icon extraction is the slow part of building rows
+        if cancel.is_cancelled() {
+            return None;
+        }
+
+        // A window and an app can share a title ("Notepad") - keep both
+        let key = match candidate.action {
+            Some(_) => format!("action:{}", candidate.title.to_lowercase()),
+            None => candidate.title.to_lowercase(),
+        };
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+
+        let icon = candidate.target.as_deref().and_then(icons::icon_for);
+
+        if let Some(action) = candidate.action {
+            rows.push(
+                ResultRow::new(candidate.title, candidate.subtitle)
+                    .with_action(action)
+                    .with_icon(icon),
+            );
+        } else {
+            let actions = candidate
+                .target
+                .as_deref()
+                .map(|t| file_actions::actions_for(std::path::Path::new(t)))
+                .unwrap_or_default();
+            rows.push(
+                ResultRow::new(candidate.title.clone(), candidate.subtitle)
+                    .with_action(Action::Run(candidate.title))
+                    .with_icon(icon)
+                    .with_actions(actions),
+            );
+        }
+
+        if rows.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+
+    // Nothing local matches: offer a web search instead
+    if rows.is_empty() {
+        rows.push(
+            ResultRow::new(format!("Search the web for \"{}\"", query), "Web search")
+                .with_action(Action::Open(web_search::search_url(query))),
+        );
+    }
+
+    Some(rows)
+}
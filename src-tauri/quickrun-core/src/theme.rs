@@ -0,0 +1,258 @@
+// theme.rs - How the windows look
+//
+// The theme is the "theme" object in settings.json:
+//
+//   "theme": {
+//     "mode": "dark",               (or "light")
+//     "accent": "#007acc",          (focus rings and highlights)
+//     "background_opacity": 1.0,    (launcher background, 0.3 to 1)
+//     "window_opacity": 1.0,        (the whole launcher, text included,
+//                                    0.5 to 1)
+//     "corner_radius": 8,           (launcher corners, in pixels)
+//     "font_family": "",            (CSS font list; "" = the built-in one)
+//     "custom_css_path": null,      (a stylesheet applied after QuickRun's own)
+//     "backdrop": "none",           (or "acrylic"/"mica": blurred desktop
+//                                    behind the launcher, Windows 11 only)
+//     "tray_icon": "color"          (or "monochrome": white or black to
+//                                    match the taskbar)
+//   }
+//
+// Windows get it through the get_theme command and a ThemeChanged event,
+// as a LoadedTheme that also carries the custom stylesheet's contents, so
+// the webview never reads files itself.
+
+use crate::os::Registry;
+use crate::runner;
+use serde::{Deserialize, Serialize};
+
+/// Registry key with Windows' light and dark mode choices (under
+/// HKEY_CURRENT_USER)
+const PERSONALIZE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize";
+
+/// Largest custom stylesheet loaded (anything bigger is most likely the
+/// wrong file)
+const MAX_CUSTOM_CSS_BYTES: u64 = 256 * 1024;
+
+/// Lowest background opacity accepted (below this the text is unreadable)
+pub const MIN_BACKGROUND_OPACITY: f64 = 0.3;
+
+/// Lowest opacity accepted for the whole launcher
+pub const MIN_WINDOW_OPACITY: f64 = 0.5;
+
+/// Largest corner radius accepted, in pixels
+pub const MAX_CORNER_RADIUS: u32 = 32;
+
+/// Dark or light colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// What shows through the launcher's background
+///
+/// Acrylic and Mica are drawn by Windows 11 behind the see-through
+/// background; elsewhere, or while the resource policy turns blur off, the
+/// launcher falls back to plain transparency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backdrop {
+    /// Plain transparency (the desktop, unblurred)
+    #[default]
+    None,
+    /// Blurred desktop, as in the Start menu
+    Acrylic,
+    /// The wallpaper's tint, as in Settings and Explorer
+    Mica,
+}
+
+/// How the tray icon is drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayIcon {
+    /// The app icon
+    #[default]
+    Color,
+    /// The app icon's outline in one color: white on a dark taskbar, black
+    /// on a light one, like Windows' own tray icons
+    Monochrome,
+}
+
+/// The appearance settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub mode: ThemeMode,
+    /// Accent color as "#rgb" or "#rrggbb"
+    pub accent: String,
+    /// Opacity of the launcher background (MIN_BACKGROUND_OPACITY to 1)
+    pub background_opacity: f64,
+    /// Opacity of the whole launcher, text included (MIN_WINDOW_OPACITY to 1)
+    pub window_opacity: f64,
+    /// Radius of the launcher's corners, in pixels
+    pub corner_radius: u32,
+    /// CSS font-family list ("" = the built-in one)
+    pub font_family: String,
+    /// Stylesheet applied after the built-in styles (may start with ~)
+    pub custom_css_path: Option<String>,
+    /// Blurred background behind the launcher
+    pub backdrop: Backdrop,
+    /// Look of the tray icon
+    pub tray_icon: TrayIcon,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::Dark,
+            accent: "#007acc".to_string(),
+            background_opacity: 1.0,
+            window_opacity: 1.0,
+            corner_radius: 8,
+            font_family: String::new(),
+            custom_css_path: None,
+            backdrop: Backdrop::None,
+            tray_icon: TrayIcon::Color,
+        }
+    }
+}
+
+impl Theme {
+    /// Check the values that go into CSS (used before saving a change)
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_hex_color(&self.accent) {
+            return Err(format!("Accent color '{}' must look like #007acc", self.accent));
+        }
+        if !(MIN_BACKGROUND_OPACITY..=1.0).contains(&self.background_opacity) {
+            return Err(format!(
+                "Background opacity must be between {} and 1",
+                MIN_BACKGROUND_OPACITY
+            ));
+        }
+        if !(MIN_WINDOW_OPACITY..=1.0).contains(&self.window_opacity) {
+            return Err(format!("Launcher opacity must be between {} and 1", MIN_WINDOW_OPACITY));
+        }
+        if self.corner_radius > MAX_CORNER_RADIUS {
+            return Err(format!("Corner radius can be at most {} pixels", MAX_CORNER_RADIUS));
+        }
+        if self.font_family.contains([';', '{', '}', '<', '>']) {
+            return Err("Font family must be a plain list of fonts".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Is the taskbar light? ("Choose your default Windows mode" in Settings;
+/// dark when unset, as before Windows 10 1903)
+pub fn taskbar_is_light(registry: &dyn Registry) -> bool {
+    matches!(registry.get_dword(PERSONALIZE_KEY, "SystemUsesLightTheme"), Ok(Some(1..)))
+}
+
+/// "#rgb" or "#rrggbb"
+fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// A theme as sent to the windows: the settings plus the custom
+/// stylesheet's contents (None if there isn't one or it can't be read)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoadedTheme {
+    #[serde(flatten)]
+    pub theme: Theme,
+    pub custom_css: Option<String>,
+}
+
+/// Read the custom stylesheet (if any) for sending `theme` to the windows
+pub fn load(theme: &Theme) -> LoadedTheme {
+    let custom_css = theme
+        .custom_css_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .and_then(|path| match read_stylesheet(path) {
+            Ok(css) => Some(css),
+            Err(e) => {
+                eprintln!("[Theme] {}", e);
+                None
+            }
+        });
+
+    LoadedTheme { theme: theme.clone(), custom_css }
+}
+
+fn read_stylesheet(path: &str) -> Result<String, String> {
+    let path = runner::expand_home(path);
+    let size = std::fs::metadata(&path)
+        .map_err(|e| format!("Can't read custom CSS '{}': {}", path.display(), e))?
+        .len();
+    if size > MAX_CUSTOM_CSS_BYTES {
+        return Err(format!("Custom CSS '{}' is too large ({} bytes)", path.display(), size));
+    }
+    std::fs::read_to_string(&path).map_err(|e| format!("Can't read custom CSS '{}': {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn the_default_theme_is_valid() {
+        assert!(Theme::default().validate().is_ok());
+    }
+
+    #[test]
+    fn css_values_are_checked() {
+        let with = |change: fn(&mut Theme)| {
+            let mut theme = Theme::default();
+            change(&mut theme);
+            theme.validate()
+        };
+        assert!(with(|t| t.accent = "#abc".to_string()).is_ok());
+        assert!(with(|t| t.accent = "blue".to_string()).is_err());
+        assert!(with(|t| t.accent = "#12345g".to_string()).is_err());
+        assert!(with(|t| t.background_opacity = 0.1).is_err());
+        assert!(with(|t| t.background_opacity = 1.5).is_err());
+        assert!(with(|t| t.window_opacity = 0.8).is_ok());
+        assert!(with(|t| t.window_opacity = 0.3).is_err());
+        assert!(with(|t| t.corner_radius = MAX_CORNER_RADIUS + 1).is_err());
+        assert!(with(|t| t.font_family = "Cascadia Code, monospace".to_string()).is_ok());
+        assert!(with(|t| t.font_family = "x; } body { display: none".to_string()).is_err());
+    }
+
+    #[test]
+    fn the_taskbar_is_dark_unless_windows_says_light() {
+        use crate::os::memory::MemoryRegistry;
+
+        assert!(!taskbar_is_light(&MemoryRegistry::new()));
+        assert!(taskbar_is_light(&MemoryRegistry::new().with_dword(PERSONALIZE_KEY, "SystemUsesLightTheme", 1)));
+        assert!(!taskbar_is_light(&MemoryRegistry::new().with_dword(PERSONALIZE_KEY, "SystemUsesLightTheme", 0)));
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let theme: Theme = serde_json::from_value(json!({ "mode": "light", "corner_radius": 0 })).unwrap();
+        assert_eq!(theme.mode, ThemeMode::Light);
+        assert_eq!(theme.corner_radius, 0);
+        assert_eq!(theme.accent, Theme::default().accent);
+        assert_eq!(theme.backdrop, Backdrop::None);
+    }
+
+    #[test]
+    fn backdrops_use_their_lowercase_names() {
+        let theme: Theme = serde_json::from_value(json!({ "backdrop": "acrylic" })).unwrap();
+        assert_eq!(theme.backdrop, Backdrop::Acrylic);
+        assert!(serde_json::from_value::<Theme>(json!({ "backdrop": "glass" })).is_err());
+    }
+
+    #[test]
+    fn a_missing_stylesheet_loads_without_custom_css() {
+        let theme = Theme { custom_css_path: Some("Z:\\no\\such\\theme.css".to_string()), ..Theme::default() };
+        assert_eq!(load(&theme).custom_css, None);
+        assert_eq!(load(&Theme::default()).custom_css, None);
+    }
+}
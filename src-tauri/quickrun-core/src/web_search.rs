@@ -0,0 +1,139 @@
+// web_search.rs - Web search fallback and bang keywords
+//
+// Two ways to search the web from the launcher:
+// - Bangs: the first word picks a site, the rest is the query
+//   ("g rust traits", "yt lofi", "w tauri"; "!g ..." works too). Without
+//   the "!", a bang gives way to anything the input would run on this PC
+//   (an alias, a program on PATH, a Start Menu app), so "gh pr list" still
+//   runs the GitHub CLI while "!gh pr list" searches GitHub
+// - Fallback: when the input doesn't resolve to anything, the launcher
+//   offers "Search the web for ..." using the default search template
+//
+// Bangs are a user-editable keyword → URL template table stored in
+// settings.json ("search_bangs"); "{query}" in the template is replaced with
+// the URL-encoded query. The defaults below are used until the user edits it.
+
+use serde::{Deserialize, Serialize};
+
+/// One bang: keyword → URL template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bang {
+    pub keyword: String,
+    pub template: String,
+    /// Friendly site name for result rows (e.g., "YouTube")
+    #[serde(default)]
+    pub name: String,
+}
+
+/// The built-in bang table
+fn default_bangs() -> Vec<Bang> {
+    [
+        ("g", "Google", "https://www.google.com/search?q={query}"),
+        ("ddg", "DuckDuckGo", "https://duckduckgo.com/?q={query}"),
+        ("yt", "YouTube", "https://www.youtube.com/results?search_query={query}"),
+        ("w", "Wikipedia", "https://en.wikipedia.org/wiki/Special:Search?search={query}"),
+        ("gh", "GitHub", "https://github.com/search?q={query}"),
+        ("crates", "crates.io", "https://crates.io/search?q={query}"),
+    ]
+    .into_iter()
+    .map(|(keyword, name, template)| Bang {
+        keyword: keyword.to_string(),
+        template: template.to_string(),
+        name: name.to_string(),
+    })
+    .collect()
+}
+
+/// Load the bang table (user's table from settings, or the defaults)
+pub fn load_bangs() -> Vec<Bang> {
+    crate::settings::load().search_bangs.unwrap_or_else(default_bangs)
+}
+
+/// Save the bang table, dropping incomplete rows
+pub fn save_bangs(bangs: Vec<Bang>) -> Result<(), String> {
+    let bangs: Vec<Bang> = bangs.into_iter().filter_map(normalize_bang).collect();
+    crate::settings::update(|settings| settings.search_bangs = Some(bangs)).map(|_| ())
+}
+
+/// `bang` as it's saved (trimmed, keyword without "!"), or None if it's
+/// incomplete (no keyword, or no {query} in the URL)
+pub fn normalize_bang(bang: Bang) -> Option<Bang> {
+    let bang = Bang {
+        keyword: bang.keyword.trim().trim_start_matches('!').to_string(),
+        template: bang.template.trim().to_string(),
+        name: bang.name.trim().to_string(),
+    };
+    (!bang.keyword.is_empty() && bang.template.contains("{query}")).then_some(bang)
+}
+
+/// Fill a URL template with the encoded query
+fn fill_template(template: &str, query: &str) -> String {
+    let encoded: String = url::form_urlencoded::byte_serialize(query.as_bytes()).collect();
+    template.replace("{query}", &encoded)
+}
+
+/// If the input starts with a bang keyword and has a query, return
+/// (site name, search URL) - unless the keyword has no "!" and the input
+/// runs something on this PC instead
+pub fn bang_search(input: &str) -> Option<(String, String)> {
+    find_bang(input, &load_bangs(), |input| crate::runner::resolve(input).is_ok())
+}
+
+/// bang_search() with the given bang table, and `runs_locally` telling
+/// whether input would run something
+fn find_bang(input: &str, bangs: &[Bang], runs_locally: impl Fn(&str) -> bool) -> Option<(String, String)> {
+    let (word, query) = input.trim().split_once(char::is_whitespace)?;
+    let keyword = word.trim_start_matches('!');
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+
+    let bang = bangs.iter().find(|b| b.keyword.eq_ignore_ascii_case(keyword))?;
+    if keyword == word && runs_locally(input) {
+        return None;
+    }
+
+    let name = if bang.name.is_empty() { bang.keyword.clone() } else { bang.name.clone() };
+    Some((name, fill_template(&bang.template, query)))
+}
+
+/// The fallback "search the web" URL for `query`
+pub fn search_url(query: &str) -> String {
+    fill_template(&crate::settings::load().web_search_template, query.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bangs_search_their_site() {
+        let found = find_bang("yt lofi beats", &default_bangs(), |_| false);
+        let url = "https://www.youtube.com/results?search_query=lofi+beats";
+        assert_eq!(found, Some(("YouTube".to_string(), url.to_string())));
+        assert_eq!(find_bang("yt", &default_bangs(), |_| false), None);
+        assert_eq!(find_bang("nope lofi", &default_bangs(), |_| false), None);
+    }
+
+    #[test]
+    fn commands_on_this_pc_come_before_bare_bangs() {
+        // gh.exe (the GitHub CLI) is on PATH
+        let gh_on_path = |input: &str| input.starts_with("gh ");
+        assert_eq!(find_bang("gh pr list", &default_bangs(), gh_on_path), None);
+        let (site, _) = find_bang("!gh pr list", &default_bangs(), gh_on_path).unwrap();
+        assert_eq!(site, "GitHub");
+    }
+
+    #[test]
+    fn incomplete_bangs_are_not_saved() {
+        let bang = |keyword: &str, template: &str| Bang {
+            keyword: keyword.to_string(),
+            template: template.to_string(),
+            name: String::new(),
+        };
+        assert_eq!(normalize_bang(bang(" !so ", "https://so.com/?q={query}")).unwrap().keyword, "so");
+        assert_eq!(normalize_bang(bang("!!", "https://so.com/?q={query}")), None);
+        assert_eq!(normalize_bang(bang("so", "https://so.com/")), None);
+    }
+}
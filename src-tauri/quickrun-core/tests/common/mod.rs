@@ -0,0 +1,96 @@
+// tests/common/mod.rs - Synthetic PATH and Start Menu fixture
+//
+// Builds a small fake machine in the temp folder and points the process at
+// it (PATH, PATHEXT, APPDATA, ProgramData, and QuickRun's own folder), so
+// resolution and indexing can be checked against known contents without
+// touching the user's settings, history or index. Shared by the integration tests and
+// the benchmarks (benches/resolver.rs includes this file).
+//
+// Environment variables are process-wide, so the fixture is built once and
+// every test in a binary uses the same one.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Files in the first PATH folder
+pub const BIN_FILES: &[&str] = &["notepad.exe", "node.cmd", "npm.cmd", "build.bat", "readme.txt"];
+
+/// Files in the second PATH folder ("notepad.exe" is shadowed by the first)
+pub const TOOLS_FILES: &[&str] = &["notepad.exe", "code.cmd", "python.exe", "7z.EXE"];
+
+/// Per-user Start Menu shortcuts (relative to Programs)
+pub const USER_SHORTCUTS: &[&str] = &[
+    "Notepad++.lnk",
+    "Visual Studio Code\\Visual Studio Code.lnk",
+    "Visual Studio Code\\Uninstall Visual Studio Code.lnk",
+    "GitHub.url",
+];
+
+/// All-users Start Menu shortcuts ("Notepad++" duplicates the per-user one)
+pub const COMMON_SHORTCUTS: &[&str] = &[
+    "Paint.lnk",
+    "Microsoft Edge.lnk",
+    "Notepad++.lnk",
+    "Accessories\\Snipping Tool.lnk",
+    "Accessories\\desktop.ini",
+];
+
+/// Paths of the fixture folders
+pub struct Fixture {
+    pub root: PathBuf,
+    pub bin: PathBuf,
+    pub tools: PathBuf,
+}
+
+/// Same relative path index.rs uses under APPDATA and ProgramData
+fn programs_dir(base: &Path) -> PathBuf {
+    base.join("Microsoft\\Windows\\Start Menu\\Programs")
+}
+
+/// Create empty files (and their parent folders) under `dir`
+fn touch_all(dir: &Path, names: &[&str]) {
+    for name in names {
+        let path = dir.join(name.replace('\\', std::path::MAIN_SEPARATOR_STR));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"").unwrap();
+    }
+}
+
+/// Build the fixture (once) and point the environment at it
+pub fn fixture() -> &'static Fixture {
+    static FIXTURE: OnceLock<Fixture> = OnceLock::new();
+    FIXTURE.get_or_init(|| {
+        let root = std::env::temp_dir().join(format!("quickrun-fixture-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let bin = root.join("bin");
+        let tools = root.join("tools");
+        touch_all(&bin, BIN_FILES);
+        touch_all(&tools, TOOLS_FILES);
+
+        let appdata = root.join("AppData");
+        let program_data = root.join("ProgramData");
+        touch_all(&programs_dir(&appdata), USER_SHORTCUTS);
+        touch_all(&programs_dir(&program_data), COMMON_SHORTCUTS);
+
+        // Settings, history, plugins and the index cache live in an empty
+        // folder of their own
+        let config = root.join("config");
+        std::fs::create_dir_all(&config).unwrap();
+
+        let path = std::env::join_paths([&bin, &tools]).unwrap();
+        std::env::set_var("PATH", path);
+        std::env::set_var("PATHEXT", ".COM;.EXE;.BAT;.CMD");
+        std::env::set_var("APPDATA", &appdata);
+        std::env::set_var("ProgramData", &program_data);
+        std::env::set_var(quickrun_core::storage::CONFIG_DIR_VAR, &config);
+        // VS Code's recent list, outside Windows (XDG_DATA_HOME)
+        std::env::set_var("XDG_DATA_HOME", &appdata);
+
+        quickrun_core::index::rebuild();
+
+        Fixture { root, bin, tools }
+    })
+}
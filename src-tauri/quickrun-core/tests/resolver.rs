@@ -0,0 +1,139 @@
+// tests/resolver.rs - Golden tests for command resolution and indexing
+//
+// Runs the resolver against the synthetic machine in common/mod.rs. Checks
+// that depend on Windows' case-insensitive file names are Windows-only.
+// Suggestion rankings are checked everywhere else: on Windows, suggestions
+// also list the machine's own windows, games, browser profiles and VS Code
+// projects, which the fixture can't replace.
+
+mod common;
+
+use common::fixture;
+use quickrun_core::{index, ranking, runner, suggestions};
+use tokio_util::sync::CancellationToken;
+
+/// Titles of the suggestion rows for `query` (with a token of its own, so
+/// parallel tests don't cancel each other)
+fn suggested_titles(query: &str) -> Vec<String> {
+    suggestions::suggest(query, &CancellationToken::new())
+        .expect("query was not cancelled")
+        .into_iter()
+        .map(|row| row.title)
+        .collect()
+}
+
+#[test]
+fn explicit_extension_resolves_to_first_path_folder() {
+    let fixture = fixture();
+    assert_eq!(runner::resolve_on_path("python.exe"), Some(fixture.tools.join("python.exe")));
+    assert_eq!(runner::resolve_on_path("node.cmd"), Some(fixture.bin.join("node.cmd")));
+}
+
+#[test]
+fn pathext_is_appended_to_bare_names() {
+    let fixture = fixture();
+    assert_eq!(runner::resolve_on_path("7z"), Some(fixture.tools.join("7z.EXE")));
+}
+
+#[cfg(windows)]
+#[test]
+fn earlier_path_folder_shadows_later_one() {
+    let fixture = fixture();
+    let resolved = runner::resolve_on_path("notepad").expect("notepad resolves");
+    assert_eq!(resolved.parent(), Some(fixture.bin.as_path()));
+}
+
+#[cfg(windows)]
+#[test]
+fn bare_names_try_every_pathext_extension() {
+    let fixture = fixture();
+    assert_eq!(runner::resolve_on_path("build"), Some(fixture.bin.join("build.BAT")));
+    assert_eq!(runner::resolve_on_path("code"), Some(fixture.tools.join("code.CMD")));
+}
+
+#[test]
+fn unknown_commands_and_non_executables_do_not_resolve() {
+    fixture();
+    assert_eq!(runner::resolve_on_path("does-not-exist"), None);
+    assert_eq!(runner::resolve_on_path("readme"), None);
+}
+
+#[test]
+fn path_commands_are_sorted_deduplicated_stems() {
+    fixture();
+    assert_eq!(
+        index::path_commands(),
+        ["7z", "build", "code", "node", "notepad", "npm", "python"]
+    );
+}
+
+#[test]
+fn start_menu_index_merges_both_menus() {
+    fixture();
+    let names: Vec<String> = index::entries().into_iter().map(|app| app.name).collect();
+
+    for expected in ["GitHub", "Microsoft Edge", "Notepad++", "Paint", "Snipping Tool", "Visual Studio Code"] {
+        assert!(names.contains(&expected.to_string()), "{} missing from {:?}", expected, names);
+    }
+    // Duplicates collapse, uninstallers and non-shortcuts are skipped
+    assert_eq!(names.iter().filter(|n| *n == "Notepad++").count(), 1);
+    assert!(!names.iter().any(|n| n.starts_with("Uninstall") || n == "desktop"));
+}
+
+#[test]
+fn duplicate_shortcut_prefers_the_per_user_menu() {
+    let fixture = fixture();
+    let app = index::find("notepad++").expect("Notepad++ is indexed");
+    assert!(app.path.starts_with(fixture.root.join("AppData")), "{:?}", app.path);
+}
+
+#[test]
+fn explicit_paths_are_detected() {
+    assert!(runner::is_explicit_path("C:\\Windows\\notepad.exe"));
+    assert!(runner::is_explicit_path(".\\script.bat"));
+    assert!(runner::is_explicit_path("~\\Downloads"));
+    assert!(!runner::is_explicit_path("notepad"));
+}
+
+#[test]
+fn arguments_split_like_a_command_line() {
+    assert_eq!(runner::split_args("code \"My Project\" -n"), ["code", "My Project", "-n"]);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn suggestions_rank_fixture_apps_and_commands() {
+    fixture();
+    let titles = suggested_titles("note");
+
+    // Both are prefix matches: alphabetical order decides
+    let app = titles.iter().position(|t| t == "Notepad++").expect("app suggested");
+    let command = titles.iter().position(|t| t == "notepad").expect("command suggested");
+    assert!(command < app, "{:?}", titles);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn suggestions_are_deterministic() {
+    fixture();
+    assert_eq!(suggested_titles("o"), suggested_titles("o"));
+}
+
+#[test]
+fn a_new_query_cancels_the_previous_one() {
+    fixture();
+    let stale = suggestions::begin_query();
+    let fresh = suggestions::begin_query();
+    assert!(stale.is_cancelled());
+    assert!(!fresh.is_cancelled());
+    assert!(suggestions::suggest("note", &stale).is_none());
+}
+
+#[test]
+fn match_scores_are_ordered() {
+    let exact = ranking::match_score("notepad", "notepad");
+    let prefix = ranking::match_score("notepad++", "notepad");
+    let word = ranking::match_score("Microsoft Edge", "edge");
+    let substring = ranking::match_score("mspaint", "paint");
+    assert!(exact > prefix && prefix > word && word > substring && substring.is_some());
+}
@@ -0,0 +1,299 @@
+// archive.rs - "Compress to zip here" and "Extract here" file actions
+//
+// Both operations run on a blocking background thread so the launcher stays
+// responsive, the same way the speed test keyword works:
+// - The action immediately returns a placeholder row ("Compressing...")
+// - Progress is reported with JobProgress events (see events.rs)
+// - The final row arrives as a JobFinished event
+//
+// Selecting the placeholder row cancels the operation. A cancelled or failed
+// operation removes whatever it had written so far - a half-written zip or a
+// half-extracted folder is worse than nothing.
+
+use crate::events::{self, AppEvent, Job};
+use crate::results::{Action, ResultRow};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+/// Only one archive operation at a time (they share the cancel flag)
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Set by cancel(), checked between chunks
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Copy buffer size; progress and cancellation are checked once per chunk
+const CHUNK_BYTES: usize = 256 * 1024;
+
+/// Tracks bytes processed and emits a progress event when the percentage changes
+struct Reporter<'a> {
+    app: &'a AppHandle,
+    total: u64,
+    done: u64,
+    last_percent: u8,
+}
+
+impl Reporter<'_> {
+    fn advance(&mut self, bytes: u64, current: &str) {
+        self.done += bytes;
+        let percent = if self.total == 0 {
+            100
+        } else {
+            ((self.done * 100) / self.total).min(100) as u8
+        };
+        if percent != self.last_percent {
+            self.last_percent = percent;
+            // The detail is the entry being processed (relative path)
+            events::emit(
+                self.app,
+                AppEvent::JobProgress { job: Job::Archive, percent, detail: current.to_string() },
+            );
+        }
+    }
+}
+
+/// Copy `reader` into `writer` in chunks, reporting progress and honouring
+/// cancellation
+fn copy_chunked(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    reporter: &mut Reporter,
+    current: &str,
+) -> Result<(), String> {
+    let mut buffer = vec![0u8; CHUNK_BYTES];
+    loop {
+        if CANCELLED.load(Ordering::SeqCst) {
+            return Err("Cancelled".to_string());
+        }
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read '{}': {}", current, e))?;
+        if read == 0 {
+            return Ok(());
+        }
+        writer
+            .write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to write '{}': {}", current, e))?;
+        reporter.advance(read as u64, current);
+    }
+}
+
+/// `dir\name.ext`, or `dir\name (2).ext` etc. if that already exists
+fn unique_path(dir: &Path, name: &str, extension: Option<&str>) -> PathBuf {
+    let file_name = |suffix: String| match extension {
+        Some(ext) => format!("{}{}.{}", name, suffix, ext),
+        None => format!("{}{}", name, suffix),
+    };
+
+    let mut candidate = dir.join(file_name(String::new()));
+    let mut n = 2;
+    while candidate.exists() {
+        candidate = dir.join(file_name(format!(" ({})", n)));
+        n += 1;
+    }
+    candidate
+}
+
+/// Every file and folder under `root` as (path, name inside the zip), plus
+/// the total size of the files
+fn collect_entries(root: &Path) -> Result<(Vec<(PathBuf, String)>, u64), String> {
+    let base = root.parent().unwrap_or(root);
+    let mut entries = Vec::new();
+    let mut total = 0;
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(path) = pending.pop() {
+        let metadata = std::fs::metadata(&path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+
+        // Zip entry names always use forward slashes
+        let name = path
+            .strip_prefix(base)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        if metadata.is_dir() {
+            let children = std::fs::read_dir(&path)
+                .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+            pending.extend(children.flatten().map(|child| child.path()));
+            entries.push((path, format!("{}/", name)));
+        } else {
+            total += metadata.len();
+            entries.push((path, name));
+        }
+    }
+
+    Ok((entries, total))
+}
+
+/// Write `source` (file or folder) into a new zip at `destination`
+fn compress_to(app: &AppHandle, source: &Path, destination: &Path) -> Result<(), String> {
+    let (entries, total) = collect_entries(source)?;
+    let mut reporter = Reporter { app, total, done: 0, last_percent: 0 };
+
+    let file = File::create(destination)
+        .map_err(|e| format!("Failed to create '{}': {}", destination.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(total > u32::MAX as u64);
+
+    for (path, name) in entries {
+        if name.ends_with('/') {
+            zip.add_directory(name.as_str(), options)
+                .map_err(|e| format!("Failed to add '{}': {}", name, e))?;
+            continue;
+        }
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add '{}': {}", name, e))?;
+        let mut input = File::open(&path)
+            .map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+        copy_chunked(&mut input, &mut zip, &mut reporter, &name)?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish '{}': {}", destination.display(), e))?;
+    Ok(())
+}
+
+/// Extract the zip at `source` into the (new) folder `destination`
+fn extract_to(app: &AppHandle, source: &Path, destination: &Path) -> Result<(), String> {
+    let file = File::open(source)
+        .map_err(|e| format!("Failed to open '{}': {}", source.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("'{}' is not a valid zip file: {}", source.display(), e))?;
+
+    let total = (0..archive.len())
+        .filter_map(|i| archive.by_index_raw(i).ok().map(|entry| entry.size()))
+        .sum();
+    let mut reporter = Reporter { app, total, done: 0, last_percent: 0 };
+
+    std::fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create '{}': {}", destination.display(), e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read zip entry: {}", e))?;
+
+        // enclosed_name() rejects absolute paths and "..", so a malicious
+        // zip can't write outside the destination folder
+        let Some(relative) = entry.enclosed_name() else {
+            eprintln!("[Archive] Skipping unsafe entry: {}", entry.name());
+            continue;
+        };
+        let target = destination.join(relative);
+        let name = entry.name().to_string();
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create '{}': {}", target.display(), e))?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+        let mut output = File::create(&target)
+            .map_err(|e| format!("Failed to create '{}': {}", target.display(), e))?;
+        copy_chunked(&mut entry, &mut output, &mut reporter, &name)?;
+    }
+
+    Ok(())
+}
+
+/// Run `operation` in the background, then emit the final row
+///
+/// `destination` is removed if the operation fails or is cancelled.
+fn run_in_background(
+    app: &AppHandle,
+    destination: PathBuf,
+    placeholder: ResultRow,
+    done_title: String,
+    operation: impl FnOnce(&AppHandle, &Path) -> Result<(), String> + Send + 'static,
+) -> Result<Vec<ResultRow>, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Another archive operation is already running".to_string());
+    }
+    CANCELLED.store(false, Ordering::SeqCst);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let row = match operation(&app, &destination) {
+            Ok(()) => {
+                let target = destination.to_string_lossy().to_string();
+                ResultRow::new(done_title, target.clone()).with_action(Action::Reveal(target))
+            }
+            Err(e) => {
+                // Clean up the partial output
+                if destination.is_dir() {
+                    let _ = std::fs::remove_dir_all(&destination);
+                } else {
+                    let _ = std::fs::remove_file(&destination);
+                }
+                eprintln!("[Archive] {}", e);
+                if CANCELLED.load(Ordering::SeqCst) {
+                    ResultRow::new("Cancelled", "Partial output was removed")
+                } else {
+                    ResultRow::new(e, "Archive operation failed")
+                }
+            }
+        };
+        RUNNING.store(false, Ordering::SeqCst);
+        events::emit(&app, AppEvent::JobFinished { job: Job::Archive, rows: vec![row] });
+    });
+
+    Ok(vec![placeholder])
+}
+
+/// Start compressing `path` (file or folder) into a zip next to it
+pub fn compress(app: &AppHandle, path: &str) -> Result<Vec<ResultRow>, String> {
+    let source = PathBuf::from(path);
+    if !source.exists() {
+        return Err(format!("File not found: {}", path));
+    }
+    let dir = source.parent().ok_or_else(|| format!("Can't compress '{}'", path))?;
+    let name = source.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let destination = unique_path(dir, &name, Some("zip"));
+
+    eprintln!("[Archive] Compressing {} → {}", path, destination.display());
+
+    let placeholder = ResultRow::new(format!("Compressing {}...", name), "Select to cancel")
+        .with_action(Action::CancelArchive);
+    let done_title = format!("Created {}", destination.file_name().unwrap_or_default().to_string_lossy());
+
+    run_in_background(app, destination, placeholder, done_title, move |app, destination| {
+        compress_to(app, &source, destination)
+    })
+}
+
+/// Start extracting the zip at `path` into a new folder next to it
+pub fn extract(app: &AppHandle, path: &str) -> Result<Vec<ResultRow>, String> {
+    let source = PathBuf::from(path);
+    if !source.is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+    let dir = source.parent().ok_or_else(|| format!("Can't extract '{}'", path))?;
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let destination = unique_path(dir, &stem, None);
+
+    eprintln!("[Archive] Extracting {} → {}", path, destination.display());
+
+    let placeholder = ResultRow::new(format!("Extracting {}...", stem), "Select to cancel")
+        .with_action(Action::CancelArchive);
+    let done_title = format!("Extracted to {}", destination.file_name().unwrap_or_default().to_string_lossy());
+
+    run_in_background(app, destination, placeholder, done_title, move |app, destination| {
+        extract_to(app, &source, destination)
+    })
+}
+
+/// Cancel the running archive operation (if any)
+pub fn cancel() {
+    if RUNNING.load(Ordering::SeqCst) {
+        CANCELLED.store(true, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,187 @@
+// authenticode.rs - Check who signed an executable
+//
+// The updater runs an installer it downloaded, so before doing that it
+// makes sure the file carries a valid Authenticode signature and that the
+// certificate was issued to QuickRun's publisher. The signature is checked
+// by WinVerifyTrust, the same check Windows makes before running a
+// downloaded program: the file must be unmodified since signing, and the
+// certificate must chain to a trusted root and not be revoked. The
+// publisher is the certificate's subject name, as shown under Properties >
+// Digital Signatures.
+//
+// An installer dropped on the update window is for a machine that can't
+// reach GitHub, which likely can't reach the certificate's revocation lists
+// either: it's checked against those Windows has cached, and if there are
+// none, revocation isn't held against it (see verify_publisher_offline).
+
+use std::path::Path;
+
+/// Make sure `path` has a valid signature from `publisher`
+pub fn verify_publisher(path: &Path, publisher: &str) -> Result<(), String> {
+    check_publisher(path, publisher, false)
+}
+
+/// verify_publisher() without going online: revocation is checked against
+/// what's cached, and only if anything is
+pub fn verify_publisher_offline(path: &Path, publisher: &str) -> Result<(), String> {
+    check_publisher(path, publisher, true)
+}
+
+fn check_publisher(path: &Path, publisher: &str, offline: bool) -> Result<(), String> {
+    let signer = signer(path, offline)?;
+    if !signer.trim().eq_ignore_ascii_case(publisher) {
+        return Err(format!(
+            "{} is signed by \"{}\", not by {}",
+            file_name(path),
+            signer,
+            publisher
+        ));
+    }
+    eprintln!("[Authenticode] Signed by {}: {}", signer, path.display());
+    Ok(())
+}
+
+/// The name of whoever signed `path`, if its signature is valid
+#[cfg(windows)]
+fn signer(path: &Path, offline: bool) -> Result<String, String> {
+    use windows::Win32::Security::WinTrust::{WTD_CACHE_ONLY_URL_RETRIEVAL, WTD_REVOKE_NONE, WTD_REVOKE_WHOLECHAIN};
+    const CERT_E_REVOCATION_FAILURE: i32 = 0x800B010E_u32 as i32;
+
+    let flags = if offline { WTD_CACHE_ONLY_URL_RETRIEVAL } else { Default::default() };
+    let verified = match unsafe { verify_trust(path, WTD_REVOKE_WHOLECHAIN, flags) } {
+        Err(CERT_E_REVOCATION_FAILURE) if offline => {
+            eprintln!("[Authenticode] Nothing cached to check revocation with: {}", path.display());
+            unsafe { verify_trust(path, WTD_REVOKE_NONE, flags) }
+        }
+        verified => verified,
+    };
+    verified
+        .map_err(|status| describe_failure(&file_name(path), status))?
+        .ok_or_else(|| format!("Failed to read the certificate {} is signed with", file_name(path)))
+}
+
+/// Verify `path`'s signature with WinVerifyTrust, checking revocation as
+/// `revocation` says (and `flags` allow), and read who signed it. A failure
+/// is WinVerifyTrust's status.
+#[cfg(windows)]
+unsafe fn verify_trust(
+    path: &Path,
+    revocation: windows::Win32::Security::WinTrust::WINTRUST_DATA_REVOCATION_CHECKS,
+    flags: windows::Win32::Security::WinTrust::WINTRUST_DATA_PROVIDER_FLAGS,
+) -> Result<Option<String>, i32> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::{GUID, PCWSTR};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Security::WinTrust::{
+        WinVerifyTrust, WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_DATA_0, WINTRUST_FILE_INFO,
+        WTD_CHOICE_FILE, WTD_STATEACTION_CLOSE, WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+    };
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut file = WINTRUST_FILE_INFO {
+        cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: PCWSTR(wide.as_ptr()),
+        ..Default::default()
+    };
+    let mut data = WINTRUST_DATA {
+        cbStruct: std::mem::size_of::<WINTRUST_DATA>() as u32,
+        dwUIChoice: WTD_UI_NONE,
+        fdwRevocationChecks: revocation,
+        dwUnionChoice: WTD_CHOICE_FILE,
+        Anonymous: WINTRUST_DATA_0 { pFile: &mut file },
+        // Keep the verified signature around to read its certificate
+        dwStateAction: WTD_STATEACTION_VERIFY,
+        dwProvFlags: flags,
+        ..Default::default()
+    };
+    let mut action: GUID = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+
+    let status = WinVerifyTrust(HWND::default(), &mut action, &mut data as *mut _ as *mut _);
+    let signer = if status == 0 { signer_name(data.hWVTStateData) } else { None };
+
+    data.dwStateAction = WTD_STATEACTION_CLOSE;
+    WinVerifyTrust(HWND::default(), &mut action, &mut data as *mut _ as *mut _);
+
+    if status != 0 {
+        return Err(status);
+    }
+    Ok(signer)
+}
+
+/// The subject name of the first signer's certificate in a verified
+/// WinVerifyTrust state
+#[cfg(windows)]
+unsafe fn signer_name(state: windows::Win32::Foundation::HANDLE) -> Option<String> {
+    use windows::Win32::Security::Cryptography::{CertGetNameStringW, CERT_NAME_SIMPLE_DISPLAY_TYPE};
+    use windows::Win32::Security::WinTrust::{WTHelperGetProvSignerFromChain, WTHelperProvDataFromStateData};
+
+    let provider = WTHelperProvDataFromStateData(state);
+    if provider.is_null() {
+        return None;
+    }
+    let signer = WTHelperGetProvSignerFromChain(provider, 0, false, 0);
+    if signer.is_null() || (*signer).csCertChain == 0 || (*signer).pasCertChain.is_null() {
+        return None;
+    }
+    let certificate = (*(*signer).pasCertChain).pCert;
+
+    let mut name = [0u16; 256];
+    let len = CertGetNameStringW(certificate, CERT_NAME_SIMPLE_DISPLAY_TYPE, 0, None, Some(&mut name));
+    // The length includes the terminating NUL
+    (len > 1).then(|| String::from_utf16_lossy(&name[..len as usize - 1]))
+}
+
+#[cfg(not(windows))]
+fn signer(_path: &Path, _offline: bool) -> Result<String, String> {
+    Err("Checking signatures is only supported on Windows".to_string())
+}
+
+/// What a failed WinVerifyTrust status means, for the user
+fn describe_failure(file: &str, status: i32) -> String {
+    const TRUST_E_NOSIGNATURE: u32 = 0x800B0100;
+    const TRUST_E_BAD_DIGEST: u32 = 0x80096010;
+    const TRUST_E_EXPLICIT_DISTRUST: u32 = 0x800B0111;
+    const CERT_E_EXPIRED: u32 = 0x800B0101;
+    const CERT_E_REVOKED: u32 = 0x800B010C;
+    const CERT_E_UNTRUSTEDROOT: u32 = 0x800B0109;
+    const CERT_E_REVOCATION_FAILURE: u32 = 0x800B010E;
+
+    match status as u32 {
+        TRUST_E_NOSIGNATURE => format!("{} isn't signed", file),
+        TRUST_E_BAD_DIGEST => format!("{} was modified after it was signed", file),
+        TRUST_E_EXPLICIT_DISTRUST | CERT_E_REVOKED => format!("{} is signed with a revoked certificate", file),
+        CERT_E_EXPIRED => format!("{} is signed with an expired certificate", file),
+        CERT_E_UNTRUSTEDROOT => format!("{} is signed with an untrusted certificate", file),
+        CERT_E_REVOCATION_FAILURE => {
+            format!("Couldn't check whether the certificate {} is signed with was revoked (offline?)", file)
+        }
+        code => format!("{}'s signature isn't valid (error 0x{:08X})", file, code),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_explained() {
+        assert_eq!(describe_failure("setup.exe", 0x800B0100_u32 as i32), "setup.exe isn't signed");
+        assert_eq!(describe_failure("setup.exe", 0x80096010_u32 as i32), "setup.exe was modified after it was signed");
+        assert_eq!(
+            describe_failure("setup.exe", 0x80070005_u32 as i32),
+            "setup.exe's signature isn't valid (error 0x80070005)"
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn signatures_are_only_checked_on_windows() {
+        assert!(verify_publisher(Path::new("setup.exe"), "Swatto").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+// backdrop.rs - Acrylic / Mica behind the launcher
+//
+// The launcher window is transparent. With "backdrop" set in the theme (see
+// quickrun_core::theme), Windows 11 draws a blurred backdrop behind it
+// through DWM's system backdrop attribute, and the launcher's own background
+// becomes more see-through to let it show (data-backdrop in styles.css).
+//
+// Windows 10 and early Windows 11 builds refuse the attribute, and the
+// resource policy turns blur off for reduced effects and battery saver; in
+// both cases the launcher keeps plain transparency. The backdrop actually
+// in use is reported with a BackdropChanged event, so the launcher only
+// thins its background when there is something behind it.
+
+use crate::events::{self, AppEvent};
+use crate::settings_state::SettingsState;
+use quickrun_core::resource_policy;
+use quickrun_core::theme::{Backdrop, ThemeMode};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+/// The backdrop in use on the launcher
+static ACTIVE: Mutex<Backdrop> = Mutex::new(Backdrop::None);
+
+/// The backdrop in use on the launcher
+pub fn active() -> Backdrop {
+    ACTIVE.lock().map(|active| *active).unwrap_or_default()
+}
+
+/// Give the launcher the backdrop the theme asks for, if the resource policy
+/// and Windows allow it (call at startup and when either changes)
+pub fn apply(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let theme = app.state::<SettingsState>().get().theme;
+    let wanted = if resource_policy::current().blur { theme.backdrop } else { Backdrop::None };
+
+    let backdrop = match set_backdrop(&window, wanted, theme.mode) {
+        Ok(()) => wanted,
+        Err(e) => {
+            if wanted != Backdrop::None {
+                eprintln!("[Backdrop] {}", e);
+                // Clear whatever was set before, so the fallback is plain transparency
+                let _ = set_backdrop(&window, Backdrop::None, theme.mode);
+            }
+            Backdrop::None
+        }
+    };
+
+    let Ok(mut active) = ACTIVE.lock() else {
+        return;
+    };
+    if *active != backdrop {
+        *active = backdrop;
+        events::emit(app, AppEvent::BackdropChanged { backdrop });
+    }
+}
+
+#[cfg(windows)]
+fn set_backdrop<R: Runtime>(window: &WebviewWindow<R>, backdrop: Backdrop, mode: ThemeMode) -> Result<(), String> {
+    use std::ffi::c_void;
+    use windows::core::BOOL;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Dwm::{
+        DwmSetWindowAttribute, DWMSBT_MAINWINDOW, DWMSBT_NONE, DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE,
+        DWMWA_USE_IMMERSIVE_DARK_MODE, DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_DEFAULT, DWMWCP_ROUND,
+    };
+
+    let hwnd = HWND(window.hwnd().map_err(|e| format!("Failed to get the launcher window: {}", e))?.0);
+    let (kind, corners) = match backdrop {
+        Backdrop::None => (DWMSBT_NONE, DWMWCP_DEFAULT),
+        Backdrop::Acrylic => (DWMSBT_TRANSIENTWINDOW, DWMWCP_ROUND),
+        Backdrop::Mica => (DWMSBT_MAINWINDOW, DWMWCP_ROUND),
+    };
+    // The backdrop's tint follows the window's dark mode
+    let dark = BOOL::from(mode == ThemeMode::Dark);
+
+    unsafe {
+        let _ = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_USE_IMMERSIVE_DARK_MODE,
+            &dark as *const BOOL as *const c_void,
+            std::mem::size_of::<BOOL>() as u32,
+        );
+        // The backdrop fills the whole window; Windows rounds its corners
+        let _ = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_WINDOW_CORNER_PREFERENCE,
+            &corners as *const _ as *const c_void,
+            std::mem::size_of_val(&corners) as u32,
+        );
+        DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_SYSTEMBACKDROP_TYPE,
+            &kind as *const _ as *const c_void,
+            std::mem::size_of_val(&kind) as u32,
+        )
+    }
+    .map_err(|e| format!("This version of Windows has no {:?} backdrop: {}", backdrop, e))
+}
+
+#[cfg(not(windows))]
+fn set_backdrop<R: Runtime>(_window: &WebviewWindow<R>, backdrop: Backdrop, _mode: ThemeMode) -> Result<(), String> {
+    match backdrop {
+        Backdrop::None => Ok(()),
+        _ => Err(format!("The {:?} backdrop needs Windows 11", backdrop)),
+    }
+}
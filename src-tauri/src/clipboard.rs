@@ -0,0 +1,15 @@
+// clipboard.rs - Clipboard access
+//
+// Thin wrapper around the arboard crate so result rows can copy values
+// (IP addresses, URLs, header values) without the frontend needing
+// clipboard permissions.
+
+/// Copy `text` to the system clipboard
+pub fn copy_text(text: &str) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| format!("Failed to open clipboard: {}", e))?;
+
+    clipboard
+        .set_text(text.to_string())
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))
+}
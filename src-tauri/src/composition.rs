@@ -0,0 +1,56 @@
+// composition.rs - IME composition tracking
+//
+// Chinese, Japanese and Korean input goes through an IME: the user types
+// phonetic keys, the IME shows candidate text, and only the committed text
+// is what they meant. Every keystroke of that half-composed text fires an
+// "input" event, which would send a burst of meaningless suggestion queries.
+//
+// The frontend reports compositionstart/compositionend here. While the
+// setting "ime_wait_for_commit" is on (the default), queries are held back
+// until the composition commits:
+// - The frontend skips suggest/complete for input events marked isComposing
+// - On commit, the backend waits "ime_commit_delay_ms" (default 150) before
+//   announcing it, because many IMEs end one composition and immediately
+//   start the next (e.g., Japanese phrase-by-phrase conversion); only a
+//   commit that stays committed is worth a query
+// - An ImeComposition event (see events.rs) tells the launcher when to query
+//   again
+
+use crate::events::{self, AppEvent};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Whether an IME composition is in progress
+static COMPOSING: AtomicBool = AtomicBool::new(false);
+
+/// Bumped on every change, so a delayed commit notice can tell it's stale
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Whether an IME composition is in progress
+pub fn is_composing() -> bool {
+    COMPOSING.load(Ordering::SeqCst)
+}
+
+/// Record a composition start or end reported by the frontend
+///
+/// A start is announced right away; an end only after the commit delay, and
+/// only if no new composition started in the meantime.
+pub fn set_composing(app: &AppHandle, composing: bool) {
+    COMPOSING.store(composing, Ordering::SeqCst);
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    if composing {
+        events::emit(app, AppEvent::ImeComposition { composing: true });
+        return;
+    }
+
+    let delay = quickrun_core::settings::load().ime_commit_delay_ms;
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(delay));
+        if GENERATION.load(Ordering::SeqCst) == generation && !is_composing() {
+            events::emit(&app, AppEvent::ImeComposition { composing: false });
+        }
+    });
+}
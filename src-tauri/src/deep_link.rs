@@ -0,0 +1,93 @@
+// deep_link.rs - quickrun:// links
+//
+// With "deep_links" on in Settings (off by default), QuickRun registers the
+// quickrun:// scheme for the current user through the deep-link plugin, so
+// a web page, a shortcut or another app can open
+//
+//   quickrun://run?cmd=notepad%20todo.txt   Run a command
+//   quickrun://show                         Open the launcher
+//
+// (the full list is in quickrun_core::launch_args). Windows starts
+// quickrun.exe with the link as its argument, and the single-instance
+// plugin hands it to the running copy, which gets it in handle_args.
+//
+// Anything can open a link, so a run request asks before running, and links
+// are ignored while the setting is off (e.g., a registration left behind by
+// a copy that was moved). A portable copy doesn't write to the registry, so
+// it never registers the scheme.
+
+use crate::settings_state::SettingsState;
+use quickrun_core::{i18n, storage};
+use quickrun_core::launch_args::{self, Request};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// Register or unregister the scheme to match "deep_links" (call at startup,
+/// so the registration follows the exe if it moved, and when it changes)
+pub fn apply(app: &AppHandle, enabled: bool) {
+    if storage::is_portable() {
+        eprintln!("[DeepLink] Portable mode, leaving the quickrun:// scheme unregistered");
+        return;
+    }
+    let result = if enabled {
+        app.deep_link().register(launch_args::URL_SCHEME)
+    } else if app.deep_link().is_registered(launch_args::URL_SCHEME).unwrap_or(false) {
+        app.deep_link().unregister(launch_args::URL_SCHEME)
+    } else {
+        Ok(())
+    };
+    if let Err(e) = result {
+        let title = i18n::text("notify.deep_link_failed");
+        crate::notify::error(app, &title, &e.to_string());
+    }
+}
+
+/// Do what `link` asks for (if links are on)
+pub fn open(app: &AppHandle, link: &str) {
+    if !app.state::<SettingsState>().get().deep_links {
+        eprintln!("[DeepLink] Links are off, ignoring {}", link);
+        return;
+    }
+    let request = match launch_args::parse_url(link) {
+        Ok(request) => request,
+        Err(e) => {
+            crate::notify::error(app, &i18n::text("notify.bad_arguments"), &e);
+            return;
+        }
+    };
+
+    match request {
+        // The prompt blocks, so keep it off the thread that delivered the link
+        Request::Run(command) => {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                if confirm_run(&command) {
+                    crate::handle_request(&app, &Request::Run(command));
+                } else {
+                    eprintln!("[DeepLink] Not running '{}'", command);
+                }
+            });
+        }
+        request => crate::handle_request(app, &request),
+    }
+}
+
+/// Ask whether to run `command`, which came from a link
+#[cfg(windows)]
+fn confirm_run(command: &str) -> bool {
+    use windows::core::HSTRING;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDYES, MB_DEFBUTTON2, MB_ICONWARNING, MB_SETFOREGROUND, MB_TOPMOST, MB_YESNO,
+    };
+
+    let title = i18n::text("deep_link.confirm.title");
+    let body = i18n::format("deep_link.confirm.body", &[command]);
+    let style = MB_YESNO | MB_ICONWARNING | MB_DEFBUTTON2 | MB_TOPMOST | MB_SETFOREGROUND;
+    unsafe { MessageBoxW(None, &HSTRING::from(body), &HSTRING::from(title), style) == IDYES }
+}
+
+/// Other platforms: no prompt to ask with, so never run
+#[cfg(not(windows))]
+fn confirm_run(_command: &str) -> bool {
+    false
+}
@@ -0,0 +1,212 @@
+// default_apps.rs - Preferred terminal, editor, and browser
+//
+// Features that open "a terminal", "an editor", or "a browser" go through
+// this module instead of hard-coding cmd/notepad/the system default, so the
+// user's choice in Settings applies everywhere:
+// - open_terminal: `>` prefix, ssh hosts, "terminal here" style actions
+// - open_terminal_elevated: the `admin` keyword
+// - open_in_editor: opening files/projects for editing
+// - open_url: web results, release pages, bookmarks
+//
+// Settings (settings.json), each optional:
+// - preferred_terminal: "wt", "pwsh", "powershell", "cmd", or a path (default: cmd)
+// - preferred_editor: e.g. "code" or a path (default: notepad)
+// - preferred_browser: e.g. "firefox" or a path (default: system default browser)
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEFAULT_TERMINAL: &str = "cmd";
+const DEFAULT_EDITOR: &str = "notepad";
+
+/// The three preferences, as shown in the Settings window
+///
+/// Empty strings mean "use the default".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefaultApps {
+    pub terminal: String,
+    pub editor: String,
+    pub browser: String,
+}
+
+/// Load the current preferences
+pub fn load() -> DefaultApps {
+    let settings = quickrun_core::settings::load();
+    DefaultApps {
+        terminal: settings.preferred_terminal,
+        editor: settings.preferred_editor,
+        browser: settings.preferred_browser,
+    }
+}
+
+/// Save the preferences (whitespace is trimmed, empty = default)
+pub fn save(apps: &DefaultApps) -> Result<(), String> {
+    quickrun_core::settings::update(|settings| {
+        settings.preferred_terminal = apps.terminal.trim().to_string();
+        settings.preferred_editor = apps.editor.trim().to_string();
+        settings.preferred_browser = apps.browser.trim().to_string();
+    })
+    .map(|_| ())
+}
+
+/// Resolve a preference to an executable path (falls back to the name itself
+/// and lets the OS search for it)
+fn resolve(program: &str) -> PathBuf {
+    let path = crate::runner::expand_home(program);
+    if crate::runner::is_explicit_path(program) {
+        return path;
+    }
+    crate::runner::resolve_on_path(program).unwrap_or(path)
+}
+
+/// Lowercase file stem of a program ("C:\...\pwsh.exe" → "pwsh")
+fn program_kind(program: &Path) -> String {
+    program
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Spawn a console program in its own new console window
+fn spawn_console(command: &mut Command) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+        command.creation_flags(CREATE_NEW_CONSOLE);
+    }
+
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open terminal: {}", e))
+}
+
+/// Open the preferred terminal, optionally in `dir` and running `command`
+///
+/// The terminal stays open after the command finishes so output can be read.
+pub fn open_terminal(dir: Option<&Path>, command: Option<&str>) -> Result<(), String> {
+    open_terminal_with_env(dir, command, &[])
+}
+
+/// Open the preferred terminal (see open_terminal) with `environment` set
+/// on it, e.g. from an environment preset
+pub fn open_terminal_with_env(
+    dir: Option<&Path>,
+    command: Option<&str>,
+    environment: &[(String, String)],
+) -> Result<(), String> {
+    let preferred = quickrun_core::settings::load().preferred_terminal;
+    let program = resolve(if preferred.is_empty() { DEFAULT_TERMINAL } else { &preferred });
+
+    let mut cmd = Command::new(&program);
+    cmd.envs(environment.iter().cloned());
+
+    match program_kind(&program).as_str() {
+        // Windows Terminal takes the directory as an argument and runs the
+        // command through cmd in the default profile
+        "wt" => {
+            if let Some(dir) = dir {
+                cmd.arg("-d").arg(dir);
+            }
+            if let Some(command) = command {
+                cmd.args(["cmd", "/K", command]);
+            }
+        }
+        "pwsh" | "powershell" => {
+            cmd.arg("-NoExit");
+            if let Some(command) = command {
+                cmd.args(["-Command", command]);
+            }
+        }
+        // cmd and anything unrecognised: cmd-style /K
+        _ => {
+            if let Some(command) = command {
+                cmd.args(["/K", command]);
+            }
+        }
+    }
+
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+
+    spawn_console(&mut cmd)
+}
+
+/// Open the preferred terminal as administrator (UAC prompt), in `dir` and
+/// optionally running `command`
+///
+/// Windows starts elevated programs in System32, so the folder is passed
+/// to the terminal itself (cd, Set-Location or -d) instead of as the
+/// working directory.
+pub fn open_terminal_elevated(dir: &Path, command: Option<&str>) -> Result<(), String> {
+    let preferred = quickrun_core::settings::load().preferred_terminal;
+    let program = resolve(if preferred.is_empty() { DEFAULT_TERMINAL } else { &preferred });
+    let parameters = elevated_parameters(&program_kind(&program), dir, command);
+
+    crate::runner::spawn_elevated_with_args(&program.to_string_lossy(), &parameters)
+}
+
+/// Command line that makes a terminal of `kind` start in `dir` and run
+/// `command` (see open_terminal_elevated)
+fn elevated_parameters(kind: &str, dir: &Path, command: Option<&str>) -> String {
+    let dir = dir.to_string_lossy();
+    // "C:\" would escape the closing quote
+    let quoted_dir = if dir.ends_with('\\') { format!("\"{}.\"", dir) } else { format!("\"{}\"", dir) };
+
+    match kind {
+        "wt" => match command {
+            Some(command) => format!("-d {} cmd /K {}", quoted_dir, command),
+            None => format!("-d {}", quoted_dir),
+        },
+        "pwsh" | "powershell" => {
+            let mut script = format!("Set-Location -LiteralPath '{}'", dir.replace('\'', "''"));
+            if let Some(command) = command {
+                script.push_str("; ");
+                script.push_str(command);
+            }
+            format!("-NoExit -Command \"{}\"", script.replace('"', "\\\""))
+        }
+        // cmd and anything unrecognised: cmd-style /K
+        _ => match command {
+            Some(command) => format!("/K cd /d {} && {}", quoted_dir, command),
+            None => format!("/K cd /d {}", quoted_dir),
+        },
+    }
+}
+
+/// Open `path` in the preferred editor
+pub fn open_in_editor(path: &Path) -> Result<(), String> {
+    let preferred = quickrun_core::settings::load().preferred_editor;
+    let program = resolve(if preferred.is_empty() { DEFAULT_EDITOR } else { &preferred });
+
+    Command::new(&program)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open editor '{}': {}", program.display(), e))
+}
+
+/// Open `url` in the preferred browser, or the system default if none is set
+pub fn open_url(url: &str) -> Result<(), String> {
+    let preferred = quickrun_core::settings::load().preferred_browser;
+    if preferred.is_empty() {
+        return crate::runner::open_with_shell(url);
+    }
+
+    let program = resolve(&preferred);
+    Command::new(&program)
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open browser '{}': {}", program.display(), e))
+}
+
+/// Does `target` look like a web URL (as opposed to a file or folder)?
+pub fn is_url(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
@@ -0,0 +1,62 @@
+// desktops.rs - Keeping the launcher in reach
+//
+// Two settings stop the launcher from getting lost:
+// - "always_on_top": it stays above other windows, fullscreen ones included
+//   (applied at startup and whenever the setting changes)
+// - "all_desktops": with several virtual desktops, it opens on the one the
+//   user is on. Windows shows a window on the desktop it was last on,
+//   switching desktops to do so, and has no public API to pin a window to
+//   all of them - so each time the launcher is shown it's moved to the
+//   desktop of the window that was active (see focus.rs) through
+//   IVirtualDesktopManager.
+
+use crate::focus;
+use quickrun_core::Settings;
+use tauri::{Runtime, WebviewWindow};
+
+/// Apply "always_on_top" to the launcher
+pub fn apply<R: Runtime>(window: &WebviewWindow<R>, settings: &Settings) {
+    if let Err(e) = window.set_always_on_top(settings.always_on_top) {
+        eprintln!("[Desktops] Could not change always-on-top: {}", e);
+    }
+}
+
+/// Move the launcher to the virtual desktop of the window that was active
+/// before it opened (call before showing it)
+#[cfg(windows)]
+pub fn move_to_current<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    use windows::core::GUID;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_APARTMENTTHREADED};
+    use windows::Win32::UI::Shell::{IVirtualDesktopManager, VirtualDesktopManager};
+
+    let previous = HWND(focus::previous_window() as *mut std::ffi::c_void);
+    if previous.is_invalid() {
+        return Ok(());
+    }
+    let launcher = HWND(window.hwnd().map_err(|e| format!("Failed to get the launcher window: {}", e))?.0);
+
+    unsafe {
+        // The shell needs COM on this thread; S_FALSE (already initialized) is fine
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let manager: IVirtualDesktopManager = CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL)
+            .map_err(|e| format!("Failed to open the virtual desktop manager: {}", e))?;
+
+        // The taskbar and the desktop itself are on no desktop in particular
+        let Some(target) = manager.GetWindowDesktopId(previous).ok().filter(|id| *id != GUID::zeroed()) else {
+            return Ok(());
+        };
+        if manager.GetWindowDesktopId(launcher).ok() == Some(target) {
+            return Ok(());
+        }
+        manager
+            .MoveWindowToDesktop(launcher, &target)
+            .map_err(|e| format!("Failed to move the launcher to the current desktop: {}", e))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn move_to_current<R: Runtime>(_window: &WebviewWindow<R>) -> Result<(), String> {
+    Ok(())
+}
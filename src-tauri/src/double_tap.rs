@@ -0,0 +1,237 @@
+// double_tap.rs - Open the launcher by double-tapping a modifier key
+//
+// Some users can't spare a chord, so the launcher can also be opened by
+// tapping Ctrl (or Shift, or Alt) twice in quick succession (setting
+// "double_tap": "ctrl", "shift", "alt", or "" for off - the default).
+//
+// RegisterHotKey can't express "a modifier on its own", so this installs a
+// low-level keyboard hook (WH_KEYBOARD_LL) on a dedicated thread:
+// - A tap is the modifier going down and up within TAP_MAX_MS with no other
+//   key pressed in between (so Ctrl+C, Ctrl+V never count)
+// - Two taps within "double_tap_interval_ms" (default 400) open the launcher
+// - Injected input (e.g., our own Ctrl+V paste) is ignored
+//
+// A low-level hook sits in front of every keystroke on the system, so it is
+// only installed while the feature is on, and the callback does nothing but
+// bookkeeping: the launcher is toggled from the thread's message loop, never
+// from inside the hook.
+
+use std::sync::Arc;
+
+/// Longest press that still counts as a tap
+#[cfg(windows)]
+const TAP_MAX_MS: u32 = 300;
+
+/// Thread id of the hook thread (0 = not started), for reload()
+#[cfg(windows)]
+static THREAD_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+/// Modifiers that can be double-tapped, as stored in the setting
+pub const MODIFIERS: &[&str] = &["ctrl", "shift", "alt"];
+
+/// Validate and normalize a "double_tap" setting value ("" = off)
+pub fn parse(text: &str) -> Result<String, String> {
+    let text = text.trim().to_lowercase();
+    match text.as_str() {
+        "" | "off" | "none" => Ok(String::new()),
+        "control" => Ok("ctrl".to_string()),
+        m if MODIFIERS.contains(&m) => Ok(text),
+        _ => Err(format!("Can't double-tap '{}' - use Ctrl, Shift or Alt", text)),
+    }
+}
+
+/// The configured modifier, or None when double-tap is off
+pub fn configured() -> Option<String> {
+    parse(&quickrun_core::settings::load().double_tap).ok().filter(|m| !m.is_empty())
+}
+
+#[cfg(windows)]
+mod win32 {
+    use super::TAP_MAX_MS;
+    use std::cell::RefCell;
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, PostThreadMessageW, SetWindowsHookExW, UnhookWindowsHookEx,
+        HHOOK, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG, WH_KEYBOARD_LL, WM_APP, WM_KEYDOWN, WM_SYSKEYDOWN,
+    };
+
+    /// Posted by reload() to re-read the setting
+    pub const WM_RELOAD: u32 = WM_APP + 1;
+    /// Posted by the hook when a double tap is detected
+    const WM_DOUBLE_TAP: u32 = WM_APP + 2;
+
+    /// Left/right virtual-key codes for a modifier name
+    fn modifier_keys(modifier: &str) -> [u32; 2] {
+        match modifier {
+            "shift" => [0xA0, 0xA1], // VK_LSHIFT, VK_RSHIFT
+            "alt" => [0xA4, 0xA5],   // VK_LMENU, VK_RMENU
+            _ => [0xA2, 0xA3],       // VK_LCONTROL, VK_RCONTROL
+        }
+    }
+
+    /// Tap detection state (lives on the hook thread)
+    #[derive(Default)]
+    struct Tracker {
+        keys: [u32; 2],
+        interval_ms: u32,
+        /// The modifier is held down
+        down: bool,
+        /// When it went down (hook time, ms)
+        down_at: u32,
+        /// No other key was pressed while it was down
+        clean: bool,
+        /// When the previous tap ended, if it can still be followed by a second
+        last_tap: Option<u32>,
+    }
+
+    impl Tracker {
+        /// Feed one key event; returns true when it completes a double tap
+        fn key(&mut self, vk: u32, is_down: bool, time: u32) -> bool {
+            if !self.keys.contains(&vk) {
+                // Anything else typed in between breaks the sequence
+                if is_down {
+                    self.clean = false;
+                    self.last_tap = None;
+                }
+                return false;
+            }
+
+            if is_down {
+                // Auto-repeat sends more key-downs while held
+                if !self.down {
+                    self.down = true;
+                    self.down_at = time;
+                    self.clean = true;
+                }
+                return false;
+            }
+
+            if !self.down {
+                return false;
+            }
+            self.down = false;
+
+            let is_tap = self.clean && time.wrapping_sub(self.down_at) <= TAP_MAX_MS;
+            if !is_tap {
+                self.last_tap = None;
+                return false;
+            }
+            match self.last_tap {
+                Some(previous) if time.wrapping_sub(previous) <= self.interval_ms => {
+                    self.last_tap = None;
+                    true
+                }
+                _ => {
+                    self.last_tap = Some(time);
+                    false
+                }
+            }
+        }
+    }
+
+    thread_local! {
+        static TRACKER: RefCell<Tracker> = RefCell::new(Tracker::default());
+    }
+
+    /// The low-level keyboard hook; must return quickly
+    unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0 {
+            let event = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+            if !event.flags.contains(LLKHF_INJECTED) {
+                let message = wparam.0 as u32;
+                let is_down = message == WM_KEYDOWN || message == WM_SYSKEYDOWN;
+                let fired = TRACKER.with(|t| t.borrow_mut().key(event.vkCode, is_down, event.time));
+                if fired {
+                    let _ = PostThreadMessageW(GetCurrentThreadId(), WM_DOUBLE_TAP, WPARAM(0), LPARAM(0));
+                }
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    /// Install or remove the hook to match the setting
+    fn apply_setting(hook: &mut Option<HHOOK>) {
+        let modifier = super::configured();
+
+        if let Some(modifier) = &modifier {
+            let interval_ms = quickrun_core::settings::load().double_tap_interval_ms;
+            TRACKER.with(|t| {
+                *t.borrow_mut() = Tracker { keys: modifier_keys(modifier), interval_ms, ..Default::default() };
+            });
+        }
+
+        match (modifier.is_some(), hook.is_some()) {
+            (true, false) => match unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), None, 0) } {
+                Ok(installed) => {
+                    *hook = Some(installed);
+                    eprintln!("[DoubleTap] Keyboard hook installed");
+                }
+                Err(e) => eprintln!("Warning: Could not install keyboard hook: {}", e),
+            },
+            (false, true) => {
+                if let Some(installed) = hook.take() {
+                    let _ = unsafe { UnhookWindowsHookEx(installed) };
+                    eprintln!("[DoubleTap] Keyboard hook removed");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Hook thread: keeps the hook in line with the setting and runs
+    /// `on_double_tap` for each detected double tap
+    pub fn run(on_double_tap: impl Fn()) {
+        let mut hook = None;
+        apply_setting(&mut hook);
+
+        // Low-level hooks are called through this thread's message loop
+        let mut msg = MSG::default();
+        while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+            match msg.message {
+                WM_DOUBLE_TAP => on_double_tap(),
+                WM_RELOAD => apply_setting(&mut hook),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Start the hook thread; `on_double_tap` runs on that thread
+#[cfg(windows)]
+pub fn start(on_double_tap: Arc<dyn Fn() + Send + Sync>) {
+    use std::sync::atomic::Ordering;
+
+    std::thread::spawn(move || {
+        let thread_id = unsafe { windows::Win32::System::Threading::GetCurrentThreadId() };
+        THREAD_ID.store(thread_id, Ordering::SeqCst);
+        win32::run(|| on_double_tap());
+    });
+}
+
+/// Other platforms: no low-level keyboard hook, double-tap is unavailable
+#[cfg(not(windows))]
+pub fn start(_on_double_tap: Arc<dyn Fn() + Send + Sync>) {
+    if configured().is_some() {
+        eprintln!("Warning: Double-tap activation is only supported on Windows");
+    }
+}
+
+/// Re-read the "double_tap" setting (installs or removes the hook)
+#[cfg(windows)]
+pub fn reload() {
+    use std::sync::atomic::Ordering;
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::PostThreadMessageW;
+
+    let thread_id = THREAD_ID.load(Ordering::SeqCst);
+    if thread_id != 0 {
+        unsafe {
+            let _ = PostThreadMessageW(thread_id, win32::WM_RELOAD, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+/// Other platforms: nothing to reload
+#[cfg(not(windows))]
+pub fn reload() {}
@@ -0,0 +1,89 @@
+// events.rs - Events from the backend to the windows
+//
+// Everything the backend tells the windows goes out as a single Tauri event,
+// "app-event", whose payload is an AppEvent tagged by "type":
+//
+//   { "type": "window_shown", "last_input": null }
+//   { "type": "job_progress", "job": "speedtest", "percent": 40, "detail": "download" }
+//
+// The frontend subscribes through src/events.ts, which mirrors this enum as
+// a TypeScript union, so each payload shape is written down once on each
+// side. A new subsystem adds a variant here and a member to that union.
+
+use quickrun_core::index::IndexUpdated;
+use quickrun_core::resource_policy::ResourcePolicy;
+use quickrun_core::results::ResultRow;
+use quickrun_core::theme::{Backdrop, LoadedTheme};
+use quickrun_core::Settings;
+use serde::Serialize;
+use tauri::{Emitter, Runtime};
+
+/// Name of the Tauri event that carries every AppEvent
+const EVENT_NAME: &str = "app-event";
+
+/// Something the windows may want to react to
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AppEvent {
+    /// The launcher was shown (start from a clean slate, or from the last
+    /// input if "remember_last_input" is on)
+    WindowShown { last_input: Option<String> },
+    /// An IME composition started, or its commit settled (see composition.rs)
+    ImeComposition { composing: bool },
+    /// Settings were saved, from the Settings window or by hand
+    SettingsChanged { settings: Settings },
+    /// The theme changed (also announced in SettingsChanged, but this one
+    /// carries the custom stylesheet)
+    ThemeChanged { theme: LoadedTheme },
+    /// Windows' reduced effects or battery saver setting changed
+    ResourcePolicyChanged { policy: ResourcePolicy },
+    /// The launcher's Acrylic/Mica backdrop was turned on or off (see backdrop.rs)
+    BackdropChanged { backdrop: Backdrop },
+    /// The launcher's size changed (see scale.rs)
+    UiScaleChanged { scale: f64 },
+    /// The app index was rebuilt in the background
+    IndexUpdated(IndexUpdated),
+    /// An update install moved on to its next stage
+    UpdateProgress { stage: UpdateStage },
+    /// More of the update installer was downloaded (`total` and `percent`
+    /// are unknown if the server didn't say how big it is)
+    UpdateDownloadProgress { bytes: u64, total: Option<u64>, percent: Option<u8> },
+    /// A background job made progress (`detail` depends on the job)
+    JobProgress { job: Job, percent: u8, detail: String },
+    /// A background job finished; its rows replace the placeholder row
+    JobFinished { job: Job, rows: Vec<ResultRow> },
+}
+
+/// Background jobs started from the launcher, which answer with a
+/// placeholder row and report back through events
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Job {
+    /// "speedtest" (detail: the phase being measured)
+    Speedtest,
+    /// "Compress to zip here" / "Extract here" (detail: the entry being processed)
+    Archive,
+    /// "scaffold <template> <name>" (no progress, only the result)
+    Scaffold,
+}
+
+/// Stages of installing an update (see updater.rs)
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStage {
+    Downloading,
+    InstallerLaunched,
+    /// The installer is running without its wizard; QuickRun exits and is
+    /// started again once it's done
+    InstallingSilently,
+    OpeningReleasePage,
+    /// A portable copy's new program is in place; QuickRun restarts on it
+    Restarting,
+    /// The download was cancelled from the update window
+    Cancelled,
+}
+
+/// Send an event to all windows (failures are ignored - windows may be closed)
+pub fn emit<R: Runtime>(emitter: &impl Emitter<R>, event: AppEvent) {
+    let _ = emitter.emit(EVENT_NAME, event);
+}
@@ -0,0 +1,89 @@
+// explorer.rs - The folder the user was looking at in Explorer
+//
+// When the launcher was opened from an Explorer window, commands work in
+// the folder shown there: `> git status`, `code .`, `terminal here` and
+// `admin`. Explorer windows are listed by
+// the shell's ShellWindows collection; the one whose HWND is the window
+// that was active before the launcher (see focus.rs) is asked for its
+// location, which is a file:// URL for ordinary folders. On Windows 11 the
+// tabs of a window all share its HWND, so the one shown (the window's top
+// ShellTabWindowClass child) is picked among them.
+//
+// The shell is asked from a thread of its own, which enters a
+// single-threaded COM apartment and leaves it again: the caller may be an
+// async runtime worker, which mustn't be left in an apartment it didn't
+// choose.
+//
+// Virtual locations (This PC, Control Panel, libraries) have no path, so
+// they count as "not in a folder".
+
+use crate::focus;
+use std::path::PathBuf;
+
+/// Folder shown in the window that was active before the launcher opened,
+/// if that was an Explorer window on a real folder
+pub fn previous_folder() -> Option<PathBuf> {
+    match focus::previous_window() {
+        0 => None,
+        hwnd => folder_of_window(hwnd),
+    }
+}
+
+#[cfg(windows)]
+fn folder_of_window(hwnd: isize) -> Option<PathBuf> {
+    use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+
+    std::thread::spawn(move || unsafe {
+        if CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_err() {
+            return None;
+        }
+        let folder = shown_folder(hwnd);
+        CoUninitialize();
+        folder
+    })
+    .join()
+    .ok()
+    .flatten()
+}
+
+/// The folder in the tab Explorer window `hwnd` shows (COM must be
+/// initialized on the calling thread)
+#[cfg(windows)]
+unsafe fn shown_folder(hwnd: isize) -> Option<PathBuf> {
+    use windows::core::{w, Interface, PCWSTR, VARIANT};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{CoCreateInstance, IServiceProvider, CLSCTX_ALL};
+    use windows::Win32::UI::Shell::{IShellBrowser, IShellWindows, IWebBrowserApp, ShellWindows, SID_STopLevelBrowser};
+    use windows::Win32::UI::WindowsAndMessaging::FindWindowExW;
+
+    // The tab shown is the first in z-order; windows without tabs have none
+    let window = HWND(hwnd as *mut std::ffi::c_void);
+    let shown_tab = FindWindowExW(Some(window), None, w!("ShellTabWindowClass"), PCWSTR::null()).ok();
+
+    let shell_windows: IShellWindows = CoCreateInstance(&ShellWindows, None, CLSCTX_ALL).ok()?;
+    for i in 0..shell_windows.Count().ok()? {
+        let Ok(browser) = shell_windows.Item(&VARIANT::from(i)).and_then(|item| item.cast::<IWebBrowserApp>()) else {
+            continue;
+        };
+        if browser.HWND().map(|handle| handle.0) != Ok(hwnd) {
+            continue;
+        }
+        if let Some(shown_tab) = shown_tab {
+            let tab = browser
+                .cast::<IServiceProvider>()
+                .and_then(|provider| provider.QueryService::<IShellBrowser>(&SID_STopLevelBrowser))
+                .and_then(|shell_browser| shell_browser.GetWindow());
+            if tab.ok() != Some(shown_tab) {
+                continue;
+            }
+        }
+        let location = browser.LocationURL().ok()?.to_string();
+        return url::Url::parse(&location).ok()?.to_file_path().ok().filter(|path| path.is_dir());
+    }
+    None
+}
+
+#[cfg(not(windows))]
+fn folder_of_window(_hwnd: isize) -> Option<PathBuf> {
+    None
+}
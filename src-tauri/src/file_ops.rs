@@ -0,0 +1,149 @@
+// file_ops.rs - Creating files and folders from the launcher
+//
+// Used by the `new` and `mkdir` keywords (and anything else that creates
+// things on disk). Every path goes through validation first so a typo can't
+// produce a file Explorer can't delete (reserved names like "con", trailing
+// dots, invalid characters) and nothing is ever overwritten.
+//
+// Templates live in the templates folder next to settings.json
+// (%APPDATA%\QuickRun\templates):
+// - `new note.md` uses templates\default.md if it exists (otherwise empty)
+// - `new note.md from meeting` uses templates\meeting.md (or any file named
+//   "meeting" with another extension)
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+/// Characters Windows doesn't allow in file names
+const INVALID_NAME_CHARS: &[char] = &['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Device names Windows reserves (with or without an extension)
+const RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// The templates folder (created on first use)
+pub fn templates_dir() -> PathBuf {
+    let dir = crate::get_config_dir().join("templates");
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Where relative paths are created: the Desktop, or home if there is none
+pub fn default_base_dir() -> PathBuf {
+    dirs::desktop_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Is `name` usable as a single Windows file or folder name?
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("The name is empty".to_string());
+    }
+    if name.contains(INVALID_NAME_CHARS) {
+        return Err(format!("'{}' contains characters not allowed in file names", name));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err(format!("'{}' can't end with a dot or space", name));
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim().to_lowercase();
+    if RESERVED_NAMES.contains(&stem.as_str()) {
+        return Err(format!("'{}' is a reserved name on Windows", name));
+    }
+    Ok(())
+}
+
+/// Turn user input into a path that's safe to create
+///
+/// - `~` expands to the home folder; relative paths are relative to `base`
+/// - Every new component is validated (existing drive/prefix is kept as-is)
+/// - `..` is rejected so the result is where it looks like it is
+/// - The path must not exist yet
+pub fn resolve_new_path(input: &str, base: &Path) -> Result<PathBuf, String> {
+    let input = input.trim().trim_matches('"');
+    if input.is_empty() {
+        return Err("No name given".to_string());
+    }
+
+    let path = crate::runner::expand_home(input);
+    let path = if path.is_absolute() { path } else { base.join(path) };
+
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => validate_name(&name.to_string_lossy())?,
+            Component::ParentDir => return Err(format!("'..' isn't allowed in '{}'", input)),
+            _ => {}
+        }
+    }
+
+    if path.exists() {
+        return Err(format!("'{}' already exists", path.display()));
+    }
+
+    Ok(path)
+}
+
+/// Find a template by name (stem or full file name) in the templates folder
+fn find_template(name: &str) -> Option<PathBuf> {
+    std::fs::read_dir(templates_dir())
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            file_name.eq_ignore_ascii_case(name) || stem.eq_ignore_ascii_case(name)
+        })
+}
+
+/// Create a new file at `path` (parent folders included)
+///
+/// Contents come from `template` if given (error if it doesn't exist), else
+/// from templates\default.<ext> if present, else the file is empty.
+pub fn create_file(path: &Path, template: Option<&str>) -> Result<(), String> {
+    let template_path = match template {
+        Some(name) => Some(
+            find_template(name)
+                .filter(|p| p.is_file())
+                .ok_or_else(|| format!("No template named '{}' in {}", name, templates_dir().display()))?,
+        ),
+        None => path
+            .extension()
+            .map(|ext| templates_dir().join(format!("default.{}", ext.to_string_lossy())))
+            .filter(|p| p.is_file()),
+    };
+
+    let contents = match &template_path {
+        Some(p) => std::fs::read(p).map_err(|e| format!("Failed to read template: {}", e))?,
+        None => Vec::new(),
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+    }
+
+    // create_new: never overwrite, even if something appeared since validation
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| format!("Failed to create '{}': {}", path.display(), e))?;
+    file.write_all(&contents)
+        .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+
+    eprintln!("[FileOps] Created file: {}", path.display());
+    Ok(())
+}
+
+/// Create a new folder at `path` (parent folders included)
+pub fn create_dir(path: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(path)
+        .map_err(|e| format!("Failed to create '{}': {}", path.display(), e))?;
+
+    eprintln!("[FileOps] Created folder: {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,113 @@
+// focus.rs - The window that was active before the launcher appeared
+//
+// The launcher steals focus when it opens. It needs to give it back when
+// it's dismissed without running anything (Escape, hotkey again) and for
+// actions like pasting a clipboard history entry into the app the user was
+// typing in - so the foreground window is recorded every time the launcher
+// is shown.
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+/// HWND of the window that was in the foreground when the launcher opened (0 = none)
+static PREVIOUS: AtomicIsize = AtomicIsize::new(0);
+
+/// Record the current foreground window (call before showing the launcher)
+#[cfg(windows)]
+pub fn remember_foreground() {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    PREVIOUS.store(hwnd.0 as isize, Ordering::SeqCst);
+}
+
+#[cfg(not(windows))]
+pub fn remember_foreground() {}
+
+/// HWND of the window that was active before the launcher opened (0 = none)
+pub fn previous_window() -> isize {
+    PREVIOUS.load(Ordering::SeqCst)
+}
+
+/// Center of the previously active window, in physical pixels (None if
+/// there was none or it's gone)
+#[cfg(windows)]
+pub fn previous_center() -> Option<(i32, i32)> {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::UI::WindowsAndMessaging::{GetWindowRect, IsWindow};
+
+    let hwnd = HWND(PREVIOUS.load(Ordering::SeqCst) as *mut std::ffi::c_void);
+    if hwnd.is_invalid() || !unsafe { IsWindow(Some(hwnd)) }.as_bool() {
+        return None;
+    }
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut rect) }.ok()?;
+    Some(((rect.left + rect.right) / 2, (rect.top + rect.bottom) / 2))
+}
+
+#[cfg(not(windows))]
+pub fn previous_center() -> Option<(i32, i32)> {
+    None
+}
+
+/// Bring the previously active window back to the front
+#[cfg(windows)]
+pub fn restore_previous() -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{IsWindow, SetForegroundWindow};
+
+    let hwnd = HWND(PREVIOUS.load(Ordering::SeqCst) as *mut std::ffi::c_void);
+    if hwnd.is_invalid() || !unsafe { IsWindow(Some(hwnd)) }.as_bool() {
+        return Err("The previously active window is gone".to_string());
+    }
+    if !unsafe { SetForegroundWindow(hwnd) }.as_bool() {
+        return Err("Could not activate the previous window".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn restore_previous() -> Result<(), String> {
+    Err("Restoring focus is only supported on Windows".to_string())
+}
+
+/// Focus the previous window and press Ctrl+V in it
+#[cfg(windows)]
+pub fn paste_into_previous() -> Result<(), String> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        VIRTUAL_KEY, VK_CONTROL,
+    };
+
+    restore_previous()?;
+    // Give the target a moment to become active before the keystrokes arrive
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    let key = |vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                dwFlags: flags,
+                ..Default::default()
+            },
+        },
+    };
+    let v = VIRTUAL_KEY(b'V' as u16);
+    let inputs = [
+        key(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
+        key(v, KEYBD_EVENT_FLAGS(0)),
+        key(v, KEYEVENTF_KEYUP),
+        key(VK_CONTROL, KEYEVENTF_KEYUP),
+    ];
+
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        return Err("Failed to send paste keystrokes".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn paste_into_previous() -> Result<(), String> {
+    Err("Pasting into another window is only supported on Windows".to_string())
+}
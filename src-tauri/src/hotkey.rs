@@ -0,0 +1,438 @@
+// hotkey.rs - Layout-aware global hotkey
+//
+// The launcher hotkey (setting "hotkey", default "Alt+Space") is registered
+// directly with RegisterHotKey by virtual-key code on a dedicated thread:
+//
+// - Named keys (Space, Tab, F1-F24, ...) have fixed virtual-key codes
+// - Character keys ("Alt+Q", "Ctrl+Alt+ö") are looked up in the keyboard
+//   layout of the foreground window with VkKeyScanEx, so the chord follows
+//   the character, not the US key position
+// - Chords with both Ctrl and Alt are checked against AltGr: on layouts
+//   where Ctrl+Alt+key types a character (AltGr+Q = @ on German), grabbing
+//   it would stop the user from typing that character, so it is refused
+//
+// Keyboard layouts are per thread and WM_INPUTLANGCHANGE is only delivered
+// to the thread whose layout changed (the app being typed in), so this
+// thread polls the foreground window's layout instead and re-registers the
+// hotkey when it changes.
+//
+// On other platforms the global-shortcut plugin is used as before.
+//
+// The hotkey can be paused from the tray menu (e.g., for a game that uses
+// the same chord): it is unregistered until resumed, so the chord reaches
+// other apps again.
+//
+// The outcome of the last registration is kept for the About window (see
+// status), and a failure shows a toast (see notify.rs).
+
+use quickrun_core::{i18n, Settings};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// Whether the hotkey is paused
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Outcome of the last attempt to register the hotkey (None = not tried yet)
+static REGISTRATION: Mutex<Option<Result<(), String>>> = Mutex::new(None);
+
+/// Whether the hotkey is working
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum Status {
+    /// Not registered yet (e.g., during the startup delay)
+    Waiting,
+    Registered,
+    Paused,
+    /// Registration failed, usually because another app has the chord
+    Failed { error: String },
+}
+
+/// Whether the hotkey is working, and if not, why
+pub fn status() -> Status {
+    if is_paused() {
+        return Status::Paused;
+    }
+    match &*REGISTRATION.lock().unwrap_or_else(|e| e.into_inner()) {
+        None => Status::Waiting,
+        Some(Ok(())) => Status::Registered,
+        Some(Err(error)) => Status::Failed { error: error.clone() },
+    }
+}
+
+/// Tell the user the hotkey isn't working (the tray icon still opens the
+/// launcher)
+fn report_failure(app: &AppHandle, error: &str) {
+    let title = i18n::text("notify.hotkey_failed");
+    crate::notify::error(app, &title, &i18n::format("notify.hotkey_failed.body", &[error]));
+}
+
+/// Note the outcome of registering the hotkey
+fn record(result: &Result<(), String>) {
+    *REGISTRATION.lock().unwrap_or_else(|e| e.into_inner()) = Some(result.clone());
+}
+
+/// Is the hotkey paused?
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+/// Pause or resume the hotkey
+pub fn set_paused(paused: bool) {
+    PAUSED.store(paused, Ordering::SeqCst);
+    reload();
+}
+
+/// Thread id of the hotkey thread (0 = not started), for reload()
+#[cfg(windows)]
+static THREAD_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+/// A parsed chord
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+    pub key: Key,
+}
+
+/// The non-modifier part of a chord
+#[derive(Debug, Clone, PartialEq)]
+pub enum Key {
+    /// A key with a fixed virtual-key code (Space, F5, ...)
+    Named(u16),
+    /// A character, resolved per keyboard layout
+    Char(char),
+}
+
+/// Virtual-key codes for named keys
+fn named_key(name: &str) -> Option<u16> {
+    let vk = match name.to_lowercase().as_str() {
+        "space" => 0x20,
+        "tab" => 0x09,
+        "enter" | "return" => 0x0D,
+        "esc" | "escape" => 0x1B,
+        "backspace" => 0x08,
+        "insert" => 0x2D,
+        "delete" => 0x2E,
+        "home" => 0x24,
+        "end" => 0x23,
+        "pageup" => 0x21,
+        "pagedown" => 0x22,
+        "up" => 0x26,
+        "down" => 0x28,
+        "left" => 0x25,
+        "right" => 0x27,
+        "pause" => 0x13,
+        "scrolllock" => 0x91,
+        name => {
+            // F1-F24 → 0x70-0x87
+            let n: u16 = name.strip_prefix('f')?.parse().ok()?;
+            if !(1..=24).contains(&n) {
+                return None;
+            }
+            0x70 + n - 1
+        }
+    };
+    Some(vk)
+}
+
+/// Parse "Ctrl+Alt+K" style text
+pub fn parse(text: &str) -> Result<Chord, String> {
+    let mut chord = Chord { ctrl: false, alt: false, shift: false, win: false, key: Key::Named(0) };
+    let mut key = None;
+
+    for part in text.split('+').map(str::trim) {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" => chord.ctrl = true,
+            "alt" => chord.alt = true,
+            "shift" => chord.shift = true,
+            "win" | "super" | "meta" => chord.win = true,
+            "" => return Err(format!("Invalid hotkey '{}'", text)),
+            _ if key.is_some() => return Err(format!("Hotkey '{}' has more than one key", text)),
+            _ => {
+                key = Some(match named_key(part) {
+                    Some(vk) => Key::Named(vk),
+                    None => {
+                        let mut chars = part.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(c), None) => Key::Char(c),
+                            _ => return Err(format!("Unknown key '{}' in hotkey '{}'", part, text)),
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    chord.key = key.ok_or_else(|| format!("Hotkey '{}' has no key", text))?;
+    if !(chord.ctrl || chord.alt || chord.win) {
+        return Err(format!("Hotkey '{}' needs Ctrl, Alt or Win", text));
+    }
+    Ok(chord)
+}
+
+/// The configured chord (falls back to the default if the setting is invalid)
+pub fn configured() -> Chord {
+    let text = quickrun_core::settings::load().hotkey;
+    parse(&text).unwrap_or_else(|e| {
+        let default = Settings::default().hotkey;
+        eprintln!("Warning: {} - using {}", e, default);
+        parse(&default).expect("default hotkey parses")
+    })
+}
+
+#[cfg(windows)]
+mod win32 {
+    use super::{Chord, Key};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetKeyboardLayout, MapVirtualKeyExW, RegisterHotKey, ToUnicodeEx, UnregisterHotKey,
+        VkKeyScanExW, MAPVK_VK_TO_VSC, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT,
+        MOD_SHIFT, MOD_WIN,
+    };
+    use windows::Win32::UI::TextServices::HKL;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetMessageW, GetWindowThreadProcessId, SetTimer, MSG, WM_APP,
+        WM_HOTKEY, WM_TIMER,
+    };
+
+    /// Hotkey id passed to RegisterHotKey
+    const HOTKEY_ID: i32 = 1;
+    /// Posted by reload() to re-read the setting
+    pub const WM_RELOAD: u32 = WM_APP + 1;
+    /// Layout poll interval
+    const LAYOUT_POLL_MS: u32 = 1000;
+
+    /// Keyboard layout of the window the user is typing in
+    fn foreground_layout() -> HKL {
+        unsafe {
+            let thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+            GetKeyboardLayout(thread)
+        }
+    }
+
+    /// Virtual-key code for the chord's key on `layout`
+    fn resolve_vk(chord: &Chord, layout: HKL) -> Result<u16, String> {
+        match chord.key {
+            Key::Named(vk) => Ok(vk),
+            Key::Char(c) => {
+                let mut units = [0u16; 2];
+                if c.encode_utf16(&mut units).len() != 1 {
+                    return Err(format!("'{}' can't be used in a hotkey", c));
+                }
+                let result = unsafe { VkKeyScanExW(units[0], layout) };
+                if result == -1 {
+                    return Err(format!("'{}' isn't on the current keyboard layout", c));
+                }
+                Ok((result as u16) & 0xFF)
+            }
+        }
+    }
+
+    /// Does Ctrl+Alt+`vk` (= AltGr+key) type a character on `layout`?
+    fn is_altgr_character(vk: u16, layout: HKL) -> bool {
+        let mut state = [0u8; 256];
+        state[0x11] = 0x80; // VK_CONTROL
+        state[0x12] = 0x80; // VK_MENU
+        let mut buffer = [0u16; 8];
+        unsafe {
+            let scan = MapVirtualKeyExW(vk as u32, MAPVK_VK_TO_VSC, Some(layout));
+            // Flag 4: don't change the keyboard state (dead keys)
+            ToUnicodeEx(vk as u32, scan, &state, &mut buffer, 4, Some(layout)) > 0
+        }
+    }
+
+    /// Register the chord for `layout` (unregistering any previous one),
+    /// unless the hotkey is paused
+    fn register(chord: &Chord, layout: HKL) -> Result<(), String> {
+        unsafe {
+            let _ = UnregisterHotKey(None, HOTKEY_ID);
+        }
+        if super::is_paused() {
+            return Ok(());
+        }
+
+        let result = grab(chord, layout);
+        super::record(&result);
+        result
+    }
+
+    /// RegisterHotKey the chord for `layout`
+    fn grab(chord: &Chord, layout: HKL) -> Result<(), String> {
+        let vk = resolve_vk(chord, layout)?;
+        if chord.ctrl && chord.alt && is_altgr_character(vk, layout) {
+            return Err("Ctrl+Alt with this key is AltGr on the current layout (it types a character)".to_string());
+        }
+
+        let mut modifiers = MOD_NOREPEAT;
+        if chord.ctrl {
+            modifiers |= MOD_CONTROL;
+        }
+        if chord.alt {
+            modifiers |= MOD_ALT;
+        }
+        if chord.shift {
+            modifiers |= MOD_SHIFT;
+        }
+        if chord.win {
+            modifiers |= MOD_WIN;
+        }
+
+        unsafe { RegisterHotKey(None, HOTKEY_ID, modifiers, vk as u32) }
+            .map_err(|e| format!("RegisterHotKey failed (is another app using it?): {}", e))
+    }
+
+    /// Hotkey thread: registers, re-registers on layout change, dispatches presses
+    pub fn run(app: &AppHandle, on_press: impl Fn()) {
+        let mut chord = super::configured();
+        let mut layout = foreground_layout();
+        let mut vk = resolve_vk(&chord, layout).ok();
+
+        if let Err(e) = register(&chord, layout) {
+            super::report_failure(app, &e);
+        }
+
+        unsafe {
+            // Thread timer (no window): WM_TIMER arrives in this thread's queue
+            SetTimer(None, 0, LAYOUT_POLL_MS, None);
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                match msg.message {
+                    WM_HOTKEY if msg.wParam.0 as i32 == HOTKEY_ID => on_press(),
+                    WM_TIMER => {
+                        let current = foreground_layout();
+                        if current == layout {
+                            continue;
+                        }
+                        layout = current;
+                        // Re-register if the character moved to another key,
+                        // or if the AltGr check may now give another answer
+                        let new_vk = resolve_vk(&chord, layout).ok();
+                        let altgr_sensitive = chord.ctrl && chord.alt;
+                        if new_vk != vk || altgr_sensitive {
+                            vk = new_vk;
+                            if let Err(e) = register(&chord, layout) {
+                                eprintln!("[Hotkey] Layout changed, hotkey unavailable: {}", e);
+                            } else {
+                                eprintln!("[Hotkey] Layout changed, hotkey re-registered");
+                            }
+                        }
+                    }
+                    WM_RELOAD => {
+                        chord = super::configured();
+                        vk = resolve_vk(&chord, layout).ok();
+                        if let Err(e) = register(&chord, layout) {
+                            super::report_failure(app, &e);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // WM_QUIT (stop): let the next process have the chord
+            let _ = UnregisterHotKey(None, HOTKEY_ID);
+        }
+    }
+}
+
+/// Start listening for the hotkey; `on_press` runs on the hotkey thread
+#[cfg(windows)]
+pub fn start(app: &AppHandle, on_press: impl Fn() + Send + Sync + 'static) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let thread_id = unsafe { windows::Win32::System::Threading::GetCurrentThreadId() };
+        THREAD_ID.store(thread_id, Ordering::SeqCst);
+        win32::run(&app, on_press);
+        THREAD_ID.store(0, Ordering::SeqCst);
+    });
+}
+
+/// Other platforms: register through the global-shortcut plugin
+#[cfg(not(windows))]
+pub fn start(app: &AppHandle, on_press: impl Fn() + Send + Sync + 'static) {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+    let chord = configured();
+    let text = format!(
+        "{}{}{}{}{}",
+        if chord.ctrl { "Ctrl+" } else { "" },
+        if chord.alt { "Alt+" } else { "" },
+        if chord.shift { "Shift+" } else { "" },
+        if chord.win { "Super+" } else { "" },
+        match chord.key {
+            Key::Char(c) => c.to_uppercase().to_string(),
+            Key::Named(_) => "Space".to_string(),
+        }
+    );
+    let Ok(shortcut) = text.parse::<Shortcut>() else {
+        let error = format!("Could not parse hotkey '{}'", text);
+        report_failure(app, &error);
+        record(&Err(error));
+        return;
+    };
+    let result = app
+        .global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                on_press();
+            }
+        })
+        .map_err(|e| e.to_string());
+    if let Err(e) = &result {
+        report_failure(app, e);
+    }
+    record(&result);
+}
+
+/// Re-read the "hotkey" setting and re-register
+#[cfg(windows)]
+pub fn reload() {
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::PostThreadMessageW;
+
+    let thread_id = THREAD_ID.load(Ordering::SeqCst);
+    if thread_id != 0 {
+        unsafe {
+            let _ = PostThreadMessageW(thread_id, win32::WM_RELOAD, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+/// Other platforms: the plugin registration is fixed until restart (a paused
+/// hotkey is ignored when pressed instead)
+#[cfg(not(windows))]
+pub fn reload() {}
+
+/// Release the hotkey for good (before restarting, so the new process can
+/// register the same chord)
+#[cfg(windows)]
+pub fn stop(_app: &AppHandle) {
+    use std::time::{Duration, Instant};
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+    let thread_id = THREAD_ID.load(Ordering::SeqCst);
+    if thread_id == 0 {
+        return;
+    }
+    unsafe {
+        let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }
+    // A hotkey can only be unregistered by its own thread; wait for it
+    let deadline = Instant::now() + Duration::from_millis(500);
+    while THREAD_ID.load(Ordering::SeqCst) != 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Other platforms: drop the plugin registration
+#[cfg(not(windows))]
+pub fn stop(app: &AppHandle) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        eprintln!("[Hotkey] Failed to unregister: {}", e);
+    }
+}
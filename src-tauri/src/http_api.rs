@@ -0,0 +1,271 @@
+// http_api.rs - Local HTTP API
+//
+// With "http_api" on in Settings (off by default), QuickRun answers HTTP
+// requests on 127.0.0.1 only, never from other machines, so Stream Deck
+// actions, macro pads and scripts can use the launcher:
+//
+//   POST /run      {"command": "notepad"}   Run it as if typed into the launcher
+//   GET  /suggest?q=note                     The launcher's suggestions
+//   GET  /history?limit=20                   Recent commands, most recent first
+//
+// Every request must carry the token shown in Settings, as
+// "Authorization: Bearer <token>" (or "?token=<token>" for tools that can
+// only open a URL), so web pages and other programs can't use it. Replies
+// are JSON; failures are {"error": "..."} with a 4xx or 5xx status. The
+// token is kept in TOKEN_FILE next to settings.json rather than in it, so
+// settings exports, backups and profile copies never carry it.
+//
+// A few worker threads (WORKERS) take turns answering requests, so a flood
+// of them can't start a thread each. The server follows the settings:
+// started, stopped or moved to another port as they change, and the token
+// is checked against the current one.
+
+use crate::settings_state::SettingsState;
+use quickrun_core::{get_config_dir, history, i18n, results, suggestions};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+use url::Url;
+
+/// Largest request body read (a command is a line of text)
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// History entries returned when no limit is given
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Threads answering requests
+const WORKERS: usize = 4;
+
+/// File in the config folder holding the token
+const TOKEN_FILE: &str = "http-api-token.txt";
+
+/// The running server, with its port
+static SERVER: Mutex<Option<(u16, Arc<Server>)>> = Mutex::new(None);
+
+/// What a request asks for
+#[derive(Debug, PartialEq)]
+enum Route {
+    Run,
+    Suggest(String),
+    History(usize),
+    NotFound,
+}
+
+/// Start, stop or move the server to match "http_api" (call at startup and
+/// when it changes)
+pub fn apply(app: &AppHandle) {
+    let api = app.state::<SettingsState>().get().http_api;
+    let mut current = SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    if api.enabled && current.as_ref().is_some_and(|(port, _)| *port == api.port) {
+        return;
+    }
+    if let Some((_, server)) = current.take() {
+        // Each call lets one waiting worker go
+        (0..WORKERS).for_each(|_| server.unblock());
+        eprintln!("[HttpApi] Stopped");
+    }
+    if !api.enabled {
+        return;
+    }
+    // The first time it's turned on
+    if token().is_empty() {
+        if let Err(e) = new_token() {
+            eprintln!("[HttpApi] {}", e);
+        }
+    }
+
+    match Server::http((Ipv4Addr::LOCALHOST, api.port)) {
+        Ok(server) => {
+            eprintln!("[HttpApi] Listening on 127.0.0.1:{}", api.port);
+            let server = Arc::new(server);
+            *current = Some((api.port, server.clone()));
+            for _ in 0..WORKERS {
+                let (app, server) = (app.clone(), server.clone());
+                std::thread::spawn(move || {
+                    for request in server.incoming_requests() {
+                        handle(&app, request);
+                    }
+                });
+            }
+        }
+        Err(e) => {
+            let title = i18n::text("notify.http_api_failed");
+            crate::notify::error(app, &title, &format!("127.0.0.1:{}: {}", api.port, e));
+        }
+    }
+}
+
+/// The token requests must carry ("" until the API is first turned on)
+pub fn token() -> String {
+    std::fs::read_to_string(get_config_dir().join(TOKEN_FILE))
+        .map(|token| token.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Replace the token with a new random one (32 hex digits), returning it
+pub fn new_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    fill_random(&mut bytes)?;
+    let token: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    std::fs::write(get_config_dir().join(TOKEN_FILE), &token).map_err(|e| format!("Failed to save the token: {}", e))?;
+    Ok(token)
+}
+
+#[cfg(windows)]
+fn fill_random(bytes: &mut [u8]) -> Result<(), String> {
+    use windows::Win32::Security::Cryptography::{BCryptGenRandom, BCRYPT_USE_SYSTEM_PREFERRED_RNG};
+
+    unsafe { BCryptGenRandom(None, bytes, BCRYPT_USE_SYSTEM_PREFERRED_RNG) }
+        .ok()
+        .map_err(|e| format!("Failed to make a token: {}", e))
+}
+
+#[cfg(not(windows))]
+fn fill_random(bytes: &mut [u8]) -> Result<(), String> {
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(bytes))
+        .map_err(|e| format!("Failed to make a token: {}", e))
+}
+
+/// Answer one request
+fn handle(app: &AppHandle, mut request: Request) {
+    let url = parse_url(request.url());
+    let token = token();
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.as_str().to_string());
+    let query_token = url.as_ref().and_then(|url| query_value(url, "token"));
+    if !authorized(authorization.as_deref(), query_token.as_deref(), &token) {
+        reply(request, 401, json!({ "error": "Missing or wrong token" }));
+        return;
+    }
+
+    let method = request.method().clone();
+    let route = url.as_ref().map_or(Route::NotFound, route);
+    let (status, body) = match (method, route) {
+        (Method::Post, Route::Run) => {
+            let mut body = String::new();
+            match request.as_reader().take(MAX_BODY_BYTES).read_to_string(&mut body) {
+                Ok(_) => run(app, &body),
+                Err(e) => (400, json!({ "error": format!("Failed to read the request: {}", e) })),
+            }
+        }
+        (Method::Get, Route::Suggest(query)) => {
+            let mut rows = suggestions::suggest(&query, &Default::default()).unwrap_or_default();
+            // Icons are data URLs, far bigger than the rest of a row
+            rows.iter_mut().for_each(|row| row.icon = None);
+            (200, json!(rows))
+        }
+        (Method::Get, Route::History(limit)) => {
+            let entries: Vec<history::HistoryEntry> = history::load().into_iter().take(limit).collect();
+            (200, json!(entries))
+        }
+        (_, Route::NotFound) => (404, json!({ "error": "Not found" })),
+        _ => (405, json!({ "error": "Method not allowed" })),
+    };
+    reply(request, status, body);
+}
+
+/// POST /run with `body` ({"command": "..."})
+fn run(app: &AppHandle, body: &str) -> (u16, Value) {
+    let command = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|body| body.get("command")?.as_str().map(str::to_string))
+        .filter(|command| !command.trim().is_empty());
+    let Some(command) = command else {
+        return (400, json!({ "error": "Expected a JSON body like {\"command\": \"notepad\"}" }));
+    };
+
+    match tauri::async_runtime::block_on(crate::run_input(app, command)) {
+        Ok(mut outcome) => {
+            match &mut outcome {
+                results::RunOutcome::Launched => crate::sounds::play(app, crate::sounds::Cue::Launch),
+                results::RunOutcome::Results { rows } => rows.iter_mut().for_each(|row| row.icon = None),
+                results::RunOutcome::Done => {}
+            }
+            (200, json!(outcome))
+        }
+        Err(e) => (422, json!({ "error": e })),
+    }
+}
+
+/// Send `body` as JSON
+fn reply(request: Request, status: u16, body: Value) {
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        eprintln!("[HttpApi] Failed to reply: {}", e);
+    }
+}
+
+/// The request path and query as a URL
+fn parse_url(path: &str) -> Option<Url> {
+    Url::parse(&format!("http://127.0.0.1{}", path)).ok()
+}
+
+/// The value of `key` in the query string
+fn query_value(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs().find(|(name, _)| name == key).map(|(_, value)| value.into_owned())
+}
+
+/// What `url` asks for
+fn route(url: &Url) -> Route {
+    match url.path().trim_end_matches('/') {
+        "/run" => Route::Run,
+        "/suggest" => Route::Suggest(query_value(url, "q").unwrap_or_default()),
+        "/history" => Route::History(
+            query_value(url, "limit")
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(DEFAULT_HISTORY_LIMIT),
+        ),
+        _ => Route::NotFound,
+    }
+}
+
+/// Does the request carry `token`, in an Authorization header or the query?
+fn authorized(authorization: Option<&str>, query_token: Option<&str>, token: &str) -> bool {
+    let given = authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or(query_token)
+        .map(str::trim);
+    !token.is_empty() && given.is_some_and(|given| same(given.as_bytes(), token.as_bytes()))
+}
+
+/// Compare without stopping at the first difference, so the time taken
+/// gives nothing away
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_need_the_token() {
+        assert!(authorized(Some("Bearer abc123"), None, "abc123"));
+        assert!(authorized(None, Some("abc123"), "abc123"));
+        assert!(!authorized(Some("Bearer abc124"), None, "abc123"));
+        assert!(!authorized(Some("abc123"), None, "abc123"));
+        assert!(!authorized(None, None, "abc123"));
+        // No token set: nothing gets in
+        assert!(!authorized(Some("Bearer "), Some(""), ""));
+    }
+
+    #[test]
+    fn paths_pick_the_route() {
+        let route_of = |path: &str| route(&parse_url(path).unwrap());
+        assert_eq!(route_of("/run"), Route::Run);
+        assert_eq!(route_of("/suggest?q=note%20pad&token=x"), Route::Suggest("note pad".to_string()));
+        assert_eq!(route_of("/history/?limit=5"), Route::History(5));
+        assert_eq!(route_of("/history?limit=lots"), Route::History(DEFAULT_HISTORY_LIMIT));
+        assert_eq!(route_of("/settings"), Route::NotFound);
+    }
+}
@@ -0,0 +1,208 @@
+// jump_list.rs - The taskbar and Start menu jump list
+//
+// Right-clicking QuickRun on the taskbar (when pinned) or in Start shows:
+// - Recent: the commands run last, as in the tray's Recent submenu
+// - Tasks: "Show launcher" and "Settings"
+// so the launcher is in reach even while the hotkey is taken by another app.
+//
+// Each entry is a shortcut to quickrun.exe with the matching flag (--show,
+// --settings, --run "<command>", see quickrun_core::launch_args); the
+// single-instance plugin hands it to the running copy.
+//
+// The list is rebuilt with the tray menu (after a launch, a language or
+// profile change), on a thread of its own as building it goes through COM.
+// Recent commands the user removed from the list are left out, since
+// Windows refuses a list that adds them back.
+
+use quickrun_core::{history, i18n};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// Most recent commands in the jump list (Windows may show fewer)
+const RECENT_ITEMS: usize = 8;
+
+/// The entries the list was last built with, so an unchanged list isn't
+/// rebuilt (also keeps two rebuilds from overlapping)
+static LAST: Mutex<Option<Entries>> = Mutex::new(None);
+
+/// (title, quickrun.exe arguments) for each category
+#[derive(Debug, Clone, PartialEq)]
+struct Entries {
+    recent_title: String,
+    recent: Vec<(String, String)>,
+    tasks: Vec<(String, String)>,
+}
+
+/// Rebuild the jump list from the history and the language in use
+pub fn refresh(app: &AppHandle) {
+    let entries = Entries {
+        recent_title: i18n::text("tray.recent"),
+        recent: history::load()
+            .into_iter()
+            .take(RECENT_ITEMS)
+            .map(|entry| (crate::short_label(&entry.command), run_args(&entry.command)))
+            .collect(),
+        tasks: vec![
+            (i18n::text("jump_list.show"), "--show".to_string()),
+            (i18n::text("tray.settings"), "--settings".to_string()),
+        ],
+    };
+    let app_id = app.config().identifier.clone();
+
+    std::thread::spawn(move || {
+        let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+        if last.as_ref() == Some(&entries) {
+            return;
+        }
+        match build(&app_id, &entries) {
+            Ok(()) => *last = Some(entries),
+            Err(e) => eprintln!("[JumpList] Failed to update the jump list: {}", e),
+        }
+    });
+}
+
+/// Arguments that run `command` (see launch_args)
+fn run_args(command: &str) -> String {
+    format!("--run {}", quote_arg(command))
+}
+
+/// Quote `arg` so the C runtime's parsing gives it back as one argument
+/// (quotes are escaped, and backslashes before them doubled)
+fn quote_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        if c == '"' {
+            backslashes = backslashes * 2 + 1;
+        }
+        quoted.push_str(&"\\".repeat(backslashes));
+        quoted.push(c);
+        backslashes = 0;
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(windows)]
+fn build(app_id: &str, entries: &Entries) -> Result<(), String> {
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED};
+    use windows::Win32::UI::Shell::Common::IObjectArray;
+    use windows::Win32::UI::Shell::{DestinationList, ICustomDestinationList, IShellLinkW};
+
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to find quickrun.exe: {}", e))?;
+
+    unsafe {
+        // The shell needs COM on this thread; S_FALSE (already initialized) is fine
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let list: ICustomDestinationList = CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("Failed to open the jump list: {}", e))?;
+        list.SetAppID(&HSTRING::from(app_id)).map_err(|e| e.to_string())?;
+        let mut slots = 0u32;
+        let removed: IObjectArray = list.BeginList(&mut slots).map_err(|e| e.to_string())?;
+
+        // Arguments of the entries the user removed
+        let mut removed_args = Vec::new();
+        for i in 0..removed.GetCount().unwrap_or(0) {
+            if let Ok(link) = removed.GetAt::<IShellLinkW>(i) {
+                let mut buffer = [0u16; 1024];
+                if link.GetArguments(&mut buffer).is_ok() {
+                    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+                    removed_args.push(String::from_utf16_lossy(&buffer[..len]));
+                }
+            }
+        }
+
+        let recent: Vec<(String, String)> = entries
+            .recent
+            .iter()
+            .filter(|(_, args)| !removed_args.contains(args))
+            .take(slots as usize)
+            .cloned()
+            .collect();
+        if !recent.is_empty() {
+            let category = links(&exe, &recent)?.cast::<IObjectArray>().map_err(|e| e.to_string())?;
+            list.AppendCategory(&HSTRING::from(entries.recent_title.as_str()), &category)
+                .map_err(|e| e.to_string())?;
+        }
+        let tasks = links(&exe, &entries.tasks)?.cast::<IObjectArray>().map_err(|e| e.to_string())?;
+        list.AddUserTasks(&tasks).map_err(|e| e.to_string())?;
+        list.CommitList().map_err(|e| e.to_string())
+    }
+}
+
+/// Shortcuts to `exe` with each entry's title and arguments
+#[cfg(windows)]
+fn links(
+    exe: &std::path::Path,
+    entries: &[(String, String)],
+) -> Result<windows::Win32::UI::Shell::Common::IObjectCollection, String> {
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::Common::IObjectCollection;
+    use windows::Win32::UI::Shell::EnumerableObjectCollection;
+
+    unsafe {
+        let collection: IObjectCollection = CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("Failed to make a jump list category: {}", e))?;
+        for (title, args) in entries {
+            shortcut(exe, title, args)
+                .and_then(|link| collection.AddObject(&link))
+                .map_err(|e| format!("Failed to make the shortcut '{}': {}", title, e))?;
+        }
+        Ok(collection)
+    }
+}
+
+/// A shortcut to `exe` with `args`, shown as `title`
+#[cfg(windows)]
+fn shortcut(
+    exe: &std::path::Path,
+    title: &str,
+    args: &str,
+) -> windows::core::Result<windows::Win32::UI::Shell::IShellLinkW> {
+    use windows::core::{Interface, HSTRING, PROPVARIANT};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(&HSTRING::from(exe))?;
+        link.SetArguments(&HSTRING::from(args))?;
+        link.SetIconLocation(&HSTRING::from(exe), 0)?;
+        link.SetDescription(&HSTRING::from(title))?;
+        // The title is what the jump list shows
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &PROPVARIANT::from(title))?;
+        store.Commit()?;
+        Ok(link)
+    }
+}
+
+/// Other platforms have no jump list
+#[cfg(not(windows))]
+fn build(_app_id: &str, _entries: &Entries) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_passed_as_one_argument() {
+        assert_eq!(run_args("notepad"), "--run \"notepad\"");
+        assert_eq!(quote_arg("notepad todo.txt"), "\"notepad todo.txt\"");
+        assert_eq!(quote_arg("echo \"hi\""), "\"echo \\\"hi\\\"\"");
+        // Backslashes only count before a quote (or the closing one)
+        assert_eq!(quote_arg(r"C:\Tools\"), r#""C:\Tools\\""#);
+        assert_eq!(quote_arg(r#"a\"b"#), r#""a\\\"b""#);
+    }
+}
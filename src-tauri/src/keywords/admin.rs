@@ -0,0 +1,22 @@
+// keywords/admin.rs - `admin`: an administrator terminal where you are
+//
+//   admin                  → elevated terminal in the folder open in Explorer
+//   admin netstat -abn     → ...running the command (the terminal stays open)
+//
+// The folder is the one shown in the Explorer window that was active when
+// the launcher opened (see explorer.rs), or the home folder otherwise. The
+// terminal is the preferred one from Settings.
+
+use crate::results::ResultRow;
+use crate::{default_apps, explorer};
+
+/// Handle `admin [command]`
+pub fn open(args: &str) -> Result<Vec<ResultRow>, String> {
+    let dir = explorer::previous_folder()
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| "Could not find a folder to open the terminal in".to_string())?;
+
+    default_apps::open_terminal_elevated(&dir, (!args.is_empty()).then_some(args))?;
+
+    Ok(vec![ResultRow::new("Opened an administrator terminal", dir.to_string_lossy())])
+}
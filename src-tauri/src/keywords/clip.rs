@@ -0,0 +1,73 @@
+// keywords/clip.rs - `clip [search]` clipboard history
+//
+// Lists remembered clipboard entries (see providers/clipboard_history.rs),
+// most recent first, filtered by the search text. Selecting an entry copies
+// it back to the clipboard - or pastes it straight into the previously
+// active window when "clipboard_paste_on_select" is on. Both are available
+// from the row's action menu either way.
+//
+// `clip clear` forgets the whole history.
+
+use crate::providers::clipboard_history;
+use crate::results::{Action, ResultRow, RowAction};
+
+/// Longest title shown for an entry (the rest is cut off)
+const MAX_TITLE_CHARS: usize = 80;
+
+/// First non-empty line, shortened for display
+fn title_for(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    if line.chars().count() > MAX_TITLE_CHARS || text.trim().lines().count() > 1 {
+        let short: String = line.chars().take(MAX_TITLE_CHARS).collect();
+        format!("{}…", short)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Handle `clip [search]` and `clip clear`
+pub fn search(args: &str) -> Result<Vec<ResultRow>, String> {
+    if args.eq_ignore_ascii_case("clear") {
+        clipboard_history::clear()?;
+        return Ok(vec![ResultRow::new("Clipboard history cleared", "Clipboard")]);
+    }
+
+    if !clipboard_history::is_enabled() {
+        return Ok(vec![ResultRow::new(
+            "Clipboard history is off",
+            "Turn on \"Remember clipboard history\" in Settings",
+        )]);
+    }
+
+    let query = args.to_lowercase();
+    let paste = clipboard_history::paste_on_select();
+
+    let rows: Vec<ResultRow> = clipboard_history::entries()
+        .into_iter()
+        .filter(|entry| entry.text.to_lowercase().contains(&query))
+        .map(|entry| {
+            let lines = entry.text.lines().count();
+            let subtitle = if lines > 1 {
+                format!("{} - {} lines", super::time_ago(entry.copied_at), lines)
+            } else {
+                super::time_ago(entry.copied_at)
+            };
+            let main = if paste {
+                Action::Paste(entry.text.clone())
+            } else {
+                Action::Copy(entry.text.clone())
+            };
+            ResultRow::new(title_for(&entry.text), subtitle)
+                .with_action(main)
+                .with_actions(vec![
+                    RowAction::new("Copy", Action::Copy(entry.text.clone())),
+                    RowAction::new("Paste into previous window", Action::Paste(entry.text)),
+                ])
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return Ok(vec![ResultRow::new("No matching clipboard entries", "Clipboard")]);
+    }
+    Ok(rows)
+}
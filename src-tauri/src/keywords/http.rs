@@ -0,0 +1,113 @@
+// keywords/http.rs - `http <url>` status/header probe
+//
+// A quick health check for a website or API endpoint:
+// - Sends a HEAD request (falling back to GET if the server rejects HEAD)
+// - Follows redirects and reports the final URL
+// - Shows status code, response time, Server header
+// - For HTTPS, shows when the TLS certificate expires
+//
+// Examples:
+// - "http example.com" → probes https://example.com
+// - "http http://localhost:8080/health"
+
+use crate::results::{Action, ResultRow};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Add a scheme if the user left it off (HTTPS by default)
+fn normalize_url(input: &str) -> String {
+    if input.starts_with("http://") || input.starts_with("https://") {
+        input.to_string()
+    } else {
+        format!("https://{}", input)
+    }
+}
+
+/// Describe when the peer certificate expires, e.g. "in 42 days (Mar  1 12:00:00 2026 +00:00)"
+fn certificate_expiry(der: &[u8]) -> Option<String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    let not_after = cert.validity().not_after;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let days = (not_after.timestamp() - now) / 86_400;
+
+    Some(if days >= 0 {
+        format!("in {} days ({})", days, not_after)
+    } else {
+        format!("EXPIRED {} days ago ({})", -days, not_after)
+    })
+}
+
+/// Probe `input` and return result rows describing the response
+pub async fn probe(input: &str) -> Result<Vec<ResultRow>, String> {
+    if input.is_empty() {
+        return Err("Usage: http <url>".to_string());
+    }
+
+    let url = normalize_url(input);
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("QuickRun/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(15))
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .tls_info(true)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let started = Instant::now();
+
+    let mut response = client
+        .head(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+
+    // Some servers don't implement HEAD - retry with GET
+    if response.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED
+        || response.status() == reqwest::StatusCode::NOT_IMPLEMENTED
+    {
+        response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+    }
+
+    let elapsed = started.elapsed();
+    let status = response.status();
+    let final_url = response.url().to_string();
+
+    let mut rows = vec![
+        ResultRow::new(
+            format!(
+                "{} {}",
+                status.as_str(),
+                status.canonical_reason().unwrap_or("")
+            ),
+            format!("Status ({} ms)", elapsed.as_millis()),
+        ),
+        ResultRow::new(final_url.clone(), "Final URL")
+            .with_action(Action::Open(final_url.clone())),
+    ];
+
+    if let Some(server) = response
+        .headers()
+        .get(reqwest::header::SERVER)
+        .and_then(|v| v.to_str().ok())
+    {
+        rows.push(ResultRow::new(server, "Server").with_action(Action::Copy(server.to_string())));
+    }
+
+    if let Some(expiry) = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .and_then(certificate_expiry)
+    {
+        rows.push(ResultRow::new(expiry, "TLS certificate expires"));
+    }
+
+    Ok(rows)
+}
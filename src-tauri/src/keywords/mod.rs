@@ -0,0 +1,81 @@
+// keywords/mod.rs - Built-in keyword commands
+//
+// A keyword is the first word of the input (e.g., `http` in
+// `http https://example.com`). Instead of being resolved as a program, the
+// rest of the input is handed to the keyword's handler, which returns result
+// rows for the launcher to display.
+//
+// Adding a keyword:
+// 1. Create a module in this folder with an async handler
+// 2. Add a match arm in dispatch()
+//
+// Long-running keywords (like `speedtest`) return a placeholder row right
+// away and emit a JobFinished event (see events.rs) with the final rows when
+// they finish.
+
+mod admin;
+mod clip;
+mod http;
+mod myip;
+mod new;
+mod recent;
+pub mod rename;
+mod scaffold;
+mod speedtest;
+mod terminal;
+
+use crate::results::ResultRow;
+use tauri::AppHandle;
+
+/// Split input into (keyword, arguments)
+///
+/// "http  https://example.com " → ("http", "https://example.com")
+fn split_keyword(input: &str) -> (&str, &str) {
+    let input = input.trim();
+    match input.split_once(char::is_whitespace) {
+        Some((keyword, args)) => (keyword, args.trim()),
+        None => (input, ""),
+    }
+}
+
+/// Describe a Unix timestamp relative to now: "just now", "5 min ago",
+/// "3 h ago", "2 days ago"
+fn time_ago(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let seconds = now.saturating_sub(timestamp);
+
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+/// Run the input as a keyword command, if it starts with one
+///
+/// Returns:
+/// - None if the first word isn't a keyword (caller should resolve it as a program)
+/// - Some(Ok(rows)) with the rows to display
+/// - Some(Err(message)) if the keyword failed
+pub async fn dispatch(app: &AppHandle, input: &str) -> Option<Result<Vec<ResultRow>, String>> {
+    let (keyword, args) = split_keyword(input);
+
+    match keyword.to_lowercase().as_str() {
+        "http" => Some(http::probe(args).await),
+        "admin" => Some(admin::open(args)),
+        "speedtest" => Some(speedtest::start(app)),
+        "myip" => Some(myip::lookup(args).await),
+        "rename" => Some(rename::preview(args)),
+        "new" => Some(new::new_file(args)),
+        "mkdir" => Some(new::make_dir(args)),
+        "scaffold" => Some(scaffold::start(app, args)),
+        "clip" => Some(clip::search(args)),
+        "recent" => Some(recent::list(args)),
+        "terminal" if args.eq_ignore_ascii_case("here") => Some(terminal::here(app)),
+        _ => None,
+    }
+}
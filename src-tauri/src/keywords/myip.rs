@@ -0,0 +1,135 @@
+// keywords/myip.rs - `myip` public/local address lookup
+//
+// Shows the public IPv4 and IPv6 addresses (as seen from the internet), a
+// rough geolocation for the public address, and the local addresses of each
+// network interface. Every address row copies the address when selected -
+// handy when someone asks "what IP should I whitelist?".
+//
+// Public lookups are cached for a few minutes; `myip refresh` bypasses the cache.
+//
+// Providers can be overridden in settings.json:
+// - myip_v4_url: returns the IPv4 address as plain text
+// - myip_v6_url: returns the IPv6 address as plain text
+// - myip_geo_url: returns JSON with city/region/country/org fields (ipinfo.io format)
+
+use crate::results::{Action, ResultRow};
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_V4_URL: &str = "https://api.ipify.org";
+const DEFAULT_V6_URL: &str = "https://api6.ipify.org";
+const DEFAULT_GEO_URL: &str = "https://ipinfo.io/json";
+
+/// How long public lookup results are reused
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Last public lookup: when it ran and the rows it produced
+static CACHE: Mutex<Option<(Instant, Vec<ResultRow>)>> = Mutex::new(None);
+
+/// Geolocation response (ipinfo.io format, all fields optional)
+#[derive(Debug, Deserialize)]
+struct GeoInfo {
+    city: Option<String>,
+    region: Option<String>,
+    country: Option<String>,
+    org: Option<String>,
+}
+
+/// Fetch a plain-text address from `url`; None if the network has no route
+/// for that address family (common for IPv6)
+async fn fetch_address(client: &reqwest::Client, url: &str) -> Option<String> {
+    let text = client.get(url).send().await.ok()?.text().await.ok()?;
+    let address = text.trim();
+    (!address.is_empty()).then(|| address.to_string())
+}
+
+/// Look up the public addresses and location
+async fn public_rows() -> Result<Vec<ResultRow>, String> {
+    let settings = quickrun_core::settings::load();
+    let v4_url = settings.myip_v4_url.unwrap_or_else(|| DEFAULT_V4_URL.to_string());
+    let v6_url = settings.myip_v6_url.unwrap_or_else(|| DEFAULT_V6_URL.to_string());
+    let geo_url = settings.myip_geo_url.unwrap_or_else(|| DEFAULT_GEO_URL.to_string());
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("QuickRun/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let (v4, v6) = tokio::join!(
+        fetch_address(&client, &v4_url),
+        fetch_address(&client, &v6_url)
+    );
+
+    let mut rows = Vec::new();
+
+    match v4 {
+        Some(ip) => rows.push(ResultRow::new(ip.clone(), "Public IPv4").with_action(Action::Copy(ip))),
+        None => rows.push(ResultRow::new("Unavailable", "Public IPv4")),
+    }
+    if let Some(ip) = v6 {
+        rows.push(ResultRow::new(ip.clone(), "Public IPv6").with_action(Action::Copy(ip)));
+    }
+
+    if let Ok(response) = client.get(&geo_url).send().await {
+        if let Ok(geo) = response.json::<GeoInfo>().await {
+            let location: Vec<String> = [geo.city, geo.region, geo.country]
+                .into_iter()
+                .flatten()
+                .filter(|s| !s.is_empty())
+                .collect();
+            if !location.is_empty() {
+                let mut subtitle = "Location".to_string();
+                if let Some(org) = geo.org {
+                    subtitle = format!("Location ({})", org);
+                }
+                rows.push(ResultRow::new(location.join(", "), subtitle));
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Local interface addresses (loopback excluded)
+fn local_rows() -> Vec<ResultRow> {
+    let Ok(interfaces) = local_ip_address::list_afinet_netifas() else {
+        return Vec::new();
+    };
+
+    interfaces
+        .into_iter()
+        .filter(|(_, ip)| !ip.is_loopback())
+        .map(|(name, ip)| {
+            let ip = ip.to_string();
+            ResultRow::new(ip.clone(), format!("Local - {}", name)).with_action(Action::Copy(ip))
+        })
+        .collect()
+}
+
+/// Handle `myip [refresh]`
+pub async fn lookup(args: &str) -> Result<Vec<ResultRow>, String> {
+    let force_refresh = args.eq_ignore_ascii_case("refresh");
+
+    let cached = CACHE.lock().ok().and_then(|cache| {
+        cache
+            .as_ref()
+            .filter(|(at, _)| !force_refresh && at.elapsed() < CACHE_TTL)
+            .map(|(_, rows)| rows.clone())
+    });
+
+    let mut rows = match cached {
+        Some(rows) => rows,
+        None => {
+            let rows = public_rows().await?;
+            if let Ok(mut cache) = CACHE.lock() {
+                *cache = Some((Instant::now(), rows.clone()));
+            }
+            rows
+        }
+    };
+
+    rows.extend(local_rows());
+    Ok(rows)
+}
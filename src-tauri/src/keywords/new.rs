@@ -0,0 +1,67 @@
+// keywords/new.rs - `new` and `mkdir` quick creation
+//
+//   new note.md                      → Desktop\note.md
+//   new note.md in ~\Documents       → %USERPROFILE%\Documents\note.md
+//   new standup.md from meeting in ~ → from templates\meeting.*
+//   mkdir ~\Projects\demo            → creates the folder (and parents)
+//
+// Relative paths are created on the Desktop. The new file or folder is opened
+// right away, and the result row offers the usual file actions.
+
+use crate::file_ops;
+use crate::results::{Action, ResultRow};
+use std::path::Path;
+
+/// Split "x in y" at the last " in " (case-insensitive): ("x", Some("y"))
+fn split_last<'a>(input: &'a str, separator: &str) -> (&'a str, Option<&'a str>) {
+    match input.to_ascii_lowercase().rfind(separator) {
+        Some(i) => (input[..i].trim(), Some(input[i + separator.len()..].trim())),
+        None => (input.trim(), None),
+    }
+}
+
+/// Row describing what was created
+fn created_row(path: &Path, actions: Vec<crate::results::RowAction>) -> ResultRow {
+    let target = path.to_string_lossy().to_string();
+    ResultRow::new(
+        format!("Created {}", path.file_name().unwrap_or_default().to_string_lossy()),
+        target.clone(),
+    )
+    .with_action(Action::Open(target))
+    .with_actions(actions)
+}
+
+/// Handle `new <name> [from <template>] [in <folder>]`
+pub fn new_file(args: &str) -> Result<Vec<ResultRow>, String> {
+    if args.is_empty() {
+        return Err("Usage: new <name> [from <template>] [in <folder>]".to_string());
+    }
+
+    let (rest, folder) = split_last(args, " in ");
+    let (name, template) = split_last(rest, " from ");
+
+    let base = match folder {
+        Some(folder) => crate::runner::expand_home(folder),
+        None => file_ops::default_base_dir(),
+    };
+    let path = file_ops::resolve_new_path(name, &base)?;
+
+    file_ops::create_file(&path, template)?;
+    crate::runner::open_with_shell(&path.to_string_lossy())?;
+
+    Ok(vec![created_row(&path, crate::file_actions::actions_for(&path))])
+}
+
+/// Handle `mkdir <path>`
+pub fn make_dir(args: &str) -> Result<Vec<ResultRow>, String> {
+    if args.is_empty() {
+        return Err("Usage: mkdir <path>".to_string());
+    }
+
+    let path = file_ops::resolve_new_path(args, &file_ops::default_base_dir())?;
+
+    file_ops::create_dir(&path)?;
+    crate::runner::open_with_shell(&path.to_string_lossy())?;
+
+    Ok(vec![created_row(&path, crate::file_actions::folder_actions_for(&path))])
+}
@@ -0,0 +1,137 @@
+// keywords/recent.rs - `recent <folder>` recently modified files
+//
+// Lists the newest files under a configured folder, so "that file I just
+// downloaded" is one query away:
+//
+//   recent downloads      → newest files in Downloads
+//   recent shots report   → newest files in Screenshots containing "report"
+//   recent                → the configured folder names
+//
+// Folders are a name → path table in settings.json ("recent_folders"); the
+// defaults below cover Downloads, Screenshots, Desktop and Documents.
+// Subfolders are searched a few levels deep.
+
+use crate::results::{Action, ResultRow};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Most rows shown
+const MAX_RESULTS: usize = 20;
+/// How many folder levels below the configured folder are searched
+const MAX_DEPTH: usize = 3;
+/// Stop scanning after this many files (keeps huge folders responsive)
+const MAX_SCANNED: usize = 20_000;
+
+/// Folder name → path, from settings or the defaults
+fn folders() -> BTreeMap<String, PathBuf> {
+    if let Some(folders) = quickrun_core::settings::load().recent_folders {
+        return folders
+            .into_iter()
+            .map(|(name, path)| (name.to_lowercase(), crate::runner::expand_home(&path)))
+            .collect();
+    }
+
+    let mut folders = BTreeMap::new();
+    if let Some(dir) = dirs::download_dir() {
+        folders.insert("downloads".to_string(), dir);
+    }
+    if let Some(dir) = dirs::picture_dir() {
+        folders.insert("screenshots".to_string(), dir.join("Screenshots"));
+    }
+    if let Some(dir) = dirs::desktop_dir() {
+        folders.insert("desktop".to_string(), dir);
+    }
+    if let Some(dir) = dirs::document_dir() {
+        folders.insert("documents".to_string(), dir);
+    }
+    folders
+}
+
+/// Collect (modified time, path) for files under `dir`
+fn scan(dir: &Path, depth: usize, files: &mut Vec<(SystemTime, PathBuf)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if files.len() >= MAX_SCANNED {
+            return;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if depth < MAX_DEPTH {
+                scan(&entry.path(), depth + 1, files);
+            }
+        } else if let Ok(modified) = metadata.modified() {
+            files.push((modified, entry.path()));
+        }
+    }
+}
+
+/// Handle `recent [folder] [filter]`
+pub fn list(args: &str) -> Result<Vec<ResultRow>, String> {
+    let folders = folders();
+    let (name, filter) = match args.split_once(char::is_whitespace) {
+        Some((name, filter)) => (name.to_lowercase(), filter.trim().to_lowercase()),
+        None => (args.to_lowercase(), String::new()),
+    };
+
+    // Unknown or missing name: list the folders that can be used
+    let Some(root) = folders.get(&name) else {
+        return Ok(folders
+            .iter()
+            .map(|(name, path)| {
+                ResultRow::new(format!("recent {}", name), path.to_string_lossy())
+                    .with_action(Action::Run(format!("recent {}", name)))
+            })
+            .collect());
+    };
+
+    if !root.is_dir() {
+        return Err(format!("Folder not found: {}", root.display()));
+    }
+
+    let mut files = Vec::new();
+    scan(root, 0, &mut files);
+    files.retain(|(_, path)| {
+        filter.is_empty()
+            || path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase().contains(&filter))
+                .unwrap_or(false)
+    });
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+
+    if files.is_empty() {
+        return Ok(vec![ResultRow::new("No files found", root.to_string_lossy())]);
+    }
+
+    Ok(files
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(modified, path)| {
+            let seconds = modified
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let location = path
+                .parent()
+                .and_then(|parent| parent.strip_prefix(root).ok())
+                .map(|relative| relative.to_string_lossy().to_string())
+                .filter(|relative| !relative.is_empty());
+            let subtitle = match location {
+                Some(relative) => format!("{} - {}", super::time_ago(seconds), relative),
+                None => super::time_ago(seconds),
+            };
+            let target = path.to_string_lossy().to_string();
+
+            ResultRow::new(path.file_name().unwrap_or_default().to_string_lossy(), subtitle)
+                .with_action(Action::Open(target.clone()))
+                .with_icon(crate::icons::icon_for(&target))
+                .with_actions(crate::file_actions::actions_for(&path))
+        })
+        .collect())
+}
@@ -0,0 +1,324 @@
+// keywords/rename.rs - `rename <folder> <pattern> -> <new name>` batch rename
+//
+// Renames every file in a folder matching a wildcard pattern:
+//
+//   rename ~\Pictures\Trip IMG_* -> Trip_{n:03}
+//
+// Running it shows a preview (old → new, one row per file); selecting the
+// top row applies it. The pattern language is deliberately small:
+// - Source: `*` matches any run of characters, `?` a single one (case-insensitive)
+// - Target:
+//   - {n} - counter starting at 1; {n:03} pads it to 3 digits
+//   - {name} - the original name without extension
+//   - {ext} - the original extension (without the dot)
+//   - {1}, {2}, ... - the text matched by the 1st, 2nd, ... wildcard
+//   - If the target has no extension, the original extension is kept
+//
+// Files are numbered in name order. Applying is all-or-nothing: files are
+// first moved to temporary names and then to their final names, and any
+// failure rolls back what was already done. A record of the renames is
+// saved in the config directory (rename-undo\) so the "undo" row can put the
+// old names back.
+
+use crate::results::{Action, ResultRow};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Most preview rows shown (the apply row always covers every file)
+const MAX_PREVIEW_ROWS: usize = 100;
+
+/// A saved batch rename, used to undo it
+#[derive(Debug, Serialize, Deserialize)]
+struct UndoRecord {
+    folder: PathBuf,
+    /// (old name, new name) pairs
+    renames: Vec<(String, String)>,
+}
+
+/// Match `name` against a wildcard `pattern`, returning the text captured by
+/// each wildcard
+fn match_wildcard(pattern: &[char], name: &[char], captures: &mut Vec<String>) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => {
+            // Shortest match first so later literals anchor as early as possible
+            for split in 0..=name.len() {
+                captures.push(name[..split].iter().collect());
+                if match_wildcard(&pattern[1..], &name[split..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+            false
+        }
+        Some('?') => {
+            let Some(c) = name.first() else {
+                return false;
+            };
+            captures.push(c.to_string());
+            if match_wildcard(&pattern[1..], &name[1..], captures) {
+                return true;
+            }
+            captures.pop();
+            false
+        }
+        Some(p) => match name.first() {
+            Some(c) if c.to_lowercase().eq(p.to_lowercase()) => {
+                match_wildcard(&pattern[1..], &name[1..], captures)
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Build the new name for one file from the target pattern
+fn expand_target(target: &str, original: &str, counter: usize, captures: &[String]) -> Result<String, String> {
+    let path = Path::new(original);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path.extension().map(|e| e.to_string_lossy().to_string());
+
+    let mut result = String::new();
+    let mut rest = target;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|i| start + i)
+            .ok_or_else(|| format!("Unclosed '{{' in '{}'", target))?;
+        let token = &rest[start + 1..end];
+
+        match token {
+            "n" => result.push_str(&counter.to_string()),
+            "name" => result.push_str(&stem),
+            "ext" => result.push_str(ext.as_deref().unwrap_or("")),
+            _ if token.starts_with("n:") => {
+                let width: usize = token[2..]
+                    .parse()
+                    .map_err(|_| format!("Invalid counter format '{{{}}}'", token))?;
+                // {n:03} and {n:3} both mean "pad to 3 digits"
+                result.push_str(&format!("{:0width$}", counter, width = width));
+            }
+            _ => {
+                let index: usize = token
+                    .parse()
+                    .map_err(|_| format!("Unknown placeholder '{{{}}}'", token))?;
+                let capture = index
+                    .checked_sub(1)
+                    .and_then(|i| captures.get(i))
+                    .ok_or_else(|| format!("The pattern has no wildcard {{{}}}", index))?;
+                result.push_str(capture);
+            }
+        }
+
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    // Keep the original extension unless the target sets one
+    if !target.contains('.') {
+        if let Some(ext) = ext {
+            result = format!("{}.{}", result, ext);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parse `<folder> <pattern> [->] <target>` and compute the renames
+///
+/// Returns the folder and the (old name, new name) pairs, in name order.
+/// Files whose name wouldn't change are left out.
+fn plan(args: &str) -> Result<(PathBuf, Vec<(String, String)>), String> {
+    let usage = "Usage: rename <folder> <pattern> -> <new name>, e.g. rename ~\\Pictures IMG_* -> Trip_{n:03}";
+
+    let parts: Vec<String> = crate::runner::split_args(args)
+        .into_iter()
+        .filter(|p| p != "->" && p != "→")
+        .collect();
+    let [folder, pattern, target] = parts.as_slice() else {
+        return Err(usage.to_string());
+    };
+
+    let folder = crate::runner::expand_home(folder);
+    if !folder.is_dir() {
+        return Err(format!("Folder not found: {}", folder.display()));
+    }
+
+    let mut names: Vec<String> = std::fs::read_dir(&folder)
+        .map_err(|e| format!("Failed to read '{}': {}", folder.display(), e))?
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let mut renames = Vec::new();
+    let mut counter = 0;
+
+    for name in &names {
+        let mut captures = Vec::new();
+        if !match_wildcard(&pattern, &name.chars().collect::<Vec<_>>(), &mut captures) {
+            continue;
+        }
+        counter += 1;
+        let new_name = expand_target(target, name, counter, &captures)?;
+        crate::file_ops::validate_name(&new_name)?;
+        if new_name != *name {
+            renames.push((name.clone(), new_name));
+        }
+    }
+
+    if renames.is_empty() {
+        return Err("No files would be renamed".to_string());
+    }
+
+    // Every new name must be unique, and must not overwrite a file that
+    // isn't itself being renamed away
+    let renamed_from: Vec<String> = renames.iter().map(|(old, _)| old.to_lowercase()).collect();
+    let mut targets: Vec<String> = Vec::new();
+    for (old, new) in &renames {
+        let key = new.to_lowercase();
+        if targets.contains(&key) {
+            return Err(format!("More than one file would be named '{}'", new));
+        }
+        if folder.join(new).exists() && !renamed_from.contains(&key) {
+            return Err(format!("Renaming '{}' would overwrite '{}'", old, new));
+        }
+        targets.push(key);
+    }
+
+    Ok((folder, renames))
+}
+
+/// Perform the renames in `folder` all-or-nothing
+///
+/// Two phases (old → temp, temp → new) so that chains and swaps
+/// (a → b, b → c) work; on failure everything done so far is reversed.
+fn rename_all(folder: &Path, renames: &[(String, String)]) -> Result<(), String> {
+    let temp_name = |i: usize| folder.join(format!(".quickrun-rename-{}.tmp", i));
+
+    // Undo steps in reverse order, ignoring errors (best effort)
+    let roll_back = |steps: &[(PathBuf, PathBuf)]| {
+        for (from, to) in steps.iter().rev() {
+            let _ = std::fs::rename(to, from);
+        }
+    };
+
+    let mut done: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let steps = renames
+        .iter()
+        .enumerate()
+        .map(|(i, (old, _))| (folder.join(old), temp_name(i)))
+        .chain(
+            renames
+                .iter()
+                .enumerate()
+                .map(|(i, (_, new))| (temp_name(i), folder.join(new))),
+        )
+        .collect::<Vec<_>>();
+
+    for (from, to) in steps {
+        if let Err(e) = std::fs::rename(&from, &to) {
+            roll_back(&done);
+            return Err(format!(
+                "Failed to rename '{}': {} (no files were changed)",
+                from.file_name().unwrap_or_default().to_string_lossy(),
+                e
+            ));
+        }
+        done.push((from, to));
+    }
+
+    Ok(())
+}
+
+/// Folder holding undo records
+fn undo_dir() -> PathBuf {
+    crate::get_config_dir().join("rename-undo")
+}
+
+/// Handle `rename ...`: show the preview
+pub fn preview(args: &str) -> Result<Vec<ResultRow>, String> {
+    let (folder, renames) = plan(args)?;
+
+    let mut rows = vec![ResultRow::new(
+        format!("Rename {} file(s)", renames.len()),
+        format!("In {} - select to apply", folder.display()),
+    )
+    .with_action(Action::ApplyRename(args.to_string()))];
+
+    rows.extend(
+        renames
+            .iter()
+            .take(MAX_PREVIEW_ROWS)
+            .map(|(old, new)| ResultRow::new(format!("{} → {}", old, new), "Preview")),
+    );
+    if renames.len() > MAX_PREVIEW_ROWS {
+        rows.push(ResultRow::new(
+            format!("...and {} more", renames.len() - MAX_PREVIEW_ROWS),
+            "Preview",
+        ));
+    }
+
+    Ok(rows)
+}
+
+/// Apply a previewed rename (the plan is recomputed so it reflects the
+/// folder as it is now) and save an undo record
+pub fn apply(args: &str) -> Result<Vec<ResultRow>, String> {
+    let (folder, renames) = plan(args)?;
+    rename_all(&folder, &renames)?;
+
+    eprintln!("[Rename] Renamed {} file(s) in {}", renames.len(), folder.display());
+
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let record_path = undo_dir().join(format!("{}.json", stamp));
+    let record = UndoRecord { folder, renames };
+
+    let saved = std::fs::create_dir_all(undo_dir())
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string_pretty(&record).map_err(|e| e.to_string()))
+        .and_then(|json| std::fs::write(&record_path, json).map_err(|e| e.to_string()));
+
+    let row = ResultRow::new(format!("Renamed {} file(s)", record.renames.len()), "Select to undo");
+    match saved {
+        Ok(()) => Ok(vec![row.with_action(Action::UndoRename(
+            record_path.to_string_lossy().to_string(),
+        ))]),
+        Err(e) => {
+            eprintln!("Warning: Could not save rename undo record: {}", e);
+            Ok(vec![ResultRow::new(
+                format!("Renamed {} file(s)", record.renames.len()),
+                "Undo is not available",
+            )])
+        }
+    }
+}
+
+/// Undo a batch rename from its saved record
+pub fn undo(record_path: &str) -> Result<Vec<ResultRow>, String> {
+    let json = std::fs::read_to_string(record_path)
+        .map_err(|e| format!("Failed to read undo record: {}", e))?;
+    let record: UndoRecord =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to parse undo record: {}", e))?;
+
+    let reversed: Vec<(String, String)> = record
+        .renames
+        .iter()
+        .map(|(old, new)| (new.clone(), old.clone()))
+        .collect();
+    rename_all(&record.folder, &reversed)?;
+    let _ = std::fs::remove_file(record_path);
+
+    eprintln!("[Rename] Undid rename of {} file(s) in {}", reversed.len(), record.folder.display());
+
+    Ok(vec![ResultRow::new(
+        format!("Restored {} original name(s)", reversed.len()),
+        "Undo complete",
+    )])
+}
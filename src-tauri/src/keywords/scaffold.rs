@@ -0,0 +1,176 @@
+// keywords/scaffold.rs - `scaffold <template> <name> [in <folder>]`
+//
+// A lightweight cookiecutter: copies a folder template into a new project
+// folder, runs an optional post-create command, and opens the result in the
+// preferred editor.
+//
+//   scaffold rust-cli my-tool              → <projects dir>\my-tool
+//   scaffold rust-cli my-tool in ~\src     → %USERPROFILE%\src\my-tool
+//
+// Templates are folders in the templates directory
+// (%APPDATA%\QuickRun\templates\rust-cli\...). While copying, "{{name}}" is
+// replaced with the project name in file/folder names and in the contents
+// of UTF-8 text files.
+//
+// A template may contain a scaffold.json (not copied):
+//   { "post_create": "cargo init --name {{name}}" }
+// The command runs in the new folder through cmd in the background; the
+// editor opens once it finishes.
+//
+// The default parent folder is the "projects_dir" setting, or ~\Projects.
+
+use crate::events::{self, AppEvent, Job};
+use crate::file_ops;
+use crate::results::{Action, ResultRow};
+use serde::Deserialize;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Template options file, read from the template root
+const OPTIONS_FILE: &str = "scaffold.json";
+
+/// Placeholder replaced with the project name
+const NAME_PLACEHOLDER: &str = "{{name}}";
+
+#[derive(Debug, Default, Deserialize)]
+struct TemplateOptions {
+    /// Command to run in the new folder after copying
+    post_create: Option<String>,
+}
+
+/// Copy `source` into `destination`, substituting the project name
+fn copy_template(source: &Path, destination: &Path, name: &str) -> Result<(), String> {
+    std::fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create '{}': {}", destination.display(), e))?;
+
+    let entries = std::fs::read_dir(source)
+        .map_err(|e| format!("Failed to read '{}': {}", source.display(), e))?;
+
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().replace(NAME_PLACEHOLDER, name);
+        let target = destination.join(&file_name);
+        let path = entry.path();
+
+        if path.is_dir() {
+            copy_template(&path, &target, name)?;
+            continue;
+        }
+
+        let bytes = std::fs::read(&path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        // Only text files get substitution; binaries are copied untouched
+        let bytes = match String::from_utf8(bytes) {
+            Ok(text) => text.replace(NAME_PLACEHOLDER, name).into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        std::fs::write(&target, bytes)
+            .map_err(|e| format!("Failed to write '{}': {}", target.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Run the post-create command in `dir`, returning an error with its output
+/// if it fails
+fn run_post_create(dir: &Path, command: &str) -> Result<(), String> {
+    let mut cmd = std::process::Command::new("cmd");
+    cmd.args(["/C", command]).current_dir(dir);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    Err(format!("'{}' failed: {}", command, detail))
+}
+
+/// Final row for a created project
+fn created_row(dir: &Path) -> ResultRow {
+    let target = dir.to_string_lossy().to_string();
+    ResultRow::new(
+        format!("Created {}", dir.file_name().unwrap_or_default().to_string_lossy()),
+        target.clone(),
+    )
+    .with_action(Action::Open(target))
+    .with_actions(crate::file_actions::folder_actions_for(dir))
+}
+
+/// Handle `scaffold <template> <name> [in <folder>]`
+pub fn start(app: &AppHandle, args: &str) -> Result<Vec<ResultRow>, String> {
+    let (rest, folder) = match args.to_ascii_lowercase().rfind(" in ") {
+        Some(i) => (args[..i].trim(), Some(args[i + 4..].trim())),
+        None => (args.trim(), None),
+    };
+    let Some((template, name)) = rest.split_once(char::is_whitespace) else {
+        return Err("Usage: scaffold <template> <name> [in <folder>]".to_string());
+    };
+    let name = name.trim();
+    file_ops::validate_name(name)?;
+
+    let template_dir = file_ops::templates_dir().join(template);
+    if !template_dir.is_dir() {
+        return Err(format!(
+            "No template folder named '{}' in {}",
+            template,
+            file_ops::templates_dir().display()
+        ));
+    }
+
+    let parent = match folder {
+        Some(folder) => crate::runner::expand_home(folder),
+        None => quickrun_core::settings::load()
+            .projects_dir
+            .map(|dir| crate::runner::expand_home(&dir))
+            .or_else(|| dirs::home_dir().map(|home| home.join("Projects")))
+            .unwrap_or_else(file_ops::default_base_dir),
+    };
+    let destination = file_ops::resolve_new_path(name, &parent)?;
+
+    let options: TemplateOptions = std::fs::read_to_string(template_dir.join(OPTIONS_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    copy_template(&template_dir, &destination, name)?;
+    let _ = std::fs::remove_file(destination.join(OPTIONS_FILE));
+    eprintln!("[Scaffold] Created {} from template '{}'", destination.display(), template);
+
+    let Some(command) = options.post_create.map(|c| c.replace(NAME_PLACEHOLDER, name)) else {
+        crate::default_apps::open_in_editor(&destination)?;
+        return Ok(vec![created_row(&destination)]);
+    };
+
+    // The post-create command may take a while (installs, git init, ...)
+    let app = app.clone();
+    let placeholder = ResultRow::new(format!("Setting up {}...", name), format!("Running {}", command));
+    tauri::async_runtime::spawn_blocking(move || {
+        let row = match run_post_create(&destination, &command)
+            .and_then(|_| crate::default_apps::open_in_editor(&destination))
+        {
+            Ok(()) => created_row(&destination),
+            Err(e) => {
+                eprintln!("[Scaffold] {}", e);
+                // The files are there - keep them and say what went wrong
+                let target = destination.to_string_lossy().to_string();
+                ResultRow::new(e, format!("Created {} (select to open)", target))
+                    .with_action(Action::Open(target))
+            }
+        };
+        events::emit(&app, AppEvent::JobFinished { job: Job::Scaffold, rows: vec![row] });
+    });
+
+    Ok(vec![placeholder])
+}
@@ -0,0 +1,175 @@
+// keywords/speedtest.rs - `speedtest` bandwidth/latency measurement
+//
+// Runs a lightweight speed test in a background task so the launcher stays
+// responsive:
+// 1. Ping: time several tiny requests, report the median
+// 2. Download: stream a large response and measure throughput
+// 3. Upload: POST a buffer in chunks and measure throughput
+//
+// Progress is reported with JobProgress events; the final numbers arrive as
+// a JobFinished event that replaces the results list (see events.rs).
+//
+// Endpoints default to Cloudflare's public speed test service and can be
+// overridden in settings.json:
+// - speedtest_ping_url
+// - speedtest_download_url
+// - speedtest_upload_url
+
+use crate::events::{self, AppEvent, Job};
+use crate::results::ResultRow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const DEFAULT_PING_URL: &str = "https://speed.cloudflare.com/__down?bytes=0";
+const DEFAULT_DOWNLOAD_URL: &str = "https://speed.cloudflare.com/__down?bytes=25000000";
+const DEFAULT_UPLOAD_URL: &str = "https://speed.cloudflare.com/__up";
+
+/// Number of ping samples (median is reported)
+const PING_SAMPLES: usize = 5;
+/// Upload is sent as this many POSTs so progress can be reported
+const UPLOAD_CHUNKS: usize = 4;
+/// Size of each upload POST
+const UPLOAD_CHUNK_BYTES: usize = 2_500_000;
+
+/// Only one speed test at a time - a second one would skew both results
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Report progress: `phase` is "ping", "download" or "upload", `percent`
+/// is 0-100 within that phase
+fn emit_progress(app: &AppHandle, phase: &'static str, percent: u8) {
+    events::emit(app, AppEvent::JobProgress { job: Job::Speedtest, percent, detail: phase.to_string() });
+}
+
+/// Convert bytes transferred in `elapsed` to megabits per second
+fn mbps(bytes: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64().max(0.001);
+    (bytes as f64 * 8.0) / secs / 1_000_000.0
+}
+
+/// Median round-trip time of several tiny requests, in milliseconds
+async fn measure_ping(app: &AppHandle, client: &reqwest::Client, url: &str) -> Result<u128, String> {
+    let mut samples = Vec::with_capacity(PING_SAMPLES);
+
+    for i in 0..PING_SAMPLES {
+        let started = Instant::now();
+        client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Ping failed: {}", e))?;
+        samples.push(started.elapsed().as_millis());
+
+        emit_progress(app, "ping", (((i + 1) * 100) / PING_SAMPLES) as u8);
+    }
+
+    samples.sort_unstable();
+    Ok(samples[samples.len() / 2])
+}
+
+/// Stream the download URL and return throughput in Mbps
+async fn measure_download(app: &AppHandle, client: &reqwest::Client, url: &str) -> Result<f64, String> {
+    let started = Instant::now();
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Download test failed: {}", e))?;
+
+    let total = response.content_length().unwrap_or(0) as usize;
+    let mut received = 0usize;
+    let mut last_percent = 0u8;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download test failed: {}", e))?
+    {
+        received += chunk.len();
+        if total > 0 {
+            let percent = ((received * 100) / total).min(100) as u8;
+            if percent != last_percent {
+                last_percent = percent;
+                emit_progress(app, "download", percent);
+            }
+        }
+    }
+
+    Ok(mbps(received, started.elapsed()))
+}
+
+/// POST a buffer in chunks to the upload URL and return throughput in Mbps
+async fn measure_upload(app: &AppHandle, client: &reqwest::Client, url: &str) -> Result<f64, String> {
+    let payload = vec![0u8; UPLOAD_CHUNK_BYTES];
+    let started = Instant::now();
+
+    for i in 0..UPLOAD_CHUNKS {
+        client
+            .post(url)
+            .body(payload.clone())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Upload test failed: {}", e))?;
+
+        emit_progress(app, "upload", (((i + 1) * 100) / UPLOAD_CHUNKS) as u8);
+    }
+
+    Ok(mbps(UPLOAD_CHUNKS * UPLOAD_CHUNK_BYTES, started.elapsed()))
+}
+
+/// Run all three measurements and build the result rows
+async fn run_test(app: &AppHandle) -> Result<Vec<ResultRow>, String> {
+    let settings = quickrun_core::settings::load();
+    let ping_url = settings.speedtest_ping_url.unwrap_or_else(|| DEFAULT_PING_URL.to_string());
+    let download_url = settings.speedtest_download_url.unwrap_or_else(|| DEFAULT_DOWNLOAD_URL.to_string());
+    let upload_url = settings.speedtest_upload_url.unwrap_or_else(|| DEFAULT_UPLOAD_URL.to_string());
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("QuickRun/{}", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let ping = measure_ping(app, &client, &ping_url).await?;
+    let down = measure_download(app, &client, &download_url).await?;
+    let up = measure_upload(app, &client, &upload_url).await?;
+
+    eprintln!(
+        "[Speedtest] Ping: {} ms, Down: {:.1} Mbps, Up: {:.1} Mbps",
+        ping, down, up
+    );
+
+    Ok(vec![
+        ResultRow::new(format!("{:.1} Mbps", down), "Download"),
+        ResultRow::new(format!("{:.1} Mbps", up), "Upload"),
+        ResultRow::new(format!("{} ms", ping), "Ping (median)"),
+    ])
+}
+
+/// Start a speed test in the background
+///
+/// Returns immediately with a placeholder row; the real results are
+/// emitted as a JobFinished event when the test finishes.
+pub fn start(app: &AppHandle) -> Result<Vec<ResultRow>, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A speed test is already running".to_string());
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let rows = match run_test(&app).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("[Speedtest] {}", e);
+                vec![ResultRow::new(e, "Speed test failed")]
+            }
+        };
+        RUNNING.store(false, Ordering::SeqCst);
+        events::emit(&app, AppEvent::JobFinished { job: Job::Speedtest, rows });
+    });
+
+    Ok(vec![ResultRow::new("Running speed test...", "Measuring ping")])
+}
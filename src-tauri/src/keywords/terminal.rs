@@ -0,0 +1,28 @@
+// keywords/terminal.rs - `terminal here`: a terminal where you are
+//
+//   terminal here          → preferred terminal in the folder open in Explorer
+//
+// Like `admin` without elevation. The folder is the one shown in the
+// Explorer window that was active when the launcher opened (see
+// explorer.rs), or the home folder otherwise; the terminal gets the
+// environment preset chosen for the ">" prefix.
+//
+// Only `terminal here` is a keyword: plain `terminal` still opens the
+// Windows Terminal app from the Start Menu.
+
+use crate::results::ResultRow;
+use crate::settings_state::SettingsState;
+use crate::{default_apps, env_presets, explorer};
+use tauri::{AppHandle, Manager};
+
+/// Handle `terminal here`
+pub fn here(app: &AppHandle) -> Result<Vec<ResultRow>, String> {
+    let dir = explorer::previous_folder()
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| "Could not find a folder to open the terminal in".to_string())?;
+
+    let environment = env_presets::environment(&app.state::<SettingsState>().get().terminal_env_preset)?;
+    default_apps::open_terminal_with_env(Some(&dir), None, &environment)?;
+
+    Ok(vec![ResultRow::new("Opened a terminal", dir.to_string_lossy())])
+}
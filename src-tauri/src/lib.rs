@@ -1,113 +1,80 @@
 // lib.rs - QuickRun main application setup
 //
 // This is the heart of the Tauri application. It configures and launches:
-// - System tray (icon + menu with Settings and Quit)
+// - System tray (icon + menu with Profile, Settings and Quit)
 // - Global hotkey (Alt+Space) to toggle the launcher window
-// - Window management (show/hide, center on active monitor, focus)
+// - Window management (show/hide, center on the chosen monitor, focus)
 // - Command execution (via the runner module)
 // - Settings persistence (Windows registry for startup, JSON for theme)
 // - Auto-update functionality (checks GitHub releases)
 //
 // Architecture:
 // - Tauri is a framework that combines a Rust backend with a web frontend
-// - This file contains the Rust backend logic
+// - This file contains the Rust backend logic; everything that doesn't need
+//   Tauri (resolution, indexing, suggestions, settings) is in quickrun-core
 // - The frontend is in src/main.ts and src/settings.ts
 // - Communication happens via Tauri "commands" (Rust functions callable from JS)
 
-mod runner;
+mod archive;
+mod authenticode;
+mod backdrop;
+mod clipboard;
+mod composition;
+mod deep_link;
+mod default_apps;
+mod desktops;
+mod double_tap;
+mod events;
+mod explorer;
+mod file_ops;
+mod focus;
+mod hotkey;
+mod http_api;
+mod jump_list;
+mod keywords;
+mod notify;
+mod placement;
+mod recycle;
+mod rollback;
+mod sandbox;
+mod scale;
+mod settings_state;
+mod sounds;
+mod tray_icon;
 mod updater;
 
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow, WebviewWindowBuilder};
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
+// The launcher logic lives in quickrun-core; importing its modules here keeps
+// paths like crate::runner and crate::results working in the modules above
+use quickrun_core::{
+    aliases, backup, completion, context_menu, env_presets, file_actions, history, history_export, i18n, icons,
+    importer, index, launch_args, os, path_completion, pipe, plugins, profiles, providers, resource_policy,
+    results, retention, runner, startup, storage, suggestions, theme, web_search,
+};
+use quickrun_core::Settings;
+use settings_state::SettingsState;
+use events::AppEvent;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, State, WebviewWindow, WebviewWindowBuilder};
+use tauri::menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
 use tauri::tray::TrayIconBuilder;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
-
-// Windows-specific imports for registry access (startup settings)
-#[cfg(windows)]
-use winreg::enums::*;
-#[cfg(windows)]
-use winreg::RegKey;
-
-/// Get the path to the settings file
-/// 
-/// Settings are stored as JSON in the user's config directory:
-/// - Windows: C:\Users\<username>\AppData\Roaming\QuickRun\settings.json
-/// - Creates the directory if it doesn't exist
-/// 
-/// This approach is platform-agnostic (uses dirs crate to find the right location)
-fn get_settings_path() -> PathBuf {
-    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    path.push("QuickRun");
-    std::fs::create_dir_all(&path).ok();
-    path.push("settings.json");
-    path
-}
-
-/// Load a setting from the settings file
-/// 
-/// Parameters:
-/// - key: The setting name (e.g., "light_mode")
-/// 
-/// Returns:
-/// - true if the setting exists and is true
-/// - false if the setting doesn't exist, is false, or file can't be read
-/// 
-/// This is used to persist user preferences across app restarts
-fn load_setting(key: &str) -> bool {
-    let path = get_settings_path();
-    if let Ok(contents) = std::fs::read_to_string(&path) {
-        if let Ok(settings) = serde_json::from_str::<serde_json::Value>(&contents) {
-            return settings.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
-        }
-    }
-    false
-}
-
-/// Save a setting to the settings file
-/// 
-/// Parameters:
-/// - key: The setting name (e.g., "light_mode")
-/// - value: The boolean value to save
-/// 
-/// How it works:
-/// 1. Load existing settings from file (or create empty object)
-/// 2. Update the specified key with the new value
-/// 3. Write the entire settings object back to file as pretty-printed JSON
-/// 
-/// This preserves other settings while updating just one
-fn save_setting(key: &str, value: bool) -> Result<(), String> {
-    let path = get_settings_path();
-    
-    let mut settings = if let Ok(contents) = std::fs::read_to_string(&path) {
-        serde_json::from_str(&contents).unwrap_or_else(|_| serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-    
-    settings[key] = serde_json::json!(value);
-    
-    std::fs::write(&path, serde_json::to_string_pretty(&settings).unwrap())
-        .map_err(|e| format!("Failed to save settings: {}", e))
-}
 
 /// Check if startup is enabled in Windows registry
 /// 
 /// Windows loads applications at startup from:
 /// HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Run
 /// 
-/// This function checks if QuickRun has an entry there.
+/// This function checks if QuickRun has an entry there (see startup.rs).
 /// The #[tauri::command] attribute makes this callable from JavaScript.
 /// The #[cfg(windows)] ensures it only compiles on Windows.
+/// A portable copy never starts with Windows (see set_startup_enabled).
 #[tauri::command]
 #[cfg(windows)]
 fn is_startup_enabled() -> Result<bool, String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let run_key = hkcu
-        .open_subkey("Software\\Microsoft\\Windows\\CurrentVersion\\Run")
-        .map_err(|e| format!("Failed to open registry: {}", e))?;
-    
-    Ok(run_key.get_value::<String, _>("QuickRun").is_ok())
+    if storage::is_portable() {
+        return Ok(false);
+    }
+    startup::is_enabled(&os::SystemRegistry)
 }
 
 #[tauri::command]
@@ -122,62 +89,397 @@ fn is_startup_enabled() -> Result<bool, String> {
 /// - enabled: true to add QuickRun to startup, false to remove it
 /// 
 /// How it works:
-/// - If enabled: Adds registry value "QuickRun" = path to this exe
+/// - If enabled: Adds registry value "QuickRun" = path to this exe, and
+///   removes the elevated startup task if there is one (a UAC prompt), so
+///   QuickRun isn't started twice
 /// - If disabled: Deletes the "QuickRun" registry value
 /// 
-/// Windows will automatically launch the exe at login if the value exists
+/// Windows will automatically launch the exe at login if the value exists.
+/// Not available in portable mode, which leaves nothing on the machine.
 #[tauri::command]
 #[cfg(windows)]
-fn set_startup_enabled(enabled: bool) -> Result<(), String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let run_key = hkcu
-        .open_subkey_with_flags("Software\\Microsoft\\Windows\\CurrentVersion\\Run", KEY_WRITE)
-        .map_err(|e| format!("Failed to open registry: {}", e))?;
-    
+async fn set_startup_enabled(enabled: bool) -> Result<(), String> {
+    if storage::is_portable() {
+        return Err("Start with Windows isn't available in portable mode".to_string());
+    }
+    let exe_path = std::env::current_exe()
+        .map_err(|e| format!("Failed to get exe path: {}", e))?;
     if enabled {
-        let exe_path = std::env::current_exe()
-            .map_err(|e| format!("Failed to get exe path: {}", e))?;
-        let exe_str = exe_path.to_string_lossy().to_string();
-        
-        run_key
-            .set_value("QuickRun", &exe_str)
-            .map_err(|e| format!("Failed to set registry value: {}", e))?;
-    } else {
-        run_key
-            .delete_value("QuickRun")
-            .map_err(|e| format!("Failed to delete registry value: {}", e))?;
+        let exe = exe_path.clone();
+        tauri::async_runtime::spawn_blocking(move || startup::set_task_enabled(false, &exe))
+            .await
+            .map_err(|e| format!("Startup task failed: {}", e))??;
     }
-    
-    Ok(())
+    startup::set_enabled(&os::SystemRegistry, enabled, &exe_path)
 }
 
 #[tauri::command]
 #[cfg(not(windows))]
-fn set_startup_enabled(_enabled: bool) -> Result<(), String> {
+async fn set_startup_enabled(_enabled: bool) -> Result<(), String> {
     Err("Startup settings are only supported on Windows".to_string())
 }
 
-/// Check if light mode is enabled
+/// Tauri command: is "Run with QuickRun" in Explorer's context menu? (see
+/// quickrun_core::context_menu)
+#[tauri::command]
+fn is_context_menu_enabled() -> Result<bool, String> {
+    if storage::is_portable() {
+        return Ok(false);
+    }
+    context_menu::is_enabled(&os::SystemRegistry)
+}
+
+/// Tauri command: add or remove "Run with QuickRun" in Explorer's context
+/// menu (not in portable mode, which leaves nothing on the machine)
+#[tauri::command]
+fn set_context_menu_enabled(enabled: bool) -> Result<(), String> {
+    if storage::is_portable() {
+        return Err("The Explorer context menu isn't available in portable mode".to_string());
+    }
+    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
+    context_menu::set_enabled(&os::SystemRegistry, enabled, &exe_path, &i18n::text("context_menu.label"))
+}
+
+/// Check if QuickRun starts elevated at login, through its scheduled task
+/// (see startup.rs)
+#[tauri::command]
+async fn is_elevated_startup_enabled() -> Result<bool, String> {
+    if storage::is_portable() {
+        return Ok(false);
+    }
+    tauri::async_runtime::spawn_blocking(startup::is_task_enabled)
+        .await
+        .map_err(|e| format!("Startup task failed: {}", e))
+}
+
+/// Start QuickRun elevated at login, or stop doing so
 /// 
-/// Returns the saved theme preference from settings.json.
-/// Defaults to false (dark mode) if not set.
+/// Creating or deleting the scheduled task shows a UAC prompt. Enabling it
+/// also removes the "QuickRun" Run value, which would start a second,
+/// unelevated copy.
+#[tauri::command]
+async fn set_elevated_startup_enabled(enabled: bool) -> Result<(), String> {
+    if storage::is_portable() {
+        return Err("Start with Windows isn't available in portable mode".to_string());
+    }
+    let exe_path = std::env::current_exe()
+        .map_err(|e| format!("Failed to get exe path: {}", e))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        startup::set_task_enabled(enabled, &exe_path)?;
+        if enabled {
+            startup::set_enabled(&os::SystemRegistry, false, &exe_path)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Startup task failed: {}", e))?
+}
+
+/// Tauri command: forget where the launcher was dragged to, so it opens
+/// centered again (see placement.rs)
+#[tauri::command]
+fn reset_launcher_position(app: AppHandle) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or_else(|| "Launcher window not found".to_string())?;
+    placement::reset(&window)
+}
+
+/// Tauri command: where settings are kept - portable or not, and a warning
+/// if the usual folder couldn't be written (see quickrun_core::storage)
+#[tauri::command]
+fn get_storage() -> storage::Storage {
+    storage::current().clone()
+}
+
+/// Tauri command: what each file and folder in the data folder takes up
+/// (see quickrun_core::retention)
+#[tauri::command]
+fn get_storage_usage() -> retention::StorageUsage {
+    retention::usage()
+}
+
+/// Tauri command: apply the retention limits now instead of waiting for the
+/// daily pass, and report the sizes afterwards
+#[tauri::command]
+fn run_maintenance(app: AppHandle) -> Result<retention::StorageUsage, String> {
+    retention::run_maintenance()?;
+    refresh_tray_menu(&app);
+    Ok(retention::usage())
+}
+
+/// Tauri command: the translations for `locale`, or for the language in use
+/// (see quickrun_core::i18n)
+#[tauri::command]
+fn get_strings(locale: Option<String>) -> i18n::Strings {
+    i18n::strings(&locale.unwrap_or_else(i18n::current_locale))
+}
+
+/// Tauri command: get every setting at once
 /// 
-/// Called from frontend on app startup to apply the correct theme
+/// Field names match the keys in settings.json (see quickrun_core::settings).
 #[tauri::command]
-fn is_light_mode() -> Result<bool, String> {
-    Ok(load_setting("light_mode"))
+fn get_settings(state: State<SettingsState>) -> Settings {
+    state.get()
 }
 
-/// Set light mode enabled/disabled
+/// Tauri command: change some settings, e.g. `{ watch_downloads: true }`
 /// 
-/// Parameters:
-/// - enabled: true for light mode, false for dark mode
+/// Only the keys in `patch` change. Unknown keys and values of the wrong
+/// type are rejected, as are an invalid hotkey or double-tap modifier (so a
+/// typo can't leave the launcher without a hotkey) and theme values that
+/// don't fit in CSS. Hotkey and double-tap changes take effect immediately,
+/// and open windows get the saved settings in a SettingsChanged event (plus
+/// a ThemeChanged event if the theme changed). Returns the saved settings.
+#[tauri::command]
+fn update_settings(app: AppHandle, state: State<SettingsState>, patch: serde_json::Value) -> Result<Settings, String> {
+    let before = state.get();
+    let after = state.try_update(|settings| {
+        let mut patched = settings.with_patch(&patch)?;
+        // Only validate what changed, so a bad hand-edited value elsewhere
+        // doesn't block unrelated changes
+        if patched.hotkey != settings.hotkey {
+            patched.hotkey = patched.hotkey.trim().to_string();
+            hotkey::parse(&patched.hotkey)?;
+        }
+        if patched.double_tap != settings.double_tap {
+            patched.double_tap = double_tap::parse(&patched.double_tap)?;
+        }
+        if patched.theme != settings.theme {
+            patched.theme.validate()?;
+        }
+        if patched.sounds != settings.sounds {
+            patched.sounds.validate(&settings.sounds)?;
+        }
+        if patched.ui_scale != settings.ui_scale {
+            patched.validate_ui_scale()?;
+        }
+        if patched.http_api != settings.http_api {
+            patched.http_api.validate()?;
+        }
+        if patched.env_presets != settings.env_presets
+            || patched.terminal_env_preset != settings.terminal_env_preset
+        {
+            validate_env_presets(&patched)?;
+        }
+        *settings = patched;
+        Ok(())
+    })?;
+
+    settings_state::apply_changes(&app, &before, &after);
+    events::emit(&app, AppEvent::SettingsChanged { settings: after.clone() });
+    Ok(after)
+}
+
+/// Tauri command: the local HTTP API's token ("" until it's first turned
+/// on), which isn't part of the settings
+#[tauri::command]
+fn get_http_api_token() -> String {
+    http_api::token()
+}
+
+/// Tauri command: replace the local HTTP API's token (e.g., after it was
+/// shared by mistake); the old one stops working right away
+#[tauri::command]
+fn new_http_api_token() -> Result<String, String> {
+    http_api::new_token()
+}
+
+/// Check the environment presets, and that the one chosen for the ">"
+/// prefix exists
+fn validate_env_presets(settings: &Settings) -> Result<(), String> {
+    env_presets::validate(&settings.env_presets)?;
+    let terminal = settings.terminal_env_preset.trim();
+    if !terminal.is_empty() && env_presets::find(&settings.env_presets, terminal).is_none() {
+        return Err(format!("There is no environment preset named '{}'", terminal));
+    }
+    Ok(())
+}
+
+/// Tauri command: which effects and background work to keep (see
+/// quickrun_core::resource_policy); changes arrive as ResourcePolicyChanged
+/// events
+#[tauri::command]
+fn get_resource_policy() -> resource_policy::ResourcePolicy {
+    resource_policy::current()
+}
+
+/// Tauri command: the Acrylic/Mica backdrop in use behind the launcher
+/// (see backdrop.rs); changes arrive as BackdropChanged events
+#[tauri::command]
+fn get_backdrop() -> theme::Backdrop {
+    backdrop::active()
+}
+
+/// Tauri command: how much bigger the launcher is drawn (see scale.rs);
+/// changes arrive as UiScaleChanged events
+#[tauri::command]
+fn get_ui_scale(state: State<SettingsState>) -> f64 {
+    state.get().ui_scale()
+}
+
+/// Tauri command: the theme, with the custom stylesheet's contents (see
+/// quickrun_core::theme); changes arrive as ThemeChanged events
+#[tauri::command]
+fn get_theme(state: State<SettingsState>) -> theme::LoadedTheme {
+    theme::load(&state.get().theme)
+}
+
+/// Tauri command: save settings, aliases, and history to one backup file
+/// 
+/// `path` may start with ~ (e.g., "~\\Documents\\QuickRun-settings.json").
+/// See quickrun_core::backup for the file layout.
+#[tauri::command]
+fn export_settings(path: String) -> Result<(), String> {
+    backup::export(&runner::expand_home(path.trim()))
+}
+
+/// Tauri command: restore settings, aliases, and history from a backup file
+/// 
+/// The whole file is checked before anything is replaced, including the
+/// hotkey and double-tap modifier. Sections missing from the file are left
+/// as they are. Open windows get a SettingsChanged event.
+#[tauri::command]
+fn import_settings(app: AppHandle, state: State<SettingsState>, path: String) -> Result<backup::ImportSummary, String> {
+    let backup = backup::read(&runner::expand_home(path.trim()))?;
+    if let Some(settings) = &backup.settings {
+        hotkey::parse(settings.hotkey.trim())?;
+        double_tap::parse(&settings.double_tap)?;
+        settings.theme.validate()?;
+        settings.http_api.validate()?;
+        validate_env_presets(settings)?;
+    }
+
+    let before = state.get();
+    let summary = backup::apply(backup)?;
+    state.reload();
+    let after = state.get();
+
+    settings_state::apply_changes(&app, &before, &after);
+    refresh_tray_menu(&app);
+    events::emit(&app, AppEvent::SettingsChanged { settings: after });
+    Ok(summary)
+}
+
+/// Tauri command: save the launch history to a CSV or JSON file (by the
+/// extension), returning how many commands were written
+///
+/// See quickrun_core::history_export for the columns.
+#[tauri::command]
+fn export_history(path: String) -> Result<usize, String> {
+    history_export::export(&runner::expand_home(path.trim()))
+}
+
+/// Tauri command: merge the launch history in a CSV or JSON file into the
+/// current one, returning how many commands the file had
+#[tauri::command]
+fn import_history(app: AppHandle, path: String) -> Result<usize, String> {
+    let count = history_export::import(&runner::expand_home(path.trim()))?;
+    refresh_tray_menu(&app);
+    Ok(count)
+}
+
+/// Tauri command: the profiles and which one is active
+#[tauri::command]
+fn list_profiles() -> profiles::ProfileList {
+    profiles::overview()
+}
+
+/// Tauri command: switch to another profile, creating it (as a copy of the
+/// current one) if it doesn't exist yet
+#[tauri::command]
+fn switch_profile(app: AppHandle, name: String) -> Result<Settings, String> {
+    change_profile(&app, &name)
+}
+
+/// Switch profiles and re-apply everything that depends on settings
+///
+/// The new profile's hotkey and double-tap modifier are registered, the
+/// tray menu is rebuilt to tick it, and the windows get its settings in a
+/// SettingsChanged event (theme, controls). Aliases and suggestion sources
+/// read the active profile on every query, so they follow by themselves.
+fn change_profile(app: &AppHandle, name: &str) -> Result<Settings, String> {
+    let state = app.state::<SettingsState>();
+    let before = state.get();
+    let after = profiles::switch(name)?;
+    state.replace(after.clone());
+
+    settings_state::apply_changes(app, &before, &after);
+    refresh_tray_menu(app);
+    events::emit(app, AppEvent::SettingsChanged { settings: after.clone() });
+    Ok(after)
+}
+
+/// Tauri command: the launcher input started or ended an IME composition
+/// 
+/// The launcher hears back through an ImeComposition event (see composition.rs).
+#[tauri::command]
+fn set_composing(app: AppHandle, composing: bool) {
+    composition::set_composing(&app, composing);
+}
+
+/// List all user aliases
+#[tauri::command]
+fn list_aliases() -> Vec<aliases::Alias> {
+    aliases::load()
+}
+
+/// Add or replace an alias (including its compatibility-mode options)
+#[tauri::command]
+fn save_alias(alias: aliases::Alias) -> Result<(), String> {
+    aliases::save(alias)
+}
+
+/// Delete an alias by name
+#[tauri::command]
+fn delete_alias(name: String) -> Result<(), String> {
+    aliases::remove(&name)
+}
+
+/// Get the preferred terminal, editor, and browser
+/// 
+/// Empty strings mean "use the default" (cmd, notepad, system browser)
+#[tauri::command]
+fn get_default_apps() -> default_apps::DefaultApps {
+    default_apps::load()
+}
+
+/// Set the preferred terminal, editor, and browser
 /// 
-/// Saves the preference to settings.json for persistence across restarts.
-/// The frontend applies the theme immediately without requiring a restart.
+/// Every feature that opens one of these (the ">" prefix, web results,
+/// update release pages) picks up the change on its next use.
 #[tauri::command]
-fn set_light_mode(enabled: bool) -> Result<(), String> {
-    save_setting("light_mode", enabled)
+fn set_default_apps(state: State<SettingsState>, apps: default_apps::DefaultApps) -> Result<(), String> {
+    default_apps::save(&apps)?;
+    state.reload();
+    Ok(())
+}
+
+/// Get the search bang table (keyword → URL template)
+#[tauri::command]
+fn get_search_bangs() -> Vec<web_search::Bang> {
+    web_search::load_bangs()
+}
+
+/// Replace the search bang table
+/// 
+/// Rows without a keyword or without "{query}" in the template are dropped.
+#[tauri::command]
+fn set_search_bangs(state: State<SettingsState>, bangs: Vec<web_search::Bang>) -> Result<(), String> {
+    web_search::save_bangs(bangs)?;
+    state.reload();
+    Ok(())
+}
+
+/// Tauri command: add bangs and aliases from PowerToys Run, Wox and Flow
+/// Launcher settings found on this PC
+///
+/// Existing bangs and aliases are kept; see quickrun_core::importer for what
+/// is read. Open windows get a SettingsChanged event for the new bangs.
+#[tauri::command]
+fn import_from_launchers(app: AppHandle, state: State<SettingsState>) -> Result<importer::ImportSummary, String> {
+    let summary = importer::import()?;
+    state.reload();
+    events::emit(&app, AppEvent::SettingsChanged { settings: state.get() });
+    Ok(summary)
 }
 
 /// Check for available updates from GitHub releases
@@ -185,22 +487,76 @@ fn set_light_mode(enabled: bool) -> Result<(), String> {
 /// Queries the GitHub API to check if a newer version is available.
 /// Returns update information including version, release notes, and installer URL.
 /// 
-/// Called from the About/Settings window when user clicks "Check for Updates"
+/// Called from the About window when user clicks "Check for Updates", and
+/// by the update window (tray menu: "Check for updates") when it opens
 #[tauri::command]
-async fn check_for_update() -> Result<updater::UpdateInfo, String> {
-    updater::check_for_update_impl().await
+async fn check_for_update(app: AppHandle) -> Result<updater::UpdateInfo, String> {
+    let skip_version = app.state::<SettingsState>().get().skip_version;
+    let info = updater::check_for_update_impl(skip_version.as_deref()).await?;
+    tray_icon::set_update_available(&app, info.available);
+    Ok(info)
+}
+
+/// Tauri command: the notes of each release since this one up to `version`,
+/// newest first, for the update window to show with an update
+#[tauri::command]
+async fn get_update_changelog(version: String) -> Result<Vec<updater::ReleaseNotes>, String> {
+    updater::changelog_impl(&version).await
+}
+
+/// Stop offering the update to `version`, until a newer one is released
+/// (saved as "skip_version")
+#[tauri::command]
+fn skip_update_version(app: AppHandle, state: State<SettingsState>, version: String) -> Result<(), String> {
+    let settings = state.update(|settings| settings.skip_version = Some(version))?;
+    events::emit(&app, AppEvent::SettingsChanged { settings });
+    tray_icon::set_update_available(&app, false);
+    Ok(())
 }
 
 /// Download and install an update
 /// 
 /// Downloads the installer to the temp directory and launches it.
 /// The application should exit after calling this to allow the installer to run.
+/// With the "silent_updates" setting, the installer runs without its wizard
+/// and QuickRun exits and restarts by itself.
 /// 
 /// Parameters:
 /// - update_info: Information about the update to install
 #[tauri::command]
-async fn download_and_install_update(update_info: updater::UpdateInfo) -> Result<(), String> {
-    updater::download_and_install_impl(update_info).await
+async fn download_and_install_update(app: AppHandle, update_info: updater::UpdateInfo) -> Result<(), String> {
+    let silent = app.state::<SettingsState>().get().silent_updates;
+    updater::download_and_install_impl(&app, update_info, silent).await
+}
+
+/// Stop the update download started by download_and_install_update
+#[tauri::command]
+fn cancel_update_download() {
+    updater::cancel_download();
+}
+
+/// Tauri command: the version revert_to_previous_version goes back to, if
+/// the last update kept one
+#[tauri::command]
+fn previous_version() -> Option<String> {
+    updater::previous_version()
+}
+
+/// Go back to the version before the last update, for when it broke
+/// something (run silently with the "silent_updates" setting)
+#[tauri::command]
+fn revert_to_previous_version(app: AppHandle) -> Result<(), String> {
+    let silent = app.state::<SettingsState>().get().silent_updates;
+    updater::revert_impl(&app, silent)
+}
+
+/// Run an installer downloaded by hand, for machines that can't reach
+/// GitHub - checked as a downloaded one is (run silently with the
+/// "silent_updates" setting)
+#[tauri::command]
+fn install_update_from_file(app: AppHandle, path: String) -> Result<(), String> {
+    let silent = app.state::<SettingsState>().get().silent_updates;
+    updater::install_from_file_impl(&app, std::path::Path::new(&path), silent)
 }
 
 /// Tauri command: Get the current application version
@@ -212,31 +568,315 @@ fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+/// What the About window shows for troubleshooting (see get_app_info)
+#[derive(Debug, Clone, serde::Serialize)]
+struct AppInfo {
+    version: String,
+    /// When this exe was built (YYYY-MM-DD, UTC; see build.rs)
+    build_date: String,
+    /// The folder holding the active profile's settings.json
+    config_dir: std::path::PathBuf,
+    portable: bool,
+    /// The launcher chord, e.g. "Alt+Space"
+    hotkey: String,
+    hotkey_status: hotkey::Status,
+}
+
+/// Tauri command: version, build date, settings folder and whether the
+/// hotkey is working, for the About window
+#[tauri::command]
+fn get_app_info(state: State<SettingsState>) -> AppInfo {
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        build_date: env!("QUICKRUN_BUILD_DATE").to_string(),
+        config_dir: profiles::dir(),
+        portable: storage::is_portable(),
+        hotkey: state.get().hotkey,
+        hotkey_status: hotkey::status(),
+    }
+}
+
+/// Tauri command: restart QuickRun (e.g., after switching to portable mode,
+/// or if a window stopped responding)
+#[tauri::command]
+fn restart_app(app: AppHandle) {
+    restart(&app);
+}
+
+/// Tauri command: open the folder holding the active profile's
+/// settings.json in Explorer
+#[tauri::command]
+fn open_config_folder() -> Result<(), String> {
+    runner::open_with_shell(&profiles::dir().to_string_lossy())
+}
+
+/// Tauri command: get the best inline completion for the current input
+/// 
+/// Returns the full completed text (the user's input plus the suggested rest),
+/// or None if nothing matches. The frontend renders the extra characters as
+/// ghost text and accepts them on Tab or Right-arrow.
+/// 
+/// Sources are tried in order: history, indexed apps, then PATH
+#[tauri::command]
+fn complete(input: String) -> Option<String> {
+    completion::complete(&input)
+}
+
+/// Tauri command: get the suggestion list for the current input
+/// 
+/// Returns up to 8 rows (history, Start Menu apps, PATH commands, windows,
+/// plugins), each with its app icon as a PNG data URL. Runs on a blocking
+/// thread: icon extraction, directory scans and plugin round-trips all wait
+/// on something.
+/// 
+/// Each call cancels the one before it; a cancelled query returns None so a
+/// stale answer never replaces fresher rows.
+#[tauri::command]
+async fn suggest(query: String) -> Result<Option<Vec<results::ResultRow>>, String> {
+    let cancel = suggestions::begin_query();
+    tauri::async_runtime::spawn_blocking(move || {
+        // "file | program" looks up a relative file where Enter would run it
+        let folder = pipe::split(&query).and_then(|_| explorer::previous_folder());
+        suggestions::suggest_in(&query, folder.as_deref(), &cancel)
+    })
+        .await
+        .map_err(|e| format!("Suggestion task failed: {}", e))
+}
+
+/// Tauri command: list filesystem completions for a partial path
+/// 
+/// Returns every matching entry (directories first) so the frontend can
+/// cycle through them with Tab. Empty if the input doesn't look like a path.
+#[tauri::command]
+fn complete_path(input: String) -> Vec<String> {
+    path_completion::complete_path(&input)
+}
+
 /// Tauri command: run a command from user input
 /// 
 /// This is the core function that executes user commands.
 /// 
 /// Flow:
 /// 1. Frontend calls this when user presses Enter
-/// 2. Delegates to runner::run_command() for PATH resolution and execution
-/// 3. On success: Hides the launcher window immediately
-/// 4. On error: Returns error message to display inline in the UI
+/// 2. "ssh", "rdp" and "code" searches become the command for their best
+///    match ("> ssh web01", "mstsc /v:prod-db01", `code "<folder>"`); then
+///    if the input starts with ">", run the rest in the preferred terminal
+///    (in the folder open in Explorer, if the launcher was opened from one)
+/// 3. If the input starts with ":", copy the best emoji/symbol match; with
+///    "env", the value of the best matching environment variable; with
+///    "term", open the best matching Windows Terminal profile; with "reg"
+///    and a key path, open Registry Editor at that key
+/// 4. If the input is a file followed by "| program", open it with that
+///    alias or program (see pipe.rs)
+/// 5. If the input starts with a search bang ("g rust traits"), open the search
+/// 6. If the input starts with a plugin prefix, show that plugin's rows
+/// 7. If the input starts with a keyword (e.g., "http"), run the keyword and
+///    return its result rows - the window stays open to show them
+/// 8. Otherwise delegate to runner::run_command_in() for PATH resolution and
+///    execution, in the folder open in Explorer (see explorer.rs)
+/// 9. On success: Records the command in history (and the tray's Recent
+///    submenu) and hides the launcher window
+/// 10. If nothing resolves: Offers a "Search the web" row instead
+/// 11. On other errors: Returns error message to display inline in the UI
+/// 12. Plays the launch or error sound, if sounds are on (see sounds.rs)
+/// 13. Remembers the input for the next time the launcher opens, if
+///     "remember_last_input" is on
 /// 
 /// Why hide on Rust side?
 /// - More reliable than frontend async calls
 /// - Window hides instantly before the app even starts launching
 /// - User sees immediate feedback
 #[tauri::command]
-fn run_command(app: AppHandle, input: String) -> Result<(), String> {
-    // Run the command via the runner module
-    runner::run_command(&input)?;
+async fn run_command(app: AppHandle, input: String) -> Result<results::RunOutcome, String> {
+    if app.state::<SettingsState>().get().remember_last_input {
+        if let Err(e) = history::set_last_input(&input) {
+            eprintln!("[History] {}", e);
+        }
+    }
+    let outcome = run_input(&app, input).await;
+    match outcome {
+        Ok(results::RunOutcome::Launched) => sounds::play(&app, sounds::Cue::Launch),
+        Err(_) => sounds::play(&app, sounds::Cue::Error),
+        Ok(_) => {}
+    }
+    outcome
+}
+
+/// Steps 2-11 of run_command
+async fn run_input(app: &AppHandle, input: String) -> Result<results::RunOutcome, String> {
+    // Asking Explorer for its folder is a round trip through COM, so only
+    // what runs in a folder asks, and at most once
+    let previous_folder = std::sync::OnceLock::new();
+    let folder = || previous_folder.get_or_init(explorer::previous_folder).as_deref();
+    // "ssh web01" / "rdp prod-db01" / "code quick": Enter without choosing a
+    // row opens the ssh host, rdp connection or VS Code project with exactly
+    // that name ("> ssh web01", a PuTTY session, "mstsc /v:...",
+    // `code "<folder>"`); anything else runs as typed
+    let input = providers::ssh_hosts::keyword_query(&input)
+        .and_then(providers::ssh_hosts::exact_match)
+        .or_else(|| providers::rdp_connections::keyword_query(&input).and_then(providers::rdp_connections::exact_match))
+        .or_else(|| providers::vscode_recent::keyword_query(&input).and_then(providers::vscode_recent::exact_match))
+        .unwrap_or(input);
+    if let Some(shell_command) = input.trim().strip_prefix('>') {
+        // Terminal prefix: "> git status" runs in the preferred terminal,
+        // in the environment preset chosen for it
+        let shell_command = shell_command.trim();
+        let environment = env_presets::environment(&app.state::<SettingsState>().get().terminal_env_preset)?;
+        default_apps::open_terminal_with_env(
+            folder(),
+            (!shell_command.is_empty()).then_some(shell_command),
+            &environment,
+        )?;
+    } else if let Some(emoji_query) = input.trim().strip_prefix(':') {
+        // Emoji picker: Enter without choosing a row copies the best match
+        let character = providers::emoji::best_match(emoji_query)
+            .ok_or_else(|| format!("No emoji or symbol matches '{}'", emoji_query.trim()))?;
+        clipboard::copy_text(&character)?;
+        return Ok(results::RunOutcome::Done);
+    } else if let Some(value) =
+        providers::environment_variables::keyword_query(&input).and_then(providers::environment_variables::best_match)
+    {
+        // "env java": Enter without choosing a row copies the best match's
+        // value; a name nothing matches runs env as typed
+        clipboard::copy_text(&value)?;
+        return Ok(results::RunOutcome::Done);
+    } else if let Some(command) =
+        providers::terminal_profiles::keyword_query(&input).and_then(providers::terminal_profiles::best_match)
+    {
+        // "term ubuntu": Enter without choosing a row opens the best profile
+        runner::run_command(&command)?;
+    } else if let Some(key) = providers::registry_jump::keyword_query(&input) {
+        providers::registry_jump::open(&key)?;
+    } else if let Some((target, program)) = pipe::split(&input).and_then(|_| pipe::parse(&input, folder())) {
+        runner::open_with(program, &target, folder())?;
+    } else if let Some(rows) = plugins::prefixed_rows(&input) {
+        // Plugin prefix ("gh quickrun"): show the plugin's rows, even if a
+        // bang has the same keyword
+        return Ok(results::RunOutcome::Results { rows });
+    } else if let Some((_, url)) = web_search::bang_search(&input) {
+        default_apps::open_url(&url)?;
+    } else if let Some(result) = keywords::dispatch(app, &input).await {
+        // Keywords produce results instead of launching anything
+        return result.map(|rows| results::RunOutcome::Results { rows });
+    } else if let Err(e) = runner::run_command_in(&input, folder()) {
+        // Unknown command names fall back to a web search offer; paths,
+        // aliases, and launch failures are real errors
+        let first_word = input.split_whitespace().next().unwrap_or("");
+        if runner::is_explicit_path(input.trim())
+            || runner::resolve_on_path(first_word).is_some()
+            || aliases::find(first_word).is_some()
+        {
+            return Err(e);
+        }
+        let row = results::ResultRow::new(format!("Search the web for \"{}\"", input.trim()), e)
+            .with_action(results::Action::Open(web_search::search_url(&input)));
+        return Ok(results::RunOutcome::Results { rows: vec![row] });
+    }
+    
+    // Remember it for completion (a failed write shouldn't fail the launch)
+    if let Err(e) = history::record(&input) {
+        eprintln!("Warning: Could not save history: {}", e);
+    }
+    refresh_tray_menu(app);
     
     // Success! Hide the main window immediately
     if let Some(window) = app.get_webview_window("main") {
         let _ = window.hide();
     }
     
-    Ok(())
+    Ok(results::RunOutcome::Launched)
+}
+
+/// Tauri command: perform the action attached to a result row
+/// 
+/// Parameters:
+/// - action: The row's action, as sent to the frontend in RunOutcome::Results
+/// 
+/// Returns the outcome so that "run" actions behave exactly like typing the
+/// command: launching hides the window, keywords show new rows.
+#[tauri::command]
+async fn execute_action(app: AppHandle, action: results::Action) -> Result<results::RunOutcome, String> {
+    match action {
+        results::Action::None => return Ok(results::RunOutcome::Done),
+        results::Action::Copy(text) => {
+            clipboard::copy_text(&text)?;
+            return Ok(results::RunOutcome::Done);
+        }
+        results::Action::Run(command) => return run_command(app, command).await,
+        results::Action::Open(target) => {
+            if default_apps::is_url(&target) {
+                default_apps::open_url(&target)?;
+            } else {
+                runner::open_with_shell(&target)?;
+            }
+        }
+        results::Action::RunAsAdmin(path) => runner::spawn_elevated(&path)?,
+        results::Action::Sandbox(path) => sandbox::run_in_sandbox(&path)?,
+        results::Action::Reveal(path) => file_actions::reveal(&path)?,
+        results::Action::Focus(handle) => providers::open_windows::focus(&handle)?,
+        results::Action::Plugin { plugin, data } => {
+            // Plugins can block (they're other processes) - keep it off the async runtime
+            let outcome = tauri::async_runtime::spawn_blocking(move || plugins::execute(&plugin, data))
+                .await
+                .map_err(|e| format!("Plugin task failed: {}", e))??;
+            if let plugins::Outcome::Rows(rows) = outcome {
+                return Ok(results::RunOutcome::Results { rows });
+            }
+        }
+        results::Action::Paste(text) => {
+            clipboard::copy_text(&text)?;
+            // Get out of the way first, then hand focus back and press Ctrl+V
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.hide();
+            }
+            focus::paste_into_previous()?;
+            return Ok(results::RunOutcome::Launched);
+        }
+        results::Action::Recycle(path) => {
+            // Keep the launcher open with an undo row (a toast, in effect)
+            recycle::recycle(&path)?;
+            let row = results::ResultRow::new(
+                format!("Moved to Recycle Bin: {}", path),
+                "Select to undo",
+            )
+            .with_action(results::Action::Restore(path));
+            return Ok(results::RunOutcome::Results { rows: vec![row] });
+        }
+        results::Action::Restore(path) => {
+            recycle::restore(&path)?;
+            let row = results::ResultRow::new(format!("Restored: {}", path), "Undo complete");
+            return Ok(results::RunOutcome::Results { rows: vec![row] });
+        }
+        // Archive operations run in the background; the placeholder row
+        // shows progress and cancels when selected
+        results::Action::Compress(path) => {
+            let rows = archive::compress(&app, &path)?;
+            return Ok(results::RunOutcome::Results { rows });
+        }
+        results::Action::Extract(path) => {
+            let rows = archive::extract(&app, &path)?;
+            return Ok(results::RunOutcome::Results { rows });
+        }
+        results::Action::CancelArchive => {
+            archive::cancel();
+            return Ok(results::RunOutcome::Done);
+        }
+        results::Action::ApplyRename(args) => {
+            let rows = keywords::rename::apply(&args)?;
+            return Ok(results::RunOutcome::Results { rows });
+        }
+        results::Action::UndoRename(record) => {
+            let rows = keywords::rename::undo(&record)?;
+            return Ok(results::RunOutcome::Results { rows });
+        }
+    }
+    
+    // Something was launched - get out of the way
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    
+    Ok(results::RunOutcome::Launched)
 }
 
 /// Toggle the main launcher window: show+center+focus if hidden, hide if visible
@@ -247,58 +887,112 @@ fn run_command(app: AppHandle, input: String) -> Result<(), String> {
 /// 
 /// Behavior:
 /// - If window is visible: Hide it (dismiss the launcher)
-/// - If window is hidden: Show it, center it on current monitor, and focus input
+/// - If window is hidden: Show it, center it on the chosen monitor, and focus input
 /// 
 /// Why center every time?
 /// - User might have moved to a different monitor
-/// - Ensures launcher always appears where the user is working
+/// - Ensures launcher always appears where the user is working (see placement.rs)
 fn toggle_window<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window("main") {
-        if window.is_visible().unwrap_or(false) {
-            // Already visible → hide it
-            let _ = window.hide();
+        if just_hidden_on_blur() {
+            // The click that toggles (tray icon) is what took focus away and
+            // hid the launcher, so leave it hidden
+        } else if window.is_visible().unwrap_or(false) {
+            // Already visible → hide it (nothing was run, so hand focus back)
+            dismiss_window(&window);
         } else {
             // Hidden → show, center, and focus
-            show_and_center_window(&window);
+            show_and_center_window(&window, None);
         }
     }
 }
 
-/// Show the window, center it on the active monitor, and focus the input field
+/// Hide the launcher without running anything and give focus back to the
+/// window that was active before it opened
+/// 
+/// Without this, Windows picks the next window itself after the hide - often
+/// the desktop - so the user has to click back into what they were doing.
+fn dismiss_window<R: Runtime>(window: &WebviewWindow<R>) {
+    let _ = window.hide();
+    if let Err(e) = focus::restore_previous() {
+        eprintln!("[Focus] Could not restore previous window: {}", e);
+    }
+}
+
+/// When the launcher last hid itself because it lost focus
+static HIDDEN_ON_BLUR: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// A toggle this soon after a hide on blur comes from the same click
+const BLUR_TOGGLE_GRACE: Duration = Duration::from_millis(300);
+
+/// Hide the launcher when the user clicks another window ("hide_on_blur")
+///
+/// Focus isn't handed back: it already went where the user clicked.
+fn hide_on_blur<R: Runtime>(window: &WebviewWindow<R>) {
+    if !window.state::<SettingsState>().get().hide_on_blur || !window.is_visible().unwrap_or(false) {
+        return;
+    }
+    let _ = window.hide();
+    if let Ok(mut hidden) = HIDDEN_ON_BLUR.lock() {
+        *hidden = Some(Instant::now());
+    }
+}
+
+/// Whether the launcher hid itself on blur within BLUR_TOGGLE_GRACE
+fn just_hidden_on_blur() -> bool {
+    HIDDEN_ON_BLUR
+        .lock()
+        .ok()
+        .and_then(|hidden| *hidden)
+        .is_some_and(|at| at.elapsed() < BLUR_TOGGLE_GRACE)
+}
+
+/// Hide the launcher after the user dismissed it (Escape)
+#[tauri::command]
+fn dismiss(app: AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        dismiss_window(&window);
+    }
+}
+
+/// Show the window, center it on the chosen monitor, and focus the input field
 /// 
 /// Multi-monitor support:
-/// 1. Get the monitor the window is currently on
-/// 2. Calculate the center position of that monitor
-/// 3. Move window to center position
+/// 1. Pick the monitor ("launcher_monitor": the one with the mouse pointer,
+///    the active window, the primary one, or the last one used)
+/// 2. Calculate the center position of that monitor (or the position the
+///    user dragged the launcher to on a monitor of that size)
+/// 3. Move window to that position
 /// 4. Show the window
 /// 5. Give it keyboard focus
-/// 6. Emit "window-show" event so frontend can clear input and focus it
+/// 6. Emit a WindowShown event so frontend can clear input (or put `input`,
+///    or else the remembered last one, in it) and focus it
 /// 
 /// This ensures the launcher appears on whichever monitor the user is working on
-fn show_and_center_window<R: Runtime>(window: &WebviewWindow<R>) {
-    // Center the window on the current monitor
-    if let Ok(monitor) = window.current_monitor() {
-        if let Some(monitor) = monitor {
-            let monitor_size = monitor.size();
-            let monitor_pos = monitor.position();
-            
-            // Window size is defined in tauri.conf.json (500x80)
-            let window_size = window.outer_size().unwrap_or_default();
-            
-            // Calculate centered position
-            let x = monitor_pos.x + (monitor_size.width as i32 - window_size.width as i32) / 2;
-            let y = monitor_pos.y + (monitor_size.height as i32 - window_size.height as i32) / 2;
-            
-            let _ = window.set_position(tauri::Position::Physical(tauri::PhysicalPosition { x, y }));
+fn show_and_center_window<R: Runtime>(window: &WebviewWindow<R>, input: Option<String>) {
+    // Remember where the user was, so focus can be handed back later (and
+    // so its monitor can be found)
+    focus::remember_foreground();
+    
+    if window.state::<SettingsState>().get().all_desktops {
+        if let Err(e) = desktops::move_to_current(window) {
+            eprintln!("[Desktops] {}", e);
         }
     }
+    placement::place(window);
     
     // Show and focus the window
     let _ = window.show();
     let _ = window.set_focus();
+    sounds::play(window, sounds::Cue::Show);
     
     // Emit an event to the frontend so it can clear input and focus the textbox
-    let _ = window.emit("window-show", ());
+    let last_input = if input.is_some() || !window.state::<SettingsState>().get().remember_last_input {
+        input
+    } else {
+        history::last_input()
+    };
+    events::emit(window, AppEvent::WindowShown { last_input });
 }
 
 /// Open the settings window (or show it if already open)
@@ -327,7 +1021,7 @@ fn open_settings<R: Runtime>(app: &AppHandle<R>) {
         tauri::WebviewUrl::App("settings.html".into()),
     )
     .title("QuickRun Settings")
-    .inner_size(500.0, 320.0)
+    .inner_size(500.0, 560.0)
     .resizable(false)
     .transparent(true)
     .center()
@@ -360,37 +1054,307 @@ fn open_about<R: Runtime>(app: &AppHandle<R>) {
         tauri::WebviewUrl::App("about.html".into()),
     )
     .title("About QuickRun")
-    .inner_size(500.0, 580.0)
+    .inner_size(500.0, 760.0)
+    .resizable(false)
+    .transparent(true)
+    .center()
+    .build();
+}
+
+/// Open the update window (or show it if already open), which checks for a
+/// new release right away and offers to install it (see update.ts)
+fn open_update<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(update_window) = app.get_webview_window("update") {
+        let _ = update_window.show();
+        let _ = update_window.set_focus();
+        return;
+    }
+    
+    let _update_window = WebviewWindowBuilder::new(
+        app,
+        "update",
+        tauri::WebviewUrl::App("update.html".into()),
+    )
+    .title("QuickRun Update")
+    .inner_size(500.0, 600.0)
     .resizable(false)
     .transparent(true)
     .center()
     .build();
 }
 
+/// ID of the tray icon, for finding it again to update its menu
+const TRAY_ID: &str = "main";
+
+/// Prefix of the tray menu IDs that switch profiles ("profile:work")
+const PROFILE_MENU_PREFIX: &str = "profile:";
+
+/// Prefix of the tray menu IDs that re-run a recent command ("recent:notepad")
+const RECENT_MENU_PREFIX: &str = "recent:";
+
+/// Prefix of the tray menu IDs that run a tray action ("action:0" is the
+/// first in settings)
+const TRAY_ACTION_PREFIX: &str = "action:";
+
+/// Commands in the tray's Recent submenu
+const RECENT_MENU_SIZE: usize = 10;
+
+/// Longest label shown in full in the tray menu
+const MENU_LABEL_CHARS: usize = 60;
+
+/// `text` (a command, or a tray action's label) shortened if too long for a
+/// menu
+fn short_label(text: &str) -> String {
+    if text.chars().count() > MENU_LABEL_CHARS {
+        format!("{}…", text.chars().take(MENU_LABEL_CHARS - 1).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Tray menu label for `text`: shortened if long, with "&" kept from
+/// becoming an access key
+fn menu_label(text: &str) -> String {
+    short_label(text).replace('&', "&&")
+}
+
+/// Build the tray menu, with the user's tray actions, the most recent
+/// commands, and the active profile and "Pause hotkey" ticked
+fn tray_menu<R: Runtime, M: Manager<R>>(app: &M) -> tauri::Result<Menu<R>> {
+    let mut menu = MenuBuilder::new(app);
+    let settings = app.state::<SettingsState>().get();
+    let actions = settings.tray_actions();
+    for (i, action) in actions.iter().enumerate() {
+        let id = format!("{}{}", TRAY_ACTION_PREFIX, i);
+        menu = menu.item(&MenuItemBuilder::with_id(id, menu_label(&action.label)).build(app)?);
+    }
+    if !actions.is_empty() {
+        menu = menu.separator();
+    }
+
+    let recent = history::load();
+    let mut recent_menu = SubmenuBuilder::new(app, i18n::text("tray.recent"));
+    for entry in recent.iter().take(RECENT_MENU_SIZE) {
+        let id = format!("{}{}", RECENT_MENU_PREFIX, entry.command);
+        recent_menu = recent_menu.item(&MenuItemBuilder::with_id(id, menu_label(&entry.command)).build(app)?);
+    }
+    if recent.is_empty() {
+        let empty = MenuItemBuilder::new(i18n::text("tray.recent.empty")).enabled(false).build(app)?;
+        recent_menu = recent_menu.item(&empty);
+    }
+    let recent_menu = recent_menu.build()?;
+
+    let active = profiles::active();
+    let mut profile_menu = SubmenuBuilder::new(app, i18n::text("tray.profile"));
+    for name in profiles::list() {
+        let item = CheckMenuItemBuilder::with_id(format!("{}{}", PROFILE_MENU_PREFIX, name), &name)
+            .checked(name == active)
+            .build(app)?;
+        profile_menu = profile_menu.item(&item);
+    }
+    let profile_menu = profile_menu.build()?;
+
+    let pause_item = CheckMenuItemBuilder::with_id("pause_hotkey", i18n::text("tray.pause_hotkey"))
+        .checked(hotkey::is_paused())
+        .build(app)?;
+    let about_item = MenuItemBuilder::with_id("about", i18n::text("tray.about")).build(app)?;
+    let settings_item = MenuItemBuilder::with_id("settings", i18n::text("tray.settings")).build(app)?;
+    let update_item = MenuItemBuilder::with_id("check_updates", i18n::text("tray.check_updates")).build(app)?;
+    let restart_item = MenuItemBuilder::with_id("restart", i18n::text("tray.restart")).build(app)?;
+    let quit_item = MenuItemBuilder::with_id("quit", i18n::text("tray.quit")).build(app)?;
+
+    menu.item(&recent_menu)
+        .item(&profile_menu)
+        .item(&pause_item)
+        .separator()
+        .item(&settings_item)
+        .item(&update_item)
+        .item(&about_item)
+        .separator()
+        .item(&restart_item)
+        .item(&quit_item)
+        .build()
+}
+
+/// Run `command` from the tray (the Recent submenu or a tray action), the
+/// command line (--run) or a link, as if it were typed into the launcher
+///
+/// Anything that doesn't simply launch (an error, or rows to choose from)
+/// opens the launcher with the command in it, to see what happened.
+fn rerun(app: &AppHandle, command: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match run_input(&app, command.clone()).await {
+            Ok(results::RunOutcome::Launched) => sounds::play(&app, sounds::Cue::Launch),
+            Ok(results::RunOutcome::Done) => {}
+            outcome => {
+                if let Err(e) = outcome {
+                    notify::error(&app, &i18n::format("notify.run_failed", &[&command]), &e);
+                    sounds::play(&app, sounds::Cue::Error);
+                }
+                if let Some(window) = app.get_webview_window("main") {
+                    show_and_center_window(&window, Some(command));
+                }
+            }
+        }
+    });
+}
+
+/// Another copy of QuickRun was started while this one runs: it exits right
+/// away, and this one does what its command line asked (e.g., --run), or
+/// with no arguments (e.g., from the Start menu) opens or closes the
+/// launcher in its place. A start at login (--silent) has nothing to show.
+fn on_second_instance(app: &AppHandle, args: Vec<String>) {
+    eprintln!("[App] Another copy was started: {:?}", args);
+    let silent = startup::is_silent_launch(args.clone());
+    if !handle_args(app, args) && !silent {
+        toggle_window(app);
+    }
+}
+
+/// Do what a command line asked for (see quickrun_core::launch_args): a
+/// quickrun:// link, or else its flags; false if it asked for nothing
+fn handle_args(app: &AppHandle, args: Vec<String>) -> bool {
+    // Anything else on a link's command line may have come from the link
+    if let Some(link) = launch_args::find_url(&args) {
+        deep_link::open(app, link);
+        return true;
+    }
+
+    let requests = match launch_args::parse(args) {
+        Ok(requests) => requests,
+        Err(e) => {
+            notify::error(app, &i18n::text("notify.bad_arguments"), &e);
+            return true;
+        }
+    };
+    for request in &requests {
+        handle_request(app, request);
+    }
+    !requests.is_empty()
+}
+
+/// Do one thing asked for on the command line or by a link
+fn handle_request(app: &AppHandle, request: &launch_args::Request) {
+    let window = app.get_webview_window("main");
+    match request {
+        launch_args::Request::Run(command) => rerun(app, command.clone()),
+        launch_args::Request::Show => {
+            if let Some(window) = window.filter(|window| !window.is_visible().unwrap_or(false)) {
+                show_and_center_window(&window, None);
+            }
+        }
+        launch_args::Request::Hide => {
+            if let Some(window) = window.filter(|window| window.is_visible().unwrap_or(false)) {
+                dismiss_window(&window);
+            }
+        }
+        launch_args::Request::Settings => open_settings(app),
+        launch_args::Request::Input(text) => {
+            if let Some(window) = window {
+                show_and_center_window(&window, Some(text.clone()));
+            }
+        }
+    }
+}
+
+/// Start a fresh copy of QuickRun and exit this one
+///
+/// The hotkey is released, the tray icon removed and the single-instance
+/// lock dropped first, so the new copy can register the same chord, no dead
+/// icon is left in the tray, and it isn't turned away as a second copy.
+/// The new copy gets the same arguments minus --silent, so it doesn't wait
+/// out "startup_delay_secs".
+fn restart(app: &AppHandle) -> ! {
+    eprintln!("[App] Restarting");
+    hotkey::stop(app);
+    let _ = app.remove_tray_by_id(TRAY_ID);
+    tauri_plugin_single_instance::destroy(app);
+
+    let mut env = app.env();
+    env.args_os.retain(|arg| arg != startup::SILENT_FLAG);
+    // tauri::process::restart exits without RunEvent::Exit, so pending
+    // history is written here
+    if let Err(e) = history::flush() {
+        eprintln!("Warning: Could not save history: {}", e);
+    }
+    app.cleanup_before_exit();
+    tauri::process::restart(&env)
+}
+
+/// Rebuild the tray menu (e.g., after the active profile changed), and the
+/// jump list, which shows the same recent commands
+fn refresh_tray_menu(app: &AppHandle) {
+    jump_list::refresh(app);
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match tray_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("[Tray] Failed to rebuild the menu: {}", e),
+    }
+}
+
+/// Start the hotkey, the app index and the watchers (right away, or after
+/// "startup_delay_secs" when started at login)
+fn start_listening(app: &AppHandle) {
+    // Register the global hotkey (Alt+Space unless changed in settings).
+    // This works even when the app is not focused, and follows
+    // keyboard layout switches (see hotkey.rs).
+    // Note: If registration fails, another app (like PowerToys) might be using it.
+    let app_handle = app.clone();
+    // While paused (tray menu) neither the hotkey nor a double-tap opens it
+    let activate: std::sync::Arc<dyn Fn() + Send + Sync> = std::sync::Arc::new(move || {
+        if !hotkey::is_paused() {
+            toggle_window(&app_handle);
+        }
+    });
+    let on_hotkey = activate.clone();
+    hotkey::start(app, move || on_hotkey());
+    
+    // Optional double-tap of a modifier (e.g., Ctrl, Ctrl) - off by default
+    double_tap::start(activate);
+    
+    // Serve the cached app index right away and rebuild it in the
+    // background, so startup isn't delayed by scanning the Start Menu
+    let handle = app.clone();
+    index::start_scheduler(move |update| {
+        events::emit(&handle, AppEvent::IndexUpdated(update));
+    });
+    
+    // Clipboard history watcher (idle unless enabled in Settings)
+    providers::clipboard_history::start_watcher();
+    
+    // Downloads watcher (idle unless enabled in Settings)
+    providers::downloads::start_watcher();
+
+    // Background update checks (every "update_check_hours", 0 = off)
+    updater::start_scheduler(app);
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // First, so a second copy exits before setting anything else up
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| on_second_instance(app, args)))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(updater::plugin())
+        .manage(SettingsState::load())
         .setup(|app| {
             // Build the system tray menu
-            let about_item = MenuItemBuilder::with_id("about", "About").build(app)?;
-            let settings_item = MenuItemBuilder::with_id("settings", "Settings").build(app)?;
-            let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
-            
-            let menu = MenuBuilder::new(app)
-                .item(&settings_item)
-                .item(&about_item)
-                .separator()
-                .item(&quit_item)
-                .build()?;
+            let menu = tray_menu(app.handle())?;
             
             // Create the tray icon
             // Load the icon from the generated icon files
             let icon = app.default_window_icon().unwrap().clone();
             
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(icon)
                 .tooltip("QuickRun - Press Alt+Space")
                 .menu(&menu)
@@ -399,8 +1363,34 @@ pub fn run() {
                     match event.id().as_ref() {
                         "about" => open_about(app),
                         "settings" => open_settings(app),
+                        "check_updates" => open_update(app),
+                        "pause_hotkey" => {
+                            hotkey::set_paused(!hotkey::is_paused());
+                            refresh_tray_menu(app);
+                            tray_icon::refresh(app);
+                        }
+                        "restart" => restart(app),
                         "quit" => app.exit(0),
-                        _ => {}
+                        id => {
+                            if let Some(command) = id.strip_prefix(RECENT_MENU_PREFIX) {
+                                rerun(app, command.to_string());
+                            } else if let Some(i) = id.strip_prefix(TRAY_ACTION_PREFIX) {
+                                let settings = app.state::<SettingsState>().get();
+                                let command = i.parse::<usize>().ok().and_then(|i| {
+                                    settings.tray_actions().get(i).map(|action| action.command.clone())
+                                });
+                                if let Some(command) = command {
+                                    rerun(app, command);
+                                }
+                            } else if let Some(name) = id.strip_prefix(PROFILE_MENU_PREFIX) {
+                                if let Err(e) = change_profile(app, name) {
+                                    eprintln!("[Profiles] {}", e);
+                                    // A check item ticks itself when clicked;
+                                    // put the tick back on the active profile
+                                    refresh_tray_menu(app);
+                                }
+                            }
+                        }
                     }
                 })
                 .on_tray_icon_event(|tray, event| {
@@ -416,41 +1406,160 @@ pub fn run() {
                 })
                 .build(app)?;
             
-            // Register the global hotkey: Alt+Space
-            // This works even when the app is not focused.
-            // Note: If this fails, another app (like PowerToys) might be using Alt+Space.
-            let shortcut = "Alt+Space".parse::<Shortcut>().unwrap();
+            // Tasks and recent commands for the taskbar and Start entries
+            jump_list::refresh(app.handle());
+
+            // Draw the icon for the taskbar mode and settings, and follow them
+            tray_icon::refresh(app.handle());
+            tray_icon::watch(app.handle());
             
-            let app_handle = app.handle().clone();
+            if let Some(window) = app.get_webview_window("main") {
+                desktops::apply(&window, &app.state::<SettingsState>().get());
+            }
+            backdrop::apply(app.handle());
+            scale::apply(app.handle());
             
-            // on_shortcut() automatically registers the hotkey
-            // We wrap it in a match to gracefully handle conflicts
-            if let Err(e) = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, event| {
-                if event.state == ShortcutState::Pressed {
-                    toggle_window(&app_handle);
-                }
-            }) {
-                eprintln!("Warning: Could not register Alt+Space hotkey: {}", e);
-                eprintln!("The app will still work via the tray icon (click to toggle).");
+            // Started at login (--silent): give the rest of the login a
+            // head start before registering the hotkey and scanning for apps
+            let delay = if startup::is_silent_launch(std::env::args()) {
+                app.state::<SettingsState>().get().startup_delay()
+            } else {
+                Duration::ZERO
+            };
+            if delay.is_zero() {
+                start_listening(app.handle());
+            } else {
+                eprintln!("[Startup] Started at login, waiting {}s", delay.as_secs());
+                let handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(delay);
+                    start_listening(&handle);
+                });
             }
             
+            // Follow Windows' reduced effects and battery saver settings
+            let handle = app.handle().clone();
+            resource_policy::watch(move |policy| {
+                events::emit(&handle, AppEvent::ResourcePolicyChanged { policy });
+                backdrop::apply(&handle);
+            });
+            
+            // Launch history is kept in memory and written in the
+            // background (flushed on exit, below)
+            history::start_writer();
+            
+            // Apply hand edits to settings.json (or ones synced in from
+            // another machine) without a restart
+            settings_state::watch(app.handle());
+            
+            // Keep history and the index cache within their limits
+            retention::start_maintenance();
+            
             // Start with the window hidden (user must press Alt+Space to show it)
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.hide();
             }
             
+            // Local HTTP API for Stream Deck actions and scripts (off by default)
+            http_api::apply(app.handle());
+            
+            // Keep the quickrun:// registration pointing at this exe
+            if app.state::<SettingsState>().get().deep_links {
+                deep_link::apply(app.handle(), true);
+            }
+            
+            // Start with Windows as set up by an older version: add --silent
+            if let Some(exe_path) = std::env::current_exe().ok().filter(|_| !storage::is_portable()) {
+                if let Err(e) = startup::refresh(&os::SystemRegistry, &exe_path) {
+                    eprintln!("[Startup] {}", e);
+                }
+                std::thread::spawn(move || {
+                    if let Err(e) = startup::refresh_task(&exe_path) {
+                        eprintln!("[Startup] {}", e);
+                    }
+                });
+            }
+            
+            // Started with --run, --show, a link, ... (later copies forward
+            // theirs through on_second_instance)
+            handle_args(app.handle(), std::env::args().collect());
+            
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Losing focus is when a drag of the launcher is finished with,
+            // and when the user has clicked somewhere else
+            if let tauri::WindowEvent::Focused(false) = event {
+                if window.label() == "main" {
+                    if let Some(launcher) = window.get_webview_window("main") {
+                        placement::remember_if_moved(&launcher);
+                        hide_on_blur(&launcher);
+                    }
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             run_command,
+            dismiss,
+            execute_action,
+            complete,
+            suggest,
+            complete_path,
             is_startup_enabled,
+            is_context_menu_enabled,
+            set_context_menu_enabled,
             set_startup_enabled,
-            is_light_mode,
-            set_light_mode,
+            is_elevated_startup_enabled,
+            set_elevated_startup_enabled,
+            get_storage,
+            get_storage_usage,
+            run_maintenance,
+            list_profiles,
+            switch_profile,
+            get_settings,
+            get_strings,
+            update_settings,
+            get_theme,
+            get_resource_policy,
+            get_backdrop,
+            get_ui_scale,
+            reset_launcher_position,
+            export_settings,
+            import_settings,
+            export_history,
+            import_history,
+            list_aliases,
+            save_alias,
+            delete_alias,
+            get_default_apps,
+            get_search_bangs,
+            set_search_bangs,
+            import_from_launchers,
+            set_composing,
+            set_default_apps,
             check_for_update,
+            get_update_changelog,
+            skip_update_version,
             download_and_install_update,
-            get_app_version
+            cancel_update_download,
+            previous_version,
+            revert_to_previous_version,
+            install_update_from_file,
+            get_app_version,
+            get_app_info,
+            get_http_api_token,
+            new_http_api_token,
+            open_config_folder,
+            restart_app
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            // Write any history the background writer hasn't got to yet
+            if let tauri::RunEvent::Exit = event {
+                if let Err(e) = history::flush() {
+                    eprintln!("Warning: Could not save history: {}", e);
+                }
+            }
+        });
 }
@@ -0,0 +1,50 @@
+// notify.rs - Toasts for things nobody is looking at
+//
+// Errors from things the user didn't start in the launcher window (the
+// hotkey failing to register, a command run from the tray) would otherwise
+// only reach the log. error() shows them as a Windows toast (through the
+// notification plugin) as well as logging them; info() does the same for
+// news found in the background (a new release).
+//
+// The same toast isn't repeated within REPEAT_AFTER, so a failure that
+// keeps happening (e.g., the hotkey on every settings reload) doesn't bury
+// the notification center.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+/// How long before the same toast may show again
+const REPEAT_AFTER: Duration = Duration::from_secs(60);
+
+/// The last toast shown, and when
+static LAST: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+/// Tell the user something went wrong in the background
+pub fn error<R: Runtime>(app: &AppHandle<R>, title: &str, message: &str) {
+    eprintln!("[Notify] {}: {}", title, message);
+
+    let key = format!("{}\n{}", title, message);
+    {
+        let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+        if last.as_ref().is_some_and(|(shown, at)| *shown == key && at.elapsed() < REPEAT_AFTER) {
+            return;
+        }
+        *last = Some((key, Instant::now()));
+    }
+
+    show(app, title, message);
+}
+
+/// Tell the user about something found in the background
+pub fn info<R: Runtime>(app: &AppHandle<R>, title: &str, message: &str) {
+    eprintln!("[Notify] {}: {}", title, message);
+    show(app, title, message);
+}
+
+fn show<R: Runtime>(app: &AppHandle<R>, title: &str, message: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(message).show() {
+        eprintln!("[Notify] Failed to show a notification: {}", e);
+    }
+}
@@ -0,0 +1,158 @@
+// placement.rs - Where the launcher opens
+//
+// Each time the launcher is shown it goes on a monitor chosen by
+// "launcher_monitor" in settings.json:
+// - "cursor" (default): the monitor the mouse pointer is on
+// - "focused_window": the monitor showing the window that was active
+//   (recorded by focus.rs just before the launcher is shown)
+// - "primary": the primary monitor
+// - "remembered": the monitor the launcher was on last time
+//
+// If the chosen monitor can't be found (no pointer, the active window is
+// gone), the launcher falls back to the one it was on last time, then to
+// the primary monitor.
+//
+// On that monitor it's centered, unless the user has dragged it somewhere:
+// when the launcher loses focus away from where it was put, its position
+// is saved in "launcher_positions", keyed by the monitor's resolution, and
+// used from then on for monitors of that size. reset() forgets them all.
+
+use crate::focus;
+use crate::settings_state::SettingsState;
+use quickrun_core::settings::{LauncherMonitor, WindowOffset};
+use std::sync::Mutex;
+use tauri::{Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow};
+
+/// Where the launcher was last put by place() (None = not shown yet)
+static PLACED: Mutex<Option<PhysicalPosition<i32>>> = Mutex::new(None);
+
+/// Key for a monitor in "launcher_positions" (its resolution, "2560x1440")
+fn monitor_key(size: &PhysicalSize<u32>) -> String {
+    format!("{}x{}", size.width, size.height)
+}
+
+/// The monitor to open the launcher on
+fn target_monitor<R: Runtime>(window: &WebviewWindow<R>, mode: LauncherMonitor) -> Option<Monitor> {
+    let chosen = match mode {
+        LauncherMonitor::Cursor => window
+            .cursor_position()
+            .ok()
+            .and_then(|point| window.monitor_from_point(point.x, point.y).ok().flatten()),
+        LauncherMonitor::FocusedWindow => focus::previous_center()
+            .and_then(|(x, y)| window.monitor_from_point(x as f64, y as f64).ok().flatten()),
+        LauncherMonitor::Primary => window.primary_monitor().ok().flatten(),
+        LauncherMonitor::Remembered => None,
+    };
+
+    chosen
+        .or_else(|| window.current_monitor().ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
+
+/// Position of a `window_size` window on a monitor at `monitor_pos` of
+/// `monitor_size`: at `offset` if there is one (kept fully on the monitor),
+/// centered otherwise
+fn position_on(
+    monitor_pos: PhysicalPosition<i32>,
+    monitor_size: PhysicalSize<u32>,
+    window_size: PhysicalSize<u32>,
+    offset: Option<WindowOffset>,
+) -> PhysicalPosition<i32> {
+    let free_x = monitor_size.width as i32 - window_size.width as i32;
+    let free_y = monitor_size.height as i32 - window_size.height as i32;
+    let (x, y) = match offset {
+        Some(offset) => (offset.x.clamp(0, free_x.max(0)), offset.y.clamp(0, free_y.max(0))),
+        None => (free_x / 2, free_y / 2),
+    };
+    PhysicalPosition { x: monitor_pos.x + x, y: monitor_pos.y + y }
+}
+
+/// Move the launcher to where it should open (call before showing it)
+pub fn place<R: Runtime>(window: &WebviewWindow<R>) {
+    let settings = window.state::<SettingsState>().get();
+    let Some(monitor) = target_monitor(window, settings.launcher_monitor) else {
+        return;
+    };
+
+    // Window size is defined in tauri.conf.json (500x80)
+    let window_size = window.outer_size().unwrap_or_default();
+    let offset = settings.launcher_positions.get(&monitor_key(monitor.size())).copied();
+    let position = position_on(*monitor.position(), *monitor.size(), window_size, offset);
+
+    let _ = window.set_position(tauri::Position::Physical(position));
+    if let Ok(mut placed) = PLACED.lock() {
+        *placed = Some(position);
+    }
+}
+
+/// Save the launcher's position if the user dragged it away from where
+/// place() put it (call when it loses focus)
+pub fn remember_if_moved<R: Runtime>(window: &WebviewWindow<R>) {
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let Ok(mut placed) = PLACED.lock() else {
+        return;
+    };
+    if placed.is_none_or(|placed| placed == position) {
+        return;
+    }
+    let Some(monitor) = window.current_monitor().ok().flatten() else {
+        return;
+    };
+
+    let key = monitor_key(monitor.size());
+    let offset = WindowOffset { x: position.x - monitor.position().x, y: position.y - monitor.position().y };
+    let saved = window.state::<SettingsState>().update(|settings| {
+        settings.launcher_positions.insert(key.clone(), offset);
+    });
+    match saved {
+        Ok(_) => {
+            eprintln!("[Placement] Launcher position saved for {} monitors", key);
+            *placed = Some(position);
+        }
+        Err(e) => eprintln!("[Placement] {}", e),
+    }
+}
+
+/// Forget the saved positions, so the launcher is centered again
+pub fn reset<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    window.state::<SettingsState>().update(|settings| settings.launcher_positions.clear())?;
+    if window.is_visible().unwrap_or(false) {
+        place(window);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR_POS: PhysicalPosition<i32> = PhysicalPosition { x: 1920, y: 0 };
+    const MONITOR_SIZE: PhysicalSize<u32> = PhysicalSize { width: 2560, height: 1440 };
+    const WINDOW_SIZE: PhysicalSize<u32> = PhysicalSize { width: 500, height: 80 };
+
+    #[test]
+    fn without_a_saved_position_the_launcher_is_centered() {
+        let position = position_on(MONITOR_POS, MONITOR_SIZE, WINDOW_SIZE, None);
+        assert_eq!(position, PhysicalPosition { x: 1920 + 1030, y: 680 });
+    }
+
+    #[test]
+    fn a_saved_position_is_relative_to_the_monitor() {
+        let position = position_on(MONITOR_POS, MONITOR_SIZE, WINDOW_SIZE, Some(WindowOffset { x: 100, y: 50 }));
+        assert_eq!(position, PhysicalPosition { x: 2020, y: 50 });
+    }
+
+    #[test]
+    fn a_saved_position_is_kept_on_the_monitor() {
+        let offset = WindowOffset { x: 5000, y: -20 };
+        let position = position_on(MONITOR_POS, MONITOR_SIZE, WINDOW_SIZE, Some(offset));
+        assert_eq!(position, PhysicalPosition { x: 1920 + 2060, y: 0 });
+    }
+
+    #[test]
+    fn monitors_are_keyed_by_resolution() {
+        assert_eq!(monitor_key(&MONITOR_SIZE), "2560x1440");
+    }
+}
@@ -0,0 +1,138 @@
+// recycle.rs - Move files to the Recycle Bin (and undo it)
+//
+// Deleting from the launcher must never be permanent, so files and folders
+// go to the Recycle Bin via SHFileOperation with FOF_ALLOWUNDO - the same
+// thing Explorer's Delete key does.
+//
+// Undo works by reading the Recycle Bin's own metadata. For every recycled
+// item Windows writes two files in <drive>:\$Recycle.Bin\<user SID>\:
+// - $Rxxxxxx.ext - the item itself (renamed)
+// - $Ixxxxxx.ext - metadata: original path and deletion time
+// Restoring = finding the newest $I whose original path matches, moving the
+// matching $R back, and removing the $I.
+
+use std::path::{Path, PathBuf};
+
+/// Move `path` (file or folder) to the Recycle Bin
+#[cfg(windows)]
+pub fn recycle(path: &str) -> Result<(), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::{SHFileOperationW, SHFILEOPSTRUCTW};
+
+    const FO_DELETE: u32 = 0x0003;
+    const FOF_SILENT: u16 = 0x0004;
+    const FOF_NOCONFIRMATION: u16 = 0x0010;
+    const FOF_ALLOWUNDO: u16 = 0x0040;
+    const FOF_NOERRORUI: u16 = 0x0400;
+
+    if !Path::new(path).exists() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    // pFrom is a list of paths, terminated by an extra NUL
+    let from: Vec<u16> = path.encode_utf16().chain([0, 0]).collect();
+
+    let mut operation = SHFILEOPSTRUCTW {
+        wFunc: FO_DELETE,
+        pFrom: PCWSTR(from.as_ptr()),
+        fFlags: FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_SILENT | FOF_NOERRORUI,
+        ..Default::default()
+    };
+
+    let result = unsafe { SHFileOperationW(&mut operation) };
+    if result != 0 || operation.fAnyOperationsAborted.as_bool() {
+        return Err(format!("Failed to move '{}' to the Recycle Bin (error {})", path, result));
+    }
+
+    eprintln!("[Recycle] Moved to Recycle Bin: {}", path);
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn recycle(path: &str) -> Result<(), String> {
+    Err(format!("Moving '{}' to the Recycle Bin is only supported on Windows", path))
+}
+
+/// Parse a $I metadata file: (deletion time as FILETIME, original path)
+///
+/// Layout (Windows 10+, version 2):
+/// - 8 bytes: version
+/// - 8 bytes: original size
+/// - 8 bytes: deletion time (FILETIME)
+/// - 4 bytes: path length in UTF-16 units (including NUL)
+/// - path, UTF-16LE
+fn parse_metadata(data: &[u8]) -> Option<(u64, PathBuf)> {
+    let version = u64::from_le_bytes(data.get(0..8)?.try_into().ok()?);
+    let deleted_at = u64::from_le_bytes(data.get(16..24)?.try_into().ok()?);
+
+    let path_bytes = if version >= 2 {
+        let len = u32::from_le_bytes(data.get(24..28)?.try_into().ok()?) as usize;
+        data.get(28..28 + len * 2)?
+    } else {
+        // Vista-8.1: fixed 260-character (MAX_PATH) buffer at offset 24
+        data.get(24..24 + 520)?
+    };
+
+    let units: Vec<u16> = path_bytes
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+
+    Some((deleted_at, PathBuf::from(String::from_utf16(&units).ok()?)))
+}
+
+/// Restore the most recently recycled item that came from `original`
+pub fn restore(original: &str) -> Result<(), String> {
+    let original_path = Path::new(original);
+    if original_path.exists() {
+        return Err(format!("'{}' already exists - not overwriting it", original));
+    }
+
+    // The Recycle Bin lives on the same drive as the original file
+    let drive = original
+        .get(..2)
+        .filter(|d| d.ends_with(':'))
+        .ok_or_else(|| format!("Can't determine drive for '{}'", original))?;
+    let bin = PathBuf::from(format!("{}\\$Recycle.Bin", drive));
+
+    // Newest matching $I file across the (readable) per-user folders
+    let mut best: Option<(u64, PathBuf)> = None;
+    for user_dir in std::fs::read_dir(&bin)
+        .map_err(|e| format!("Failed to read Recycle Bin: {}", e))?
+        .flatten()
+    {
+        let Ok(entries) = std::fs::read_dir(user_dir.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("$I") {
+                continue;
+            }
+            let Some((deleted_at, path)) = std::fs::read(entry.path())
+                .ok()
+                .and_then(|data| parse_metadata(&data))
+            else {
+                continue;
+            };
+            let matches = path.to_string_lossy().eq_ignore_ascii_case(original);
+            if matches && best.as_ref().map(|(t, _)| deleted_at > *t).unwrap_or(true) {
+                best = Some((deleted_at, entry.path()));
+            }
+        }
+    }
+
+    let (_, info_file) = best.ok_or_else(|| format!("'{}' is not in the Recycle Bin", original))?;
+
+    // $Ixxxxxx.ext ↔ $Rxxxxxx.ext
+    let info_name = info_file.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let data_file = info_file.with_file_name(format!("$R{}", &info_name[2..]));
+
+    std::fs::rename(&data_file, original_path)
+        .map_err(|e| format!("Failed to restore '{}': {}", original, e))?;
+    let _ = std::fs::remove_file(&info_file);
+
+    eprintln!("[Recycle] Restored: {}", original);
+    Ok(())
+}
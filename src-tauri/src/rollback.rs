@@ -0,0 +1,193 @@
+// rollback.rs - Keeping the version before an update, to go back to
+//
+// For when a new release breaks something the user relies on, updates keep
+// what's needed to reinstall the version before, in the "updates" folder of
+// the config folder:
+// - An installed QuickRun keeps each installer its updater runs, as
+//   QuickRun-<version>-setup.exe, and the installer of the version it
+//   updates from: the first version was installed by hand, so the updater
+//   downloads its installer from its own release before updating (see
+//   updater::keep_running_installer).
+// - A portable copy keeps the exe an update replaces, as
+//   QuickRun-<version>-portable.exe.
+// Only the running version's file and the one being installed are kept, so
+// the folder holds two at most.
+//
+// An installer's updater signature is kept with it, as "<installer>.sig",
+// for going back (see updater::revert_impl) to check it again before the
+// kept installer is run; a portable copy's kept exe is swapped back in once
+// its Authenticode signature checks out again.
+
+use crate::os::FileSystem;
+use crate::updater::compare_versions;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// Folder (in the config folder) the files are kept in
+const DIR: &str = "updates";
+/// Start of every kept file's name, before the version
+const PREFIX: &str = "QuickRun-";
+
+/// What's kept of a version
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    /// Its installer
+    Installer,
+    /// Its portable exe
+    Portable,
+}
+
+impl Kind {
+    /// End of a kept file's name, after the version
+    fn suffix(self) -> &'static str {
+        match self {
+            Kind::Installer => "-setup.exe",
+            Kind::Portable => "-portable.exe",
+        }
+    }
+}
+
+/// A version that can be gone back to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kept {
+    pub version: String,
+    pub path: PathBuf,
+}
+
+/// Where the files are kept
+pub fn dir() -> PathBuf {
+    crate::get_config_dir().join(DIR)
+}
+
+/// Keep `file` as `version`'s (`kind`), with its updater signature if it has
+/// one beside it (see signature_of), and delete those kept of versions other
+/// than it and `running_version`
+pub fn keep(
+    fs: &dyn FileSystem,
+    dir: &Path,
+    kind: Kind,
+    file: &Path,
+    version: &str,
+    running_version: &str,
+) -> Result<(), String> {
+    let path = dir.join(format!("{}{}{}", PREFIX, version, kind.suffix()));
+    fs.copy(file, &path)?;
+    if fs.is_file(&signature_of(file)) {
+        fs.copy(&signature_of(file), &signature_of(&path))?;
+    } else {
+        fs.remove_file(&signature_of(&path))?;
+    }
+    eprintln!("[Rollback] Kept {}", path.display());
+
+    for kept in kept(fs, dir, kind) {
+        if kept.version != version && kept.version != running_version {
+            fs.remove_file(&kept.path)?;
+            fs.remove_file(&signature_of(&kept.path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Where the updater signature of the file at `path` is: "<path>.sig"
+pub fn signature_of(path: &Path) -> PathBuf {
+    let mut signature = path.as_os_str().to_owned();
+    signature.push(".sig");
+    PathBuf::from(signature)
+}
+
+/// Whether `version`'s file (`kind`) is kept, with its updater signature if
+/// it's an installer
+pub fn is_kept(fs: &dyn FileSystem, dir: &Path, kind: Kind, version: &str) -> bool {
+    kept(fs, dir, kind).iter().any(|kept| {
+        kept.version == version && (kind == Kind::Portable || fs.is_file(&signature_of(&kept.path)))
+    })
+}
+
+/// The newest version before `running_version` that's kept (`kind`)
+pub fn previous(fs: &dyn FileSystem, dir: &Path, kind: Kind, running_version: &str) -> Option<Kept> {
+    kept(fs, dir, kind)
+        .into_iter()
+        .filter(|kept| compare_versions(&kept.version, running_version) == Ordering::Less)
+        .max_by(|a, b| compare_versions(&a.version, &b.version))
+}
+
+/// Every version kept in `dir` (`kind`)
+fn kept(fs: &dyn FileSystem, dir: &Path, kind: Kind) -> Vec<Kept> {
+    fs.read_dir(dir)
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let version = name.strip_prefix(PREFIX)?.strip_suffix(kind.suffix())?.to_string();
+            Some(Kept { version, path })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::MemoryFs;
+
+    fn kept_file(dir: &Path, version: &str, kind: Kind) -> PathBuf {
+        dir.join(format!("{}{}{}", PREFIX, version, kind.suffix()))
+    }
+
+    #[test]
+    fn only_the_running_and_new_versions_are_kept() {
+        let dir = Path::new("config").join(DIR);
+        let fs = MemoryFs::new();
+        let installer = Path::new("temp").join("QuickRun_1.3.0_x64-setup.exe");
+        fs.write(&installer, b"MZ 1.3.0").unwrap();
+        for version in ["1.1.0", "1.2.0"] {
+            fs.write(&kept_file(&dir, version, Kind::Installer), b"MZ").unwrap();
+        }
+        fs.write(&kept_file(&dir, "1.1.0", Kind::Portable), b"MZ").unwrap();
+
+        keep(&fs, &dir, Kind::Installer, &installer, "1.3.0", "1.2.0").unwrap();
+
+        assert_eq!(fs.contents(&kept_file(&dir, "1.3.0", Kind::Installer)).as_deref(), Some(&b"MZ 1.3.0"[..]));
+        assert!(!fs.is_file(&signature_of(&kept_file(&dir, "1.3.0", Kind::Installer))));
+        assert!(fs.is_file(&kept_file(&dir, "1.2.0", Kind::Installer)));
+        assert!(!fs.is_file(&kept_file(&dir, "1.1.0", Kind::Installer)));
+        // Kinds are kept apart
+        assert!(fs.is_file(&kept_file(&dir, "1.1.0", Kind::Portable)));
+    }
+
+    #[test]
+    fn signatures_are_kept_and_deleted_with_their_installer() {
+        let dir = Path::new("config").join(DIR);
+        let fs = MemoryFs::new();
+        let installer = |version: &str| Path::new("temp").join(format!("QuickRun_{}_x64-setup.exe", version));
+        for version in ["1.2.0", "1.3.0", "1.4.0"] {
+            fs.write(&installer(version), version.as_bytes()).unwrap();
+            fs.write(&signature_of(&installer(version)), b"signature").unwrap();
+        }
+
+        keep(&fs, &dir, Kind::Installer, &installer("1.2.0"), "1.2.0", "1.2.0").unwrap();
+        keep(&fs, &dir, Kind::Installer, &installer("1.3.0"), "1.3.0", "1.2.0").unwrap();
+        let kept_signature = signature_of(&kept_file(&dir, "1.2.0", Kind::Installer));
+        assert_eq!(fs.contents(&kept_signature).as_deref(), Some(&b"signature"[..]));
+
+        keep(&fs, &dir, Kind::Installer, &installer("1.4.0"), "1.4.0", "1.3.0").unwrap();
+        assert!(!fs.is_file(&kept_signature));
+        assert!(fs.is_file(&signature_of(&kept_file(&dir, "1.4.0", Kind::Installer))));
+        // A signature isn't mistaken for a kept version
+        assert_eq!(kept(&fs, &dir, Kind::Installer).len(), 2);
+    }
+
+    #[test]
+    fn previous_is_the_newest_version_before_the_running_one() {
+        let dir = Path::new("config").join(DIR);
+        let fs = MemoryFs::new();
+        for version in ["1.2.0", "1.10.0", "1.11.0"] {
+            fs.write(&kept_file(&dir, version, Kind::Installer), b"MZ").unwrap();
+        }
+        fs.write(&dir.join("notes.txt"), b"").unwrap();
+
+        let found = previous(&fs, &dir, Kind::Installer, "1.11.0").unwrap();
+        assert_eq!(found.version, "1.10.0");
+        assert_eq!(found.path, kept_file(&dir, "1.10.0", Kind::Installer));
+        assert_eq!(previous(&fs, &dir, Kind::Installer, "1.2.0"), None);
+        assert_eq!(previous(&fs, &dir, Kind::Portable, "2.0.0"), None);
+    }
+}
@@ -0,0 +1,96 @@
+// sandbox.rs - Run an executable inside Windows Sandbox
+//
+// A safe way to try an unknown download: the file is copied into a fresh
+// staging folder, which is mapped read-only into a new Windows Sandbox VM
+// and launched there at logon. Nothing in the sandbox can modify the
+// original file (or anything else on the host).
+//
+// How it works:
+// 1. Check Windows Sandbox is installed (optional Windows feature)
+// 2. Copy the file to %TEMP%\QuickRun\sandbox\<timestamp>\
+// 3. Write a .wsb configuration mapping that folder read-only with a
+//    LogonCommand that runs the file
+// 4. Open the .wsb - Windows Sandbox starts with that configuration
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where the host folder appears inside the sandbox
+const SANDBOX_FOLDER: &str = "C:\\Users\\WDAGUtilityAccount\\Desktop\\QuickRun";
+
+/// Escape text for inclusion in the .wsb XML
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Is Windows Sandbox installed?
+fn is_available() -> bool {
+    let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    Path::new(&system_root)
+        .join("System32\\WindowsSandbox.exe")
+        .is_file()
+}
+
+/// Build the .wsb configuration for running `file_name` from the mapped folder
+fn wsb_config(host_folder: &Path, file_name: &str) -> String {
+    let command = format!("{}\\{}", SANDBOX_FOLDER, file_name);
+    format!(
+        r#"<Configuration>
+  <MappedFolders>
+    <MappedFolder>
+      <HostFolder>{}</HostFolder>
+      <SandboxFolder>{}</SandboxFolder>
+      <ReadOnly>true</ReadOnly>
+    </MappedFolder>
+  </MappedFolders>
+  <LogonCommand>
+    <Command>cmd /C start "" "{}"</Command>
+  </LogonCommand>
+</Configuration>
+"#,
+        xml_escape(&host_folder.to_string_lossy()),
+        SANDBOX_FOLDER,
+        xml_escape(&command)
+    )
+}
+
+/// Launch `path` inside a new Windows Sandbox
+pub fn run_in_sandbox(path: &str) -> Result<(), String> {
+    if !is_available() {
+        return Err(
+            "Windows Sandbox is not enabled (Turn Windows features on or off → Windows Sandbox)"
+                .to_string(),
+        );
+    }
+
+    let source = Path::new(path);
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid file: {}", path))?;
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let staging: PathBuf = std::env::temp_dir()
+        .join("QuickRun")
+        .join("sandbox")
+        .join(stamp.to_string());
+
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create sandbox staging folder: {}", e))?;
+    std::fs::copy(source, staging.join(file_name))
+        .map_err(|e| format!("Failed to stage file for sandbox: {}", e))?;
+
+    let wsb_path = staging.with_extension("wsb");
+    std::fs::write(&wsb_path, wsb_config(&staging, file_name))
+        .map_err(|e| format!("Failed to write sandbox configuration: {}", e))?;
+
+    eprintln!("[Sandbox] Launching {} via {}", path, wsb_path.display());
+
+    crate::runner::open_with_shell(&wsb_path.to_string_lossy())
+}
@@ -0,0 +1,37 @@
+// scale.rs - Making the launcher bigger
+//
+// "ui_scale" in settings.json (0.75 to 2, default 1) zooms the launcher's
+// page and grows its window by the same factor. Windows' display scaling
+// already applies - the 500x80 window is in logical pixels - but on a large
+// high-DPI screen the text can still be small, and the window size in
+// tauri.conf.json can't follow a preference.
+//
+// The launcher resizes itself as results come and go (see main.ts), so it
+// reads the scale with get_ui_scale and follows UiScaleChanged events; the
+// resize here covers the window until it next does.
+
+use crate::events::{self, AppEvent};
+use crate::settings_state::SettingsState;
+use tauri::{AppHandle, LogicalSize, Manager};
+
+/// The launcher's size at 100%, as in tauri.conf.json
+const BASE_WIDTH: f64 = 500.0;
+const BASE_HEIGHT: f64 = 80.0;
+
+/// Zoom and resize the launcher to "ui_scale" (call at startup and when it
+/// changes)
+pub fn apply(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let scale = app.state::<SettingsState>().get().ui_scale();
+
+    if let Err(e) = window.set_zoom(scale) {
+        eprintln!("[Scale] Could not zoom the launcher: {}", e);
+        return;
+    }
+    if let Err(e) = window.set_size(LogicalSize::new(BASE_WIDTH * scale, BASE_HEIGHT * scale)) {
+        eprintln!("[Scale] Could not resize the launcher: {}", e);
+    }
+    events::emit(app, AppEvent::UiScaleChanged { scale });
+}
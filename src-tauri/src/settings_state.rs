@@ -0,0 +1,121 @@
+// settings_state.rs - The settings as Tauri managed state
+//
+// Commands read settings from the copy held here rather than parsing
+// settings.json on every call. Changes go through update()/try_update(),
+// which save them to disk (see quickrun_core::settings) and refresh the
+// copy. Code that saves settings on its own (e.g., default_apps::save)
+// calls reload() afterwards.
+//
+// Edits made to settings.json outside the app are picked up by watch(),
+// applied (hotkeys re-registered, a ThemeChanged event, etc.), and announced
+// to open windows with a SettingsChanged event carrying the new settings.
+
+use crate::events::{self, AppEvent};
+use quickrun_core::{history, settings, theme, Settings};
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+
+pub struct SettingsState(RwLock<Settings>);
+
+impl SettingsState {
+    /// Read settings.json (call once at startup)
+    pub fn load() -> Self {
+        Self(RwLock::new(settings::load()))
+    }
+
+    /// A snapshot of the current settings
+    pub fn get(&self) -> Settings {
+        self.0.read().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Change and save settings; returns the saved settings
+    pub fn update(&self, change: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+        Ok(self.store(settings::update(change)?))
+    }
+
+    /// Change and save settings unless `change` rejects them
+    pub fn try_update(
+        &self,
+        change: impl FnOnce(&mut Settings) -> Result<(), String>,
+    ) -> Result<Settings, String> {
+        Ok(self.store(settings::try_update(change)?))
+    }
+
+    /// Re-read settings.json
+    pub fn reload(&self) {
+        self.store(settings::reload());
+    }
+
+    /// Replace the held settings (already saved, e.g., edited by hand)
+    pub fn replace(&self, settings: Settings) {
+        self.store(settings);
+    }
+
+    fn store(&self, settings: Settings) -> Settings {
+        if let Ok(mut current) = self.0.write() {
+            *current = settings.clone();
+        }
+        settings
+    }
+}
+
+/// Apply the parts of a settings change that the backend acts on
+/// (the windows handle the rest via SettingsChanged)
+pub fn apply_changes(app: &AppHandle, before: &Settings, after: &Settings) {
+    if after.theme != before.theme {
+        events::emit(app, AppEvent::ThemeChanged { theme: theme::load(&after.theme) });
+        crate::backdrop::apply(app);
+    }
+    if after.language != before.language {
+        crate::refresh_tray_menu(app);
+        crate::tray_icon::refresh(app);
+    }
+    if after.tray_actions != before.tray_actions {
+        crate::refresh_tray_menu(app);
+    }
+    if after.deep_links != before.deep_links {
+        crate::deep_link::apply(app, after.deep_links);
+    }
+    if after.http_api != before.http_api {
+        crate::http_api::apply(app);
+    }
+    if after.hotkey != before.hotkey {
+        crate::hotkey::reload();
+    }
+    if after.theme.tray_icon != before.theme.tray_icon || after.hotkey != before.hotkey {
+        crate::tray_icon::refresh(app);
+    }
+    if after.double_tap != before.double_tap || after.double_tap_interval_ms != before.double_tap_interval_ms {
+        crate::double_tap::reload();
+    }
+    if after.ui_scale() != before.ui_scale() {
+        crate::scale::apply(app);
+    }
+    if before.remember_last_input && !after.remember_last_input {
+        if let Err(e) = history::set_last_input("") {
+            eprintln!("[History] {}", e);
+        }
+    }
+    if after.always_on_top != before.always_on_top {
+        if let Some(window) = app.get_webview_window("main") {
+            crate::desktops::apply(&window, after);
+        }
+    }
+}
+
+/// Follow settings.json for edits made outside the app (call once at startup)
+pub fn watch(app: &AppHandle) {
+    let app = app.clone();
+    settings::watch(move |after| {
+        let state = app.state::<SettingsState>();
+        let before = state.get();
+        // Our own saves show up here too, and are already applied
+        if after == before {
+            return;
+        }
+        eprintln!("[Settings] settings.json changed on disk, applying");
+        state.replace(after.clone());
+        apply_changes(&app, &before, &after);
+        events::emit(&app, AppEvent::SettingsChanged { settings: after });
+    });
+}
@@ -0,0 +1,94 @@
+// sounds.rs - Sound cues
+//
+// With "sounds" turned on in settings.json (off by default), QuickRun plays
+// a short sound when the launcher opens, when a command launches and when
+// one fails. Each cue is the Windows sound for a similar event ("Menu
+// popup", "Menu command" and "Exclamation" in the Sound control panel, so
+// they follow the sound scheme, and some schemes leave them silent) unless
+// a .wav file is given for it:
+//
+//   "sounds": {
+//     "enabled": true,
+//     "show": null,
+//     "launch": "~\\Music\\whoosh.wav",
+//     "error": null
+//   }
+//
+// Sounds play in the background through PlaySound, so they never hold up
+// the launcher; a new cue cuts off one still playing.
+
+use crate::settings_state::SettingsState;
+use tauri::{Manager, Runtime};
+
+/// Something worth a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    Show,
+    Launch,
+    Error,
+}
+
+impl Cue {
+    /// Name of the Windows sound played when no file is set
+    fn system_sound(self) -> &'static str {
+        match self {
+            Cue::Show => "MenuPopup",
+            Cue::Launch => "MenuCommand",
+            Cue::Error => "SystemExclamation",
+        }
+    }
+}
+
+/// Play the sound for `cue`, if sounds are on
+pub fn play<R: Runtime, M: Manager<R>>(manager: &M, cue: Cue) {
+    let sounds = manager.state::<SettingsState>().get().sounds;
+    if !sounds.enabled {
+        return;
+    }
+    let file = match cue {
+        Cue::Show => sounds.show,
+        Cue::Launch => sounds.launch,
+        Cue::Error => sounds.error,
+    };
+
+    match file.as_deref().map(str::trim).filter(|path| !path.is_empty()) {
+        Some(path) => {
+            let path = crate::runner::expand_home(path);
+            if !play_file(&path.to_string_lossy()) {
+                eprintln!("[Sounds] Failed to play {}", path.display());
+            }
+        }
+        // A sound scheme without this sound plays nothing, which is fine
+        None => play_system_sound(cue.system_sound()),
+    }
+}
+
+#[cfg(windows)]
+fn play_file(path: &str) -> bool {
+    use windows::Win32::Media::Audio::{SND_ASYNC, SND_FILENAME, SND_NODEFAULT};
+
+    play_sound(path, SND_FILENAME | SND_ASYNC | SND_NODEFAULT)
+}
+
+#[cfg(windows)]
+fn play_system_sound(name: &str) {
+    use windows::Win32::Media::Audio::{SND_ALIAS, SND_ASYNC, SND_NODEFAULT};
+
+    play_sound(name, SND_ALIAS | SND_ASYNC | SND_NODEFAULT);
+}
+
+#[cfg(windows)]
+fn play_sound(sound: &str, flags: windows::Win32::Media::Audio::SND_FLAGS) -> bool {
+    use windows::core::HSTRING;
+    use windows::Win32::Media::Audio::PlaySoundW;
+
+    unsafe { PlaySoundW(&HSTRING::from(sound), None, flags) }.as_bool()
+}
+
+#[cfg(not(windows))]
+fn play_file(_path: &str) -> bool {
+    true
+}
+
+#[cfg(not(windows))]
+fn play_system_sound(_name: &str) {}
@@ -0,0 +1,157 @@
+// tray_icon.rs - The tray icon's look
+//
+// The tray icon is drawn from the app icon at runtime, so only one image
+// ships:
+// - "color" (theme.tray_icon in settings.json): the app icon as it is
+// - "monochrome": the app icon's shape in white on a dark taskbar, or black
+//   on a light one ("Choose your default Windows mode" in Settings)
+//
+// A dot in the bottom-right corner shows something worth noticing: blue
+// when an update is available, amber while the hotkey is paused (pausing
+// wins, as it is what stops the launcher from opening). The tooltip says
+// the same in words.
+//
+// Windows only tells top-level windows when the taskbar mode changes, so
+// watch() polls it.
+
+use crate::settings_state::SettingsState;
+use quickrun_core::os::SystemRegistry;
+use quickrun_core::theme::{self, TrayIcon};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::{AppHandle, Manager};
+
+/// How often watch() checks the taskbar mode
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Glyph colors for a monochrome icon
+const ON_DARK: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const ON_LIGHT: [u8; 3] = [0x00, 0x00, 0x00];
+
+/// Badge colors
+const UPDATE_BADGE: [u8; 3] = [0x00, 0x78, 0xD4];
+const PAUSED_BADGE: [u8; 3] = [0xF0, 0xA3, 0x0A];
+
+/// Whether the last update check found a newer release
+static UPDATE_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// The taskbar mode the icon was last drawn for
+static TASKBAR_LIGHT: AtomicBool = AtomicBool::new(false);
+
+/// Note the outcome of an update check (redraws the icon if it changed)
+pub fn set_update_available(app: &AppHandle, available: bool) {
+    if UPDATE_AVAILABLE.swap(available, Ordering::SeqCst) != available {
+        refresh(app);
+    }
+}
+
+/// Redraw the tray icon and its tooltip for the current theme and status
+/// (call after either changes)
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(crate::TRAY_ID) else {
+        return;
+    };
+    let Some(app_icon) = app.default_window_icon() else {
+        return;
+    };
+    let settings = app.state::<SettingsState>().get();
+    let (width, height) = (app_icon.width(), app_icon.height());
+    let mut rgba = app_icon.rgba().to_vec();
+
+    let light = theme::taskbar_is_light(&SystemRegistry);
+    TASKBAR_LIGHT.store(light, Ordering::SeqCst);
+    if settings.theme.tray_icon == TrayIcon::Monochrome {
+        fill_shape(&mut rgba, if light { ON_LIGHT } else { ON_DARK });
+    }
+
+    let status = if crate::hotkey::is_paused() {
+        Some((PAUSED_BADGE, "tray.status.paused"))
+    } else if UPDATE_AVAILABLE.load(Ordering::SeqCst) {
+        Some((UPDATE_BADGE, "tray.status.update"))
+    } else {
+        None
+    };
+    let tooltip = match status {
+        Some((color, key)) => {
+            draw_badge(&mut rgba, width, height, color);
+            format!("QuickRun - {}", quickrun_core::i18n::text(key))
+        }
+        None => format!("QuickRun - Press {}", settings.hotkey),
+    };
+
+    if let Err(e) = tray.set_icon(Some(Image::new_owned(rgba, width, height))) {
+        eprintln!("[Tray] Failed to set the icon: {}", e);
+    }
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+/// Redraw the icon when the taskbar switches between light and dark (call
+/// once at startup)
+pub fn watch(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let monochrome = app.state::<SettingsState>().get().theme.tray_icon == TrayIcon::Monochrome;
+        if monochrome && theme::taskbar_is_light(&SystemRegistry) != TASKBAR_LIGHT.load(Ordering::SeqCst) {
+            refresh(&app);
+        }
+    });
+}
+
+/// Paint every pixel `color`, keeping the shape (alpha)
+fn fill_shape(rgba: &mut [u8], color: [u8; 3]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        pixel[..3].copy_from_slice(&color);
+    }
+}
+
+/// Draw a dot of `color` in the bottom-right corner, with a transparent
+/// ring around it to set it apart from the icon
+fn draw_badge(rgba: &mut [u8], width: u32, height: u32, color: [u8; 3]) {
+    let radius = width.min(height) as f32 * 0.22;
+    let gap = (radius * 0.25).max(1.0);
+    let (center_x, center_y) = (width as f32 - radius - gap, height as f32 - radius - gap);
+
+    for y in 0..height {
+        for x in 0..width {
+            let distance = (x as f32 + 0.5 - center_x).hypot(y as f32 + 0.5 - center_y);
+            let i = ((y * width + x) * 4) as usize;
+            if distance <= radius {
+                rgba[i..i + 4].copy_from_slice(&[color[0], color[1], color[2], 0xFF]);
+            } else if distance <= radius + gap {
+                rgba[i + 3] = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The pixel at (x, y) of a `size` x `size` image
+    fn pixel(rgba: &[u8], size: u32, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * size + x) * 4) as usize;
+        [rgba[i], rgba[i + 1], rgba[i + 2], rgba[i + 3]]
+    }
+
+    #[test]
+    fn the_badge_sits_in_the_bottom_right_corner() {
+        let size = 32;
+        let mut rgba = vec![0x80; (size * size * 4) as usize];
+        draw_badge(&mut rgba, size, size, UPDATE_BADGE);
+
+        assert_eq!(pixel(&rgba, size, 24, 24), [0x00, 0x78, 0xD4, 0xFF]);
+        assert_eq!(pixel(&rgba, size, 4, 4), [0x80; 4]);
+        assert_eq!(pixel(&rgba, size, 24, 4), [0x80; 4]);
+    }
+
+    #[test]
+    fn monochrome_keeps_the_shape() {
+        let mut rgba = vec![0x10, 0x20, 0x30, 0x00, 0x40, 0x50, 0x60, 0xC0];
+        fill_shape(&mut rgba, ON_DARK);
+
+        assert_eq!(rgba, vec![0xFF, 0xFF, 0xFF, 0x00, 0xFF, 0xFF, 0xFF, 0xC0]);
+    }
+}
@@ -1,16 +1,105 @@
 //! Auto-update functionality for QuickRun.
 //!
 //! Provides commands to check for updates from GitHub releases and initiate
-//! the update process.
+//! the update process, and checks in the background every
+//! "update_check_hours" (see start_scheduler).
+//!
+//! An installed QuickRun updates by running the release's installer, which
+//! the release's latest.json manifest, read through the Tauri updater plugin
+//! (see "plugins.updater" in tauri.conf.json), lists with its minisign
+//! signature: the download is written to disk as it arrives, and one that
+//! doesn't match the public key QuickRun was built with is rejected. A
+//! portable copy (see storage.rs) has nothing installed to update, so it
+//! downloads the release's portable exe next to its own as "QuickRun.exe.new",
+//! then on restart renames itself to "QuickRun.exe.old" (Windows lets a
+//! running program be renamed, not replaced), moves the new one into its
+//! place and starts it. The old one is deleted once it has exited. The
+//! portable exe's signature is its "<exe>.sig" asset instead. Both are also
+//! checked against the release's SHA256SUMS.
+//!
+//! Either way, what's needed to go back to the version before is kept (see
+//! rollback.rs and revert_impl).
+//!
+//! On a machine that can't reach GitHub, an installer downloaded by hand can
+//! be run instead (see install_from_file_impl), once it passes the same
+//! checks: its updater signature, from the ".sig" file published next to it,
+//! and Authenticode.
 
+use crate::authenticode;
+use crate::events::{self, AppEvent, UpdateStage};
+use crate::os::{FileSystem, HttpClient, HttpResponse, ProcessSpawner, SystemFs, SystemHttp, SystemSpawner};
+use crate::rollback::{self, Kind};
+use crate::settings_state::SettingsState;
+use crate::{notify, tray_icon};
+use quickrun_core::{i18n, storage};
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_updater::UpdaterExt;
 
 /// GitHub repository owner
 const GITHUB_OWNER: &str = "Swatto86";
 /// GitHub repository name  
 const GITHUB_REPO: &str = "QuickRun";
+/// Who release installers are signed by: the code signing certificate's
+/// subject name, which the release workflow builds in. An installer signed
+/// by anyone else isn't run. Release builds without one run no installer at
+/// all; debug builds skip the check (see verify_publisher)
+const PUBLISHER: Option<&str> = option_env!("QUICKRUN_PUBLISHER");
+/// The public key the Tauri updater checks installers against, which the
+/// release workflow builds in. Builds without one can't check an installer,
+/// so they open the release page instead of installing (see signed_manifest)
+const UPDATER_PUBKEY: Option<&str> = option_env!("QUICKRUN_UPDATER_PUBKEY");
+/// How often a download of unknown size reports progress (sized ones
+/// report each percent)
+const REPORT_EVERY: u64 = 1024 * 1024;
+
+/// Set by cancel_download() to stop the download in progress
+static CANCEL_DOWNLOAD: AtomicBool = AtomicBool::new(false);
+
+/// How long after startup the first background check waits, to stay out of
+/// the way of startup itself
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(60);
+/// How often the scheduler sees whether a check is due (a failed check is
+/// retried this soon, and a changed interval applies without a restart)
+const SCHEDULER_TICK: Duration = Duration::from_secs(10 * 60);
+
+/// The last version a background check announced with a toast
+static ANNOUNCED_VERSION: Mutex<Option<String>> = Mutex::new(None);
+
+/// The last releases API response, for conditional requests
+static API_CACHE: Mutex<ApiCache> = Mutex::new(ApiCache::new());
+/// The last list of releases fetched for the changelog, likewise
+static CHANGELOG_CACHE: Mutex<ApiCache> = Mutex::new(ApiCache::new());
+
+/// What's kept of the releases API's last answer. GitHub doesn't count a
+/// "304 Not Modified" against the rate limit (60 requests an hour without
+/// a token), so checks ask for the release only if it changed since
+/// (If-None-Match / If-Modified-Since) and reuse `body` when it didn't.
+#[derive(Debug, Default)]
+struct ApiCache {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// The last release JSON received
+    body: Vec<u8>,
+    /// Unix time the rate limit resets at, if it's used up; no request is
+    /// made before then
+    retry_after: Option<u64>,
+}
+
+impl ApiCache {
+    const fn new() -> Self {
+        ApiCache { etag: None, last_modified: None, body: Vec::new(), retry_after: None }
+    }
+}
 
 /// Information about an available update.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +116,78 @@ pub struct UpdateInfo {
     pub release_url: String,
     /// URL to download the installer directly (exe or msi)
     pub installer_url: Option<String>,
+    /// URL of the portable exe, which a portable copy updates to instead of
+    /// running the installer
+    #[serde(default)]
+    pub portable_url: Option<String>,
+    /// URL of the release's SHA-256 checksums (SHA256SUMS), which the
+    /// download must match before it's run or swapped in
+    #[serde(default)]
+    pub checksums_url: Option<String>,
+    /// URL of the portable exe's updater signature ("<exe>.sig"), which it
+    /// must match before it's swapped in (the installer's is read from the
+    /// updater manifest instead)
+    #[serde(default)]
+    pub signature_url: Option<String>,
+    /// The release is newer but the user skipped it (the "skip_version"
+    /// setting), so it isn't `available`
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+/// One release's notes, as listed by changelog_impl()
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseNotes {
+    pub version: String,
+    pub body: String,
+}
+
+/// Why a downloaded installer wasn't launched
+#[derive(Debug, PartialEq)]
+enum InstallError {
+    /// It couldn't be downloaded or started; the release page is opened instead
+    Failed(String),
+    /// It was downloaded but didn't pass verification, so it must not be run
+    Rejected(String),
+    /// The user stopped the download
+    Cancelled,
+}
+
+/// How the installer is run
+#[derive(Debug, Clone, PartialEq)]
+enum InstallMode {
+    /// With its wizard, for the user to click through
+    Wizard,
+    /// Silently (NSIS /S), after which `relaunch` (QuickRun's own program)
+    /// is started again
+    Silent { relaunch: PathBuf },
+}
+
+/// How an install is run, and what it calls back into besides the OS
+struct InstallOptions<'a> {
+    /// How the installer is run (a portable update has none to run)
+    mode: InstallMode,
+    /// Checks the written installer's signature (see authenticode.rs)
+    verify_signature: &'a (dyn Fn(&Path) -> Result<(), String> + Sync),
+    /// The public key updater signatures are checked with (UPDATER_PUBKEY)
+    updater_key: Option<&'a str>,
+    /// Told (bytes so far, total size if known) as the download goes
+    on_progress: &'a (dyn Fn(u64, Option<u64>) + Sync),
+    /// Told where the verified download is before it's run or swapped in, to
+    /// keep what's needed to go back (see rollback.rs)
+    keep: &'a (dyn Fn(&Path) + Sync),
+    /// Stops the download when set
+    cancel: &'a AtomicBool,
+}
+
+/// A release file to download, and what it's checked against
+struct Download<'a> {
+    url: &'a str,
+    /// The release's checksums (SHA256SUMS), if it has any
+    checksums_url: Option<&'a str>,
+    /// The file's updater signature as `tauri signer` writes it (from the
+    /// updater manifest, or the "<file>.sig" asset), if it has one
+    signature: Option<&'a str>,
 }
 
 /// Response from GitHub releases API
@@ -36,6 +197,15 @@ struct GitHubRelease {
     body: Option<String>,
     html_url: String,
     assets: Vec<GitHubAsset>,
+    #[serde(default)]
+    prerelease: bool,
+}
+
+impl GitHubRelease {
+    /// The version it's tagged with ("v1.2.0" → "1.2.0")
+    fn version(&self) -> &str {
+        self.tag_name.strip_prefix('v').unwrap_or(&self.tag_name)
+    }
 }
 
 /// Asset attached to a GitHub release
@@ -45,42 +215,15 @@ struct GitHubAsset {
     browser_download_url: String,
 }
 
-/// Parse a semantic version string into (major, minor, patch) tuple.
-fn parse_semver(version: &str) -> Option<(u32, u32, u32)> {
-    let parts: Vec<&str> = version.split('.').collect();
-    if parts.len() != 3 {
-        return None;
-    }
-
-    let major = parts[0].parse::<u32>().ok()?;
-    let minor = parts[1].parse::<u32>().ok()?;
-    let patch = parts[2].parse::<u32>().ok()?;
-
-    Some((major, minor, patch))
-}
-
-/// Compare two semantic versions. Returns:
-/// - `1` if a > b
-/// - `-1` if a < b  
-/// - `0` if a == b
-fn compare_versions(a: &str, b: &str) -> i32 {
-    let Some((a_maj, a_min, a_pat)) = parse_semver(a) else {
-        return 0;
-    };
-    let Some((b_maj, b_min, b_pat)) = parse_semver(b) else {
-        return 0;
+/// Compare two semantic versions ("1.2.0", "1.3.0-beta.1", "1.3.0+build.5")
+/// by precedence: a pre-release comes before its release and pre-releases
+/// are ordered by their identifiers ("beta.2" < "beta.11" < "rc.1"), while
+/// build metadata is ignored. Versions that don't parse compare as equal.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (Ok(a), Ok(b)) = (Version::parse(a.trim()), Version::parse(b.trim())) else {
+        return Ordering::Equal;
     };
-
-    if a_maj != b_maj {
-        return if a_maj > b_maj { 1 } else { -1 };
-    }
-    if a_min != b_min {
-        return if a_min > b_min { 1 } else { -1 };
-    }
-    if a_pat != b_pat {
-        return if a_pat > b_pat { 1 } else { -1 };
-    }
-    0
+    (a.major, a.minor, a.patch, &a.pre).cmp(&(b.major, b.minor, b.patch, &b.pre))
 }
 
 /// Find the Windows installer asset from a list of release assets.
@@ -105,105 +248,535 @@ fn find_installer_asset(assets: &[GitHubAsset]) -> Option<String> {
     None
 }
 
-/// Check for updates by querying the GitHub releases API.
+/// Find the portable build (an .exe with "portable" in its name) among a
+/// release's assets
+fn find_portable_asset(assets: &[GitHubAsset]) -> Option<String> {
+    assets
+        .iter()
+        .find(|asset| {
+            let name_lower = asset.name.to_lowercase();
+            name_lower.ends_with(".exe") && name_lower.contains("portable")
+        })
+        .map(|asset| asset.browser_download_url.clone())
+}
+
+/// Find the checksums asset for the installer at `installer_url`: a
+/// SHA256SUMS file for the release, or a "<installer>.sha256" file
+fn find_checksums_asset(assets: &[GitHubAsset], installer_url: &str) -> Option<String> {
+    let installer = file_name(installer_url).to_lowercase();
+    let per_file = format!("{}.sha256", installer);
+    assets
+        .iter()
+        .find(|asset| asset.name.to_lowercase() == per_file)
+        .or_else(|| {
+            assets
+                .iter()
+                .find(|asset| matches!(asset.name.to_lowercase().as_str(), "sha256sums" | "sha256sums.txt"))
+        })
+        .map(|asset| asset.browser_download_url.clone())
+}
+
+/// The file name at the end of a download URL
+fn file_name(url: &str) -> &str {
+    url.split('/')
+        .next_back()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("quickrun-setup.exe")
+}
+
+/// Find the updater signature for the file at `url` among a release's
+/// assets: "<file>.sig", as `tauri signer` names it
+fn find_signature_asset(assets: &[GitHubAsset], url: &str) -> Option<String> {
+    let signature = format!("{}.sig", file_name(url));
+    assets
+        .iter()
+        .find(|asset| asset.name.eq_ignore_ascii_case(&signature))
+        .map(|asset| asset.browser_download_url.clone())
+}
+
+/// The checksum listed for `file` in a checksums file, lowercase
 ///
-/// Returns information about whether an update is available and details
-/// about the latest release.
-pub async fn check_for_update_impl() -> Result<UpdateInfo, String> {
-    let current_version = env!("CARGO_PKG_VERSION");
-    let api_url = format!(
-        "https://api.github.com/repos/{}/{}/releases/latest",
-        GITHUB_OWNER, GITHUB_REPO
-    );
+/// Lines are as `sha256sum` writes them: the hex digest, then the file name
+/// (after "*" in binary mode). A line with only a digest applies to any
+/// file, as in a per-file .sha256.
+fn expected_checksum(checksums: &str, file: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace).unwrap_or((line.trim(), ""));
+        let name = name.trim().trim_start_matches('*');
+        let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        let is_sha256 = hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+        (is_sha256 && (name.is_empty() || name.eq_ignore_ascii_case(file))).then(|| hash.to_lowercase())
+    })
+}
 
-    eprintln!("[Updater] Checking for updates at: {}", api_url);
+/// A SHA-256 digest as lowercase hex
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Percent of `total` downloaded, if the size is known
+fn percent(bytes: u64, total: Option<u64>) -> Option<u8> {
+    total
+        .filter(|total| *total > 0)
+        .map(|total| (bytes.min(total) * 100 / total) as u8)
+}
+
+/// Whether getting from `previous` to `bytes` downloaded is worth reporting:
+/// at each new percent, or each REPORT_EVERY bytes if the size isn't known
+fn worth_reporting(previous: u64, bytes: u64, total: Option<u64>) -> bool {
+    match (percent(previous, total), percent(bytes, total)) {
+        (Some(before), Some(now)) => before != now,
+        _ => previous / REPORT_EVERY != bytes / REPORT_EVERY,
+    }
+}
+
+/// Arguments for cmd that start the installer at `installer` apart from
+/// QuickRun, so that it keeps running once QuickRun exits
+///
+/// A silent install is waited for (`start /wait`), then QuickRun is started
+/// again - whether or not the install succeeded, so that it's never left
+/// closed. The command mustn't start with a quote, or cmd would strip the
+/// first and last quotes of the line.
+fn installer_args(installer: &Path, mode: &InstallMode) -> Vec<String> {
+    let installer = installer.to_string_lossy();
+    match mode {
+        InstallMode::Wizard => ["/C", "start", "", &installer].map(String::from).to_vec(),
+        InstallMode::Silent { relaunch } => {
+            let relaunch = relaunch.to_string_lossy();
+            ["/C", "start", "", "/wait", &installer, "/S", "&", "start", "", &relaunch]
+                .map(String::from)
+                .to_vec()
+        }
+    }
+}
+
+/// Stop the update download in progress, if there is one
+pub fn cancel_download() {
+    CANCEL_DOWNLOAD.store(true, atomic::Ordering::SeqCst);
+}
 
-    // Create HTTP client with appropriate headers
-    let client = reqwest::Client::builder()
-        .user_agent(format!("QuickRun/{}", current_version))
-        .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    // Fetch latest release info
-    let response = client
-        .get(&api_url)
-        .header("Accept", "application/vnd.github.v3+json")
-        .send()
+/// Check a downloaded file's SHA-256 digest (`actual`, lowercase hex)
+/// against the release's checksums
+async fn verify_checksum(
+    http: &impl HttpClient,
+    checksums_url: &str,
+    file: &str,
+    actual: &str,
+) -> Result<(), InstallError> {
+    let response = http
+        .get(checksums_url, &[])
         .await
-        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
+        .map_err(|e| InstallError::Rejected(format!("Failed to download the release checksums: {}", e)))?;
+    if !response.is_success() {
+        return Err(InstallError::Rejected(format!(
+            "Failed to download the release checksums (status {})",
+            response.status
+        )));
+    }
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-
-        // Handle 404 specifically - usually means no releases exist yet
-        if status == reqwest::StatusCode::NOT_FOUND {
-            eprintln!(
-                "[Updater] No releases found on GitHub - repository may not have any published releases yet"
-            );
-            return Ok(UpdateInfo {
-                available: false,
-                version: current_version.to_string(),
-                body: String::new(),
-                current_version: current_version.to_string(),
-                release_url: format!(
-                    "https://github.com/{}/{}/releases",
-                    GITHUB_OWNER, GITHUB_REPO
-                ),
-                installer_url: None,
-            });
+    let expected = expected_checksum(&response.text(), file)
+        .ok_or_else(|| InstallError::Rejected(format!("The release checksums don't list {}", file)))?;
+    if actual != expected {
+        return Err(InstallError::Rejected(format!(
+            "{} doesn't match the release checksum (expected {}, got {}) - it may be corrupted or tampered \
+             with, so it wasn't run",
+            file, expected, actual
+        )));
+    }
+    eprintln!("[Updater] Checksum verified: {}", actual);
+    Ok(())
+}
+
+/// User agent for requests to GitHub
+fn user_agent() -> String {
+    format!("QuickRun/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Check for updates by querying the GitHub releases API.
+///
+/// Returns information about whether an update is available and details
+/// about the latest release. A release no newer than `skip_version` isn't
+/// available, only `skipped`.
+pub async fn check_for_update_impl(skip_version: Option<&str>) -> Result<UpdateInfo, String> {
+    let http = SystemHttp::new(user_agent(), Duration::from_secs(15));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let portable = storage::is_portable();
+    check_for_update_with(&http, env!("CARGO_PKG_VERSION"), skip_version, &API_CACHE, now, portable).await
+}
+
+/// The notes of each release since the running version up to
+/// `latest_version`, newest first (see fetch_changelog). Only the update
+/// window asks, when it shows an update, so background checks don't spend
+/// GitHub's rate limit on them.
+pub async fn changelog_impl(latest_version: &str) -> Result<Vec<ReleaseNotes>, String> {
+    let http = SystemHttp::new(user_agent(), Duration::from_secs(15));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    fetch_changelog(&http, env!("CARGO_PKG_VERSION"), latest_version, &CHANGELOG_CACHE, now).await
+}
+
+/// Check for updates in the background (call once at startup): shortly
+/// after startup, then every "update_check_hours". A newer release puts the
+/// update dot on the tray icon and is announced with a toast, once per
+/// version; a skipped one (see "skip_version") is left alone. The program a
+/// portable update replaced is deleted before the first check.
+pub fn start_scheduler(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        remove_replaced_program();
+        let mut last_check = None;
+        loop {
+            let settings = app.state::<SettingsState>().get();
+            if check_due(last_check, Instant::now(), settings.update_check_hours) {
+                match check_for_update_impl(settings.skip_version.as_deref()).await {
+                    Ok(info) => {
+                        last_check = Some(Instant::now());
+                        tray_icon::set_update_available(&app, info.available);
+                        if info.available && first_announcement(&info.version) {
+                            notify::info(
+                                &app,
+                                &i18n::format("notify.update_available", &[&info.version]),
+                                &i18n::text("notify.update_available.body"),
+                            );
+                        }
+                    }
+                    Err(e) => eprintln!("[Updater] Background update check failed: {}", e),
+                }
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
         }
+    });
+}
 
-        return Err(format!("GitHub API returned error {}: {}", status, body));
+/// Whether a background check is due: `hours` (0 = never) after the last
+/// one, or now if there hasn't been one. A hand-edited `hours` too large to
+/// count in seconds just means never again.
+fn check_due(last_check: Option<Instant>, now: Instant, hours: u64) -> bool {
+    match last_check {
+        _ if hours == 0 => false,
+        Some(last) => now.duration_since(last) >= Duration::from_secs(hours.saturating_mul(3600)),
+        None => true,
     }
+}
 
-    let release: GitHubRelease = response
-        .json()
-        .await
+/// Whether `version` is yet to be announced (and note that it now is)
+fn first_announcement(version: &str) -> bool {
+    let mut announced = ANNOUNCED_VERSION.lock().unwrap_or_else(|e| e.into_inner());
+    if announced.as_deref() == Some(version) {
+        return false;
+    }
+    *announced = Some(version.to_string());
+    true
+}
+
+/// When GitHub's rate limit lets requests through again (Unix seconds), if
+/// `response` says it's used up: "Retry-After" seconds from `now`, or
+/// "X-RateLimit-Reset" once "X-RateLimit-Remaining" is down to 0
+fn rate_limited_until(response: &HttpResponse, now: u64) -> Option<u64> {
+    let number = |name: &str| response.header(name).and_then(|value| value.trim().parse::<u64>().ok());
+    if let Some(seconds) = number("Retry-After") {
+        return Some(now + seconds);
+    }
+    (number("X-RateLimit-Remaining") == Some(0)).then(|| number("X-RateLimit-Reset")).flatten()
+}
+
+fn rate_limit_error(until: u64, now: u64) -> String {
+    let minutes = until.saturating_sub(now).div_ceil(60).max(1);
+    format!(
+        "GitHub's API rate limit is used up; update checks resume in {} minute{}",
+        minutes,
+        if minutes == 1 { "" } else { "s" }
+    )
+}
+
+/// check_for_update_impl() against any HTTP client and current version,
+/// with `cache` kept between checks, `now` in Unix seconds, and `portable`
+/// if this is a portable copy
+async fn check_for_update_with(
+    http: &impl HttpClient,
+    current_version: &str,
+    skip_version: Option<&str>,
+    cache: &Mutex<ApiCache>,
+    now: u64,
+    portable: bool,
+) -> Result<UpdateInfo, String> {
+    let api_url = format!(
+        "https://api.github.com/repos/{}/{}/releases/latest",
+        GITHUB_OWNER, GITHUB_REPO
+    );
+    eprintln!("[Updater] Checking for updates at: {}", api_url);
+
+    // Handle 404 specifically - usually means no releases exist yet
+    let Some(body) = cached_get(http, &api_url, cache, now).await? else {
+        eprintln!(
+            "[Updater] No releases found on GitHub - repository may not have any published releases yet"
+        );
+        return Ok(UpdateInfo {
+            available: false,
+            version: current_version.to_string(),
+            body: String::new(),
+            current_version: current_version.to_string(),
+            release_url: format!(
+                "https://github.com/{}/{}/releases",
+                GITHUB_OWNER, GITHUB_REPO
+            ),
+            installer_url: None,
+            portable_url: None,
+            checksums_url: None,
+            signature_url: None,
+            skipped: false,
+        });
+    };
+    let release: GitHubRelease = serde_json::from_slice(&body)
         .map_err(|e| format!("Failed to parse release JSON: {}", e))?;
 
-    // Extract version from tag (strip 'v' prefix if present)
-    let latest_version = release
-        .tag_name
-        .strip_prefix('v')
-        .unwrap_or(&release.tag_name)
-        .to_string();
+    let latest_version = release.version().to_string();
 
     // Compare versions
-    let is_newer = compare_versions(&latest_version, current_version) > 0;
+    let is_newer = compare_versions(&latest_version, current_version) == Ordering::Greater;
+    // Skipping a version skips it until something newer is released
+    let skipped = is_newer
+        && skip_version.is_some_and(|skipped| compare_versions(&latest_version, skipped) != Ordering::Greater);
 
     eprintln!(
-        "[Updater] Current version: {}, Latest version: {}, Update available: {}",
-        current_version, latest_version, is_newer
+        "[Updater] Current version: {}, Latest version: {}, Update available: {}{}",
+        current_version,
+        latest_version,
+        is_newer,
+        if skipped { " (skipped)" } else { "" }
     );
 
+    // A portable copy downloads the portable exe, which the checksums and
+    // signature must then cover instead
     let installer_url = find_installer_asset(&release.assets);
-
+    let portable_url = find_portable_asset(&release.assets);
+    let downloaded_url = if portable { &portable_url } else { &installer_url };
+    let checksums_url = downloaded_url
+        .as_deref()
+        .and_then(|url| find_checksums_asset(&release.assets, url));
+    let signature_url = downloaded_url
+        .as_deref()
+        .and_then(|url| find_signature_asset(&release.assets, url));
     Ok(UpdateInfo {
-        available: is_newer,
+        available: is_newer && !skipped,
         version: latest_version,
         body: release.body.unwrap_or_default(),
         current_version: current_version.to_string(),
         release_url: release.html_url,
         installer_url,
+        portable_url,
+        checksums_url,
+        signature_url,
+        skipped,
     })
 }
 
+/// GET the GitHub API's `url` through `cache` (see ApiCache): the body of
+/// the answer, the cached one if it's unchanged, or None if there's nothing
+/// there (404). No request is made while the rate limit is used up.
+async fn cached_get(
+    http: &impl HttpClient,
+    url: &str,
+    cache: &Mutex<ApiCache>,
+    now: u64,
+) -> Result<Option<Vec<u8>>, String> {
+    // Don't hold the lock across the request
+    let (etag, last_modified) = {
+        let cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(until) = cache.retry_after.filter(|until| *until > now) {
+            return Err(rate_limit_error(until, now));
+        }
+        (cache.etag.clone(), cache.last_modified.clone())
+    };
+
+    // Ask for it only if it changed since it was last fetched
+    let mut headers = vec![("Accept", "application/vnd.github.v3+json")];
+    if let Some(etag) = etag.as_deref() {
+        headers.push(("If-None-Match", etag));
+    } else if let Some(last_modified) = last_modified.as_deref() {
+        headers.push(("If-Modified-Since", last_modified));
+    }
+    let response = http
+        .get(url, &headers)
+        .await
+        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
+
+    let mut cached = cache.lock().unwrap_or_else(|e| e.into_inner());
+    cached.retry_after = rate_limited_until(&response, now);
+
+    if response.status == 304 && !cached.body.is_empty() {
+        eprintln!("[Updater] {} unchanged since it was last fetched", url);
+        return Ok(Some(cached.body.clone()));
+    }
+    if !response.is_success() {
+        if let Some(until) = cached.retry_after.filter(|_| matches!(response.status, 403 | 429)) {
+            return Err(rate_limit_error(until, now));
+        }
+        if response.status == 404 {
+            return Ok(None);
+        }
+        return Err(format!(
+            "GitHub API returned error {}: {}",
+            response.status,
+            response.text()
+        ));
+    }
+    cached.etag = response.header("ETag").map(str::to_string);
+    cached.last_modified = response.header("Last-Modified").map(str::to_string);
+    cached.body = response.body;
+    Ok(Some(cached.body.clone()))
+}
+
+/// The notes of each release after `current_version` up to `latest_version`,
+/// newest first, with the list of releases fetched through `cache` (see
+/// cached_get). Pre-releases are left out (their notes end up in the
+/// release), as are any beyond the 100 most recent releases.
+async fn fetch_changelog(
+    http: &impl HttpClient,
+    current_version: &str,
+    latest_version: &str,
+    cache: &Mutex<ApiCache>,
+    now: u64,
+) -> Result<Vec<ReleaseNotes>, String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/releases?per_page=100",
+        GITHUB_OWNER, GITHUB_REPO
+    );
+    let body = cached_get(http, &url, cache, now)
+        .await?
+        .ok_or_else(|| "No releases found on GitHub".to_string())?;
+    let releases: Vec<GitHubRelease> = serde_json::from_slice(&body)
+        .map_err(|e| format!("Failed to parse releases JSON: {}", e))?;
+    Ok(changelog_between(releases, current_version, latest_version))
+}
+
+/// The changelog fetch_changelog() returns, from the releases it fetched
+fn changelog_between(
+    mut releases: Vec<GitHubRelease>,
+    current_version: &str,
+    latest_version: &str,
+) -> Vec<ReleaseNotes> {
+    releases.retain(|release| {
+        !release.prerelease
+            && compare_versions(release.version(), current_version) == Ordering::Greater
+            && compare_versions(release.version(), latest_version) != Ordering::Greater
+    });
+    releases.sort_by(|a, b| compare_versions(b.version(), a.version()));
+    releases
+        .into_iter()
+        .map(|release| ReleaseNotes {
+            version: release.version().to_string(),
+            body: release.body.unwrap_or_default(),
+        })
+        .collect()
+}
+
 /// Download the installer and launch it, or open the release page as fallback.
 ///
 /// The installer is downloaded to the system temp directory and then launched.
 /// After launching, the application should exit to allow the installer to run.
-pub async fn download_and_install_impl(update_info: UpdateInfo) -> Result<(), String> {
-    // If we have a direct installer URL, try to download and run it
-    if let Some(installer_url) = &update_info.installer_url {
-        eprintln!("[Updater] Downloading installer from: {}", installer_url);
-        match download_and_launch_installer(installer_url).await {
+/// Each stage is reported to the windows as an UpdateProgress event, and the
+/// download's progress as UpdateDownloadProgress events. An installer that
+/// fails verification (its updater signature or Authenticode signature,
+/// or for the portable exe its checksum) is an error: it isn't run,
+/// and the release page isn't offered in its place. A download stopped by
+/// cancel_download() just ends.
+///
+/// With `silent`, the installer runs without its wizard and QuickRun exits
+/// to let it replace the program, to be started again once it's done. A
+/// portable copy downloads the portable exe instead and restarts on it.
+pub async fn download_and_install_impl(app: &AppHandle, update_info: UpdateInfo, silent: bool) -> Result<(), String> {
+    let portable = storage::is_portable();
+    let download_url = if portable { &update_info.portable_url } else { &update_info.installer_url };
+    // If we have a direct download URL, try to download and run it
+    if let Some(download_url) = download_url {
+        let what = if portable { "portable exe" } else { "installer" };
+        eprintln!("[Updater] Downloading {} from: {}", what, download_url);
+        events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::Downloading });
+        let http = SystemHttp::new(user_agent(), Duration::from_secs(300)); // 5 minute timeout for download
+        let on_progress = |bytes: u64, total: Option<u64>| {
+            let percent = percent(bytes, total);
+            events::emit(app, AppEvent::UpdateDownloadProgress { bytes, total, percent });
+        };
+        let exe = env::current_exe().map_err(|e| format!("Failed to find QuickRun's program: {}", e))?;
+        let mode = if silent { InstallMode::Silent { relaunch: exe.clone() } } else { InstallMode::Wizard };
+        // To go back to: an installed copy keeps the installer, a portable
+        // one the exe it's replacing
+        let running_version = env!("CARGO_PKG_VERSION");
+        let keep = |download: &Path| {
+            let kept = if portable {
+                rollback::keep(&SystemFs, &rollback::dir(), Kind::Portable, &exe, running_version, running_version)
+            } else {
+                let version = &update_info.version;
+                rollback::keep(&SystemFs, &rollback::dir(), Kind::Installer, download, version, running_version)
+            };
+            if let Err(e) = kept {
+                eprintln!("[Updater] Failed to keep the version to go back to: {}", e);
+            }
+        };
+        let options = InstallOptions {
+            mode,
+            verify_signature: &verify_publisher,
+            updater_key: UPDATER_PUBKEY,
+            on_progress: &on_progress,
+            keep: &keep,
+            cancel: &CANCEL_DOWNLOAD,
+        };
+        CANCEL_DOWNLOAD.store(false, atomic::Ordering::SeqCst);
+        if !portable {
+            // So that this version can be gone back to, even if it was installed by hand
+            let quietly = InstallOptions {
+                mode: InstallMode::Wizard,
+                verify_signature: &verify_publisher,
+                updater_key: UPDATER_PUBKEY,
+                on_progress: &|_, _| {},
+                keep: &|_: &Path| {},
+                cancel: &CANCEL_DOWNLOAD,
+            };
+            let (dir, temp_dir) = (rollback::dir(), env::temp_dir());
+            let kept = keep_running_installer(&http, &SystemFs, &quietly, &dir, running_version, &temp_dir).await;
+            if let Err(e) = kept {
+                eprintln!("[Updater] Failed to keep {}'s installer to go back to: {}", running_version, e);
+            }
+        }
+        let download = if portable {
+            update_portable_exe(&http, &options, &update_info, &exe).await
+        } else {
+            install_update(app, &http, &options, &update_info).await
+        };
+        match download {
+            Ok(_) if portable => {
+                eprintln!("[Updater] Restarting on the new version");
+                events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::Restarting });
+                crate::restart(app);
+            }
+            Ok(_) if silent => {
+                eprintln!("[Updater] Installing silently - exiting so the installer can replace QuickRun");
+                events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::InstallingSilently });
+                app.exit(0);
+                return Ok(());
+            }
             Ok(_) => {
                 eprintln!("[Updater] Installer launched successfully");
+                events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::InstallerLaunched });
+                return Ok(());
+            }
+            Err(InstallError::Rejected(e)) => {
+                eprintln!("[Updater] Installer rejected: {}", e);
+                return Err(e);
+            }
+            Err(InstallError::Cancelled) => {
+                eprintln!("[Updater] Download cancelled");
+                events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::Cancelled });
                 return Ok(());
             }
-            Err(e) => {
+            Err(InstallError::Failed(e)) => {
                 eprintln!(
                     "[Updater] Failed to download/launch installer: {}. Falling back to browser.",
                     e
@@ -217,106 +790,1404 @@ pub async fn download_and_install_impl(update_info: UpdateInfo) -> Result<(), St
         "[Updater] Opening release page in browser: {}",
         update_info.release_url
     );
-    open_url_in_browser(&update_info.release_url)?;
+    events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::OpeningReleasePage });
+    crate::default_apps::open_url(&update_info.release_url)?;
 
     Ok(())
 }
 
-/// Download an installer from URL and launch it.
-async fn download_and_launch_installer(url: &str) -> Result<(), String> {
-    let current_version = env!("CARGO_PKG_VERSION");
+/// The version "Revert to previous version" goes back to, if one is kept
+/// (see rollback.rs)
+pub fn previous_version() -> Option<String> {
+    rollback::previous(&SystemFs, &rollback::dir(), rollback_kind(), env!("CARGO_PKG_VERSION"))
+        .map(|kept| kept.version)
+}
 
-    // Create HTTP client
-    let client = reqwest::Client::builder()
-        .user_agent(format!("QuickRun/{}", current_version))
-        .timeout(std::time::Duration::from_secs(300)) // 5 minute timeout for download
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+/// The Tauri updater plugin, with the public key it checks installers
+/// against (see UPDATER_PUBKEY)
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    let builder = tauri_plugin_updater::Builder::new();
+    match UPDATER_PUBKEY {
+        Some(pubkey) => builder.pubkey(pubkey).build(),
+        None => builder.build(),
+    }
+}
 
-    // Start download
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to start download: {}", e))?;
+/// Go back to the version before the last update: run its kept installer
+/// (silently with `silent`, as for an update), or for a portable copy swap
+/// its kept exe back in and restart on it. Either way, the kept file must
+/// still be signed by the publisher, and a kept installer must still match
+/// the updater signature kept with it.
+pub fn revert_impl(app: &AppHandle, silent: bool) -> Result<(), String> {
+    let running_version = env!("CARGO_PKG_VERSION");
+    let dir = rollback::dir();
+    let previous = rollback::previous(&SystemFs, &dir, rollback_kind(), running_version)
+        .ok_or_else(|| "No earlier version is kept to go back to".to_string())?;
+    if !storage::is_portable() {
+        let pubkey = UPDATER_PUBKEY.ok_or("This build has no updater key to check the installer with")?;
+        verify_signature_file(&SystemFs, &previous.path, &rollback::signature_of(&previous.path), pubkey)?;
+    }
+    verify_publisher(&previous.path)?;
+    let exe = env::current_exe().map_err(|e| format!("Failed to find QuickRun's program: {}", e))?;
+    eprintln!("[Updater] Going back to {}", previous.version);
 
-    if !response.status().is_success() {
-        return Err(format!(
-            "Download failed with status: {}",
-            response.status()
-        ));
+    if storage::is_portable() {
+        swap_in(&SystemFs, &exe, &previous.path)?;
+        // Keep the version being left, to update to again
+        let replaced = with_suffix(&exe, ".old");
+        let version = &previous.version;
+        if let Err(e) = rollback::keep(&SystemFs, &dir, Kind::Portable, &replaced, running_version, version) {
+            eprintln!("[Updater] Failed to keep {}: {}", running_version, e);
+        }
+        events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::Restarting });
+        crate::restart(app);
     }
 
-    // Determine filename from URL
-    let filename = url
-        .split('/')
-        .next_back()
-        .unwrap_or("quickrun-setup.exe")
-        .to_string();
+    let mode = if silent { InstallMode::Silent { relaunch: exe } } else { InstallMode::Wizard };
+    launch_installer(&SystemSpawner, &previous.path, &mode)?;
+    if silent {
+        events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::InstallingSilently });
+        app.exit(0);
+    } else {
+        events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::InstallerLaunched });
+    }
+    Ok(())
+}
 
-    // Get temp directory
-    let temp_dir = env::temp_dir();
-    let installer_path: PathBuf = temp_dir.join(&filename);
+/// Run the installer at `file`, downloaded by hand for a machine that can't
+/// reach GitHub: it's checked and run as a downloaded one is (see
+/// install_file), silently with `silent`. Its updater signature is read
+/// from "<file>.sig", downloaded from the release along with it, and
+/// revocation is only checked against what Windows has cached (see
+/// authenticode.rs). A portable copy has nothing to install; its exe is
+/// replaced by hand instead.
+pub fn install_from_file_impl(app: &AppHandle, file: &Path, silent: bool) -> Result<(), String> {
+    if storage::is_portable() {
+        return Err("A portable copy isn't installed - replace its QuickRun.exe with the new one instead".to_string());
+    }
+    let exe = env::current_exe().map_err(|e| format!("Failed to find QuickRun's program: {}", e))?;
+    let mode = if silent { InstallMode::Silent { relaunch: exe } } else { InstallMode::Wizard };
+    // Keep it to go back to, if its name tells which version it is
+    let running_version = env!("CARGO_PKG_VERSION");
+    let version = installer_version(file);
+    let keep = |installer: &Path| {
+        let Some(version) = &version else {
+            eprintln!("[Updater] Can't tell which version {} is, so it isn't kept", installer.display());
+            return;
+        };
+        let kept = rollback::keep(&SystemFs, &rollback::dir(), Kind::Installer, installer, version, running_version);
+        if let Err(e) = kept {
+            eprintln!("[Updater] Failed to keep the version to go back to: {}", e);
+        }
+    };
+    let verify_signature = |path: &Path| {
+        let pubkey = UPDATER_PUBKEY.ok_or("This build has no updater key to check the installer with")?;
+        verify_signature_file(&SystemFs, path, &rollback::signature_of(path), pubkey)?;
+        match PUBLISHER {
+            Some(publisher) => authenticode::verify_publisher_offline(path, publisher),
+            None => verify_publisher(path),
+        }
+    };
+    let options = InstallOptions {
+        mode,
+        verify_signature: &verify_signature,
+        updater_key: UPDATER_PUBKEY,
+        on_progress: &|_, _| {},
+        keep: &keep,
+        cancel: &CANCEL_DOWNLOAD,
+    };
+    eprintln!("[Updater] Installing from: {}", file.display());
 
-    eprintln!(
-        "[Updater] Downloading to: {}",
-        installer_path.display()
-    );
+    match install_file(&SystemFs, &SystemSpawner, &options, file, &env::temp_dir()) {
+        Ok(()) if silent => {
+            events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::InstallingSilently });
+            app.exit(0);
+        }
+        Ok(()) => events::emit(app, AppEvent::UpdateProgress { stage: UpdateStage::InstallerLaunched }),
+        Err(InstallError::Failed(e) | InstallError::Rejected(e)) => {
+            eprintln!("[Updater] Installer from file not run: {}", e);
+            return Err(e);
+        }
+        // Nothing is downloaded to cancel
+        Err(InstallError::Cancelled) => {}
+    }
+    Ok(())
+}
+
+/// Make sure the installer at `path` is signed by PUBLISHER. Without one
+/// built in, there's no certificate to check against: a debug build only
+/// says so, and a release build refuses the installer.
+fn verify_publisher(path: &Path) -> Result<(), String> {
+    match PUBLISHER {
+        Some(publisher) => authenticode::verify_publisher(path, publisher),
+        None if cfg!(debug_assertions) => {
+            eprintln!("[Updater] Built without a publisher, so who signed {} isn't checked", path.display());
+            Ok(())
+        }
+        None => Err("This build has no publisher to check installers against".to_string()),
+    }
+}
+
+/// Check the installer at `installer` against the updater signature at
+/// `signature` (as the release publishes it, "<installer>.sig")
+fn verify_signature_file(
+    fs: &dyn FileSystem,
+    installer: &Path,
+    signature: &Path,
+    pubkey: &str,
+) -> Result<(), String> {
+    let name = |path: &Path| path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string();
+    let text = fs.read(signature).map_err(|_| {
+        format!("{} has no updater signature - put {} from its release next to it", name(installer), name(signature))
+    })?;
+    verify_updater_signature(fs, installer, &text, pubkey)
+}
+
+/// Check the file at `path`, as it is on disk, against the updater
+/// signature `signature`, made with the key whose public half is `pubkey`:
+/// the check the Tauri updater makes of a download
+fn verify_updater_signature(fs: &dyn FileSystem, path: &Path, signature: &[u8], pubkey: &str) -> Result<(), String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use minisign_verify::{PublicKey, Signature};
 
-    // Download the file
-    let bytes = response
-        .bytes()
+    let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+    // Both are base64 of minisign's own text format
+    let decode = |text: &[u8]| STANDARD.decode(text.trim_ascii()).ok().and_then(|text| String::from_utf8(text).ok());
+    let public_key = decode(pubkey.as_bytes())
+        .and_then(|key| PublicKey::decode(&key).ok())
+        .ok_or_else(|| "The updater key this build has isn't valid".to_string())?;
+    let signature = decode(signature)
+        .and_then(|text| Signature::decode(&text).ok())
+        .ok_or_else(|| format!("{}'s updater signature isn't valid", name))?;
+
+    let data = fs.read(path)?;
+    public_key.verify(&data, &signature, true).map_err(|e| {
+        format!(
+            "{} doesn't match its updater signature ({}) - it may be corrupted or tampered with, so it wasn't run",
+            name, e
+        )
+    })
+}
+
+/// The version of the installer at `installer`, from its name as releases
+/// name it ("QuickRun_1.3.0_x64-setup.exe" is 1.3.0)
+fn installer_version(installer: &Path) -> Option<String> {
+    let name = installer.file_name()?.to_str()?;
+    let version = name.strip_prefix("QuickRun_")?.split('_').next()?;
+    Version::parse(version).ok().map(|_| version.to_string())
+}
+
+/// What's kept to go back to: the portable exe in portable mode, otherwise
+/// the installer
+fn rollback_kind() -> Kind {
+    if storage::is_portable() {
+        Kind::Portable
+    } else {
+        Kind::Installer
+    }
+}
+
+/// Where the release's manifest (latest.json) says `version`'s installer is,
+/// and its updater signature, read through the Tauri updater.
+///
+/// The plugin's check is used for the manifest, but not its download or
+/// install: which release is offered comes from the GitHub API (for its
+/// notes, the portable exe and Skip This Version), and the installer is
+/// downloaded by download_verified() so that it's written to disk as it
+/// arrives, can be cancelled, and is Authenticode-checked and kept to go
+/// back to before it's run.
+async fn signed_manifest(app: &AppHandle, version: &str) -> Result<(String, String), InstallError> {
+    if UPDATER_PUBKEY.is_none() {
+        return Err(InstallError::Failed("This build has no updater key to check the installer with".to_string()));
+    }
+    let updater = app
+        .updater()
+        .map_err(|e| InstallError::Failed(format!("Failed to start the updater: {}", e)))?;
+    let update = updater
+        .check()
         .await
-        .map_err(|e| format!("Failed to download file: {}", e))?;
+        .map_err(|e| InstallError::Failed(format!("Failed to read the update manifest: {}", e)))?
+        .ok_or_else(|| InstallError::Failed("The update manifest has no newer version".to_string()))?;
+    if compare_versions(&update.version, version) != Ordering::Equal {
+        return Err(InstallError::Failed(format!(
+            "The update manifest is for {}, not {}",
+            update.version, version
+        )));
+    }
+    Ok((update.download_url.to_string(), update.signature))
+}
 
-    // Write to temp file
-    std::fs::write(&installer_path, &bytes)
-        .map_err(|e| format!("Failed to write installer: {}", e))?;
+/// Download the installer of the release `info` describes, as its manifest
+/// lists it (see signed_manifest), and run it (see download_and_run)
+async fn install_update(
+    app: &AppHandle,
+    http: &impl HttpClient,
+    options: &InstallOptions<'_>,
+    info: &UpdateInfo,
+) -> Result<(), InstallError> {
+    let (url, signature) = signed_manifest(app, &info.version).await?;
+    eprintln!("[Updater] Downloading signed installer from: {}", url);
+    let download = Download { url: &url, checksums_url: info.checksums_url.as_deref(), signature: Some(&signature) };
+    download_and_run(http, &SystemFs, &SystemSpawner, options, &download, &env::temp_dir()).await
+}
 
-    eprintln!(
-        "[Updater] Download complete ({} bytes). Launching installer...",
-        bytes.len()
+/// Download the portable exe of the release `info` describes, with its
+/// updater signature, and swap it in for `exe` (see
+/// download_and_swap_portable)
+async fn update_portable_exe(
+    http: &impl HttpClient,
+    options: &InstallOptions<'_>,
+    info: &UpdateInfo,
+    exe: &Path,
+) -> Result<(), InstallError> {
+    let url = info
+        .portable_url
+        .as_deref()
+        .ok_or_else(|| InstallError::Failed("The release has no portable exe".to_string()))?;
+    let signature = match info.signature_url.as_deref() {
+        Some(signature_url) => Some(fetch_signature(http, signature_url).await?),
+        None => None,
+    };
+    let download = Download { url, checksums_url: info.checksums_url.as_deref(), signature: signature.as_deref() };
+    download_and_swap_portable(http, &SystemFs, options, &download, exe).await
+}
+
+/// The updater signature at `url` (a release's "<file>.sig")
+async fn fetch_signature(http: &impl HttpClient, url: &str) -> Result<String, InstallError> {
+    let response = http
+        .get(url, &[])
+        .await
+        .map_err(|e| InstallError::Rejected(format!("Failed to download the updater signature: {}", e)))?;
+    if !response.is_success() {
+        return Err(InstallError::Rejected(format!(
+            "Failed to download the updater signature (status {})",
+            response.status
+        )));
+    }
+    Ok(response.text())
+}
+
+/// Make sure the installer of `running_version` is kept in `dir` to go back
+/// to (see rollback.rs). If it isn't (it was installed by hand, not by the
+/// updater), it's downloaded into `temp_dir` from its own release and
+/// checked as an update is (see download_verified), so that Revert works
+/// from the first update on. It's kept with its updater signature, without
+/// which Revert won't run it.
+async fn keep_running_installer(
+    http: &impl HttpClient,
+    fs: &dyn FileSystem,
+    options: &InstallOptions<'_>,
+    dir: &Path,
+    running_version: &str,
+    temp_dir: &Path,
+) -> Result<(), String> {
+    if rollback::is_kept(fs, dir, Kind::Installer, running_version) {
+        return Ok(());
+    }
+    let api_url = format!(
+        "https://api.github.com/repos/{}/{}/releases/tags/v{}",
+        GITHUB_OWNER, GITHUB_REPO, running_version
     );
+    let response = http
+        .get(&api_url, &[("Accept", "application/vnd.github.v3+json")])
+        .await
+        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
+    if !response.is_success() {
+        return Err(format!("GitHub API returned error {}: {}", response.status, response.text()));
+    }
+    let release: GitHubRelease =
+        serde_json::from_slice(&response.body).map_err(|e| format!("Failed to parse release JSON: {}", e))?;
+    let url = find_installer_asset(&release.assets).ok_or_else(|| "Its release has no installer".to_string())?;
+    let checksums_url = find_checksums_asset(&release.assets, &url);
+    let signature_url = find_signature_asset(&release.assets, &url)
+        .ok_or_else(|| "Its release has no updater signature for its installer".to_string())?;
+    let message = |e| match e {
+        InstallError::Failed(e) | InstallError::Rejected(e) => e,
+        InstallError::Cancelled => "Download cancelled".to_string(),
+    };
+    let signature = fetch_signature(http, &signature_url).await.map_err(message)?;
+
+    let path = temp_dir.join(file_name(&url));
+    let download = Download { url: &url, checksums_url: checksums_url.as_deref(), signature: Some(&signature) };
+    download_verified(http, fs, options, &download, &path).await.map_err(message)?;
+    let kept = fs
+        .write(&rollback::signature_of(&path), signature.as_bytes())
+        .and_then(|_| rollback::keep(fs, dir, Kind::Installer, &path, running_version, running_version));
+    for temp_file in [rollback::signature_of(&path), path] {
+        if let Err(e) = fs.remove_file(&temp_file) {
+            eprintln!("[Updater] {}", e);
+        }
+    }
+    kept
+}
+
+/// Download the installer at `download` into `temp_dir`, verified (see
+/// download_verified), and launch it
+async fn download_and_run(
+    http: &impl HttpClient,
+    fs: &dyn FileSystem,
+    spawner: &dyn ProcessSpawner,
+    options: &InstallOptions<'_>,
+    download: &Download<'_>,
+    temp_dir: &Path,
+) -> Result<(), InstallError> {
+    let path = temp_dir.join(file_name(download.url));
+    download_verified(http, fs, options, download, &path).await?;
+    // Beside it, to be kept with it (see rollback::keep)
+    if let Some(signature) = download.signature {
+        fs.write(&rollback::signature_of(&path), signature.as_bytes())
+            .map_err(|e| InstallError::Failed(format!("Failed to write the installer's signature: {}", e)))?;
+    }
+    (options.keep)(&path);
+    launch_installer(spawner, &path, &options.mode).map_err(InstallError::Failed)
+}
 
+/// Copy the installer at `file`, with its updater signature if it has one
+/// beside it, into `temp_dir` and launch the copy (see launch_verified), so
+/// that what's run is what was checked even if `file` changes meanwhile (on
+/// a USB stick or network share, say)
+fn install_file(
+    fs: &dyn FileSystem,
+    spawner: &dyn ProcessSpawner,
+    options: &InstallOptions<'_>,
+    file: &Path,
+    temp_dir: &Path,
+) -> Result<(), InstallError> {
+    let name = file.file_name().filter(|_| fs.is_file(file));
+    let Some(name) = name else {
+        return Err(InstallError::Failed(format!("{} isn't a file", file.display())));
+    };
+    // In a folder of its own, so that an installer already in `temp_dir`
+    // isn't copied onto itself
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_nanos()).unwrap_or_default();
+    let path = temp_dir.join(format!("QuickRun-{}-{}", std::process::id(), nanos)).join(name);
+    fs.copy(file, &path)
+        .map_err(|e| InstallError::Failed(format!("Failed to copy the installer: {}", e)))?;
+    let signature = rollback::signature_of(file);
+    if fs.is_file(&signature) {
+        fs.copy(&signature, &rollback::signature_of(&path))
+            .map_err(|e| InstallError::Failed(format!("Failed to copy the installer's signature: {}", e)))?;
+    }
+    launch_verified(spawner, options, &path)
+}
+
+/// Launch the installer at `path`, once it passes `options.verify_signature`
+/// (see authenticode.rs). An installer that doesn't is left where it is, but
+/// isn't run.
+fn launch_verified(
+    spawner: &dyn ProcessSpawner,
+    options: &InstallOptions<'_>,
+    path: &Path,
+) -> Result<(), InstallError> {
+    (options.verify_signature)(path).map_err(InstallError::Rejected)?;
+    (options.keep)(path);
+    launch_installer(spawner, path, &options.mode).map_err(InstallError::Failed)
+}
+
+/// Start the installer at `installer` (see installer_args)
+fn launch_installer(spawner: &dyn ProcessSpawner, installer: &Path, mode: &InstallMode) -> Result<(), String> {
     // Launch the installer using cmd /C start
     // This detaches the process so it continues after we exit
+    let args = installer_args(installer, mode);
     #[cfg(windows)]
     {
-        use std::os::windows::process::CommandExt;
         const CREATE_NO_WINDOW: u32 = 0x08000000;
         const DETACHED_PROCESS: u32 = 0x00000008;
 
-        std::process::Command::new("cmd")
-            .args(["/C", "start", "", installer_path.to_str().unwrap_or("")])
-            .creation_flags(CREATE_NO_WINDOW | DETACHED_PROCESS)
-            .spawn()
+        spawner
+            .spawn(Path::new("cmd"), &args, &[], None, CREATE_NO_WINDOW | DETACHED_PROCESS)
             .map_err(|e| format!("Failed to launch installer: {}", e))?;
 
         eprintln!("[Updater] Installer launched successfully");
+        Ok(())
     }
 
     #[cfg(not(windows))]
     {
-        return Err("Update installation is only supported on Windows".to_string());
+        let _ = (spawner, args);
+        Err("Update installation is only supported on Windows".to_string())
     }
+}
 
+/// Download the portable exe at `download` next to `exe` (this copy's own
+/// program) as "<exe>.new", verified (see download_verified), then swap it
+/// in (see swap_in)
+async fn download_and_swap_portable(
+    http: &impl HttpClient,
+    fs: &dyn FileSystem,
+    options: &InstallOptions<'_>,
+    download: &Download<'_>,
+    exe: &Path,
+) -> Result<(), InstallError> {
+    let staged = with_suffix(exe, ".new");
+    download_verified(http, fs, options, download, &staged).await?;
+    (options.keep)(&staged);
+    swap_in(fs, exe, &staged).map_err(InstallError::Failed)
+}
+
+/// Put the program at `staged` in place of `exe`, keeping the old one as
+/// "<exe>.old" until it's deleted (see remove_replaced_program). If the new
+/// one can't be moved in, the old one is put back.
+fn swap_in(fs: &dyn FileSystem, exe: &Path, staged: &Path) -> Result<(), String> {
+    let old = with_suffix(exe, ".old");
+    fs.remove_file(&old)?;
+    fs.rename(exe, &old)?;
+    if let Err(e) = fs.rename(staged, exe) {
+        if let Err(restore_error) = fs.rename(&old, exe) {
+            eprintln!("[Updater] {}", restore_error);
+        }
+        return Err(e);
+    }
+    eprintln!("[Updater] Swapped in the new version of {}", exe.display());
     Ok(())
 }
 
-/// Open a URL in the system's default browser.
-fn open_url_in_browser(url: &str) -> Result<(), String> {
-    #[cfg(windows)]
-    {
-        std::process::Command::new("cmd")
-            .args(["/C", "start", "", url])
-            .spawn()
-            .map_err(|e| format!("Failed to open browser: {}", e))?;
+/// `path` with `suffix` added to its name ("QuickRun.exe" → "QuickRun.exe.new")
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Delete the program a portable update replaced, if it's still there. It
+/// was running until the restart, so this waits for it to have exited.
+fn remove_replaced_program() {
+    let Ok(exe) = env::current_exe() else {
+        return;
+    };
+    if let Err(e) = SystemFs.remove_file(&with_suffix(&exe, ".old")) {
+        eprintln!("[Updater] {}", e);
     }
+}
 
-    #[cfg(not(windows))]
-    {
-        return Err("Opening browser is only supported on Windows".to_string());
+/// Download `download` into `path` and make sure it's the release's.
+///
+/// The file is written as it arrives, reporting progress to
+/// `options.on_progress`, and is deleted again if the download fails, is
+/// cancelled, or doesn't match its SHA-256 checksum or its updater signature
+/// (each checked if the release has one; see check_download). Once
+/// complete, it must also pass `options.verify_signature` (see
+/// authenticode.rs).
+async fn download_verified(
+    http: &impl HttpClient,
+    fs: &dyn FileSystem,
+    options: &InstallOptions<'_>,
+    download: &Download<'_>,
+    path: &Path,
+) -> Result<(), InstallError> {
+    let url = download.url;
+    let filename = file_name(url);
+
+    eprintln!(
+        "[Updater] Downloading to: {}",
+        path.display()
+    );
+
+    // Start from an empty file, then write the download as it comes in
+    fs.write(path, &[])
+        .map_err(|e| InstallError::Failed(format!("Failed to write {}: {}", filename, e)))?;
+    let mut hasher = Sha256::new();
+    let mut bytes = 0;
+    let mut on_chunk = |chunk: &[u8], total: Option<u64>| {
+        if options.cancel.load(atomic::Ordering::SeqCst) {
+            return Err("Download cancelled".to_string());
+        }
+        fs.append(path, chunk)
+            .map_err(|e| format!("Failed to write {}: {}", filename, e))?;
+        hasher.update(chunk);
+        let previous = bytes;
+        bytes += chunk.len() as u64;
+        if worth_reporting(previous, bytes, total) {
+            (options.on_progress)(bytes, total);
+        }
+        Ok(())
+    };
+    let streamed = http.get_streaming(url, &mut on_chunk).await;
+
+    let verified = match streamed {
+        Err(_) if options.cancel.load(atomic::Ordering::SeqCst) => Err(InstallError::Cancelled),
+        Err(e) => Err(InstallError::Failed(format!("Failed to download {}: {}", filename, e))),
+        Ok(status) if !(200..300).contains(&status) => {
+            Err(InstallError::Failed(format!("Download failed with status: {}", status)))
+        }
+        Ok(_) => check_download(http, fs, options, download, &hex(&hasher.finalize()), path).await,
+    };
+    if let Err(e) = verified {
+        if let Err(remove_error) = fs.remove_file(path) {
+            eprintln!("[Updater] {}", remove_error);
+        }
+        return Err(e);
     }
+    (options.verify_signature)(path).map_err(InstallError::Rejected)?;
 
+    eprintln!("[Updater] Download complete ({} bytes)", bytes);
     Ok(())
 }
+
+/// Check the download at `path` (whose SHA-256 is `actual`) against the
+/// release's checksums and its updater signature, made with
+/// `options.updater_key`, whichever it has. One with neither is rejected.
+async fn check_download(
+    http: &impl HttpClient,
+    fs: &dyn FileSystem,
+    options: &InstallOptions<'_>,
+    download: &Download<'_>,
+    actual: &str,
+    path: &Path,
+) -> Result<(), InstallError> {
+    let filename = file_name(download.url);
+    if download.checksums_url.is_none() && download.signature.is_none() {
+        return Err(InstallError::Rejected(format!(
+            "The release has neither checksums nor an updater signature for {}, so it can't be verified",
+            filename
+        )));
+    }
+    match download.checksums_url {
+        Some(checksums_url) => verify_checksum(http, checksums_url, filename, actual).await?,
+        None => eprintln!("[Updater] The release has no checksums for {}", filename),
+    }
+    match download.signature {
+        Some(signature) => {
+            let key = options
+                .updater_key
+                .ok_or_else(|| InstallError::Rejected("This build has no updater key to check it with".to_string()))?;
+            verify_updater_signature(fs, path, signature.as_bytes(), key).map_err(InstallError::Rejected)?;
+            eprintln!("[Updater] Updater signature verified: {}", filename);
+        }
+        None => eprintln!("[Updater] The release has no updater signature for {}", filename),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::os::memory::{FakeHttp, MemoryFs, RecordingSpawner};
+    use tauri::async_runtime::block_on;
+
+    const LATEST_URL: &str = "https://api.github.com/repos/Swatto86/QuickRun/releases/latest";
+    const SETUP_URL: &str = "https://github.com/Swatto86/QuickRun/releases/download/v1.3.0/QuickRun_1.3.0_x64-setup.exe";
+    const SUMS_URL: &str = "https://github.com/Swatto86/QuickRun/releases/download/v1.3.0/SHA256SUMS";
+    const PORTABLE_URL: &str = "https://example.com/portable.exe";
+    const RELEASES_URL: &str = "https://api.github.com/repos/Swatto86/QuickRun/releases?per_page=100";
+
+    /// A GitHub release as the API returns it
+    fn release_json(tag: &str) -> String {
+        serde_json::json!({
+            "tag_name": tag,
+            "body": "Bug fixes",
+            "html_url": format!("https://github.com/Swatto86/QuickRun/releases/tag/{}", tag),
+            "assets": [
+                { "name": "QuickRun_portable.exe", "browser_download_url": PORTABLE_URL },
+                { "name": "QuickRun_1.3.0_x64-setup.exe", "browser_download_url": SETUP_URL },
+                { "name": "SHA256SUMS", "browser_download_url": SUMS_URL },
+            ],
+        })
+        .to_string()
+    }
+
+    /// The temp folder download_and_run() downloads the installer into in
+    /// these tests
+    const TEMP: &str = "C:\\Temp";
+
+    /// Where download_and_run() writes the installer in these tests
+    fn installer() -> PathBuf {
+        Path::new(TEMP).join("QuickRun_1.3.0_x64-setup.exe")
+    }
+
+    /// Download an installer from SETUP_URL and launch it, with a signature
+    /// check every installer passes
+    fn install(fs: &MemoryFs, spawner: &RecordingSpawner) -> Result<(), InstallError> {
+        let cancel = AtomicBool::new(false);
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &|_: &Path| Ok(()),
+            updater_key: None,
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+        install_with(fs, spawner, &options)
+    }
+
+    fn install_with(fs: &MemoryFs, spawner: &RecordingSpawner, options: &InstallOptions) -> Result<(), InstallError> {
+        let sums = format!("{}  QuickRun_1.3.0_x64-setup.exe\n", hex(&Sha256::digest(b"MZ installer")));
+        let http = FakeHttp::new()
+            .with_response(SETUP_URL, 200, "MZ installer")
+            .with_response(SUMS_URL, 200, sums);
+        let download = Download { url: SETUP_URL, checksums_url: Some(SUMS_URL), signature: None };
+        block_on(download_and_run(&http, fs, spawner, options, &download, Path::new(TEMP)))
+    }
+
+    /// Update the portable copy at C:\Tools\QuickRun.exe from PORTABLE_URL
+    fn update_portable(http: &FakeHttp, fs: &MemoryFs) -> Result<(), InstallError> {
+        let cancel = AtomicBool::new(false);
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &|_: &Path| Ok(()),
+            updater_key: None,
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+        update_portable_with(http, fs, &options, Some(SUMS_URL))
+    }
+
+    fn update_portable_with(
+        http: &FakeHttp,
+        fs: &MemoryFs,
+        options: &InstallOptions,
+        checksums_url: Option<&str>,
+    ) -> Result<(), InstallError> {
+        let exe = Path::new("C:\\Tools\\QuickRun.exe");
+        let download = Download { url: PORTABLE_URL, checksums_url, signature: None };
+        block_on(download_and_swap_portable(http, fs, options, &download, exe))
+    }
+
+    fn check(http: &FakeHttp, current: &str) -> Result<UpdateInfo, String> {
+        check_skipping(http, current, None)
+    }
+
+    /// check() with the "skip_version" setting
+    fn check_skipping(http: &FakeHttp, current: &str, skip_version: Option<&str>) -> Result<UpdateInfo, String> {
+        block_on(check_for_update_with(http, current, skip_version, &Mutex::new(ApiCache::new()), 0, false))
+    }
+
+    #[test]
+    fn newer_release_is_available() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("v1.3.0"));
+        let info = check(&http, "1.2.9").unwrap();
+
+        assert!(info.available);
+        assert_eq!(info.version, "1.3.0");
+        assert_eq!(info.current_version, "1.2.9");
+        assert_eq!(info.body, "Bug fixes");
+        assert_eq!(info.installer_url.as_deref(), Some(SETUP_URL));
+        assert_eq!(info.checksums_url.as_deref(), Some(SUMS_URL));
+    }
+
+    #[test]
+    fn same_or_older_release_is_not_available() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("1.3.0"));
+        assert!(!check(&http, "1.3.0").unwrap().available);
+        assert!(!check(&http, "1.10.0").unwrap().available);
+    }
+
+    #[test]
+    fn pre_releases_come_before_their_release() {
+        let ordered = [
+            "1.2.0-alpha",
+            "1.2.0-alpha.1",
+            "1.2.0-alpha.beta",
+            "1.2.0-beta",
+            "1.2.0-beta.2",
+            "1.2.0-beta.11",
+            "1.2.0-rc.1",
+            "1.2.0",
+            "1.2.1-beta.1",
+            "1.10.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(compare_versions(pair[0], pair[1]), Ordering::Less, "{} < {}", pair[0], pair[1]);
+            assert_eq!(compare_versions(pair[1], pair[0]), Ordering::Greater, "{} > {}", pair[1], pair[0]);
+        }
+    }
+
+    #[test]
+    fn build_metadata_and_unparseable_versions_compare_equal() {
+        assert_eq!(compare_versions("1.2.0+build.5", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2.0-beta.1+sha.abc", "1.2.0-beta.1+sha.def"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2", "1.1.0"), Ordering::Equal);
+        assert_eq!(compare_versions("nightly", "1.1.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn release_is_newer_than_its_pre_release() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("v1.3.0"));
+        assert!(check(&http, "1.3.0-rc.2").unwrap().available);
+
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("v1.3.0-beta.1"));
+        assert!(!check(&http, "1.3.0").unwrap().available);
+        assert!(check(&http, "1.2.9").unwrap().available);
+    }
+
+    #[test]
+    fn skipped_version_is_offered_again_once_a_newer_one_is_out() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("v1.3.0"));
+        let info = check_skipping(&http, "1.2.0", Some("1.3.0")).unwrap();
+        assert!(!info.available);
+        assert!(info.skipped);
+        assert_eq!(info.version, "1.3.0");
+
+        let info = check_skipping(&http, "1.2.0", Some("1.2.5")).unwrap();
+        assert!(info.available);
+        assert!(!info.skipped);
+
+        // Nothing to skip when already up to date
+        assert!(!check_skipping(&http, "1.3.0", Some("1.3.0")).unwrap().skipped);
+    }
+
+    #[test]
+    fn background_checks_run_every_few_hours_unless_off() {
+        let start = Instant::now();
+        let hours = |n: u64| Duration::from_secs(n * 3600);
+
+        assert!(check_due(None, start, 24));
+        assert!(!check_due(Some(start), start + hours(23), 24));
+        assert!(check_due(Some(start), start + hours(24), 24));
+        assert!(!check_due(None, start, 0));
+        assert!(!check_due(Some(start), start + hours(1000), 0));
+        assert!(!check_due(Some(start), start + hours(1000), u64::MAX));
+    }
+
+    #[test]
+    fn each_version_is_announced_once() {
+        assert!(first_announcement("9.0.0"));
+        assert!(!first_announcement("9.0.0"));
+        assert!(first_announcement("9.0.1"));
+    }
+
+    #[test]
+    fn no_releases_is_not_an_error() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 404, "Not Found");
+        let info = check(&http, "1.2.0").unwrap();
+
+        assert!(!info.available);
+        assert_eq!(info.version, "1.2.0");
+        assert_eq!(info.installer_url, None);
+    }
+
+    #[test]
+    fn api_errors_are_reported() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 403, "rate limited");
+        let error = check(&http, "1.2.0").unwrap_err();
+        assert!(error.contains("403") && error.contains("rate limited"), "{}", error);
+
+        let error = check(&FakeHttp::new(), "1.2.0").unwrap_err();
+        assert!(error.starts_with("Failed to fetch release info"), "{}", error);
+    }
+
+    #[test]
+    fn unchanged_releases_are_not_downloaded_again() {
+        let cache = Mutex::new(ApiCache::new());
+        let http = FakeHttp::new()
+            .with_response(LATEST_URL, 200, release_json("v1.3.0"))
+            .with_response_headers(LATEST_URL, &[("etag", "W/\"abc\"")]);
+        assert!(block_on(check_for_update_with(&http, "1.2.0", None, &cache, 0, false)).unwrap().available);
+        assert!(!http.sent_headers.lock().unwrap()[0].iter().any(|(name, _)| name == "If-None-Match"));
+
+        let http = FakeHttp::new().with_response(LATEST_URL, 304, "");
+        let info = block_on(check_for_update_with(&http, "1.2.0", None, &cache, 0, false)).unwrap();
+        assert!(http.sent_headers.lock().unwrap()[0].contains(&("If-None-Match".into(), "W/\"abc\"".into())));
+        assert!(info.available);
+        assert_eq!(info.version, "1.3.0");
+    }
+
+    #[test]
+    fn used_up_rate_limit_is_waited_out() {
+        let cache = Mutex::new(ApiCache::new());
+        let http = FakeHttp::new()
+            .with_response(LATEST_URL, 403, "API rate limit exceeded")
+            .with_response_headers(LATEST_URL, &[("X-RateLimit-Remaining", "0"), ("X-RateLimit-Reset", "1000")]);
+        let error = block_on(check_for_update_with(&http, "1.2.0", None, &cache, 100, false)).unwrap_err();
+        assert!(error.contains("resume in 15 minutes"), "{}", error);
+
+        // No request until the reset
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("v1.3.0"));
+        assert!(block_on(check_for_update_with(&http, "1.2.0", None, &cache, 999, false)).is_err());
+        assert!(http.requested.lock().unwrap().is_empty());
+        assert!(block_on(check_for_update_with(&http, "1.2.0", None, &cache, 1000, false)).unwrap().available);
+    }
+
+    #[test]
+    fn retry_after_is_honoured() {
+        let http = FakeHttp::new()
+            .with_response(LATEST_URL, 429, "")
+            .with_response_headers(LATEST_URL, &[("Retry-After", "30")]);
+        assert_eq!(rate_limited_until(&block_on(http.get(LATEST_URL, &[])).unwrap(), 100), Some(130));
+        let error = check(&http, "1.2.0").unwrap_err();
+        assert!(error.contains("resume in 1 minute"), "{}", error);
+    }
+
+    #[test]
+    fn malformed_release_json_is_reported() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, "<html>");
+        assert!(check(&http, "1.2.0").unwrap_err().starts_with("Failed to parse release JSON"));
+    }
+
+    /// fetch_changelog() from 1.2.0 to 1.3.0 with a fresh cache
+    fn changelog(http: &FakeHttp) -> Result<Vec<ReleaseNotes>, String> {
+        block_on(fetch_changelog(http, "1.2.0", "1.3.0", &Mutex::new(ApiCache::new()), 0))
+    }
+
+    #[test]
+    fn changelog_covers_every_release_since_the_current_one() {
+        let release = |tag: &str, notes: &str, prerelease: bool| {
+            serde_json::json!({
+                "tag_name": tag,
+                "body": notes,
+                "html_url": "https://github.com/Swatto86/QuickRun/releases",
+                "assets": [],
+                "prerelease": prerelease,
+            })
+        };
+        let releases = serde_json::json!([
+            release("v1.4.0-beta.1", "Beta", true),
+            release("v1.3.0", "Bug fixes", false),
+            release("v1.1.0", "Old", false),
+            release("v1.2.10", "Faster search", false),
+            release("v1.2.0", "Current", false),
+        ]);
+        let http = FakeHttp::new().with_response(RELEASES_URL, 200, releases.to_string());
+
+        let notes = |version: &str, body: &str| ReleaseNotes { version: version.into(), body: body.into() };
+        assert_eq!(changelog(&http).unwrap(), [notes("1.3.0", "Bug fixes"), notes("1.2.10", "Faster search")]);
+        assert!(changelog(&FakeHttp::new().with_response(RELEASES_URL, 500, "")).is_err());
+    }
+
+    #[test]
+    fn checks_leave_the_changelog_to_the_update_window() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("v1.3.0"));
+        assert!(check(&http, "1.2.0").unwrap().available);
+        assert_eq!(*http.requested.lock().unwrap(), [LATEST_URL]);
+    }
+
+    #[test]
+    fn changelog_is_fetched_through_the_cache() {
+        let cache = Mutex::new(ApiCache::new());
+        let http = FakeHttp::new()
+            .with_response(RELEASES_URL, 200, "[]")
+            .with_response_headers(RELEASES_URL, &[("etag", "W/\"list\"")]);
+        block_on(fetch_changelog(&http, "1.2.0", "1.3.0", &cache, 0)).unwrap();
+
+        let http = FakeHttp::new().with_response(RELEASES_URL, 304, "");
+        assert!(block_on(fetch_changelog(&http, "1.2.0", "1.3.0", &cache, 0)).unwrap().is_empty());
+        assert!(http.sent_headers.lock().unwrap()[0].contains(&("If-None-Match".into(), "W/\"list\"".into())));
+
+        // A used up rate limit is waited out
+        let http = FakeHttp::new()
+            .with_response(RELEASES_URL, 429, "")
+            .with_response_headers(RELEASES_URL, &[("Retry-After", "60")]);
+        assert!(block_on(fetch_changelog(&http, "1.2.0", "1.3.0", &cache, 0)).is_err());
+        let http = FakeHttp::new().with_response(RELEASES_URL, 200, "[]");
+        assert!(block_on(fetch_changelog(&http, "1.2.0", "1.3.0", &cache, 59)).is_err());
+        assert!(http.requested.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn installer_asset_skips_portable_builds() {
+        let asset = |name: &str| GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+        };
+
+        let assets = [asset("QuickRun_portable.exe"), asset("other-setup.exe"), asset("QuickRun_setup.exe")];
+        assert_eq!(find_installer_asset(&assets).as_deref(), Some("https://example.com/QuickRun_setup.exe"));
+
+        let assets = [asset("QuickRun_portable.exe"), asset("setup.exe")];
+        assert_eq!(find_installer_asset(&assets).as_deref(), Some("https://example.com/setup.exe"));
+
+        assert_eq!(find_installer_asset(&[asset("QuickRun.msi")]), None);
+    }
+
+    #[test]
+    fn installer_is_written_before_it_runs() {
+        let fs = MemoryFs::new();
+        let spawner = RecordingSpawner::new();
+
+        let result = install(&fs, &spawner);
+
+        let written = fs.contents(&installer());
+        assert_eq!(written.as_deref(), Some(&b"MZ installer"[..]));
+        if cfg!(windows) {
+            result.unwrap();
+        } else {
+            assert!(result.is_err());
+            assert!(spawner.spawned().is_empty());
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn installer_is_launched_detached() {
+        let spawner = RecordingSpawner::new();
+
+        install(&MemoryFs::new(), &spawner).unwrap();
+
+        let spawned = spawner.spawned();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].program, Path::new("cmd"));
+        let installer = installer().display().to_string();
+        assert_eq!(spawned[0].args, ["/C", "start", "", installer.as_str()]);
+        assert_eq!(spawned[0].creation_flags, 0x08000000 | 0x00000008);
+    }
+
+    #[test]
+    fn silent_installs_are_waited_for_then_quickrun_restarts() {
+        let installer = Path::new("C:\\Temp\\QuickRun_1.3.0_x64-setup.exe");
+        let relaunch = PathBuf::from("C:\\Program Files\\QuickRun\\quickrun.exe");
+
+        assert_eq!(
+            installer_args(installer, &InstallMode::Wizard),
+            ["/C", "start", "", "C:\\Temp\\QuickRun_1.3.0_x64-setup.exe"]
+        );
+        assert_eq!(
+            installer_args(installer, &InstallMode::Silent { relaunch }),
+            [
+                "/C",
+                "start",
+                "",
+                "/wait",
+                "C:\\Temp\\QuickRun_1.3.0_x64-setup.exe",
+                "/S",
+                "&",
+                "start",
+                "",
+                "C:\\Program Files\\QuickRun\\quickrun.exe"
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_download_swaps_nothing_in() {
+        let http = FakeHttp::new().with_response(PORTABLE_URL, 500, "");
+        let exe = Path::new("C:\\Tools\\QuickRun.exe");
+        let fs = MemoryFs::new();
+        fs.write(exe, b"MZ old").unwrap();
+
+        let error = update_portable(&http, &fs).unwrap_err();
+
+        assert_eq!(error, InstallError::Failed("Download failed with status: 500".to_string()));
+        assert_eq!(fs.contents(exe).as_deref(), Some(&b"MZ old"[..]));
+        assert!(!fs.is_file(Path::new("C:\\Tools\\QuickRun.exe.new")));
+    }
+
+    #[test]
+    fn checksums_asset_prefers_the_installers_own() {
+        let asset = |name: &str| GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+        };
+
+        let assets = [asset("SHA256SUMS"), asset("QuickRun_1.3.0_x64-setup.exe.sha256")];
+        let url = find_checksums_asset(&assets, SETUP_URL);
+        assert_eq!(url.as_deref(), Some("https://example.com/QuickRun_1.3.0_x64-setup.exe.sha256"));
+        assert_eq!(find_checksums_asset(&assets[..1], SETUP_URL).as_deref(), Some("https://example.com/SHA256SUMS"));
+        assert_eq!(find_checksums_asset(&[asset("checksums.md5")], SETUP_URL), None);
+    }
+
+    #[test]
+    fn checksums_are_read_as_sha256sum_writes_them() {
+        let hash = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+        let sums = format!("{}  QuickRun_portable.exe\n{} *dist/QuickRun_1.3.0_x64-setup.exe\n", "0".repeat(64), hash);
+
+        let expected = expected_checksum(&sums, "QuickRun_1.3.0_x64-setup.exe");
+        assert_eq!(expected.as_deref(), Some(hash.to_lowercase().as_str()));
+        assert_eq!(expected_checksum(&sums, "other.exe"), None);
+        assert_eq!(expected_checksum(&format!("{}\n", hash), "any.exe"), Some(hash.to_lowercase()));
+        assert_eq!(expected_checksum("not-a-hash  any.exe", "any.exe"), None);
+        assert_eq!(hex(&Sha256::digest(b"test")), hash.to_lowercase());
+    }
+
+    #[test]
+    fn portable_exe_with_a_wrong_or_missing_checksum_is_rejected() {
+        let wrong = format!("{}  portable.exe\n", hex(&Sha256::digest(b"something else")));
+        for (status, sums) in [(200, wrong), (200, String::new()), (404, String::new())] {
+            let http = FakeHttp::new()
+                .with_response(PORTABLE_URL, 200, "MZ new")
+                .with_response(SUMS_URL, status, sums);
+            let fs = MemoryFs::new();
+
+            let result = update_portable(&http, &fs);
+
+            assert!(matches!(result, Err(InstallError::Rejected(_))), "{:?}", result);
+            assert!(!fs.is_file(Path::new("C:\\Tools\\QuickRun.exe.new")));
+        }
+    }
+
+    #[test]
+    fn installer_without_a_valid_signature_is_written_but_not_run() {
+        let fs = MemoryFs::new();
+        let spawner = RecordingSpawner::new();
+        let cancel = AtomicBool::new(false);
+        let unsigned = |path: &Path| Err(format!("{} isn't signed", path.display()));
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &unsigned,
+            updater_key: None,
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+
+        let result = install_with(&fs, &spawner, &options);
+
+        assert_eq!(result, Err(InstallError::Rejected(format!("{} isn't signed", installer().display()))));
+        assert!(fs.contents(&installer()).is_some());
+        assert!(spawner.spawned().is_empty());
+    }
+
+    #[test]
+    fn installer_from_a_file_is_copied_checked_and_run() {
+        let file = Path::new("usb").join("QuickRun_1.3.0_x64-setup.exe");
+        let fs = MemoryFs::new();
+        fs.write(&file, b"MZ installer").unwrap();
+        let spawner = RecordingSpawner::new();
+        let cancel = AtomicBool::new(false);
+        let checked = std::sync::Mutex::new(Vec::new());
+        let verify_signature = |path: &Path| {
+            checked.lock().unwrap().push(path.to_path_buf());
+            Ok(())
+        };
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &verify_signature,
+            updater_key: None,
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+
+        let result = install_file(&fs, &spawner, &options, &file, Path::new("temp"));
+
+        let checked = checked.lock().unwrap().clone();
+        let [copy] = &checked[..] else { panic!("{:?}", checked) };
+        assert!(copy.starts_with("temp"), "{}", copy.display());
+        assert_eq!(copy.file_name(), file.file_name());
+        assert_eq!(fs.contents(copy).as_deref(), Some(&b"MZ installer"[..]));
+        assert_eq!(result.is_ok(), cfg!(windows), "{:?}", result);
+    }
+
+    #[test]
+    fn installer_already_in_the_temp_folder_is_copied_elsewhere() {
+        let temp = Path::new("temp");
+        let file = temp.join("QuickRun_1.3.0_x64-setup.exe");
+        let fs = MemoryFs::new();
+        fs.write(&file, b"MZ installer").unwrap();
+        let cancel = AtomicBool::new(false);
+        let checked = std::sync::Mutex::new(Vec::new());
+        let verify_signature = |path: &Path| {
+            checked.lock().unwrap().push(path.to_path_buf());
+            Ok(())
+        };
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &verify_signature,
+            updater_key: None,
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+
+        let _ = install_file(&fs, &RecordingSpawner::new(), &options, &file, temp);
+
+        let checked = checked.lock().unwrap().clone();
+        assert_eq!(checked.len(), 1);
+        assert_ne!(checked[0], file);
+        assert!(checked[0].starts_with(temp), "{}", checked[0].display());
+    }
+
+    /// An updater public key, and its signature of b"MZ installer", as
+    /// `tauri signer` writes them (base64 of minisign's text format)
+    const PUBKEY: &str = concat!(
+        "dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXkgMDEyMzQ1Njc4OUFCQ0RFRgpSV1FCSTBWbmlhdk43d09oQjcvenpo",
+        "QytIWERkR09kTHdKbG41Tll3bTZVTlh4M2NobVFTVlRHNAo=",
+    );
+    const SIGNATURE: &str = concat!(
+        "dW50cnVzdGVkIGNvbW1lbnQ6IHNpZ25hdHVyZSBmcm9tIHRhdXJpIHNlY3JldCBrZXkKUlVRQkkwVm5pYXZON3hUZWVBZ0pWaXNscVZN",
+        "UysrZlQxYkZsRVhmTCtUOXFCdVdaWDM4K3JiZmpndldDdFhBRm5JUWdpL0VwZGQxS01pTVZPT25xK2dKRFM5QzNVd25GWWc0PQp0cnVz",
+        "dGVkIGNvbW1lbnQ6IHRpbWVzdGFtcDoxNzYwMDAwMDAwCWZpbGU6UXVpY2tSdW5fMS4zLjBfeDY0LXNldHVwLmV4ZQpZOUVEOUVUU3pY",
+        "WFhmZFo2aW5hU2UxbEpBNEtRa1g2eFZHcThMSnphaG1wOUF0SDNnOGJLTzB1RDdWbzIwSnRBSTRFb3k2c1doa3U5cmx4SkJCU1BBQT09",
+        "Cg==",
+    );
+
+    #[test]
+    fn installer_files_need_their_updater_signature() {
+        let installer = Path::new("usb").join("QuickRun_1.3.0_x64-setup.exe");
+        let signature = Path::new("usb").join("QuickRun_1.3.0_x64-setup.exe.sig");
+        let fs = MemoryFs::new();
+        fs.write(&installer, b"MZ installer").unwrap();
+        let verify = || verify_signature_file(&fs, &installer, &signature, PUBKEY);
+
+        assert!(verify().unwrap_err().contains("has no updater signature"));
+        fs.write(&signature, SIGNATURE.as_bytes()).unwrap();
+        assert_eq!(verify(), Ok(()));
+
+        fs.write(&installer, b"MZ tampered").unwrap();
+        assert!(verify().unwrap_err().contains("doesn't match its updater signature"));
+        fs.write(&signature, b"not a signature").unwrap();
+        assert!(verify().unwrap_err().contains("updater signature isn't valid"));
+    }
+
+    #[test]
+    fn downloads_are_checked_against_their_updater_signature() {
+        for (body, signed) in [("MZ installer", true), ("MZ tampered", false)] {
+            let http = FakeHttp::new().with_response(SETUP_URL, 200, body);
+            let fs = MemoryFs::new();
+            let spawner = RecordingSpawner::new();
+            let cancel = AtomicBool::new(false);
+            let options = InstallOptions {
+                mode: InstallMode::Wizard,
+                verify_signature: &|_: &Path| Ok(()),
+                updater_key: Some(PUBKEY),
+                on_progress: &|_, _| {},
+                keep: &|_: &Path| {},
+                cancel: &cancel,
+            };
+            let download = Download { url: SETUP_URL, checksums_url: None, signature: Some(SIGNATURE) };
+
+            let result = block_on(download_and_run(&http, &fs, &spawner, &options, &download, Path::new(TEMP)));
+
+            if signed {
+                assert_eq!(result.is_ok(), cfg!(windows), "{:?}", result);
+                assert!(fs.is_file(&installer()));
+            } else {
+                let rejected = matches!(&result, Err(InstallError::Rejected(e)) if e.contains("updater signature"));
+                assert!(rejected, "{:?}", result);
+                assert!(!fs.is_file(&installer()));
+                assert!(spawner.spawned().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn signature_asset_is_the_downloads_own() {
+        let asset = |name: &str| GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+        };
+        let assets = [asset("QuickRun_1.3.0_x64-setup.exe.sig"), asset("QuickRun_portable.exe.sig")];
+
+        let url = find_signature_asset(&assets, "https://example.com/QuickRun_portable.exe");
+        assert_eq!(url.as_deref(), Some("https://example.com/QuickRun_portable.exe.sig"));
+        assert_eq!(find_signature_asset(&assets[..1], PORTABLE_URL), None);
+    }
+
+    #[test]
+    fn missing_or_unsigned_installer_files_are_not_run() {
+        let file = Path::new("usb").join("QuickRun_1.3.0_x64-setup.exe");
+        let fs = MemoryFs::new();
+        let spawner = RecordingSpawner::new();
+        let cancel = AtomicBool::new(false);
+        let unsigned = |path: &Path| Err(format!("{} isn't signed", path.display()));
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &unsigned,
+            updater_key: None,
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+        let temp = Path::new("temp");
+
+        let missing = install_file(&fs, &spawner, &options, &file, temp);
+        assert_eq!(missing, Err(InstallError::Failed(format!("{} isn't a file", file.display()))));
+
+        fs.write(&file, b"MZ installer").unwrap();
+        let unsigned = install_file(&fs, &spawner, &options, &file, temp);
+        let rejected = matches!(&unsigned, Err(InstallError::Rejected(e)) if e.ends_with("x64-setup.exe isn't signed"));
+        assert!(rejected, "{:?}", unsigned);
+        assert!(spawner.spawned().is_empty());
+    }
+
+    #[test]
+    fn installer_version_is_read_from_its_name() {
+        let version = |name: &str| installer_version(Path::new(name));
+        assert_eq!(version("QuickRun_1.3.0_x64-setup.exe").as_deref(), Some("1.3.0"));
+        assert_eq!(version("Downloads/QuickRun_1.4.0-beta.1_x64-setup.exe").as_deref(), Some("1.4.0-beta.1"));
+        assert_eq!(version("setup.exe"), None);
+        assert_eq!(version("QuickRun_latest_x64-setup.exe"), None);
+    }
+
+    #[test]
+    fn portable_copies_update_to_the_portable_exe() {
+        let http = FakeHttp::new().with_response(LATEST_URL, 200, release_json("v1.3.0"));
+        let cache = Mutex::new(ApiCache::new());
+        let info = block_on(check_for_update_with(&http, "1.2.0", None, &cache, 0, true)).unwrap();
+        assert_eq!(info.portable_url.as_deref(), Some(PORTABLE_URL));
+        assert_eq!(info.checksums_url.as_deref(), Some(SUMS_URL));
+    }
+
+    #[test]
+    fn portable_update_swaps_in_the_new_exe() {
+        let sums = format!("{}  portable.exe\n", hex(&Sha256::digest(b"MZ new")));
+        let http = FakeHttp::new()
+            .with_response(PORTABLE_URL, 200, "MZ new")
+            .with_response(SUMS_URL, 200, sums);
+        let exe = Path::new("C:\\Tools\\QuickRun.exe");
+        let fs = MemoryFs::new();
+        fs.write(exe, b"MZ old").unwrap();
+
+        update_portable(&http, &fs).unwrap();
+
+        assert_eq!(fs.contents(exe).as_deref(), Some(&b"MZ new"[..]));
+        assert_eq!(fs.contents(Path::new("C:\\Tools\\QuickRun.exe.old")).as_deref(), Some(&b"MZ old"[..]));
+        assert!(!fs.is_file(Path::new("C:\\Tools\\QuickRun.exe.new")));
+    }
+
+    #[test]
+    fn installer_of_the_running_version_is_kept_from_its_release() {
+        const TAG_URL: &str = "https://api.github.com/repos/Swatto86/QuickRun/releases/tags/v1.2.0";
+        const OLD_SETUP_URL: &str =
+            "https://github.com/Swatto86/QuickRun/releases/download/v1.2.0/QuickRun_1.2.0_x64-setup.exe";
+        const OLD_SUMS_URL: &str = "https://github.com/Swatto86/QuickRun/releases/download/v1.2.0/SHA256SUMS";
+        const OLD_SIG_URL: &str =
+            "https://github.com/Swatto86/QuickRun/releases/download/v1.2.0/QuickRun_1.2.0_x64-setup.exe.sig";
+        let release = serde_json::json!({
+            "tag_name": "v1.2.0",
+            "html_url": "https://github.com/Swatto86/QuickRun/releases/tag/v1.2.0",
+            "assets": [
+                { "name": "QuickRun_1.2.0_x64-setup.exe", "browser_download_url": OLD_SETUP_URL },
+                { "name": "QuickRun_1.2.0_x64-setup.exe.sig", "browser_download_url": OLD_SIG_URL },
+                { "name": "SHA256SUMS", "browser_download_url": OLD_SUMS_URL },
+            ],
+        });
+        let sums = format!("{}  QuickRun_1.2.0_x64-setup.exe\n", hex(&Sha256::digest(b"MZ installer")));
+        let http = FakeHttp::new()
+            .with_response(TAG_URL, 200, release.to_string())
+            .with_response(OLD_SETUP_URL, 200, "MZ installer")
+            .with_response(OLD_SIG_URL, 200, SIGNATURE)
+            .with_response(OLD_SUMS_URL, 200, sums);
+        let (dir, temp_dir) = (Path::new("config").join("updates"), Path::new("temp"));
+        let fs = MemoryFs::new();
+        let cancel = AtomicBool::new(false);
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &|_: &Path| Ok(()),
+            updater_key: Some(PUBKEY),
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+
+        block_on(keep_running_installer(&http, &fs, &options, &dir, "1.2.0", temp_dir)).unwrap();
+        let kept = rollback::previous(&fs, &dir, Kind::Installer, "1.3.0").unwrap();
+        assert_eq!(kept.version, "1.2.0");
+        assert_eq!(fs.contents(&kept.path).as_deref(), Some(&b"MZ installer"[..]));
+        verify_signature_file(&fs, &kept.path, &rollback::signature_of(&kept.path), PUBKEY).unwrap();
+        assert!(!fs.is_file(&temp_dir.join("QuickRun_1.2.0_x64-setup.exe")));
+        assert!(!fs.is_file(&temp_dir.join("QuickRun_1.2.0_x64-setup.exe.sig")));
+
+        // Once kept, it isn't downloaded again
+        let requests = http.requested.lock().unwrap().len();
+        block_on(keep_running_installer(&http, &fs, &options, &dir, "1.2.0", temp_dir)).unwrap();
+        assert_eq!(http.requested.lock().unwrap().len(), requests);
+    }
+
+    #[test]
+    fn downloads_that_cant_be_verified_are_rejected() {
+        let http = FakeHttp::new().with_response(SETUP_URL, 200, "MZ installer");
+        let fs = MemoryFs::new();
+        let cancel = AtomicBool::new(false);
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &|_: &Path| Ok(()),
+            updater_key: Some(PUBKEY),
+            on_progress: &|_, _| {},
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+        let download = Download { url: SETUP_URL, checksums_url: None, signature: None };
+
+        let result = block_on(download_verified(&http, &fs, &options, &download, &installer()));
+        assert!(matches!(result, Err(InstallError::Rejected(_))));
+        assert!(!fs.is_file(&installer()));
+    }
+
+    #[test]
+    fn rejected_portable_exe_leaves_the_running_one_alone() {
+        let sums = format!("{}  portable.exe\n", hex(&Sha256::digest(b"something else")));
+        let http = FakeHttp::new()
+            .with_response(PORTABLE_URL, 200, "MZ new")
+            .with_response(SUMS_URL, 200, sums);
+        let exe = Path::new("C:\\Tools\\QuickRun.exe");
+        let fs = MemoryFs::new();
+        fs.write(exe, b"MZ old").unwrap();
+
+        assert!(matches!(update_portable(&http, &fs), Err(InstallError::Rejected(_))));
+        assert_eq!(fs.contents(exe).as_deref(), Some(&b"MZ old"[..]));
+        assert!(!fs.is_file(Path::new("C:\\Tools\\QuickRun.exe.new")));
+        assert!(!fs.is_file(Path::new("C:\\Tools\\QuickRun.exe.old")));
+    }
+
+    #[test]
+    fn only_verified_downloads_are_kept() {
+        for signed in [true, false] {
+            let cancel = AtomicBool::new(false);
+            let kept_paths = std::sync::Mutex::new(Vec::new());
+            let keep = |path: &Path| kept_paths.lock().unwrap().push(path.to_path_buf());
+            let verify_signature = |_: &Path| if signed { Ok(()) } else { Err("Not signed".to_string()) };
+            let options = InstallOptions {
+                mode: InstallMode::Wizard,
+                verify_signature: &verify_signature,
+                updater_key: None,
+                on_progress: &|_, _| {},
+                keep: &keep,
+                cancel: &cancel,
+            };
+
+            let _ = install_with(&MemoryFs::new(), &RecordingSpawner::new(), &options);
+
+            let expected = if signed { vec![installer()] } else { vec![] };
+            assert_eq!(*kept_paths.lock().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn download_reports_progress_as_it_goes() {
+        let http = FakeHttp::new().with_response(PORTABLE_URL, 200, "MZ portable");
+        let fs = MemoryFs::new();
+        let cancel = AtomicBool::new(false);
+        let reported = std::sync::Mutex::new(Vec::new());
+        let on_progress = |bytes, total| reported.lock().unwrap().push((bytes, total));
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &|_: &Path| Ok(()),
+            updater_key: None,
+            on_progress: &on_progress,
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+
+        let _ = update_portable_with(&http, &fs, &options, None);
+
+        // FakeHttp streams 4 bytes at a time
+        assert_eq!(*reported.lock().unwrap(), [(4, Some(11)), (8, Some(11)), (11, Some(11))]);
+    }
+
+    #[test]
+    fn cancelled_download_is_deleted_and_not_swapped_in() {
+        let http = FakeHttp::new().with_response(PORTABLE_URL, 200, "MZ portable");
+        let exe = Path::new("C:\\Tools\\QuickRun.exe");
+        let fs = MemoryFs::new();
+        fs.write(exe, b"MZ old").unwrap();
+        let cancel = AtomicBool::new(false);
+        // Cancel once the first chunk is in
+        let on_progress = |_, _| cancel.store(true, atomic::Ordering::SeqCst);
+        let options = InstallOptions {
+            mode: InstallMode::Wizard,
+            verify_signature: &|_: &Path| Ok(()),
+            updater_key: None,
+            on_progress: &on_progress,
+            keep: &|_: &Path| {},
+            cancel: &cancel,
+        };
+
+        let result = update_portable_with(&http, &fs, &options, None);
+
+        assert_eq!(result, Err(InstallError::Cancelled));
+        assert_eq!(fs.contents(exe).as_deref(), Some(&b"MZ old"[..]));
+        assert!(!fs.is_file(Path::new("C:\\Tools\\QuickRun.exe.new")));
+    }
+
+    #[test]
+    fn progress_is_reported_each_percent_or_each_mib() {
+        assert_eq!(percent(50, Some(200)), Some(25));
+        assert_eq!(percent(50, None), None);
+        assert_eq!(percent(0, Some(0)), None);
+        assert!(worth_reporting(0, 2, Some(200)));
+        assert!(!worth_reporting(2, 3, Some(200)));
+        assert!(!worth_reporting(0, REPORT_EVERY - 1, None));
+        assert!(worth_reporting(REPORT_EVERY - 1, REPORT_EVERY, None));
+    }
+}
@@ -4,6 +4,7 @@
 //
 // Features:
 // - Displays app version dynamically
+// - Diagnostics: build date, settings folder and hotkey status
 // - Check for updates functionality
 // - Links to GitHub repository
 //
@@ -19,6 +20,24 @@ const checkUpdatesBtn = document.getElementById("check-updates-btn") as HTMLButt
 const updateStatus = document.getElementById("update-status") as HTMLParagraphElement;
 const closeBtn = document.getElementById("close-btn") as HTMLButtonElement;
 const versionEl = document.getElementById("about-version") as HTMLParagraphElement;
+const openConfigBtn = document.getElementById("open-config-btn") as HTMLButtonElement;
+
+/// Whether the hotkey is working (see src-tauri/src/hotkey.rs)
+type HotkeyStatus =
+  | { state: "waiting" }
+  | { state: "registered" }
+  | { state: "paused" }
+  | { state: "failed"; error: string };
+
+/// What get_app_info returns (see AppInfo in src-tauri/src/lib.rs)
+interface AppInfo {
+  version: string;
+  build_date: string;
+  config_dir: string;
+  portable: boolean;
+  hotkey: string;
+  hotkey_status: HotkeyStatus;
+}
 
 let updateCheckInProgress = false;
 
@@ -40,6 +59,35 @@ async function loadVersion() {
   }
 }
 
+/// Describe the hotkey's status, e.g. "Alt+Space (paused from the tray)"
+function describeHotkey(hotkey: string, status: HotkeyStatus): string {
+  switch (status.state) {
+    case "registered":
+      return `${hotkey} (working)`;
+    case "paused":
+      return `${hotkey} (paused from the tray)`;
+    case "waiting":
+      return `${hotkey} (not registered yet - startup delay)`;
+    case "failed":
+      return `${hotkey} (not working: ${status.error})`;
+  }
+}
+
+/// Load and display the diagnostics: version, build date, settings folder
+/// and whether the hotkey is working
+async function loadInfo() {
+  try {
+    const info = await invoke<AppInfo>("get_app_info");
+    document.getElementById("info-version")!.textContent = `v${info.version}`;
+    document.getElementById("info-build-date")!.textContent = info.build_date;
+    document.getElementById("info-config-dir")!.textContent =
+      info.portable ? `${info.config_dir} (portable)` : info.config_dir;
+    document.getElementById("info-hotkey")!.textContent = describeHotkey(info.hotkey, info.hotkey_status);
+  } catch (error) {
+    console.error("Failed to get app info:", error);
+  }
+}
+
 /// Show update check status message
 ///
 /// Parameters:
@@ -103,6 +151,7 @@ async function checkForUpdates() {
       current_version: string;
       release_url: string;
       installer_url: string | null;
+      skipped: boolean;
     }>("check_for_update");
     
     if (updateInfo.available) {
@@ -131,6 +180,8 @@ async function checkForUpdates() {
           showUpdateStatus(`Failed to open download page: ${error}`, true, false);
         }
       }
+    } else if (updateInfo.skipped) {
+      showUpdateStatus(`v${updateInfo.version} is available (skipped)`, false, false);
     } else {
       showUpdateStatus("You are running the latest version", false, true);
     }
@@ -146,9 +197,18 @@ async function checkForUpdates() {
 // Wire up event handlers
 checkUpdatesBtn?.addEventListener("click", checkForUpdates);
 
+openConfigBtn?.addEventListener("click", async () => {
+  try {
+    await invoke("open_config_folder");
+  } catch (error) {
+    alert(`Failed to open the settings folder: ${error}`);
+  }
+});
+
 closeBtn?.addEventListener("click", () => {
   currentWindow.close();
 });
 
 // Load version on page load
 window.addEventListener("DOMContentLoaded", loadVersion);
+window.addEventListener("DOMContentLoaded", loadInfo);
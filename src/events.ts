@@ -0,0 +1,169 @@
+// events.ts - Events from the backend
+//
+// The backend sends everything as one "app-event" Tauri event whose payload
+// is tagged by "type" (see src-tauri/src/events.rs). AppEvent mirrors that
+// enum, and onAppEvent() subscribes to one type with a handler that gets
+// the matching payload, so windows never spell out event names or payload
+// shapes themselves.
+//
+//   onAppEvent("index_updated", (event) => console.log(event.apps));
+
+import { listen } from "@tauri-apps/api/event";
+
+/// Result row types - these mirror results.rs in the Rust backend
+///
+/// Actions are opaque to the frontend: they're passed back to
+/// execute_action unchanged, so new backend action types need no changes here.
+export type Action = { type: string; value?: unknown };
+
+export interface RowAction {
+  label: string;
+  action: Action;
+}
+
+export interface ResultRow {
+  title: string;
+  subtitle: string;
+  action: Action;
+  icon: string | null;
+  actions: RowAction[];
+}
+
+/// The look of the windows (see quickrun-core/src/theme.rs)
+/// What shows through the launcher's background (Acrylic/Mica need Windows 11)
+export type Backdrop = "none" | "acrylic" | "mica";
+
+/// The tray icon: the app icon, or one color to match the taskbar
+export type TrayIcon = "color" | "monochrome";
+
+export interface Theme {
+  mode: "dark" | "light";
+  accent: string;
+  background_opacity: number;
+  window_opacity: number;
+  corner_radius: number;
+  font_family: string;
+  custom_css_path: string | null;
+  backdrop: Backdrop;
+  tray_icon: TrayIcon;
+}
+
+/// A theme as sent by get_theme and theme_changed, with the custom
+/// stylesheet's contents
+export interface LoadedTheme extends Theme {
+  custom_css: string | null;
+}
+
+/// Which monitor the launcher opens on
+export type LauncherMonitor = "cursor" | "focused_window" | "primary" | "remembered";
+
+/// The settings, as returned by get_settings and sent with settings_changed
+/// (field names match settings.json; see quickrun-core/src/settings.rs).
+/// Only the fields the windows use are listed.
+/// Sound cues (see src-tauri/src/sounds.rs); null = the Windows sound
+export interface Sounds {
+  enabled: boolean;
+  show: string | null;
+  launch: string | null;
+  error: string | null;
+}
+
+/// An extra tray menu entry (see quickrun-core/src/settings.rs)
+export interface TrayAction {
+  label: string;
+  command: string;
+}
+
+/// The local HTTP API (see src-tauri/src/http_api.rs)
+export interface HttpApi {
+  enabled: boolean;
+  port: number;
+}
+
+export interface Settings {
+  theme: Theme;
+  language: string;
+  hotkey: string;
+  double_tap: string;
+  launcher_monitor: LauncherMonitor;
+  hide_on_blur: boolean;
+  always_on_top: boolean;
+  all_desktops: boolean;
+  ui_scale: number;
+  startup_delay_secs: number;
+  tray_actions: TrayAction[];
+  deep_links: boolean;
+  http_api: HttpApi;
+  sounds: Sounds;
+  remember_last_input: boolean;
+  ime_wait_for_commit: boolean;
+  import_shell_history: boolean;
+  clipboard_history: boolean;
+  clipboard_paste_on_select: boolean;
+  watch_downloads: boolean;
+  update_check_hours: number;
+  silent_updates: boolean;
+  skip_version: string | null;
+}
+
+/// Which effects Windows' reduced effects and battery saver settings allow
+/// (see quickrun-core/src/resource_policy.rs)
+export interface ResourcePolicy {
+  animations: boolean;
+  blur: boolean;
+  scheduled_rebuilds: boolean;
+  battery_saver: boolean;
+}
+
+/// Background jobs that report back through job_progress / job_finished
+export type Job = "speedtest" | "archive" | "scaffold";
+
+export type AppEvent =
+  | { type: "window_shown"; last_input: string | null }
+  | { type: "ime_composition"; composing: boolean }
+  | { type: "settings_changed"; settings: Settings }
+  | { type: "theme_changed"; theme: LoadedTheme }
+  | { type: "resource_policy_changed"; policy: ResourcePolicy }
+  | { type: "backdrop_changed"; backdrop: Backdrop }
+  | { type: "ui_scale_changed"; scale: number }
+  | { type: "index_updated"; apps: number; path_commands: number }
+  | {
+      type: "update_progress";
+      stage:
+        | "downloading"
+        | "installer_launched"
+        | "installing_silently"
+        | "opening_release_page"
+        | "restarting"
+        | "cancelled";
+    }
+  | { type: "update_download_progress"; bytes: number; total: number | null; percent: number | null }
+  | { type: "job_progress"; job: Job; percent: number; detail: string }
+  | { type: "job_finished"; job: Job; rows: ResultRow[] };
+
+type AppEventOf<T extends AppEvent["type"]> = Extract<AppEvent, { type: T }>;
+
+/// Handlers by event type
+const handlers = new Map<string, Array<(event: AppEvent) => void>>();
+
+/// Whether this window is listening for "app-event" yet
+let subscribed = false;
+
+/// Run `handler` for every backend event of the given type
+export function onAppEvent<T extends AppEvent["type"]>(
+  type: T,
+  handler: (event: AppEventOf<T>) => void | Promise<void>,
+) {
+  const list = handlers.get(type) ?? [];
+  list.push(handler as (event: AppEvent) => void);
+  handlers.set(type, list);
+
+  if (!subscribed) {
+    subscribed = true;
+    listen<AppEvent>("app-event", (event) => {
+      for (const handle of handlers.get(event.payload.type) ?? []) {
+        handle(event.payload);
+      }
+    });
+  }
+}
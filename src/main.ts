@@ -5,49 +5,411 @@
 // - Hiding the window when the user presses Escape
 // - Focusing and clearing the input when the window is shown
 // - Displaying inline error messages
+// - Ghost-text completion (accepted with Tab or Right-arrow)
+// - Tab-cycling through filesystem matches for partial paths ("C:\Prog")
+// - Showing result rows produced by keywords (e.g., "http example.com")
+// - Holding queries back while an IME composition is in progress
+// - Typing | on a selected file result to open it with an alias
+// - Sizing the window to the results, at the "ui_scale" size
 
 import { invoke } from "@tauri-apps/api/core";
-import { listen } from "@tauri-apps/api/event";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { LogicalSize } from "@tauri-apps/api/dpi";
+import { onAppEvent, type ResultRow, type Settings } from "./events";
+import { followTheme } from "./theme";
 
 const commandInput = document.getElementById("command-input") as HTMLInputElement;
 const errorMessage = document.getElementById("error-message") as HTMLDivElement;
+const ghostTyped = document.getElementById("ghost-typed") as HTMLSpanElement;
+const ghostRest = document.getElementById("ghost-rest") as HTMLSpanElement;
+const resultsList = document.getElementById("results") as HTMLUListElement;
 const currentWindow = getCurrentWebviewWindow();
 
-/// Load and apply theme on startup
-/// 
-/// Checks the saved theme preference and applies it immediately.
-/// 
-/// Flow:
-/// 1. Call Rust backend to read theme setting from JSON file
-/// 2. Apply the theme by setting data-theme attribute on <html>
-/// 3. CSS variables change based on data-theme value
-/// 4. If there's an error reading the setting, default to dark theme
+/// Apply the saved theme, and switch instantly whenever it changes (from
+/// the Settings window or a hand edit to settings.json) - see theme.ts
+followTheme();
+
+/// The current completion (full text), or null if there is none
+let completion: string | null = null;
+
+/// Show the part of `completion` beyond what the user typed as ghost text
+function renderGhost() {
+  const typed = commandInput.value;
+  if (completion && completion.length > typed.length) {
+    ghostTyped.textContent = typed;
+    ghostRest.textContent = completion.slice(typed.length);
+  } else {
+    clearGhost();
+  }
+}
+
+/// Remove any ghost text
+function clearGhost() {
+  completion = null;
+  ghostTyped.textContent = "";
+  ghostRest.textContent = "";
+}
+
+/// Whether queries wait for IME compositions to commit (see composition.rs)
+let waitForCommit = true;
+
+/// True for input events that are part of an uncommitted IME composition
+/// and should not be sent to the backend
+function isUncommitted(e: Event): boolean {
+  return waitForCommit && e instanceof InputEvent && e.isComposing;
+}
+
+/// Follow settings changes, from the Settings window or hand edits to
+/// settings.json (the backend has already re-registered hotkeys by the time
+/// this arrives)
+onAppEvent("settings_changed", ({ settings }) => {
+  waitForCommit = settings.ime_wait_for_commit;
+});
+
+/// Ask the backend for the best completion of `typed`
 /// 
-/// This runs once at startup to ensure the launcher uses the user's preferred theme
-async function loadTheme() {
+/// Responses can arrive out of order, so a stale answer (for text the user
+/// has already changed) is ignored.
+async function refreshCompletion(typed: string) {
+  if (!typed.trim()) {
+    clearGhost();
+    return;
+  }
+  
   try {
-    const isLight = await invoke<boolean>("is_light_mode");
-    document.documentElement.setAttribute("data-theme", isLight ? "light" : "dark");
+    const result = await invoke<string | null>("complete", { input: typed });
+    if (commandInput.value !== typed) {
+      return;
+    }
+    completion = result;
+    renderGhost();
   } catch (err) {
-    // Default to dark theme if there's an error
-    document.documentElement.setAttribute("data-theme", "dark");
+    clearGhost();
   }
 }
 
-loadTheme();
+/// Update the completion whenever the input changes
+commandInput.addEventListener("input", async (e) => {
+  if (isUncommitted(e)) {
+    clearGhost();
+    return;
+  }
+  await refreshCompletion(commandInput.value);
+});
+
+/// Path completion cycle state: the matches for the text the user typed,
+/// and which one is currently shown in the input
+let pathCycle: { matches: string[]; index: number } | null = null;
+
+/// Cycle through filesystem completions for a partial path
+/// 
+/// The first Tab asks the backend for matches and shows the first one;
+/// further Tabs (Shift+Tab backwards) step through the list as long as the
+/// input still holds the match we put there.
+/// 
+/// Returns false if the input isn't a completable path.
+async function cyclePathCompletion(backwards: boolean): Promise<boolean> {
+  const current = commandInput.value;
+  
+  if (pathCycle && pathCycle.matches[pathCycle.index] === current) {
+    const count = pathCycle.matches.length;
+    pathCycle.index = (pathCycle.index + (backwards ? count - 1 : 1)) % count;
+  } else {
+    const matches = await invoke<string[]>("complete_path", { input: current });
+    if (matches.length === 0) {
+      pathCycle = null;
+      return false;
+    }
+    pathCycle = { matches, index: 0 };
+  }
+  
+  commandInput.value = pathCycle.matches[pathCycle.index];
+  clearGhost();
+  return true;
+}
+
+/// Tab: cycle path completions, or accept the ghost-text completion
+/// Right-arrow at the end of the input also accepts the ghost text
+commandInput.addEventListener("keydown", async (e) => {
+  const caretAtEnd = commandInput.selectionStart === commandInput.value.length;
+  
+  if (e.key === "Tab") {
+    e.preventDefault();
+    try {
+      if (await cyclePathCompletion(e.shiftKey)) {
+        return;
+      }
+    } catch (err) {
+      console.error("Path completion failed:", err);
+    }
+  } else if (!(e.key === "ArrowRight" && caretAtEnd)) {
+    return;
+  }
+  
+  if (completion) {
+    e.preventDefault();
+    commandInput.value = completion;
+    clearGhost();
+  }
+});
+
+/// What run_command did (mirrors RunOutcome in results.rs; the row types
+/// are in events.ts)
+type RunOutcome =
+  | { kind: "launched" }
+  | { kind: "results"; rows: ResultRow[] }
+  | { kind: "done" };
+
+/// Window geometry: the launcher grows downward to fit result rows
+const WINDOW_WIDTH = 500;
+const BASE_HEIGHT = 80;
+const ROW_HEIGHT = 44;
+const MAX_VISIBLE_ROWS = 8;
+
+/// How much bigger the page is zoomed (see src-tauri/src/scale.rs); window
+/// sizes are multiplied by it
+let uiScale = 1;
+
+/// Size the window to fit `visibleRows` result rows
+async function fitWindow(visibleRows: number) {
+  const extra = visibleRows > 0 ? visibleRows * ROW_HEIGHT + 8 : 0;
+  await currentWindow.setSize(new LogicalSize(WINDOW_WIDTH * uiScale, (BASE_HEIGHT + extra) * uiScale));
+}
+
+invoke<number>("get_ui_scale").then((scale) => (uiScale = scale));
+onAppEvent("ui_scale_changed", async ({ scale }) => {
+  uiScale = scale;
+  await fitWindow(Math.min(resultsList.children.length, MAX_VISIBLE_ROWS));
+});
+
+let rows: ResultRow[] = [];
+let selectedIndex = -1;
+
+/// While the secondary-action menu is open, the rows it replaced (restored on Escape)
+let rowsBeforeMenu: ResultRow[] | null = null;
+
+/// Render result rows and resize the window to fit them
+async function showResults(newRows: ResultRow[]) {
+  rows = newRows;
+  rowsBeforeMenu = null;
+  selectedIndex = -1;
+  resultsList.innerHTML = "";
+  
+  rows.forEach((row, index) => {
+    const item = document.createElement("li");
+    item.className = "result-row";
+    
+    if (row.icon) {
+      const icon = document.createElement("img");
+      icon.className = "result-icon";
+      icon.src = row.icon;
+      item.appendChild(icon);
+    }
+    
+    const text = document.createElement("div");
+    text.className = "result-text";
+    
+    const title = document.createElement("div");
+    title.className = "result-title";
+    title.textContent = row.title;
+    
+    const subtitle = document.createElement("div");
+    subtitle.className = "result-subtitle";
+    subtitle.textContent = row.subtitle;
+    
+    text.append(title, subtitle);
+    item.appendChild(text);
+    item.addEventListener("click", () => executeRow(index));
+    item.addEventListener("contextmenu", (e) => {
+      e.preventDefault();
+      showActionMenu(index);
+    });
+    resultsList.appendChild(item);
+  });
+  
+  resultsList.style.maxHeight = `${MAX_VISIBLE_ROWS * ROW_HEIGHT}px`;
+  await fitWindow(Math.min(rows.length, MAX_VISIBLE_ROWS));
+}
+
+/// Highlight the row at `index` (keyboard navigation)
+function selectRow(index: number) {
+  selectedIndex = index;
+  Array.from(resultsList.children).forEach((item, i) => {
+    item.classList.toggle("selected", i === index);
+    if (i === index) {
+      item.scrollIntoView({ block: "nearest" });
+    }
+  });
+}
+
+/// Apply the outcome of running a command or row action
+async function handleOutcome(outcome: RunOutcome) {
+  suggestedFor = null;
+  if (outcome.kind === "launched") {
+    // Window already hidden by Rust - reset for next time
+    commandInput.value = "";
+    clearGhost();
+    hideError();
+    await showResults([]);
+  } else if (outcome.kind === "results") {
+    hideError();
+    await showResults(outcome.rows);
+  }
+}
 
-/// Listen for theme changes from settings window
+/// Replace the rows with the secondary actions of the row at `index`
 /// 
-/// When the user changes theme in settings:
-/// 1. Settings window calls set_light_mode() in Rust
-/// 2. Settings window emits "theme-changed" event to all windows
-/// 3. This listener receives the event and updates the theme
-/// 4. Theme changes instantly without restart
+/// Opened with Shift+Enter or right-click; Escape goes back to the rows.
+async function showActionMenu(index: number) {
+  const row = rows[index];
+  if (!row || row.actions.length === 0) {
+    return;
+  }
+  
+  const previous = rowsBeforeMenu ?? rows;
+  await showResults(
+    row.actions.map((a) => ({
+      title: a.label,
+      subtitle: row.title,
+      action: a.action,
+      icon: null,
+      actions: [],
+    }))
+  );
+  rowsBeforeMenu = previous;
+  selectRow(0);
+}
+
+/// Perform the action of the row at `index`
+async function executeRow(index: number) {
+  const row = rows[index];
+  if (!row) {
+    return;
+  }
+  
+  try {
+    const outcome = await invoke<RunOutcome>("execute_action", { action: row.action });
+    await handleOutcome(outcome);
+  } catch (error) {
+    showError(String(error));
+  }
+}
+
+// Input the visible rows are suggestions for (null when they are keyword
+// results or an action menu, which a refresh must not replace)
+let suggestedFor: string | null = null;
+
+/// Fetch and show suggestions for `typed`
 /// 
-/// This enables live theme switching across all windows
-listen<{ theme: string }>("theme-changed", (event) => {
-  document.documentElement.setAttribute("data-theme", event.payload.theme);
+/// Like completion, answers for text the user has already changed are dropped.
+/// The backend also cancels superseded queries, which answer null.
+async function refreshSuggestions(typed: string) {
+  try {
+    const suggestions = await invoke<ResultRow[] | null>("suggest", { query: typed });
+    if (suggestions && commandInput.value === typed) {
+      await showResults(suggestions);
+      suggestedFor = typed;
+    }
+  } catch (err) {
+    console.error("Failed to get suggestions:", err);
+  }
+}
+
+/// Refresh the suggestion list as the user types
+commandInput.addEventListener("input", async (e) => {
+  pathCycle = null;
+  if (isUncommitted(e)) {
+    return;
+  }
+  await refreshSuggestions(commandInput.value);
+});
+
+/// Report IME compositions to the backend, which answers with
+/// an ime_composition event once a commit has settled
+commandInput.addEventListener("compositionstart", () => {
+  invoke("set_composing", { composing: true }).catch(console.error);
+});
+commandInput.addEventListener("compositionend", () => {
+  invoke("set_composing", { composing: false }).catch(console.error);
+});
+
+/// The composed text was committed: query for it now (the input events
+/// during the composition were skipped)
+onAppEvent("ime_composition", async (event) => {
+  if (event.composing || !waitForCommit) {
+    return;
+  }
+  const typed = commandInput.value;
+  await refreshCompletion(typed);
+  await refreshSuggestions(typed);
+});
+
+/// The app index was rebuilt in the background: re-run the visible
+/// suggestions so newly installed apps show up without retyping
+onAppEvent("index_updated", async () => {
+  const typed = commandInput.value;
+  if (typed !== "" && suggestedFor === typed && rowsBeforeMenu === null) {
+    await refreshSuggestions(typed);
+  }
+});
+
+/// Background jobs (e.g., "speedtest") deliver their final rows via this event
+onAppEvent("job_finished", (event) => {
+  suggestedFor = null;
+  showResults(event.rows);
+});
+
+/// Background job progress, shown in the placeholder row's subtitle:
+/// - speedtest: the phase being measured and its percentage
+/// - archive (compress/extract): the entry being processed
+onAppEvent("job_progress", (event) => {
+  const subtitle = resultsList.querySelector(".result-subtitle");
+  if (!subtitle) {
+    return;
+  }
+  subtitle.textContent = event.job === "archive"
+    ? `${event.percent}% - ${event.detail} (select to cancel)`
+    : `Measuring ${event.detail}... ${event.percent}%`;
+});
+
+/// Arrow keys move the selection through result rows
+commandInput.addEventListener("keydown", (e) => {
+  if (rows.length === 0) {
+    return;
+  }
+  
+  if (e.key === "ArrowDown") {
+    e.preventDefault();
+    selectRow(Math.min(selectedIndex + 1, rows.length - 1));
+  } else if (e.key === "ArrowUp") {
+    e.preventDefault();
+    selectRow(Math.max(selectedIndex - 1, -1));
+  }
+});
+
+/// The file or folder a row stands for (the target of its "Open file
+/// location" action), or null
+function rowPath(row: ResultRow): string | null {
+  const reveal = row.actions.find((a) => a.action.type === "reveal");
+  return reveal ? String(reveal.action.value) : null;
+}
+
+/// Typing | with a file result selected starts "open it with...": the
+/// file's path goes in front of the | and the aliases are suggested
+/// (see pipe.rs)
+commandInput.addEventListener("keydown", async (e) => {
+  if (e.key !== "|" || selectedIndex < 0 || rowsBeforeMenu) {
+    return;
+  }
+  const path = rowPath(rows[selectedIndex]);
+  if (!path) {
+    return;
+  }
+  e.preventDefault();
+  commandInput.value = `"${path}" | `;
+  clearGhost();
+  hideError();
+  await refreshSuggestions(commandInput.value);
 });
 
 /// Run the command when the user presses Enter
@@ -56,10 +418,12 @@ listen<{ theme: string }>("theme-changed", (event) => {
 /// 1. User types a command and presses Enter
 /// 2. Call Rust backend with the command text
 /// 3. Rust resolves it via PATH (like Windows Run dialog)
-/// 4. Rust spawns the process and hides the window
-/// 5. If success: Clear input and error (window already hidden)
+/// 4. Rust spawns the process and hides the window (or returns keyword results)
+/// 5. If success: Clear input and error (window already hidden), or show results
 /// 6. If error: Show error inline, select text for easy correction
 /// 
+/// If a result row is selected, Enter performs that row's action instead.
+/// 
 /// Error handling:
 /// - Command not found → "Command not found: xyz"
 /// - Empty input → Do nothing (early return)
@@ -67,6 +431,16 @@ listen<{ theme: string }>("theme-changed", (event) => {
 commandInput.addEventListener("keydown", async (e) => {
   if (e.key === "Enter") {
     e.preventDefault();
+    
+    if (selectedIndex >= 0) {
+      if (e.shiftKey) {
+        await showActionMenu(selectedIndex);
+      } else {
+        await executeRow(selectedIndex);
+      }
+      return;
+    }
+    
     const command = commandInput.value.trim();
     
     if (!command) {
@@ -81,12 +455,9 @@ commandInput.addEventListener("keydown", async (e) => {
       // The Rust side will:
       // 1. Resolve the command via PATH (check explicit path first, then search PATH)
       // 2. Spawn the process detached (no blocking)
-      // 3. Hide the window immediately on success
-      await invoke("run_command", { input: commandToRun });
-      
-      // Success: Clear the UI (window already hidden by Rust)
-      commandInput.value = "";
-      hideError();
+      // 3. Hide the window immediately on success (or return keyword results)
+      const outcome = await invoke<RunOutcome>("run_command", { input: commandToRun });
+      await handleOutcome(outcome);
       
     } catch (error) {
       // Failure: show the error, keep window open and focused
@@ -99,29 +470,55 @@ commandInput.addEventListener("keydown", async (e) => {
   } else if (e.key === "Escape") {
     /// Escape key: hide the window and clear everything
     /// This is the "dismiss" action - user changed their mind
+    /// (If the action menu is open, Escape just closes the menu.)
     e.preventDefault();
+    if (rowsBeforeMenu) {
+      await showResults(rowsBeforeMenu);
+      return;
+    }
     commandInput.value = "";
+    clearGhost();
     hideError();
-    await currentWindow.hide();
+    await showResults([]);
+    // The backend hides the window and hands focus back to the previous app
+    await invoke("dismiss");
   }
 });
 
-/// Listen for the "window-show" event from Rust
+/// Listen for the window_shown event from Rust
 /// 
 /// This event is emitted by the Rust backend when:
 /// - User presses Alt+Space (global hotkey)
 /// - User clicks the system tray icon
 /// 
 /// What we do:
-/// - Clear any previous command text
+/// - Clear any previous command text, or put back the last input (selected,
+///   so typing replaces it) if "remember_last_input" is on
 /// - Hide any previous error messages
 /// - Focus the input so user can start typing immediately
+/// - Re-read the IME setting (it may have changed in Settings)
 /// 
-/// This ensures a clean slate every time the launcher appears
-listen("window-show", () => {
-  commandInput.value = "";
+/// This ensures a clean slate every time the launcher appears. The backend
+/// may still have something to offer for empty input (e.g., a new download).
+onAppEvent("window_shown", async ({ last_input }) => {
+  commandInput.value = last_input ?? "";
+  clearGhost();
   hideError();
+  showResults([]);
   commandInput.focus();
+  commandInput.select();
+  waitForCommit = await invoke<Settings>("get_settings")
+    .then((settings) => settings.ime_wait_for_commit)
+    .catch(() => true);
+  
+  try {
+    const suggestions = await invoke<ResultRow[] | null>("suggest", { query: "" });
+    if (suggestions && commandInput.value === "") {
+      await showResults(suggestions);
+    }
+  } catch (err) {
+    console.error("Failed to get suggestions:", err);
+  }
 });
 
 /// Helper: show an inline error message
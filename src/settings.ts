@@ -4,84 +4,346 @@
 // clicks "Settings" in the system tray menu.
 //
 // Features:
+// - Profile picker (each profile has its own settings and aliases)
+// - Language (the window's text comes from the backend's translations: each
+//   element with data-i18n / data-i18n-title gets the string with that key)
 // - Startup with Windows toggle (modifies Windows registry)
-// - Light/Dark theme toggle (saves to JSON, applies immediately)
-// - Cross-window communication (theme changes apply to launcher window too)
+// - Theme: light/dark, accent color, background and launcher opacity, blur, corner
+//   radius, font, and a custom stylesheet (applied immediately, see theme.ts)
+// - PowerShell history suggestions toggle
+// - Clipboard history and paste-on-select toggles
+// - New downloads suggestion toggle
+// - Background update check interval and silent installation toggle
+// - Wait-for-IME-commit toggle
+// - Launcher hotkey (validated by the backend, applied immediately)
+// - Double-tap modifier (Off/Ctrl/Shift/Alt)
+// - Launcher size (ui_scale)
+// - Sound cues toggle
+// - Preferred terminal, editor, and browser (saved when a field loses focus)
+// - "Run with QuickRun" in Explorer's context menu (registry)
+// - quickrun:// links toggle
+// - Local HTTP API: on/off, port and token
+// - Tray actions, one "label = command" per line
+// - Search bangs table, one "keyword template" per line
+// - Import of bangs and aliases from PowerToys Run, Wox and Flow Launcher
+// - Export/import of settings, aliases, and history (for moving machines)
+// - Export/import of the launch history alone, as CSV or JSON
+// - Follows changes made elsewhere (e.g., settings.json edited by hand)
 //
 // Architecture:
 // - Calls Rust backend via Tauri commands for settings persistence
-// - Uses event system to notify main window of theme changes
+// - The backend announces every change with a settings_changed event (and
+//   theme changes with theme_changed, which every window restyles from)
 // - Changes apply immediately without restart
 
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import { getAllWebviewWindows } from "@tauri-apps/api/webviewWindow";
+import {
+  onAppEvent,
+  type Backdrop,
+  type HttpApi,
+  type LauncherMonitor,
+  type Settings,
+  type Theme,
+  type TrayIcon,
+} from "./events";
+import { followTheme } from "./theme";
 
 // Get references to UI elements
 const startupCheckbox = document.getElementById("startup-checkbox") as HTMLInputElement;
+const contextMenuCheckbox = document.getElementById("context-menu-checkbox") as HTMLInputElement;
+const contextMenuDescription = document.getElementById("context-menu-description") as HTMLParagraphElement;
+const startupDescription = document.getElementById("startup-description") as HTMLParagraphElement;
+const elevatedStartupCheckbox = document.getElementById("elevated-startup-checkbox") as HTMLInputElement;
+const elevatedStartupDescription = document.getElementById("elevated-startup-description") as HTMLParagraphElement;
+const startupDelaySelect = document.getElementById("startup-delay-select") as HTMLSelectElement;
+const storageWarning = document.getElementById("storage-warning") as HTMLDivElement;
+const profileSelect = document.getElementById("profile-select") as HTMLSelectElement;
 const lightModeCheckbox = document.getElementById("light-mode-checkbox") as HTMLInputElement;
+const accentInput = document.getElementById("accent-input") as HTMLInputElement;
+const opacityInput = document.getElementById("opacity-input") as HTMLInputElement;
+const windowOpacityInput = document.getElementById("window-opacity-input") as HTMLInputElement;
+const radiusInput = document.getElementById("radius-input") as HTMLInputElement;
+const fontInput = document.getElementById("font-input") as HTMLInputElement;
+const customCssInput = document.getElementById("custom-css-input") as HTMLInputElement;
+const backdropSelect = document.getElementById("backdrop-select") as HTMLSelectElement;
+const trayIconSelect = document.getElementById("tray-icon-select") as HTMLSelectElement;
+const shellHistoryCheckbox = document.getElementById("shell-history-checkbox") as HTMLInputElement;
+const clipboardHistoryCheckbox = document.getElementById("clipboard-history-checkbox") as HTMLInputElement;
+const clipboardPasteCheckbox = document.getElementById("clipboard-paste-checkbox") as HTMLInputElement;
+const downloadsCheckbox = document.getElementById("downloads-checkbox") as HTMLInputElement;
+const updateCheckSelect = document.getElementById("update-check-select") as HTMLSelectElement;
+const silentUpdatesCheckbox = document.getElementById("silent-updates-checkbox") as HTMLInputElement;
+const imeWaitCheckbox = document.getElementById("ime-wait-checkbox") as HTMLInputElement;
+const hotkeyInput = document.getElementById("hotkey-input") as HTMLInputElement;
+const doubleTapSelect = document.getElementById("double-tap-select") as HTMLSelectElement;
+const monitorSelect = document.getElementById("monitor-select") as HTMLSelectElement;
+const resetPositionButton = document.getElementById("reset-position-button") as HTMLButtonElement;
+const languageSelect = document.getElementById("language-select") as HTMLSelectElement;
+const uiScaleSelect = document.getElementById("ui-scale-select") as HTMLSelectElement;
+const soundsCheckbox = document.getElementById("sounds-checkbox") as HTMLInputElement;
+const rememberInputCheckbox = document.getElementById("remember-input-checkbox") as HTMLInputElement;
+const deepLinksCheckbox = document.getElementById("deep-links-checkbox") as HTMLInputElement;
+const deepLinksDescription = document.getElementById("deep-links-description") as HTMLParagraphElement;
+const httpApiCheckbox = document.getElementById("http-api-checkbox") as HTMLInputElement;
+const httpApiPortInput = document.getElementById("http-api-port-input") as HTMLInputElement;
+const httpApiTokenInput = document.getElementById("http-api-token-input") as HTMLInputElement;
+const httpApiTokenButton = document.getElementById("http-api-token-button") as HTMLButtonElement;
+const hideOnBlurCheckbox = document.getElementById("hide-on-blur-checkbox") as HTMLInputElement;
+const alwaysOnTopCheckbox = document.getElementById("always-on-top-checkbox") as HTMLInputElement;
+const allDesktopsCheckbox = document.getElementById("all-desktops-checkbox") as HTMLInputElement;
+const terminalInput = document.getElementById("terminal-input") as HTMLInputElement;
+const editorInput = document.getElementById("editor-input") as HTMLInputElement;
+const browserInput = document.getElementById("browser-input") as HTMLInputElement;
+const bangsInput = document.getElementById("bangs-input") as HTMLTextAreaElement;
+const importLaunchersButton = document.getElementById("import-launchers-button") as HTMLButtonElement;
+const trayActionsInput = document.getElementById("tray-actions-input") as HTMLTextAreaElement;
+const storageUsageList = document.getElementById("storage-usage") as HTMLUListElement;
+const maintenanceButton = document.getElementById("maintenance-button") as HTMLButtonElement;
+const exportHistoryButton = document.getElementById("export-history-button") as HTMLButtonElement;
+const importHistoryButton = document.getElementById("import-history-button") as HTMLButtonElement;
+const exportButton = document.getElementById("export-button") as HTMLButtonElement;
+const importButton = document.getElementById("import-button") as HTMLButtonElement;
+const restartButton = document.getElementById("restart-button") as HTMLButtonElement;
 const closeButton = document.getElementById("close-button") as HTMLButtonElement;
 const currentWindow = getCurrentWebviewWindow();
 
-/// Apply theme to all open windows
-/// 
-/// This function:
-/// 1. Updates the theme on this settings window
-/// 2. Finds all other windows (specifically the main launcher window)
-/// 3. Emits a "theme-changed" event to each window
-/// 4. Those windows listen for this event and update their theme
+/// Save some settings (only the given keys change)
+async function updateSettings(patch: Partial<Settings>): Promise<Settings> {
+  return await invoke<Settings>("update_settings", { patch });
+}
+
+/// The theme as last loaded or saved (theme changes send the whole object)
+let theme: Theme | null = null;
+
+/// Show `saved` in the theme controls
+function showTheme(saved: Theme) {
+  theme = saved;
+  lightModeCheckbox.checked = saved.mode === "light";
+  // The color picker only takes #rrggbb
+  accentInput.value = saved.accent.length === 4
+    ? "#" + [...saved.accent.slice(1)].map((digit) => digit + digit).join("")
+    : saved.accent;
+  opacityInput.value = String(Math.round(saved.background_opacity * 100));
+  windowOpacityInput.value = String(Math.round(saved.window_opacity * 100));
+  radiusInput.value = String(saved.corner_radius);
+  fontInput.value = saved.font_family;
+  customCssInput.value = saved.custom_css_path ?? "";
+  backdropSelect.value = saved.backdrop;
+  trayIconSelect.value = saved.tray_icon;
+}
+
+/// Save a change to the theme
 /// 
-/// Why emit events instead of directly manipulating windows?
-/// - Cleaner separation of concerns
-/// - Each window manages its own DOM
-/// - Works even if windows are on different monitors
-async function applyTheme(isLight: boolean) {
-  const theme = isLight ? "light" : "dark";
-  document.documentElement.setAttribute("data-theme", theme);
-  
-  // Also update the main window's theme if it exists
-  const windows = await getAllWebviewWindows();
-  windows.forEach(window => {
-    if (window.label !== "settings") {
-      window.emit("theme-changed", { theme });
-    }
+/// Every window (this one included) restyles itself through the
+/// theme_changed event that follows. Invalid values are refused by the
+/// backend, and the controls go back to the saved theme.
+async function updateTheme(changes: Partial<Theme>) {
+  if (!theme) {
+    return;
+  }
+  try {
+    showTheme((await updateSettings({ theme: { ...theme, ...changes } })).theme);
+  } catch (error) {
+    console.error("Failed to update theme:", error);
+    alert("Failed to update theme: " + error);
+    showTheme((await invoke<Settings>("get_settings")).theme);
+  }
+}
+
+// This window follows the theme too
+followTheme();
+
+/// Translations for this window (see quickrun-core/src/i18n.rs)
+let strings: Record<string, string> = {};
+
+/// The text for `key`, with {0}, {1}, ... replaced by `args`
+function t(key: string, ...args: (string | number)[]): string {
+  return args.reduce<string>((text, arg, i) => text.replaceAll(`{${i}}`, String(arg)), strings[key] ?? key);
+}
+
+/// Put the current language's text into the elements marked for it
+async function loadStrings() {
+  strings = await invoke<Record<string, string>>("get_strings");
+  document.querySelectorAll<HTMLElement>("[data-i18n]").forEach((element) => {
+    const text = strings[element.dataset.i18n!];
+    if (text) element.textContent = text;
+  });
+  document.querySelectorAll<HTMLElement>("[data-i18n-title]").forEach((element) => {
+    const text = strings[element.dataset.i18nTitle!];
+    if (text) element.title = text;
   });
+  document.title = t("settings.title");
+}
+
+/// Where settings are kept (see quickrun-core/src/storage.rs)
+interface Storage {
+  dir: string;
+  portable: boolean;
+  in_memory: boolean;
+  warning: string | null;
+}
+
+/// What the data folder takes up (see quickrun-core/src/retention.rs)
+interface StorageUsage {
+  dir: string;
+  total_bytes: number;
+  items: { name: string; bytes: number; entries: number | null }[];
+}
+
+/// A size for people ("1.4 MB")
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+/// Show the size of each file and folder in the data folder
+function showStorageUsage(usage: StorageUsage) {
+  storageUsageList.replaceChildren(
+    ...usage.items.map((item) => {
+      const row = document.createElement("li");
+      const name = document.createElement("span");
+      name.textContent = item.entries === null ? item.name : t("settings.storage.entries", item.name, item.entries);
+      const size = document.createElement("span");
+      size.textContent = formatBytes(item.bytes);
+      row.append(name, size);
+      return row;
+    })
+  );
+  storageUsageList.title = `${usage.dir} - ${formatBytes(usage.total_bytes)} in all`;
 }
 
 /// Load current settings from backend and update UI
 /// 
-/// Called when settings window opens.
+/// Called when settings window opens, and again whenever the settings
+/// change (e.g., settings.json was edited by hand while the window was open).
 /// 
 /// Flow:
-/// 1. Call Rust backend to check if startup is enabled (reads Windows registry)
-/// 2. Update startup checkbox to match
-/// 3. Call Rust backend to get theme preference (reads JSON file)
-/// 4. Update light mode checkbox to match
-/// 5. Apply the theme immediately (affects both settings and launcher windows)
+/// 1. Translate the window into the language in use
+/// 2. Call Rust backend to check if startup is enabled (reads Windows registry
+///    and Task Scheduler)
+/// 3. Update startup checkboxes to match
+/// 4. Get all other settings in one call and update the controls to match
+/// 5. Apply the theme to this window
 async function loadSettings() {
   try {
-    const startupEnabled = await invoke<boolean>("is_startup_enabled");
-    startupCheckbox.checked = startupEnabled;
+    await loadStrings();
+
+    await loadStartup();
+    contextMenuCheckbox.checked = await invoke<boolean>("is_context_menu_enabled");
+
+    // A portable copy doesn't write to the registry
+    const storage = await invoke<Storage>("get_storage");
+    if (storage.portable) {
+      startupCheckbox.disabled = true;
+      startupDescription.textContent = t("settings.startup.portable");
+      elevatedStartupCheckbox.disabled = true;
+      elevatedStartupDescription.textContent = t("settings.startup.portable");
+      contextMenuCheckbox.disabled = true;
+      contextMenuDescription.textContent = t("settings.startup.portable");
+      deepLinksCheckbox.disabled = true;
+      deepLinksDescription.textContent = t("settings.startup.portable");
+    }
+
+    // Say so if settings can't be saved where they normally are
+    storageWarning.textContent = storage.warning ?? "";
+    storageWarning.className = storage.warning ? "error-visible" : "error-hidden";
+
+    showStorageUsage(await invoke<StorageUsage>("get_storage_usage"));
 
-    const lightMode = await invoke<boolean>("is_light_mode");
-    lightModeCheckbox.checked = lightMode;
-    await applyTheme(lightMode);
+    await loadProfiles();
+
+    const settings = await invoke<Settings>("get_settings");
+    showTheme(settings.theme);
+
+    shellHistoryCheckbox.checked = settings.import_shell_history;
+    clipboardHistoryCheckbox.checked = settings.clipboard_history;
+    clipboardPasteCheckbox.checked = settings.clipboard_paste_on_select;
+    downloadsCheckbox.checked = settings.watch_downloads;
+    updateCheckSelect.value = String(settings.update_check_hours);
+    silentUpdatesCheckbox.checked = settings.silent_updates;
+    imeWaitCheckbox.checked = settings.ime_wait_for_commit;
+
+    hotkeyInput.value = settings.hotkey;
+    doubleTapSelect.value = settings.double_tap;
+    monitorSelect.value = settings.launcher_monitor;
+    languageSelect.value = settings.language;
+    uiScaleSelect.value = String(settings.ui_scale);
+    startupDelaySelect.value = String(settings.startup_delay_secs);
+    soundsCheckbox.checked = settings.sounds.enabled;
+    rememberInputCheckbox.checked = settings.remember_last_input;
+    deepLinksCheckbox.checked = settings.deep_links;
+    await showHttpApi(settings.http_api);
+    hideOnBlurCheckbox.checked = settings.hide_on_blur;
+    alwaysOnTopCheckbox.checked = settings.always_on_top;
+    allDesktopsCheckbox.checked = settings.all_desktops;
+    trayActionsInput.value = settings.tray_actions.map((action) => `${action.label} = ${action.command}`).join("\n");
+
+    const apps = await invoke<{ terminal: string; editor: string; browser: string }>("get_default_apps");
+    terminalInput.value = apps.terminal;
+    editorInput.value = apps.editor;
+    browserInput.value = apps.browser;
+
+    bangs = await invoke<Bang[]>("get_search_bangs");
+    bangsInput.value = bangs.map((bang) => `${bang.keyword} ${bang.template}`).join("\n");
   } catch (error) {
     console.error("Failed to load settings:", error);
   }
 }
 
+/// Value of the "New profile..." entry (never a valid profile name)
+const NEW_PROFILE = "";
+
+/// Fill the profile picker, selecting the active profile
+async function loadProfiles() {
+  const { active, profiles } = await invoke<{ active: string; profiles: string[] }>("list_profiles");
+  profileSelect.replaceChildren(
+    ...profiles.map((name) => new Option(name, name, false, name === active)),
+    new Option("New profile...", NEW_PROFILE),
+  );
+  profileSelect.value = active;
+}
+
+/// Switch profiles (or create one) when the selection changes
+/// 
+/// A new profile starts as a copy of the current one. The controls refresh
+/// through the settings_changed event that follows the switch.
+profileSelect.addEventListener("change", async () => {
+  let name: string | null = profileSelect.value;
+  if (name === NEW_PROFILE) {
+    name = prompt("Name of the new profile (letters, digits, - and _):", "");
+  }
+  try {
+    if (name) {
+      await invoke("switch_profile", { name });
+    }
+  } catch (error) {
+    console.error("Failed to switch profile:", error);
+    alert("Failed to switch profile: " + error);
+  }
+  await loadProfiles();
+});
+
+/// Update both startup checkboxes from the registry and Task Scheduler
+async function loadStartup() {
+  startupCheckbox.checked = await invoke<boolean>("is_startup_enabled");
+  elevatedStartupCheckbox.checked = await invoke<boolean>("is_elevated_startup_enabled");
+}
+
 /// Handle startup checkbox change
 /// 
 /// When user toggles "Start with Windows":
 /// 1. Call Rust backend to modify Windows registry
-/// 2. If success: Checkbox stays in new state
+/// 2. If success: Checkbox stays in new state (and the elevated one is
+///    cleared, as only one of them can be on)
 /// 3. If error: Revert checkbox and show error alert
 /// 
 /// Registry location: HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Run
 startupCheckbox.addEventListener("change", async () => {
   try {
     await invoke("set_startup_enabled", { enabled: startupCheckbox.checked });
+    await loadStartup();
   } catch (error) {
     console.error("Failed to set startup:", error);
     // Revert checkbox on error - give user feedback that it didn't work
@@ -90,24 +352,500 @@ startupCheckbox.addEventListener("change", async () => {
   }
 });
 
+/// Handle elevated startup checkbox change (Windows asks for permission;
+/// declining reverts the checkbox)
+elevatedStartupCheckbox.addEventListener("change", async () => {
+  try {
+    await invoke("set_elevated_startup_enabled", { enabled: elevatedStartupCheckbox.checked });
+    await loadStartup();
+  } catch (error) {
+    console.error("Failed to set elevated startup:", error);
+    elevatedStartupCheckbox.checked = !elevatedStartupCheckbox.checked;
+    alert("Failed to update startup setting: " + error);
+  }
+});
+
+/// Add or remove "Run with QuickRun" in Explorer's context menu (registry)
+contextMenuCheckbox.addEventListener("change", async () => {
+  try {
+    await invoke("set_context_menu_enabled", { enabled: contextMenuCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set the Explorer context menu:", error);
+    contextMenuCheckbox.checked = !contextMenuCheckbox.checked;
+    alert("Failed to update the Explorer context menu: " + error);
+  }
+});
+
+/// Handle startup delay change (takes effect at the next start at login)
+startupDelaySelect.addEventListener("change", async () => {
+  try {
+    await updateSettings({ startup_delay_secs: Number(startupDelaySelect.value) });
+  } catch (error) {
+    console.error("Failed to set startup delay:", error);
+    alert("Failed to set startup delay: " + error);
+    startupDelaySelect.value = String((await invoke<Settings>("get_settings")).startup_delay_secs);
+  }
+});
+
 /// Handle light mode checkbox change
 /// 
-/// When user toggles theme:
-/// 1. Call Rust backend to save preference to JSON file
-/// 2. Apply theme immediately to all windows (no restart needed!)
-/// 3. If error: Revert checkbox and show error alert
+/// The theme change is instant in every window - user sees it happen in
+/// real-time (see updateTheme)
+lightModeCheckbox.addEventListener("change", () => {
+  updateTheme({ mode: lightModeCheckbox.checked ? "light" : "dark" });
+});
+
+/// The other theme controls save when they change
+accentInput.addEventListener("change", () => updateTheme({ accent: accentInput.value }));
+opacityInput.addEventListener("change", () => {
+  updateTheme({ background_opacity: Number(opacityInput.value) / 100 });
+});
+windowOpacityInput.addEventListener("change", () => {
+  updateTheme({ window_opacity: Number(windowOpacityInput.value) / 100 });
+});
+radiusInput.addEventListener("change", () => updateTheme({ corner_radius: Number(radiusInput.value) }));
+fontInput.addEventListener("change", () => updateTheme({ font_family: fontInput.value.trim() }));
+backdropSelect.addEventListener("change", () => updateTheme({ backdrop: backdropSelect.value as Backdrop }));
+trayIconSelect.addEventListener("change", () => updateTheme({ tray_icon: trayIconSelect.value as TrayIcon }));
+customCssInput.addEventListener("change", () => {
+  updateTheme({ custom_css_path: customCssInput.value.trim() || null });
+});
+
+/// Handle PowerShell history checkbox change (reverts on error, like the other toggles)
+shellHistoryCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ import_shell_history: shellHistoryCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set shell history import:", error);
+    shellHistoryCheckbox.checked = !shellHistoryCheckbox.checked;
+    alert("Failed to update shell history setting: " + error);
+  }
+});
+
+/// Handle clipboard history checkbox change
+clipboardHistoryCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ clipboard_history: clipboardHistoryCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set clipboard history:", error);
+    clipboardHistoryCheckbox.checked = !clipboardHistoryCheckbox.checked;
+    alert("Failed to update clipboard history setting: " + error);
+  }
+});
+
+/// Handle paste-on-select checkbox change
+clipboardPasteCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ clipboard_paste_on_select: clipboardPasteCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set clipboard paste:", error);
+    clipboardPasteCheckbox.checked = !clipboardPasteCheckbox.checked;
+    alert("Failed to update clipboard paste setting: " + error);
+  }
+});
+
+/// Handle new downloads checkbox change
+downloadsCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ watch_downloads: downloadsCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set downloads watcher:", error);
+    downloadsCheckbox.checked = !downloadsCheckbox.checked;
+    alert("Failed to update downloads setting: " + error);
+  }
+});
+
+/// Handle update check interval change
+updateCheckSelect.addEventListener("change", async () => {
+  try {
+    await updateSettings({ update_check_hours: Number(updateCheckSelect.value) });
+  } catch (error) {
+    console.error("Failed to set update check interval:", error);
+    alert("Failed to set update check interval: " + error);
+    updateCheckSelect.value = String((await invoke<Settings>("get_settings")).update_check_hours);
+  }
+});
+
+/// Handle silent updates checkbox change
+silentUpdatesCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ silent_updates: silentUpdatesCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set silent updates:", error);
+    silentUpdatesCheckbox.checked = !silentUpdatesCheckbox.checked;
+    alert("Failed to update silent updates setting: " + error);
+  }
+});
+
+/// Handle wait-for-IME-commit checkbox change
+imeWaitCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ ime_wait_for_commit: imeWaitCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set IME setting:", error);
+    imeWaitCheckbox.checked = !imeWaitCheckbox.checked;
+    alert("Failed to update IME setting: " + error);
+  }
+});
+
+/// Save the hotkey when the field changes (reverts to the saved one if invalid)
+hotkeyInput.addEventListener("change", async () => {
+  try {
+    hotkeyInput.value = (await updateSettings({ hotkey: hotkeyInput.value })).hotkey;
+  } catch (error) {
+    console.error("Failed to set hotkey:", error);
+    alert("Failed to update hotkey: " + error);
+    hotkeyInput.value = (await invoke<Settings>("get_settings")).hotkey;
+  }
+});
+
+/// Save the double-tap modifier when the selection changes
+doubleTapSelect.addEventListener("change", async () => {
+  try {
+    doubleTapSelect.value = (await updateSettings({ double_tap: doubleTapSelect.value })).double_tap;
+  } catch (error) {
+    console.error("Failed to set double-tap:", error);
+    alert("Failed to update double-tap setting: " + error);
+    doubleTapSelect.value = (await invoke<Settings>("get_settings")).double_tap;
+  }
+});
+
+/// Save the launcher's monitor when the selection changes
+monitorSelect.addEventListener("change", async () => {
+  try {
+    await updateSettings({ launcher_monitor: monitorSelect.value as LauncherMonitor });
+  } catch (error) {
+    console.error("Failed to set launcher monitor:", error);
+    alert("Failed to update launcher monitor: " + error);
+    monitorSelect.value = (await invoke<Settings>("get_settings")).launcher_monitor;
+  }
+});
+
+/// Forget where the launcher was dragged to, so it opens centered again
+resetPositionButton.addEventListener("click", async () => {
+  try {
+    await invoke("reset_launcher_position");
+  } catch (error) {
+    console.error("Failed to reset launcher position:", error);
+    alert("Failed to reset launcher position: " + error);
+  }
+});
+
+/// Apply the retention limits now and show the new sizes
+maintenanceButton.addEventListener("click", async () => {
+  try {
+    showStorageUsage(await invoke<StorageUsage>("run_maintenance"));
+  } catch (error) {
+    console.error("Failed to clean up:", error);
+    alert("Failed to clean up: " + error);
+  }
+});
+
+/// Handle language change (the window is translated again when the
+/// settings_changed event arrives)
+languageSelect.addEventListener("change", async () => {
+  try {
+    await updateSettings({ language: languageSelect.value });
+  } catch (error) {
+    console.error("Failed to set language:", error);
+    alert("Failed to set language: " + error);
+    languageSelect.value = (await invoke<Settings>("get_settings")).language;
+  }
+});
+
+/// Handle launcher size change
+uiScaleSelect.addEventListener("change", async () => {
+  try {
+    await updateSettings({ ui_scale: Number(uiScaleSelect.value) });
+  } catch (error) {
+    console.error("Failed to set launcher size:", error);
+    alert("Failed to set launcher size: " + error);
+    uiScaleSelect.value = String((await invoke<Settings>("get_settings")).ui_scale);
+  }
+});
+
+/// Handle play-sounds checkbox change (the custom sound files are kept)
+soundsCheckbox.addEventListener("change", async () => {
+  try {
+    const { sounds } = await invoke<Settings>("get_settings");
+    await updateSettings({ sounds: { ...sounds, enabled: soundsCheckbox.checked } });
+  } catch (error) {
+    console.error("Failed to set sounds:", error);
+    alert("Failed to set sounds: " + error);
+    soundsCheckbox.checked = !soundsCheckbox.checked;
+  }
+});
+
+/// Handle remember-last-input checkbox change (turning it off forgets the input)
+rememberInputCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ remember_last_input: rememberInputCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set remember last input:", error);
+    rememberInputCheckbox.checked = !rememberInputCheckbox.checked;
+    alert("Failed to update remember last input setting: " + error);
+  }
+});
+
+/// Handle quickrun:// links checkbox change (registers or removes the scheme)
+deepLinksCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ deep_links: deepLinksCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set quickrun:// links:", error);
+    deepLinksCheckbox.checked = !deepLinksCheckbox.checked;
+    alert("Failed to update quickrun:// links setting: " + error);
+  }
+});
+
+/// Handle hide-when-clicking-elsewhere checkbox change
+hideOnBlurCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ hide_on_blur: hideOnBlurCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set hide on blur:", error);
+    hideOnBlurCheckbox.checked = !hideOnBlurCheckbox.checked;
+    alert("Failed to update hide on blur setting: " + error);
+  }
+});
+
+/// Handle always-on-top checkbox change
+alwaysOnTopCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ always_on_top: alwaysOnTopCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set always on top:", error);
+    alwaysOnTopCheckbox.checked = !alwaysOnTopCheckbox.checked;
+    alert("Failed to update always on top setting: " + error);
+  }
+});
+
+/// Handle all-virtual-desktops checkbox change
+allDesktopsCheckbox.addEventListener("change", async () => {
+  try {
+    await updateSettings({ all_desktops: allDesktopsCheckbox.checked });
+  } catch (error) {
+    console.error("Failed to set all desktops:", error);
+    allDesktopsCheckbox.checked = !allDesktopsCheckbox.checked;
+    alert("Failed to update virtual desktops setting: " + error);
+  }
+});
+
+/// Save the preferred terminal/editor/browser when any of the fields changes
+/// 
+/// All three are saved together; empty fields mean "use the default"
+async function saveDefaultApps() {
+  try {
+    await invoke("set_default_apps", {
+      apps: {
+        terminal: terminalInput.value,
+        editor: editorInput.value,
+        browser: browserInput.value,
+      },
+    });
+  } catch (error) {
+    console.error("Failed to save default apps:", error);
+    alert("Failed to save default apps: " + error);
+  }
+}
+
+[terminalInput, editorInput, browserInput].forEach((input) => {
+  input.addEventListener("change", saveDefaultApps);
+});
+
+interface Bang {
+  keyword: string;
+  template: string;
+  name: string;
+}
+
+// Last saved bang table (keeps site names for keywords that still exist)
+let bangs: Bang[] = [];
+
+/// Save the search bangs textarea ("keyword template" per line)
+async function saveBangs() {
+  const edited = bangsInput.value
+    .split("\n")
+    .map((line) => line.trim().split(/\s+/))
+    .filter((parts) => parts.length >= 2)
+    .map(([keyword, template]) => ({
+      keyword,
+      template,
+      name: bangs.find((bang) => bang.keyword === keyword)?.name ?? "",
+    }));
+
+  try {
+    await invoke("set_search_bangs", { bangs: edited });
+    bangs = edited;
+  } catch (error) {
+    console.error("Failed to save search bangs:", error);
+    alert("Failed to save search bangs: " + error);
+  }
+}
+
+bangsInput.addEventListener("change", saveBangs);
+
+/// Add bangs and aliases from other launchers' settings (nothing existing
+/// is replaced; the bangs box refreshes through the settings_changed event)
+importLaunchersButton.addEventListener("click", async () => {
+  try {
+    const summary = await invoke<{ sources: string[]; bangs: number; aliases: number; skipped: string[] }>(
+      "import_from_launchers",
+    );
+    let message = `From ${summary.sources.join(", ")}: added ${summary.bangs} bangs and ${summary.aliases} aliases`;
+    if (summary.skipped.length > 0) {
+      message += `\n\nSkipped:\n${summary.skipped.join("\n")}`;
+    }
+    alert(message);
+  } catch (error) {
+    console.error("Failed to import from other launchers:", error);
+    alert("Failed to import from other launchers: " + error);
+  }
+});
+
+/// Show the local HTTP API settings, and its token (kept apart from the
+/// settings)
+async function showHttpApi(api: HttpApi) {
+  httpApiCheckbox.checked = api.enabled;
+  httpApiPortInput.value = String(api.port);
+  showHttpApiToken(await invoke<string>("get_http_api_token"));
+}
+
+/// Show the local HTTP API's token ("" until it's first turned on)
+function showHttpApiToken(token: string) {
+  httpApiTokenInput.value = token;
+  httpApiTokenButton.disabled = !token;
+}
+
+/// Save the local HTTP API settings (turning it on the first time makes a
+/// token)
+async function saveHttpApi() {
+  try {
+    const current = (await invoke<Settings>("get_settings")).http_api;
+    const http_api = { ...current, enabled: httpApiCheckbox.checked, port: Number(httpApiPortInput.value) };
+    await showHttpApi((await updateSettings({ http_api })).http_api);
+  } catch (error) {
+    console.error("Failed to set the HTTP API:", error);
+    alert("Failed to update the HTTP API: " + error);
+    await showHttpApi((await invoke<Settings>("get_settings")).http_api);
+  }
+}
+
+httpApiCheckbox.addEventListener("change", saveHttpApi);
+httpApiPortInput.addEventListener("change", saveHttpApi);
+
+/// Replace the token (the old one stops working)
+httpApiTokenButton.addEventListener("click", async () => {
+  try {
+    showHttpApiToken(await invoke<string>("new_http_api_token"));
+  } catch (error) {
+    console.error("Failed to make a new token:", error);
+    alert("Failed to make a new token: " + error);
+  }
+});
+
+/// Save the tray actions textarea ("label = command" per line)
+trayActionsInput.addEventListener("change", async () => {
+  const tray_actions = trayActionsInput.value
+    .split("\n")
+    .map((line) => line.split("="))
+    .filter((parts) => parts.length >= 2)
+    .map(([label, ...command]) => ({ label: label.trim(), command: command.join("=").trim() }))
+    .filter((action) => action.label && action.command);
+
+  try {
+    await updateSettings({ tray_actions });
+  } catch (error) {
+    console.error("Failed to save tray actions:", error);
+    alert("Failed to save tray actions: " + error);
+  }
+});
+
+/// Suggested location for a backup file (~ is the user's profile folder)
+const DEFAULT_BACKUP_PATH = "~\\Documents\\QuickRun-settings.json";
+
+/// Export settings, aliases, and history to a file chosen by the user
+exportButton.addEventListener("click", async () => {
+  const path = prompt("Save the backup to:", DEFAULT_BACKUP_PATH);
+  if (!path) {
+    return;
+  }
+  try {
+    await invoke("export_settings", { path });
+    alert(`Saved to ${path}`);
+  } catch (error) {
+    console.error("Failed to export settings:", error);
+    alert("Failed to export settings: " + error);
+  }
+});
+
+/// Import a backup made with Export (replaces what's in the file)
 /// 
-/// The theme change is instant - user sees it happen in real-time
-lightModeCheckbox.addEventListener("change", async () => {
+/// The backend checks the whole file first, so a bad backup changes nothing.
+/// The controls refresh through the settings_changed event.
+importButton.addEventListener("click", async () => {
+  const path = prompt("Restore settings, aliases, and history from:", DEFAULT_BACKUP_PATH);
+  if (!path) {
+    return;
+  }
+  try {
+    const summary = await invoke<{ settings: boolean; aliases: number | null; history: number | null }>(
+      "import_settings",
+      { path },
+    );
+    const restored = [
+      summary.settings ? "settings" : null,
+      summary.aliases !== null ? `${summary.aliases} aliases` : null,
+      summary.history !== null ? `${summary.history} history entries` : null,
+    ].filter((part) => part !== null);
+    alert(`Restored ${restored.join(", ") || "nothing"}`);
+  } catch (error) {
+    console.error("Failed to import settings:", error);
+    alert("Failed to import settings: " + error);
+  }
+});
+
+/// Suggested location for a history file (.csv for spreadsheets, .json also works)
+const DEFAULT_HISTORY_PATH = "~\\Documents\\QuickRun-history.csv";
+
+/// Export just the launch history, with each command's frecency
+exportHistoryButton.addEventListener("click", async () => {
+  const path = prompt("Save the history to (.csv or .json):", DEFAULT_HISTORY_PATH);
+  if (!path) {
+    return;
+  }
   try {
-    await invoke("set_light_mode", { enabled: lightModeCheckbox.checked });
-    // Apply theme immediately - this updates both windows instantly
-    await applyTheme(lightModeCheckbox.checked);
+    const count = await invoke<number>("export_history", { path });
+    alert(`Saved ${count} commands to ${path}`);
   } catch (error) {
-    console.error("Failed to set light mode:", error);
-    // Revert checkbox on error
-    lightModeCheckbox.checked = !lightModeCheckbox.checked;
-    alert("Failed to update theme setting: " + error);
+    console.error("Failed to export history:", error);
+    alert("Failed to export history: " + error);
+  }
+});
+
+/// Merge an exported history into this one (nothing is removed)
+importHistoryButton.addEventListener("click", async () => {
+  const path = prompt("Merge the history from (.csv or .json):", DEFAULT_HISTORY_PATH);
+  if (!path) {
+    return;
+  }
+  try {
+    const count = await invoke<number>("import_history", { path });
+    alert(`Merged ${count} commands`);
+    showStorageUsage(await invoke<StorageUsage>("get_storage_usage"));
+  } catch (error) {
+    console.error("Failed to import history:", error);
+    alert("Failed to import history: " + error);
+  }
+});
+
+/// Restart QuickRun (e.g., after adding portable.flag next to the exe)
+restartButton.addEventListener("click", async () => {
+  try {
+    await invoke("restart_app");
+  } catch (error) {
+    console.error("Failed to restart:", error);
+    alert("Failed to restart: " + error);
   }
 });
 
@@ -118,3 +856,6 @@ closeButton.addEventListener("click", () => {
 
 // Load settings when page loads
 window.addEventListener("DOMContentLoaded", loadSettings);
+
+// Keep the controls in step with settings.json
+onAppEvent("settings_changed", loadSettings);
@@ -0,0 +1,85 @@
+// theme.ts - Applying the theme to a window
+//
+// The theme (see quickrun-core/src/theme.rs) becomes CSS variables on
+// <html>, which styles.css uses: data-theme picks the dark or light colors,
+// --accent, --bg-opacity, --window-opacity, --radius and --font-family
+// carry the rest. A
+// custom stylesheet goes in a <style> element after styles.css, so it can
+// override anything.
+//
+// followTheme() applies the saved theme and keeps the window in step with
+// theme_changed events. It also follows the resource policy (see
+// quickrun-core/src/resource_policy.rs): data-reduce-motion turns off
+// animations and data-opaque the see-through background and launcher. data-backdrop is
+// set while Windows draws Acrylic or Mica behind the launcher (see
+// src-tauri/src/backdrop.rs), which thins the background to let it show.
+
+import { invoke } from "@tauri-apps/api/core";
+import { onAppEvent, type Backdrop, type LoadedTheme, type ResourcePolicy } from "./events";
+
+/// ID of the <style> element holding the custom stylesheet
+const CUSTOM_CSS_ID = "custom-css";
+
+/// Apply `theme` to this window
+export function applyTheme(theme: LoadedTheme) {
+  const root = document.documentElement;
+  root.setAttribute("data-theme", theme.mode);
+  root.style.setProperty("--accent", theme.accent);
+  root.style.setProperty("--bg-opacity", String(theme.background_opacity));
+  root.style.setProperty("--window-opacity", String(theme.window_opacity));
+  root.style.setProperty("--radius", `${theme.corner_radius}px`);
+  if (theme.font_family.trim()) {
+    root.style.setProperty("--font-family", theme.font_family);
+  } else {
+    root.style.removeProperty("--font-family");
+  }
+
+  let style = document.getElementById(CUSTOM_CSS_ID);
+  if (theme.custom_css) {
+    if (!style) {
+      style = document.createElement("style");
+      style.id = CUSTOM_CSS_ID;
+      document.head.appendChild(style);
+    }
+    style.textContent = theme.custom_css;
+  } else {
+    style?.remove();
+  }
+}
+
+/// Turn off the effects `policy` doesn't allow in this window
+export function applyResourcePolicy(policy: ResourcePolicy) {
+  const root = document.documentElement;
+  root.toggleAttribute("data-reduce-motion", !policy.animations);
+  root.toggleAttribute("data-opaque", !policy.blur);
+}
+
+/// Mark the Acrylic/Mica backdrop in use behind the launcher ("none" = none)
+export function applyBackdrop(backdrop: Backdrop) {
+  const root = document.documentElement;
+  if (backdrop === "none") {
+    root.removeAttribute("data-backdrop");
+  } else {
+    root.setAttribute("data-backdrop", backdrop);
+  }
+}
+
+/// Apply the saved theme now and whenever it changes
+export async function followTheme() {
+  onAppEvent("theme_changed", ({ theme }) => applyTheme(theme));
+  onAppEvent("resource_policy_changed", ({ policy }) => applyResourcePolicy(policy));
+  invoke<ResourcePolicy>("get_resource_policy")
+    .then(applyResourcePolicy)
+    .catch((error) => console.error("Failed to load resource policy:", error));
+  onAppEvent("backdrop_changed", ({ backdrop }) => applyBackdrop(backdrop));
+  invoke<Backdrop>("get_backdrop")
+    .then(applyBackdrop)
+    .catch((error) => console.error("Failed to load backdrop:", error));
+  try {
+    applyTheme(await invoke<LoadedTheme>("get_theme"));
+  } catch (error) {
+    // Keep the built-in dark theme
+    console.error("Failed to load theme:", error);
+    document.documentElement.setAttribute("data-theme", "dark");
+  }
+}
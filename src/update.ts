@@ -0,0 +1,285 @@
+// update.ts - QuickRun Update window logic
+//
+// Opened from "Check for updates" in the tray menu. The window checks
+// GitHub for a newer release as soon as it opens and, if there is one,
+// shows the release notes of every version since the running one with
+// buttons to install it, to open the release
+// page, or to skip that version (it isn't offered again until a newer one is
+// released; a skipped release is still shown here, for installing anyway).
+// If the last update kept the version before it, a button goes back to it.
+// An installer downloaded by hand (for a machine that can't reach GitHub)
+// can be dropped on the window to install it.
+//
+// Architecture:
+// - check_for_update / download_and_install_update /
+//   install_update_from_file are the Rust commands in lib.rs (see
+//   updater.rs)
+// - Install progress arrives as update_progress events, and the download's
+//   as update_download_progress events; cancel_update_download
+//   stops the download
+
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { onAppEvent } from "./events";
+
+/// What check_for_update returns (see UpdateInfo in updater.rs)
+interface UpdateInfo {
+  available: boolean;
+  version: string;
+  body: string;
+  current_version: string;
+  release_url: string;
+  installer_url: string | null;
+  checksums_url: string | null;
+  signature_url: string | null;
+  skipped: boolean;
+}
+
+/// One release's notes, as get_update_changelog lists them
+interface ReleaseNotes {
+  version: string;
+  body: string;
+}
+
+const currentWindow = getCurrentWebviewWindow();
+const titleEl = document.getElementById("update-title") as HTMLHeadingElement;
+const versionsEl = document.getElementById("update-versions") as HTMLParagraphElement;
+const notesSection = document.getElementById("release-notes-section") as HTMLDivElement;
+const notesEl = document.getElementById("release-notes") as HTMLDivElement;
+const updateStatus = document.getElementById("update-status") as HTMLParagraphElement;
+const installBtn = document.getElementById("install-btn") as HTMLButtonElement;
+const releasePageBtn = document.getElementById("release-page-btn") as HTMLButtonElement;
+const skipBtn = document.getElementById("skip-btn") as HTMLButtonElement;
+const revertBtn = document.getElementById("revert-btn") as HTMLButtonElement;
+const cancelDownloadBtn = document.getElementById("cancel-download-btn") as HTMLButtonElement;
+const downloadProgress = document.getElementById("download-progress") as HTMLProgressElement;
+const closeBtn = document.getElementById("close-btn") as HTMLButtonElement;
+
+/// The release found by the last check
+let update: UpdateInfo | null = null;
+
+/// Show a status message (red for errors, green for success)
+function showUpdateStatus(message: string, isError = false, isSuccess = false) {
+  updateStatus.textContent = message;
+  if (isError) {
+    updateStatus.className = "update-status update-status-error";
+  } else if (isSuccess) {
+    updateStatus.className = "update-status update-status-success";
+  } else {
+    updateStatus.className = "update-status";
+  }
+}
+
+/// Show or hide the install button's spinner (and the download's progress
+/// bar and Cancel button)
+function setInstalling(installing: boolean) {
+  installBtn.disabled = installing;
+  installBtn.querySelector(".loading")?.classList.toggle("hidden", !installing);
+  cancelDownloadBtn.classList.toggle("hidden", !installing);
+  if (!installing) {
+    downloadProgress.classList.add("hidden");
+  }
+}
+
+/// "5.2 MB"
+function formatMegabytes(bytes: number): string {
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+/// Show the latest release's notes, then those of each release since the
+/// running version once they're fetched, newest first, under its version
+/// number when there's more than one (the latest's stay if they can't be
+/// fetched). Notes are shown as written (plain text, line breaks kept).
+async function showReleaseNotes(info: UpdateInfo) {
+  renderReleaseNotes([{ version: info.version, body: info.body }]);
+  try {
+    const changelog = await invoke<ReleaseNotes[]>("get_update_changelog", { version: info.version });
+    if (changelog.length > 0) {
+      renderReleaseNotes(changelog);
+    }
+  } catch (error) {
+    console.error("Failed to fetch the changelog:", error);
+  }
+}
+
+/// Replace the notes shown with `changelog`
+function renderReleaseNotes(changelog: ReleaseNotes[]) {
+  if (changelog.length === 1) {
+    notesEl.textContent = changelog[0].body.trim() || "No release notes.";
+    return;
+  }
+  notesEl.replaceChildren(
+    ...changelog.flatMap(({ version, body }) => {
+      const heading = document.createElement("h3");
+      heading.className = "release-notes-version";
+      heading.textContent = `v${version}`;
+      const notes = document.createElement("div");
+      notes.textContent = body.trim() || "No release notes.";
+      return [heading, notes];
+    })
+  );
+}
+
+/// Offer to go back to the version before the last update, if it was kept
+async function showRevert() {
+  const previous = await invoke<string | null>("previous_version");
+  if (previous) {
+    revertBtn.textContent = `Revert to v${previous}`;
+    revertBtn.dataset.version = previous;
+    revertBtn.classList.remove("hidden");
+  }
+}
+
+/// Ask GitHub for the latest release and show what was found
+async function checkForUpdates() {
+  try {
+    update = await invoke<UpdateInfo>("check_for_update");
+    const newer = update.available || update.skipped;
+    versionsEl.textContent = newer
+      ? `v${update.current_version} → v${update.version}`
+      : `v${update.current_version}`;
+
+    if (newer) {
+      titleEl.textContent = `QuickRun v${update.version} is available`;
+      showReleaseNotes(update);
+      notesSection.classList.remove("hidden");
+      installBtn.classList.remove("hidden");
+      releasePageBtn.classList.remove("hidden");
+      if (update.skipped) {
+        showUpdateStatus("You skipped this version - it won't be offered until a newer one is out");
+      } else {
+        skipBtn.classList.remove("hidden");
+      }
+    } else {
+      titleEl.textContent = "QuickRun is up to date";
+      showUpdateStatus("You are running the latest version", false, true);
+    }
+  } catch (error) {
+    console.error("Update check failed:", error);
+    titleEl.textContent = "Update check failed";
+    showUpdateStatus(`Update check failed: ${error}`, true);
+  }
+}
+
+/// Download and start the installer - or, for a portable copy, the portable
+/// exe, which QuickRun restarts on (the release page opens if there's nothing
+/// to download)
+installBtn.addEventListener("click", async () => {
+  if (!update) return;
+  setInstalling(true);
+  try {
+    await invoke("download_and_install_update", { updateInfo: update });
+  } catch (error) {
+    console.error("Update failed:", error);
+    showUpdateStatus(`Update failed: ${error}`, true);
+  } finally {
+    setInstalling(false);
+  }
+});
+
+cancelDownloadBtn.addEventListener("click", async () => {
+  cancelDownloadBtn.disabled = true;
+  try {
+    await invoke("cancel_update_download");
+  } finally {
+    cancelDownloadBtn.disabled = false;
+  }
+});
+
+/// Don't offer this version again, and close the window
+skipBtn.addEventListener("click", async () => {
+  if (!update) return;
+  try {
+    await invoke("skip_update_version", { version: update.version });
+    currentWindow.close();
+  } catch (error) {
+    showUpdateStatus(`Failed to skip this version: ${error}`, true);
+  }
+});
+
+/// Reinstall the version before the last update
+revertBtn.addEventListener("click", async () => {
+  if (!confirm(`Go back to QuickRun v${revertBtn.dataset.version}?`)) return;
+  revertBtn.disabled = true;
+  try {
+    await invoke("revert_to_previous_version");
+  } catch (error) {
+    showUpdateStatus(`Failed to go back: ${error}`, true);
+  } finally {
+    revertBtn.disabled = false;
+  }
+});
+
+/// Install an installer dropped on the window (checked by Rust as a
+/// downloaded one is before it runs)
+getCurrentWebview().onDragDropEvent(async (event) => {
+  if (event.payload.type !== "drop") return;
+  const [path] = event.payload.paths;
+  if (!path) return;
+  if (!path.toLowerCase().endsWith(".exe")) {
+    showUpdateStatus("Drop a QuickRun installer (.exe) to install it", true);
+    return;
+  }
+  const name = path.split(/[\\/]/).pop();
+  if (!confirm(`Install QuickRun from ${name}?`)) return;
+  try {
+    await invoke("install_update_from_file", { path });
+  } catch (error) {
+    showUpdateStatus(`Failed to install ${name}: ${error}`, true);
+  }
+});
+
+releasePageBtn.addEventListener("click", async () => {
+  if (!update) return;
+  try {
+    await invoke("plugin:opener|open", { path: update.release_url });
+  } catch (error) {
+    showUpdateStatus(`Failed to open the release page: ${error}`, true);
+  }
+});
+
+onAppEvent("update_progress", ({ stage }) => {
+  switch (stage) {
+    case "downloading":
+      showUpdateStatus("Downloading the update...");
+      break;
+    case "installer_launched":
+      showUpdateStatus("The installer is running - follow its steps to finish the update", false, true);
+      break;
+    case "installing_silently":
+      showUpdateStatus("Installing the update - QuickRun restarts when it's done", false, true);
+      break;
+    case "restarting":
+      showUpdateStatus("Restarting QuickRun on the new version...", false, true);
+      break;
+    case "opening_release_page":
+      showUpdateStatus("No installer to download - opened the release page in your browser", false, true);
+      break;
+    case "cancelled":
+      showUpdateStatus("Download cancelled");
+      break;
+  }
+});
+
+onAppEvent("update_download_progress", ({ bytes, total, percent }) => {
+  downloadProgress.classList.remove("hidden");
+  if (percent === null || total === null) {
+    // Size unknown: an indeterminate bar
+    downloadProgress.removeAttribute("value");
+    showUpdateStatus(`Downloading the update... ${formatMegabytes(bytes)}`);
+  } else {
+    downloadProgress.value = percent;
+    const size = `${formatMegabytes(bytes)} of ${formatMegabytes(total)}`;
+    showUpdateStatus(`Downloading the update... ${size} (${percent}%)`);
+  }
+});
+
+closeBtn.addEventListener("click", () => {
+  currentWindow.close();
+});
+
+window.addEventListener("DOMContentLoaded", () => {
+  checkForUpdates();
+  showRevert();
+});
@@ -50,6 +50,8 @@ $ErrorActionPreference = "Stop"
 
 # Configuration
 $CargoTomlPath = Join-Path $PSScriptRoot "src-tauri\Cargo.toml"
+$CoreCargoTomlPath = Join-Path $PSScriptRoot "src-tauri\quickrun-core\Cargo.toml"
+$CliCargoTomlPath = Join-Path $PSScriptRoot "src-tauri\quickrun-cli\Cargo.toml"
 $TauriConfPath = Join-Path $PSScriptRoot "src-tauri\tauri.conf.json"
 $PackageJsonPath = Join-Path $PSScriptRoot "package.json"
 
@@ -118,12 +120,15 @@ function Compare-Versions {
 function Update-CargoToml {
     param([string]$NewVersion)
     
-    $content = Get-Content $CargoTomlPath -Raw
-    $pattern = '(version\s*=\s*")(\d+\.\d+\.\d+)(")'
-    $replacement = "`${1}$NewVersion`${3}"
-    $updated = $content -replace $pattern, $replacement
-    
-    Set-Content -Path $CargoTomlPath -Value $updated -NoNewline
+    # The app, quickrun-core and qr share a version number
+    foreach ($path in @($CargoTomlPath, $CoreCargoTomlPath, $CliCargoTomlPath)) {
+        $content = Get-Content $path -Raw
+        $pattern = '(version\s*=\s*")(\d+\.\d+\.\d+)(")'
+        $replacement = "`${1}$NewVersion`${3}"
+        $updated = $content -replace $pattern, $replacement
+        
+        Set-Content -Path $path -Value $updated -NoNewline
+    }
 }
 
 function Update-TauriConf {
@@ -354,7 +359,7 @@ try {
 catch {
     Write-ErrorMsg "Failed to update tauri.conf.json: $_"
     # Attempt to restore Cargo.toml
-    git checkout -- $CargoTomlPath 2>$null
+    git checkout -- $CargoTomlPath $CoreCargoTomlPath $CliCargoTomlPath 2>$null
     exit 1
 }
 
@@ -366,13 +371,13 @@ try {
 catch {
     Write-ErrorMsg "Failed to update package.json: $_"
     # Attempt to restore previous files
-    git checkout -- $CargoTomlPath $TauriConfPath 2>$null
+    git checkout -- $CargoTomlPath $CoreCargoTomlPath $CliCargoTomlPath $TauriConfPath 2>$null
     exit 1
 }
 
 # Git operations
 Write-Step "Staging version changes"
-git add $CargoTomlPath $TauriConfPath $PackageJsonPath
+git add $CargoTomlPath $CoreCargoTomlPath $CliCargoTomlPath $TauriConfPath $PackageJsonPath
 if ($LASTEXITCODE -ne 0) {
     Write-ErrorMsg "Failed to stage changes"
     exit 1